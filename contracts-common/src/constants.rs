@@ -17,6 +17,15 @@ pub const NUM_WIRE_TYPES: usize = 5;
 /// The number of selectors in the circuit
 pub const NUM_SELECTORS: usize = 13;
 
+/// The number of lookup argument polynomials opened at the challenge point `zeta`
+pub const NUM_LOOKUP_ZETA_EVALS: usize = 6;
+
+/// The number of lookup argument polynomials opened at the challenge point `zeta * omega`
+pub const NUM_LOOKUP_ZETA_OMEGA_EVALS: usize = 9;
+
+/// The number of Plonk proofs in a match bundle
+pub const NUM_MATCH_PROOFS: usize = 5;
+
 /// The number of linking proofs in a match bundle
 pub const NUM_MATCH_LINKING_PROOFS: usize = 4;
 
@@ -81,26 +90,104 @@ pub const EMPTY_LEAF_VALUE: ScalarField = Fp(
     PhantomData,
 );
 
-/// The selector for the darkpool core address in the
-/// `is_implementation_upgraded` method on the Darkpool test contract
+/// The selector for the darkpool core address, used when scheduling implementation
+/// address changes & in the `is_implementation_upgraded` method on the Darkpool test contract
 pub const DARKPOOL_CORE_ADDRESS_SELECTOR: u8 = 0;
 
-/// The selector for the verifier address in the `is_implementation_upgraded`
-/// method on the Darkpool test contract
+/// The selector for the verifier address, used when scheduling implementation
+/// address changes & in the `is_implementation_upgraded` method on the Darkpool test contract
 pub const VERIFIER_ADDRESS_SELECTOR: u8 = 1;
 
-/// The selector for the vkeys address in the `is_implementation_upgraded`
-/// method on the Darkpool test contract
+/// The selector for the vkeys address, used when scheduling implementation
+/// address changes & in the `is_implementation_upgraded` method on the Darkpool test contract
 pub const VKEYS_ADDRESS_SELECTOR: u8 = 2;
 
-/// The selector for the merkle address in the `is_implementation_upgraded`
-/// method on the Darkpool test contract
+/// The selector for the merkle address, used when scheduling implementation
+/// address changes & in the `is_implementation_upgraded` method on the Darkpool test contract
 pub const MERKLE_ADDRESS_SELECTOR: u8 = 3;
 
-/// The selector for the transfer executor address in the
-/// `is_implementation_upgraded` method on the Darkpool test contract
+/// The selector for the transfer executor address, used when scheduling implementation
+/// address changes & in the `is_implementation_upgraded` method on the Darkpool test contract
 pub const TRANSFER_EXECUTOR_ADDRESS_SELECTOR: u8 = 4;
 
+/// The selector for the vkey registry address, used when scheduling implementation
+/// address changes & in the `is_implementation_upgraded` method on the Darkpool test contract
+pub const VKEY_REGISTRY_ADDRESS_SELECTOR: u8 = 5;
+
+/// The identifier of the role permitted to pause & unpause the darkpool
+pub const PAUSER_ROLE: u8 = 0;
+
+/// The identifier of the role permitted to set the protocol fee
+pub const FEE_MANAGER_ROLE: u8 = 1;
+
+/// The identifier of the role permitted to rotate the protocol public encryption key
+pub const KEY_ROTATOR_ROLE: u8 = 2;
+
+/// The identifier of the role permitted to set the addresses of the
+/// darkpool core, verifier, vkeys, Merkle, & transfer executor contracts
+pub const UPGRADER_ROLE: u8 = 3;
+
+/// The identifier of the `new_wallet` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const NEW_WALLET_OPERATION: u8 = 0;
+
+/// The identifier of deposits made via the `update_wallet` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const UPDATE_WALLET_DEPOSIT_OPERATION: u8 = 1;
+
+/// The identifier of withdrawals made via the `update_wallet` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const UPDATE_WALLET_WITHDRAWAL_OPERATION: u8 = 2;
+
+/// The identifier of the `process_match_settle` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const PROCESS_MATCH_SETTLE_OPERATION: u8 = 3;
+
+/// The identifier of the `settle_online_relayer_fee` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const SETTLE_ONLINE_RELAYER_FEE_OPERATION: u8 = 4;
+
+/// The identifier of the `settle_offline_fee` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const SETTLE_OFFLINE_FEE_OPERATION: u8 = 5;
+
+/// The identifier of the `redeem_fee` operation,
+/// used as its bit index in the darkpool's pause bitmask
+pub const REDEEM_FEE_OPERATION: u8 = 6;
+
+/// The number of darkpool operations that can be individually paused
+pub const NUM_PAUSABLE_OPERATIONS: u8 = 7;
+
+/// The darkpool's pause bitmask when all operations are paused
+pub const ALL_OPERATIONS_PAUSED: u8 = (1 << NUM_PAUSABLE_OPERATIONS) - 1;
+
+/// The identifier of the `VALID WALLET CREATE` circuit,
+/// used to key its verification key commitment in the darkpool
+pub const VALID_WALLET_CREATE_CIRCUIT_ID: u8 = 0;
+
+/// The identifier of the `VALID WALLET UPDATE` circuit,
+/// used to key its verification key commitment in the darkpool
+pub const VALID_WALLET_UPDATE_CIRCUIT_ID: u8 = 1;
+
+/// The identifier of the `VALID RELAYER FEE SETTLEMENT` circuit,
+/// used to key its verification key commitment in the darkpool
+pub const VALID_RELAYER_FEE_SETTLEMENT_CIRCUIT_ID: u8 = 2;
+
+/// The identifier of the `VALID OFFLINE FEE SETTLEMENT` circuit,
+/// used to key its verification key commitment in the darkpool
+pub const VALID_OFFLINE_FEE_SETTLEMENT_CIRCUIT_ID: u8 = 3;
+
+/// The identifier of the `VALID FEE REDEMPTION` circuit,
+/// used to key its verification key commitment in the darkpool
+pub const VALID_FEE_REDEMPTION_CIRCUIT_ID: u8 = 4;
+
+/// The identifier of the circuits verified in `process_match_settle`,
+/// used to key the commitment to their Plonk & linking verification keys in the darkpool
+pub const PROCESS_MATCH_SETTLE_CIRCUIT_ID: u8 = 5;
+
+/// The number of circuits whose verification keys may be committed to in the darkpool
+pub const NUM_CIRCUITS: u8 = 6;
+
 /// The revert message when failing to convert a
 /// u256 to a scalar
 pub const SCALAR_CONVERSION_ERROR_MESSAGE: &[u8] = b"scalar conversion error";
@@ -113,6 +200,10 @@ pub const INVALID_INPUTS_ERROR_MESSAGE: &[u8] = b"invalid inputs";
 /// operation fails
 pub const ARITHMETIC_BACKEND_ERROR_MESSAGE: &[u8] = b"arithmetic backend error";
 
+/// The revert message when attempting to verify a proof
+/// containing points not in the expected groups
+pub const MALFORMED_PROOF_ERROR_MESSAGE: &[u8] = b"malformed proof";
+
 /// The EIP-712 type string used for deposit witness data via `permitWitnessTransferFrom`.
 ///
 /// For more details see: https://docs.uniswap.org/contracts/permit2/reference/signature-transfer#single-permitwitnesstransferfrom
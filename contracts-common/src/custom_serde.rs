@@ -14,8 +14,9 @@ use crate::{
         BabyJubJubPoint, ExternalTransfer, G1Affine, G1BaseField, G2Affine, G2BaseField, MontFp256,
         NoteCiphertext, OrderSettlementIndices, PublicInputs, PublicSigningKey, ScalarField,
         ValidCommitmentsStatement, ValidFeeRedemptionStatement, ValidMatchSettleStatement,
-        ValidOfflineFeeSettlementStatement, ValidReblindStatement,
-        ValidRelayerFeeSettlementStatement, ValidWalletCreateStatement, ValidWalletUpdateStatement,
+        ValidMatchSettleWithMintStatement, ValidOfflineFeeSettlementStatement,
+        ValidReblindStatement, ValidRelayerFeeSettlementStatement, ValidWalletCreateStatement,
+        ValidWalletUpdateStatement,
     },
 };
 
@@ -277,6 +278,14 @@ impl ScalarSerializable for ValidMatchSettleStatement {
     }
 }
 
+impl ScalarSerializable for ValidMatchSettleWithMintStatement {
+    fn serialize_to_scalars(&self) -> Result<Vec<ScalarField>, SerdeError> {
+        let mut scalars = self.valid_match_settle.serialize_to_scalars()?;
+        scalars.push(address_to_scalar(self.base_mint)?);
+        Ok(scalars)
+    }
+}
+
 impl ScalarSerializable for ValidRelayerFeeSettlementStatement {
     fn serialize_to_scalars(&self) -> Result<Vec<ScalarField>, SerdeError> {
         let mut scalars: Vec<ScalarField> = vec![
@@ -360,7 +369,7 @@ pub fn bigint_from_le_bytes(bytes: &[u8]) -> Result<BigInt<NUM_U64S_FELT>, Serde
 }
 
 /// Converts an [`Address`] into a [`ScalarField`]
-fn address_to_scalar(address: Address) -> Result<ScalarField, SerdeError> {
+pub fn address_to_scalar(address: Address) -> Result<ScalarField, SerdeError> {
     // The underlying representation of the address, returned by `as_slice()`,
     // is the address bytes in big-endian form.
     let address_bytes = address.as_slice();
@@ -444,9 +453,11 @@ pub fn pk_to_u256s(pk: &PublicSigningKey) -> Result<[U256; NUM_SCALARS_PK], Serd
 mod tests {
     use crate::{
         constants::NUM_BYTES_FELT,
-        types::{G1Affine, G2Affine},
+        serde_def_types::SerdeScalarField,
+        types::{G1Affine, G2Affine, ScalarField},
     };
     use ark_ec::AffineRepr;
+    use ark_ff::{One, PrimeField};
     use ark_std::UniformRand;
     use num_bigint::BigUint;
     use rand::thread_rng;
@@ -508,4 +519,17 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn test_non_canonical_scalar_deserialization() {
+        // The modulus represents zero, but is not its canonical encoding
+        let non_canonical = SerdeScalarField(ScalarField::new_unchecked(ScalarField::MODULUS));
+        let bytes = postcard::to_allocvec(&non_canonical).unwrap();
+        assert!(postcard::from_bytes::<SerdeScalarField>(&bytes).is_err());
+
+        let canonical = SerdeScalarField(ScalarField::one());
+        let bytes = postcard::to_allocvec(&canonical).unwrap();
+        let deserialized = postcard::from_bytes::<SerdeScalarField>(&bytes).unwrap();
+        assert_eq!(deserialized.0, ScalarField::one());
+    }
 }
@@ -6,7 +6,7 @@ use ark_bn254::{g1::Config as G1Config, g2::Config as G2Config, Fq2Config, FqCon
 use ark_ec::short_weierstrass::Affine;
 use ark_ff::{BigInt, Fp, Fp2ConfigWrapper, FpConfig, MontBackend, QuadExtField};
 use core::marker::PhantomData;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as DeError, Deserialize, Serialize};
 use serde_with::{serde_as, DeserializeAs, SerializeAs};
 
 use crate::types::{G1Affine, G1BaseField, G2Affine, G2BaseField, ScalarField};
@@ -52,7 +52,30 @@ pub struct FpDef<P: FpConfig<N>, const N: usize>(
     pub PhantomData<P>,
 );
 
-impl_serde_as!(Fp<P, N>, FpDef<P, N>, P: FpConfig<N>, const N: usize);
+impl<P: FpConfig<N>, const N: usize> SerializeAs<Fp<P, N>> for FpDef<P, N> {
+    fn serialize_as<S>(source: &Fp<P, N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        <FpDef<P, N>>::serialize(source, serializer)
+    }
+}
+
+impl<'de, P: FpConfig<N>, const N: usize> DeserializeAs<'de, Fp<P, N>> for FpDef<P, N> {
+    /// Deserializes a field element, rejecting non-canonical representations,
+    /// i.e. those which are not less than the field modulus
+    fn deserialize_as<D>(deserializer: D) -> Result<Fp<P, N>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let fp = <FpDef<P, N>>::deserialize(deserializer)?;
+        if fp.0 >= P::MODULUS {
+            return Err(D::Error::custom("non-canonical field element"));
+        }
+
+        Ok(fp)
+    }
+}
 
 /// A serde-compatible type alias mirroring [`ScalarField`]
 pub type ScalarFieldDef = FpDef<MontBackend<FrConfig, 4>, 4>;
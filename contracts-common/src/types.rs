@@ -5,6 +5,7 @@ use alloy_primitives::{Address, U256};
 use ark_bn254::{g1::Config as G1Config, g2::Config as G2Config, Fq, Fq2, Fr};
 use ark_ec::short_weierstrass::Affine;
 use ark_ff::{Fp256, MontBackend};
+use core::ops::{Deref, DerefMut};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -35,7 +36,7 @@ pub type MontFp256<P> = Fp256<MontBackend<P, NUM_U64S_FELT>>;
 /// used by the verifier.
 // TODO: Give these variable human-readable names once end-to-end verifier is complete
 #[serde_as]
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
 pub struct VerificationKey {
     /// The number of gates in the circuit
     pub n: u64,
@@ -61,15 +62,104 @@ pub struct VerificationKey {
     pub x_h: G2Affine,
 }
 
+/// A [`VerificationKey`] along with the verifier options of the circuit it was derived from,
+/// i.e. its lookup argument and transcript hash.
+///
+/// The options are kept out of [`VerificationKey`] itself so that its shape is shared
+/// with existing consumers of this crate. Serialized, this is a [`VerificationKey`]
+/// with the options appended.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ExtendedVerificationKey {
+    /// The TurboPlonk verification key
+    pub vkey: VerificationKey,
+    /// The preprocessed information used to verify the lookup argument of the circuit.
+    ///
+    /// If present, proofs are verified as UltraPlonk proofs, i.e. with a Plookup argument,
+    /// otherwise they are verified as TurboPlonk proofs.
+    pub lookup_vkey: Option<LookupVerificationKey>,
+    /// The hash function underlying the Fiat-Shamir transcript
+    /// from which the challenges of the circuit's proofs are derived.
+    ///
+    /// Linking proofs between circuits are always verified using the Keccak transcript.
+    pub transcript_hash: TranscriptHash,
+}
+
+impl From<VerificationKey> for ExtendedVerificationKey {
+    /// Wraps a verification key for a circuit without lookups, using the Keccak transcript
+    fn from(vkey: VerificationKey) -> Self {
+        Self {
+            vkey,
+            lookup_vkey: None,
+            transcript_hash: TranscriptHash::Keccak,
+        }
+    }
+}
+
+impl Deref for ExtendedVerificationKey {
+    type Target = VerificationKey;
+
+    fn deref(&self) -> &Self::Target {
+        &self.vkey
+    }
+}
+
+impl DerefMut for ExtendedVerificationKey {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vkey
+    }
+}
+
+/// The hash functions over which a Plonk transcript can be instantiated
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub enum TranscriptHash {
+    /// A Keccak-256 transcript absorbing serialized proof elements,
+    /// compatible with the Jellyfish `SolidityTranscript`
+    #[default]
+    Keccak,
+    /// A Poseidon2 sponge absorbing scalars & G1 coordinates directly,
+    /// which makes the transcript efficient to compute within a circuit
+    Poseidon2,
+}
+
+/// Preprocessed information used by the verifier for circuits that make use of a lookup argument.
+///
+/// Such circuits have an additional wire, the lookup wire, and an additional selector, the lookup selector,
+/// whose preprocessed elements are kept here.
+#[serde_as]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct LookupVerificationKey {
+    /// The constant used to generate the coset of the evaluation domain for the lookup wire
+    #[serde_as(as = "ScalarFieldDef")]
+    pub lookup_k: ScalarField,
+    /// The commitment to the lookup selector polynomial
+    #[serde_as(as = "G1AffineDef")]
+    pub q_lookup_comm: G1Affine,
+    /// The commitment to the permutation polynomial of the lookup wire
+    #[serde_as(as = "G1AffineDef")]
+    pub lookup_sigma_comm: G1Affine,
+    /// The commitment to the range table polynomial
+    #[serde_as(as = "G1AffineDef")]
+    pub range_table_comm: G1Affine,
+    /// The commitment to the key table polynomial
+    #[serde_as(as = "G1AffineDef")]
+    pub key_table_comm: G1Affine,
+    /// The commitment to the table domain separation polynomial
+    #[serde_as(as = "G1AffineDef")]
+    pub table_dom_sep_comm: G1Affine,
+    /// The commitment to the domain separation selector polynomial
+    #[serde_as(as = "G1AffineDef")]
+    pub q_dom_sep_comm: G1Affine,
+}
+
 /// The Plonk verification keys used when verifying the matching and settlement of a trade
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct MatchVkeys {
     /// The verification key for `VALID COMMITMENTS`
-    pub valid_commitments_vkey: VerificationKey,
+    pub valid_commitments_vkey: ExtendedVerificationKey,
     /// The verification key for `VALID REBLIND`
-    pub valid_reblind_vkey: VerificationKey,
+    pub valid_reblind_vkey: ExtendedVerificationKey,
     /// The verification key for `VALID MATCH SETTLE`
-    pub valid_match_settle_vkey: VerificationKey,
+    pub valid_match_settle_vkey: ExtendedVerificationKey,
 }
 
 /// Preprocessed information for the verification of a linking proof
@@ -86,7 +176,7 @@ pub struct LinkingVerificationKey {
 }
 
 /// The linking verification keys used when verifying the matching of a trade
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct MatchLinkingVkeys {
     /// The verification key for the
     /// `VALID REBLIND` <-> `VALID COMMITMENTS` link
@@ -129,19 +219,151 @@ pub struct Proof {
     pub z_bar: ScalarField,
 }
 
-/// The proofs representing the matching and settlement of a trade
+/// A [`Proof`] along with the proof of the circuit's lookup argument, if it has one.
+///
+/// Serialized, this is a [`Proof`] with the lookup proof appended.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct ExtendedProof {
+    /// The TurboPlonk proof
+    pub proof: Proof,
+    /// The proof of the lookup argument, present only for circuits that make use of lookups
+    pub lookup_proof: Option<LookupProof>,
+}
+
+impl From<Proof> for ExtendedProof {
+    /// Wraps a proof for a circuit without lookups
+    fn from(proof: Proof) -> Self {
+        Self {
+            proof,
+            lookup_proof: None,
+        }
+    }
+}
+
+impl Deref for ExtendedProof {
+    type Target = Proof;
+
+    fn deref(&self) -> &Self::Target {
+        &self.proof
+    }
+}
+
+impl DerefMut for ExtendedProof {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.proof
+    }
+}
+
+/// The proof of a Plookup argument, along with the elements of the lookup wire
+#[serde_as]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct LookupProof {
+    /// The commitment to the lookup wire polynomial
+    #[serde_as(as = "G1AffineDef")]
+    pub lookup_wire_comm: G1Affine,
+    /// The commitment to the final split quotient polynomial,
+    /// the quotient polynomial being split into one more part than in TurboPlonk
+    #[serde_as(as = "G1AffineDef")]
+    pub lookup_quotient_comm: G1Affine,
+    /// The commitments to the polynomials `h_1` & `h_2` into which the sorted
+    /// concatenation of the lookup witness & table is split
+    #[serde_as(as = "[G1AffineDef; 2]")]
+    pub h_poly_comms: [G1Affine; 2],
+    /// The commitment to the grand product polynomial encoding the lookup argument
+    #[serde_as(as = "G1AffineDef")]
+    pub prod_lookup_poly_comm: G1Affine,
+    /// The evaluation of the lookup wire polynomial at the challenge point `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub lookup_wire_eval: ScalarField,
+    /// The evaluation of the permutation polynomial of the last TurboPlonk wire at the challenge point `zeta`.
+    ///
+    /// With the lookup wire appended, this is no longer the last wire, so its permutation polynomial is opened as well.
+    #[serde_as(as = "ScalarFieldDef")]
+    pub last_sigma_eval: ScalarField,
+    /// The evaluations of the polynomials involved in the lookup argument
+    pub lookup_evals: LookupEvaluations,
+}
+
+/// The evaluations of the polynomials involved in a Plookup argument,
+/// at the challenge points `zeta` and `zeta * omega`
+#[serde_as]
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct LookupEvaluations {
+    /// The evaluation of the range table polynomial at `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub range_table_eval: ScalarField,
+    /// The evaluation of the key table polynomial at `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub key_table_eval: ScalarField,
+    /// The evaluation of the table domain separation polynomial at `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub table_dom_sep_eval: ScalarField,
+    /// The evaluation of the domain separation selector polynomial at `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub q_dom_sep_eval: ScalarField,
+    /// The evaluation of the `h_1` polynomial at `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub h_1_eval: ScalarField,
+    /// The evaluation of the lookup selector polynomial at `zeta`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub q_lookup_eval: ScalarField,
+    /// The evaluation of the lookup grand product polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub prod_next_eval: ScalarField,
+    /// The evaluation of the range table polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub range_table_next_eval: ScalarField,
+    /// The evaluation of the key table polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub key_table_next_eval: ScalarField,
+    /// The evaluation of the table domain separation polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub table_dom_sep_next_eval: ScalarField,
+    /// The evaluation of the `h_1` polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub h_1_next_eval: ScalarField,
+    /// The evaluation of the `h_2` polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub h_2_next_eval: ScalarField,
+    /// The evaluation of the lookup selector polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub q_lookup_next_eval: ScalarField,
+    /// The evaluation of the fourth wire polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub w_3_next_eval: ScalarField,
+    /// The evaluation of the fifth wire polynomial at `zeta * omega`
+    #[serde_as(as = "ScalarFieldDef")]
+    pub w_4_next_eval: ScalarField,
+}
+
+/// The proofs representing the matching and settlement of a trade.
+///
+/// The verifier consumes these as [`ExtendedProof`]s, the default proof type
+/// is kept for existing consumers of this crate.
 #[derive(Serialize, Deserialize, Clone, Copy)]
-pub struct MatchProofs {
+pub struct MatchProofs<P = Proof> {
     /// Party 0's proof of `VALID COMMITMENTS`
-    pub valid_commitments_0: Proof,
+    pub valid_commitments_0: P,
     /// Party 0's proof of `VALID REBLIND`
-    pub valid_reblind_0: Proof,
+    pub valid_reblind_0: P,
     /// Party 1's proof of `VALID COMMITMENTS`
-    pub valid_commitments_1: Proof,
+    pub valid_commitments_1: P,
     /// Party 1's proof of `VALID REBLIND`
-    pub valid_reblind_1: Proof,
+    pub valid_reblind_1: P,
     /// The proof of `VALID MATCH SETTLE`
-    pub valid_match_settle: Proof,
+    pub valid_match_settle: P,
+}
+
+impl From<MatchProofs> for MatchProofs<ExtendedProof> {
+    fn from(match_proofs: MatchProofs) -> Self {
+        Self {
+            valid_commitments_0: match_proofs.valid_commitments_0.into(),
+            valid_reblind_0: match_proofs.valid_reblind_0.into(),
+            valid_commitments_1: match_proofs.valid_commitments_1.into(),
+            valid_reblind_1: match_proofs.valid_reblind_1.into(),
+            valid_match_settle: match_proofs.valid_match_settle.into(),
+        }
+    }
 }
 
 /// A proof of a group of linked inputs between two Plonk proofs
@@ -176,8 +398,13 @@ pub struct MatchLinkingProofs {
 
 /// The public coin challenges used throughout the Plonk protocol, obtained via a Fiat-Shamir transformation.
 #[serde_as]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Challenges {
+    /// The lookup challenge, used in round 1 of the prover algorithm to merge lookup witnesses & tables.
+    ///
+    /// This is always sampled, but only used when verifying lookup arguments.
+    #[serde_as(as = "ScalarFieldDef")]
+    pub tau: ScalarField,
     /// The first permutation challenge, used in round 2 of the prover algorithm
     #[serde_as(as = "ScalarFieldDef")]
     pub beta: ScalarField,
@@ -354,6 +581,23 @@ pub struct ValidMatchSettleStatement {
     pub protocol_fee: ScalarField,
 }
 
+/// The `VALID_MATCH_SETTLE` statement verified by the darkpool, which extends
+/// the protocol statement with the base mint of the traded pair.
+///
+/// The base mint is a public input of the `VALID_MATCH_SETTLE` proof,
+/// so the mint used to look up the protocol fee applicable to the match is proven.
+/// It is kept out of [`ValidMatchSettleStatement`] so that the shape of the protocol
+/// statement, as constructed by the relayer, is unchanged.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct ValidMatchSettleWithMintStatement {
+    /// The `VALID_MATCH_SETTLE` statement
+    pub valid_match_settle: ValidMatchSettleStatement,
+    /// The mint (contract address) of the base token of the traded pair
+    #[serde_as(as = "AddressDef")]
+    pub base_mint: Address,
+}
+
 /// Represents the outputs produced by one of the parties in a match
 #[serde_as]
 #[derive(Serialize, Deserialize)]
@@ -470,7 +714,7 @@ pub struct ValidFeeRedemptionStatement {
 pub struct PublicInputs(#[serde_as(as = "Vec<ScalarFieldDef>")] pub Vec<ScalarField>);
 
 /// The set of public inputs for the `MatchProofs`
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct MatchPublicInputs {
     /// The public inputs to `PARTY 0 VALID COMMITMENTS`
     pub valid_commitments_0: PublicInputs,
@@ -511,6 +755,38 @@ pub struct MatchLinkingWirePolyComms {
     pub valid_match_settle: G1Affine,
 }
 
+/// A link between two of the Plonk proofs in a [`LinkedBundle`],
+/// attesting that the proofs share a group of linked inputs
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct ProofLink {
+    /// The index in the bundle of the proof whose first wiring polynomial
+    /// commitment is the first input to the link
+    pub source: usize,
+    /// The index in the bundle of the proof whose first wiring polynomial
+    /// commitment is the second input to the link
+    pub target: usize,
+    /// The verification key for the link
+    pub linking_vkey: LinkingVerificationKey,
+    /// The proof of linked inputs between the two proofs
+    pub linking_proof: LinkingProof,
+}
+
+/// A bundle of Plonk proofs and the links between them, forming a proof-linking graph
+/// in which the proofs are the nodes and the links are the edges.
+///
+/// All of the proofs & links in the bundle are verified together using a single pairing check.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LinkedBundle {
+    /// The verification keys for each of the Plonk proofs
+    pub vkeys: Vec<ExtendedVerificationKey>,
+    /// The Plonk proofs
+    pub proofs: Vec<ExtendedProof>,
+    /// The public inputs to each of the Plonk proofs
+    pub public_inputs: Vec<PublicInputs>,
+    /// The links between the Plonk proofs
+    pub links: Vec<ProofLink>,
+}
+
 /// The elements to be used in a KZG batch opening pairing check
 pub struct OpeningElems {
     /// The LHS G1 elements in the pairing check
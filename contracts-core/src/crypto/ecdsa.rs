@@ -53,10 +53,12 @@ pub fn pubkey_to_address<H: HashBackend>(pubkey: &PublicSigningKey) -> [u8; NUM_
 #[cfg(test)]
 mod tests {
     use contracts_common::constants::{HASH_OUTPUT_SIZE, NUM_BYTES_ADDRESS, NUM_BYTES_SIGNATURE};
-    use contracts_utils::crypto::{hash_and_sign_message, random_keypair, NativeHasher};
+    use contracts_utils::crypto::{hash_and_sign_message, random_keypair};
     use ethers::types::{RecoveryMessage, Signature};
     use rand::{thread_rng, RngCore};
 
+    use crate::native::NativeHasher;
+
     use super::{EcRecoverBackend, EcdsaError};
 
     struct TestEcRecoverBackend;
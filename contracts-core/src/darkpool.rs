@@ -0,0 +1,68 @@
+//! The checks performed by the darkpool on the inputs to its methods which do not depend
+//! on its state, i.e. on the Merkle root history, the nullifier set, etc.
+//!
+//! These are defined here so that they can be run identically both by the darkpool
+//! and natively, e.g. when checking a bundle off-chain before submitting it.
+
+use contracts_common::{
+    custom_serde::{statement_to_public_inputs, SerdeError},
+    types::{
+        MatchPayload, MatchPublicInputs, ScalarField, ValidCommitmentsStatement,
+        ValidMatchSettleStatement, ValidMatchSettleWithMintStatement, ValidReblindStatement,
+    },
+};
+
+/// Checks that the order settlement indices in the `VALID COMMITMENTS` statements of the given
+/// match payloads are consistent with those in the `VALID MATCH SETTLE` statement
+pub fn check_match_settle_indices(
+    party_0_match_payload: &MatchPayload,
+    party_1_match_payload: &MatchPayload,
+    valid_match_settle_statement: &ValidMatchSettleStatement,
+) -> bool {
+    let party0_same_indices = party_0_match_payload.valid_commitments_statement.indices
+        == valid_match_settle_statement.party0_indices;
+    let party1_same_indices = party_1_match_payload.valid_commitments_statement.indices
+        == valid_match_settle_statement.party1_indices;
+
+    party0_same_indices && party1_same_indices
+}
+
+/// Checks that the protocol fee used in the `VALID MATCH SETTLE` statement is the given fee,
+/// i.e. the fee configured in the darkpool for the statement's base mint
+pub fn check_match_settle_protocol_fee(
+    valid_match_settle_statement: &ValidMatchSettleStatement,
+    protocol_fee: ScalarField,
+) -> bool {
+    valid_match_settle_statement.protocol_fee == protocol_fee
+}
+
+/// Checks that a batch of matches has at least one match, and that the number of
+/// match payloads for each party is equal to the number of `VALID MATCH SETTLE` statements
+pub fn check_match_settle_batch_lengths(
+    party_0_match_payloads: &[MatchPayload],
+    party_1_match_payloads: &[MatchPayload],
+    valid_match_settle_statements: &[ValidMatchSettleWithMintStatement],
+) -> bool {
+    let num_matches = valid_match_settle_statements.len();
+    num_matches > 0
+        && party_0_match_payloads.len() == num_matches
+        && party_1_match_payloads.len() == num_matches
+}
+
+/// Serializes the statements used in verifying the settlement of a
+/// matched trade into scalars, and builds the [`MatchPublicInputs`] struct
+pub fn match_statements_to_public_inputs(
+    valid_commitments_0: &ValidCommitmentsStatement,
+    valid_commitments_1: &ValidCommitmentsStatement,
+    valid_reblind_0: &ValidReblindStatement,
+    valid_reblind_1: &ValidReblindStatement,
+    valid_match_settle: &ValidMatchSettleWithMintStatement,
+) -> Result<MatchPublicInputs, SerdeError> {
+    Ok(MatchPublicInputs {
+        valid_commitments_0: statement_to_public_inputs(valid_commitments_0)?,
+        valid_commitments_1: statement_to_public_inputs(valid_commitments_1)?,
+        valid_reblind_0: statement_to_public_inputs(valid_reblind_0)?,
+        valid_reblind_1: statement_to_public_inputs(valid_reblind_1)?,
+        valid_match_settle: statement_to_public_inputs(valid_match_settle)?,
+    })
+}
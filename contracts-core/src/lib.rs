@@ -9,5 +9,9 @@
 extern crate alloc;
 
 pub mod crypto;
+pub mod darkpool;
+pub mod merkle;
+#[cfg(any(test, feature = "native"))]
+pub mod native;
 pub mod transcript;
 pub mod verifier;
@@ -0,0 +1,622 @@
+//! An incremental Merkle tree, used to accumulate all of the wallet commitments in the dark pool,
+//! defined agnostically of the storage in which its state is persisted.
+//!
+//! Only the path of siblings for the next leaf to be inserted is stored, so that each insertion
+//! takes a number of hashes & storage accesses linear in the height of the tree. This allows the
+//! same insertion logic to run both in the Stylus Merkle contract and natively, e.g. in an indexer.
+//!
+//! Heights are counted from the root, i.e. the root is at height 0 and the leaves are at the
+//! height of the tree.
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use contracts_common::{constants::EMPTY_LEAF_VALUE, types::ScalarField};
+use core::{convert::Infallible, marker::PhantomData};
+
+use crate::crypto::poseidon::compute_poseidon_hash;
+
+/// A two-to-one hash, used to compute the value of an internal node from its children
+pub trait TwoToOneHasher {
+    /// Hashes the values of a left & right child into the value of their parent
+    fn hash(left: ScalarField, right: ScalarField) -> ScalarField;
+}
+
+/// The Poseidon2 two-to-one hash used in the dark pool's Merkle tree
+pub struct PoseidonTwoToOneHasher;
+impl TwoToOneHasher for PoseidonTwoToOneHasher {
+    fn hash(left: ScalarField, right: ScalarField) -> ScalarField {
+        compute_poseidon_hash(&[left, right])
+    }
+}
+
+/// The storage in which the state of an [`IncrementalMerkleTree`] is persisted.
+///
+/// Leaves are inserted into the active tree. Once the active tree is full, the next
+/// insertion starts a new, empty active tree.
+pub trait MerkleStorage {
+    /// The error that occurs when reading from the storage
+    type Error;
+
+    /// Returns the next index at which to insert a leaf in the active tree
+    fn next_index(&self) -> u128;
+
+    /// Sets the next index at which to insert a leaf in the active tree
+    fn set_next_index(&mut self, next_index: u128);
+
+    /// Returns the sibling at the given height on the path of the next leaf to be inserted
+    fn sibling(&self, height: usize) -> Result<ScalarField, Self::Error>;
+
+    /// Sets the sibling at the given height on the path of the next leaf to be inserted
+    fn set_sibling(&mut self, height: usize, value: ScalarField);
+
+    /// Stores a new root of the active tree.
+    ///
+    /// The next index is updated to account for the leaves inserted
+    /// in computing the root before this is called
+    fn store_root(&mut self, root: ScalarField);
+
+    /// Starts a new active tree once the current one is full.
+    ///
+    /// The active tree is reset to an empty tree after this is called
+    fn start_new_tree(&mut self);
+
+    /// Called with each sibling on the opening path of an inserted leaf, i.e. the value of
+    /// the node at the given height & index at the time of the insertion, from the root down
+    fn on_opening_node(&mut self, _height: usize, _index: u128, _value: ScalarField) {}
+
+    /// Called once a leaf is inserted, after each of the siblings on its opening path
+    fn on_insertion(&mut self, _index: u128, _value: ScalarField) {}
+}
+
+/// An incremental Merkle tree, persisting its state in the given storage
+/// and computing its internal nodes with the given two-to-one hash
+pub struct IncrementalMerkleTree<'a, S, H> {
+    /// The storage in which the state of the tree is persisted
+    storage: &'a mut S,
+    /// The values of a node at each height of an empty tree, going from root to leaf,
+    /// exclusive of the root. The height of the tree is the number of values.
+    zeros: &'a [ScalarField],
+    #[doc(hidden)]
+    _phantom: PhantomData<H>,
+}
+
+impl<'a, S, H> IncrementalMerkleTree<'a, S, H>
+where
+    S: MerkleStorage,
+    H: TwoToOneHasher,
+{
+    /// Creates a tree over the given storage, with the given values
+    /// of a node at each height of an empty tree
+    pub fn new(storage: &'a mut S, zeros: &'a [ScalarField]) -> Self {
+        IncrementalMerkleTree {
+            storage,
+            zeros,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the height of the tree, exclusive of the root
+    pub fn height(&self) -> usize {
+        self.zeros.len()
+    }
+
+    /// Returns the number of leaves in a full tree
+    pub fn capacity(&self) -> u128 {
+        2_u128.pow(self.height() as u32)
+    }
+
+    /// Resets the active tree to an empty tree, storing its root
+    pub fn reset(&mut self) {
+        self.storage.set_next_index(0);
+        for height in 1..=self.height() {
+            self.storage.set_sibling(height, self.zero(height));
+        }
+
+        let root = H::hash(self.zeros[0], self.zeros[0]);
+        self.storage.store_root(root);
+    }
+
+    /// Inserts a leaf into the tree
+    pub fn insert(&mut self, value: ScalarField) -> Result<(), S::Error> {
+        let insert_index = self.next_insert_index();
+        self.insert_recursive(
+            value,
+            self.height(),
+            insert_index,
+            true, /* subtree_filled */
+        )?;
+        self.storage.on_insertion(insert_index, value);
+
+        Ok(())
+    }
+
+    /// Inserts a batch of leaves into consecutive indices of the tree,
+    /// splitting the batch across new trees as the active tree fills.
+    ///
+    /// This makes the same calls to the storage's `on_opening_node` & `on_insertion` hooks
+    /// as inserting the leaves one at a time, but computes each internal node shared
+    /// between the leaves' paths only once.
+    pub fn insert_batch(&mut self, values: &[ScalarField]) -> Result<(), S::Error> {
+        let mut remaining_values = values;
+        while !remaining_values.is_empty() {
+            let start_index = self.next_insert_index();
+            let capacity = (self.capacity() - start_index) as usize;
+            let (tree_values, rest) =
+                remaining_values.split_at(capacity.min(remaining_values.len()));
+
+            self.insert_batch_into_active_tree(start_index, tree_values)?;
+            remaining_values = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the value of an empty node at the given height
+    fn zero(&self, height: usize) -> ScalarField {
+        self.zeros[height - 1]
+    }
+
+    /// Returns the index at which to insert the next leaf,
+    /// first starting a new tree if the active tree is full
+    fn next_insert_index(&mut self) -> u128 {
+        let insert_index = self.storage.next_index();
+        if insert_index < self.capacity() {
+            return insert_index;
+        }
+
+        self.storage.start_new_tree();
+        self.reset();
+        0
+    }
+
+    /// Recursive helper for inserting a value into the tree,
+    /// updating the sibling pathway along the way
+    fn insert_recursive(
+        &mut self,
+        value: ScalarField,
+        height: usize,
+        insert_index: u128,
+        subtree_filled: bool,
+    ) -> Result<(), S::Error> {
+        // Base case (root)
+        if height == 0 {
+            let next_index = self.storage.next_index() + 1;
+            self.storage.set_next_index(next_index);
+            self.storage.store_root(value);
+            return Ok(());
+        }
+
+        // Fetch the least significant bit of the insertion index, this tells us
+        // whether (at the current height), we are hashing into the left or right
+        // hand value
+        let next_index = insert_index >> 1;
+        let is_left = (insert_index & 1) == 0;
+
+        // If the subtree rooted at the current node is filled, update the sibling value
+        // for the next insertion. There are two cases here:
+        //      1. The current insertion index is a left child; in this case the updated
+        //         sibling value is the newly computed node value.
+        //      2. The current insertion index is a right child; in this case, the subtree
+        //         of the parent is filled as well, meaning we should set the updated sibling
+        //         to the zero value at this height; representing an empty child of the parent's
+        //         sibling
+        let current_sibling_value = self.storage.sibling(height)?;
+        if subtree_filled {
+            let new_sibling_value = if is_left { value } else { self.zero(height) };
+            self.storage.set_sibling(height, new_sibling_value);
+        }
+
+        // Mux between hashing the current value as the left or right sibling depending on
+        // the index being inserted into
+        let mut new_subtree_filled = false;
+        let next_value = if is_left {
+            H::hash(value, current_sibling_value)
+        } else {
+            new_subtree_filled = subtree_filled;
+            H::hash(current_sibling_value, value)
+        };
+
+        self.insert_recursive(next_value, height - 1, next_index, new_subtree_filled)?;
+
+        // Emit the sibling coordinates and value
+        let sibling_idx = if is_left {
+            insert_index + 1
+        } else {
+            insert_index - 1
+        };
+        self.storage
+            .on_opening_node(height, sibling_idx, current_sibling_value);
+
+        Ok(())
+    }
+
+    /// A helper to insert a non-empty batch of values into consecutive indices of the
+    /// active tree, starting at the given index. The batch must fit in the active tree.
+    ///
+    /// The tree is computed level by level, from the leaves up to the root, hashing each
+    /// internal node covering the batch once. The sibling path is read once up front,
+    /// and each of its slots is written at most once, if its value changed.
+    ///
+    /// Only the root after the final insertion is stored,
+    /// as the intermediate roots are never computed.
+    fn insert_batch_into_active_tree(
+        &mut self,
+        start_index: u128,
+        values: &[ScalarField],
+    ) -> Result<(), S::Error> {
+        let tree_height = self.height();
+        let zeros = self.zeros;
+        let end_index = start_index + values.len() as u128;
+        let sibling_path = (1..=tree_height)
+            .map(|height| self.storage.sibling(height))
+            .collect::<Result<Vec<ScalarField>, S::Error>>()?;
+
+        // `levels[height]` holds the values of the nodes at the given height which cover
+        // the batch, i.e. those from index `start_index >> (tree_height - height)` onwards
+        let mut levels = vec![Vec::new(); tree_height + 1];
+        levels[tree_height] = values.to_vec();
+        for height in (0..tree_height).rev() {
+            let shift = tree_height - height;
+            let child_start = start_index >> (shift - 1);
+            let children = &levels[height + 1];
+            let child_value = |index| {
+                batch_node_value(
+                    children,
+                    child_start,
+                    index,
+                    sibling_path[height],
+                    zeros[height],
+                )
+            };
+
+            let level = ((start_index >> shift)..=((end_index - 1) >> shift))
+                .map(|index| H::hash(child_value(2 * index), child_value(2 * index + 1)))
+                .collect();
+            levels[height] = level;
+        }
+
+        // Make the same calls as sequential insertions. The sibling of a node at the time
+        // of its insertion is either a filled subtree to its left, whose value is final,
+        // or an empty subtree to its right
+        for (insert_index, value) in (start_index..end_index).zip(values) {
+            for height in 1..=tree_height {
+                let shift = tree_height - height;
+                let node_index = insert_index >> shift;
+                let is_left = (node_index & 1) == 0;
+
+                let (sibling_idx, sibling_value) = if is_left {
+                    (node_index + 1, zeros[height - 1])
+                } else {
+                    let sibling_value = batch_node_value(
+                        &levels[height],
+                        start_index >> shift,
+                        node_index - 1,
+                        sibling_path[height - 1],
+                        zeros[height - 1],
+                    );
+                    (node_index - 1, sibling_value)
+                };
+
+                self.storage
+                    .on_opening_node(height, sibling_idx, sibling_value);
+            }
+
+            self.storage.on_insertion(insert_index, *value);
+        }
+
+        // The sibling path for the next insertion holds the left sibling of each of the
+        // next leaf's ancestors which is a right child, and the zero value otherwise
+        for height in 1..=tree_height {
+            let shift = tree_height - height;
+            let node_index = end_index >> shift;
+            let new_sibling_value = if (node_index & 1) == 1 {
+                batch_node_value(
+                    &levels[height],
+                    start_index >> shift,
+                    node_index - 1,
+                    sibling_path[height - 1],
+                    zeros[height - 1],
+                )
+            } else {
+                zeros[height - 1]
+            };
+
+            if new_sibling_value != sibling_path[height - 1] {
+                self.storage.set_sibling(height, new_sibling_value);
+            }
+        }
+
+        self.storage.set_next_index(end_index);
+        self.storage.store_root(levels[0][0]);
+
+        Ok(())
+    }
+}
+
+/// Returns the value of the node at the given index, given the values of the nodes
+/// at its height which cover a batch insertion, starting at `start_index`.
+///
+/// Nodes to the left of the batch are filled subtrees, only the rightmost of which may be
+/// requested, i.e. the given left sibling. Nodes to the right of the batch are empty.
+fn batch_node_value(
+    level: &[ScalarField],
+    start_index: u128,
+    index: u128,
+    left_sibling: ScalarField,
+    zero: ScalarField,
+) -> ScalarField {
+    if index < start_index {
+        return left_sibling;
+    }
+
+    level
+        .get((index - start_index) as usize)
+        .copied()
+        .unwrap_or(zero)
+}
+
+/// Computes the values of a node at each height of an empty tree of the given height,
+/// going from root to leaf, exclusive of the root
+pub fn compute_zeros<H: TwoToOneHasher>(height: usize) -> Vec<ScalarField> {
+    let mut zeros = vec![EMPTY_LEAF_VALUE; height];
+    for i in (0..height.saturating_sub(1)).rev() {
+        zeros[i] = H::hash(zeros[i + 1], zeros[i + 1]);
+    }
+
+    zeros
+}
+
+/// A [`MerkleStorage`] holding the state of the tree in memory,
+/// e.g. for tracking the tree off-chain
+#[derive(Default)]
+pub struct MemoryMerkleStorage {
+    /// The next index at which to insert a leaf in the active tree
+    pub next_index: u128,
+    /// The current path of siblings for the next leaf to be inserted, keyed by height
+    pub sibling_path: BTreeMap<usize, ScalarField>,
+    /// The identifier of the active tree, incremented each time a new tree is started
+    pub tree_id: u64,
+    /// The roots stored, in order, each paired with the identifier of the tree to which it belongs
+    pub root_history: Vec<(u64, ScalarField)>,
+}
+
+impl MemoryMerkleStorage {
+    /// Returns the current root of the active tree, if the tree has been initialized
+    pub fn root(&self) -> Option<ScalarField> {
+        self.root_history.last().map(|(_, root)| *root)
+    }
+}
+
+impl MerkleStorage for MemoryMerkleStorage {
+    type Error = Infallible;
+
+    fn next_index(&self) -> u128 {
+        self.next_index
+    }
+
+    fn set_next_index(&mut self, next_index: u128) {
+        self.next_index = next_index;
+    }
+
+    fn sibling(&self, height: usize) -> Result<ScalarField, Self::Error> {
+        Ok(self.sibling_path.get(&height).copied().unwrap_or_default())
+    }
+
+    fn set_sibling(&mut self, height: usize, value: ScalarField) {
+        self.sibling_path.insert(height, value);
+    }
+
+    fn store_root(&mut self, root: ScalarField) {
+        self.root_history.push((self.tree_id, root));
+    }
+
+    fn start_new_tree(&mut self) {
+        self.tree_id += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+    use ark_std::UniformRand;
+    use contracts_common::types::ScalarField;
+    use contracts_utils::merkle::new_ark_merkle_tree;
+    use rand::{thread_rng, Rng};
+
+    use super::{
+        compute_zeros, IncrementalMerkleTree, MemoryMerkleStorage, MerkleStorage,
+        PoseidonTwoToOneHasher,
+    };
+
+    /// The maximum height of the trees used in testing
+    const MAX_TEST_HEIGHT: usize = 5;
+    /// The number of random insertion sequences to test
+    const NUM_TRIALS: usize = 20;
+
+    /// A call made to the storage hooks during insertion
+    #[derive(Debug, PartialEq)]
+    enum Hook {
+        /// A call to `on_opening_node`
+        OpeningNode(usize, u128, ScalarField),
+        /// A call to `on_insertion`
+        Insertion(u128, ScalarField),
+    }
+
+    /// An in-memory storage which records the calls made to its hooks
+    #[derive(Default)]
+    struct RecordingStorage {
+        /// The underlying storage
+        storage: MemoryMerkleStorage,
+        /// The calls made to the hooks, in order, paired with the active tree
+        hooks: Vec<(u64, Hook)>,
+    }
+
+    impl MerkleStorage for RecordingStorage {
+        type Error = <MemoryMerkleStorage as MerkleStorage>::Error;
+
+        fn next_index(&self) -> u128 {
+            self.storage.next_index()
+        }
+
+        fn set_next_index(&mut self, next_index: u128) {
+            self.storage.set_next_index(next_index)
+        }
+
+        fn sibling(&self, height: usize) -> Result<ScalarField, Self::Error> {
+            self.storage.sibling(height)
+        }
+
+        fn set_sibling(&mut self, height: usize, value: ScalarField) {
+            self.storage.set_sibling(height, value)
+        }
+
+        fn store_root(&mut self, root: ScalarField) {
+            self.storage.store_root(root)
+        }
+
+        fn start_new_tree(&mut self) {
+            self.storage.start_new_tree()
+        }
+
+        fn on_opening_node(&mut self, height: usize, index: u128, value: ScalarField) {
+            self.hooks.push((
+                self.storage.tree_id,
+                Hook::OpeningNode(height, index, value),
+            ));
+        }
+
+        fn on_insertion(&mut self, index: u128, value: ScalarField) {
+            self.hooks
+                .push((self.storage.tree_id, Hook::Insertion(index, value)));
+        }
+    }
+
+    /// Generates random leaves, and random sizes of the batches in which to insert them
+    fn random_batches(num_leaves: usize, max_batch_size: usize) -> Vec<Vec<ScalarField>> {
+        let mut rng = thread_rng();
+        let mut batches = Vec::new();
+        let mut remaining = num_leaves;
+        while remaining > 0 {
+            let batch_size = rng.gen_range(1..=remaining.min(max_batch_size));
+            batches.push(
+                (0..batch_size)
+                    .map(|_| ScalarField::rand(&mut rng))
+                    .collect(),
+            );
+            remaining -= batch_size;
+        }
+
+        batches
+    }
+
+    #[test]
+    fn test_empty_root() {
+        for height in 1..=MAX_TEST_HEIGHT {
+            let zeros = compute_zeros::<PoseidonTwoToOneHasher>(height);
+            let mut storage = MemoryMerkleStorage::default();
+            IncrementalMerkleTree::<_, PoseidonTwoToOneHasher>::new(&mut storage, &zeros).reset();
+
+            assert_eq!(
+                storage.root(),
+                Some(new_ark_merkle_tree(height).root()),
+                "Empty root incorrect at height {height}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_insertions() {
+        let mut rng = thread_rng();
+        for _ in 0..NUM_TRIALS {
+            let height = rng.gen_range(1..=MAX_TEST_HEIGHT);
+            let capacity = 2_usize.pow(height as u32);
+            let num_leaves = rng.gen_range(1..=3 * capacity);
+
+            let zeros = compute_zeros::<PoseidonTwoToOneHasher>(height);
+            let mut storage = MemoryMerkleStorage::default();
+            let mut ark_merkles = vec![new_ark_merkle_tree(height)];
+            IncrementalMerkleTree::<_, PoseidonTwoToOneHasher>::new(&mut storage, &zeros).reset();
+
+            // Insert the leaves either one at a time or as a batch, at random
+            let mut next_index = 0;
+            for batch in random_batches(num_leaves, capacity + 1) {
+                let mut tree =
+                    IncrementalMerkleTree::<_, PoseidonTwoToOneHasher>::new(&mut storage, &zeros);
+                if batch.len() == 1 || rng.gen_bool(0.5) {
+                    for leaf in &batch {
+                        tree.insert(*leaf).unwrap();
+                    }
+                } else {
+                    tree.insert_batch(&batch).unwrap();
+                }
+
+                for leaf in &batch {
+                    let tree_id = next_index / capacity;
+                    if tree_id == ark_merkles.len() {
+                        ark_merkles.push(new_ark_merkle_tree(height));
+                    }
+
+                    ark_merkles[tree_id]
+                        .update(next_index % capacity, leaf)
+                        .unwrap();
+                    next_index += 1;
+                }
+
+                // The active tree is the one containing the last inserted leaf
+                let tree_id = (next_index - 1) / capacity;
+                assert_eq!(storage.tree_id, tree_id as u64, "Incorrect active tree");
+                assert_eq!(
+                    storage.root(),
+                    Some(ark_merkles[tree_id].root()),
+                    "Root incorrect at height {height} after {next_index} insertions"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_matches_sequential() {
+        let mut rng = thread_rng();
+        for _ in 0..NUM_TRIALS {
+            let height = rng.gen_range(1..=MAX_TEST_HEIGHT);
+            let capacity = 2_usize.pow(height as u32);
+            let batches = random_batches(rng.gen_range(1..=3 * capacity), 2 * capacity);
+            let zeros = compute_zeros::<PoseidonTwoToOneHasher>(height);
+
+            let mut sequential_storage = RecordingStorage::default();
+            let mut tree = IncrementalMerkleTree::<_, PoseidonTwoToOneHasher>::new(
+                &mut sequential_storage,
+                &zeros,
+            );
+            tree.reset();
+            for leaf in batches.iter().flatten() {
+                tree.insert(*leaf).unwrap();
+            }
+
+            let mut batch_storage = RecordingStorage::default();
+            let mut tree =
+                IncrementalMerkleTree::<_, PoseidonTwoToOneHasher>::new(&mut batch_storage, &zeros);
+            tree.reset();
+            for batch in &batches {
+                tree.insert_batch(batch).unwrap();
+            }
+
+            assert_eq!(
+                sequential_storage.hooks, batch_storage.hooks,
+                "Batch insertion hooks differ from sequential insertion hooks"
+            );
+            assert_eq!(
+                sequential_storage.storage.sibling_path, batch_storage.storage.sibling_path,
+                "Batch insertion sibling path differs from sequential insertion sibling path"
+            );
+            assert_eq!(
+                sequential_storage.storage.next_index, batch_storage.storage.next_index,
+                "Batch insertion next index differs from sequential insertion next index"
+            );
+            assert_eq!(
+                sequential_storage.storage.root(),
+                batch_storage.storage.root(),
+                "Batch insertion root differs from sequential insertion root"
+            );
+        }
+    }
+}
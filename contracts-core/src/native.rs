@@ -0,0 +1,49 @@
+//! Backends for the verifier & transcript which run natively using arkworks, i.e. without
+//! the Stylus VM's EC precompiles or its accelerated Keccak implementation.
+//!
+//! These allow proofs to be verified off-chain exactly as the contracts would verify them,
+//! e.g. so that relayers can check a bundle locally before submitting it.
+
+use alloy_primitives::keccak256;
+use ark_bn254::Bn254;
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::One;
+use contracts_common::{
+    backends::{G1ArithmeticBackend, G1ArithmeticError, HashBackend},
+    constants::HASH_OUTPUT_SIZE,
+    types::{G1Affine, G2Affine, ScalarField},
+};
+
+/// A hashing backend that runs natively, i.e.
+/// without using a Stylus VM-accelerated Keccak implementation
+pub struct NativeHasher;
+
+impl HashBackend for NativeHasher {
+    fn hash(input: &[u8]) -> [u8; HASH_OUTPUT_SIZE] {
+        keccak256(input).0
+    }
+}
+
+/// A G1 arithmetic backend that runs natively using arkworks, i.e.
+/// without using the Stylus VM's EC precompiles
+pub struct NativeG1ArithmeticBackend;
+
+impl G1ArithmeticBackend for NativeG1ArithmeticBackend {
+    fn ec_add(a: G1Affine, b: G1Affine) -> Result<G1Affine, G1ArithmeticError> {
+        Ok((a + b).into_affine())
+    }
+
+    fn ec_scalar_mul(a: ScalarField, b: G1Affine) -> Result<G1Affine, G1ArithmeticError> {
+        Ok((b * a).into_affine())
+    }
+
+    fn ec_pairing_check(
+        a_1: G1Affine,
+        b_1: G2Affine,
+        a_2: G1Affine,
+        b_2: G2Affine,
+    ) -> Result<bool, G1ArithmeticError> {
+        Ok(Bn254::multi_pairing([a_1, a_2], [b_1, b_2]).0
+            == <Bn254 as Pairing>::TargetField::one())
+    }
+}
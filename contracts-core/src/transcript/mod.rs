@@ -1,16 +1,160 @@
 //! A simple transcript used for computing challenge values via the Fiat-Shamir transformation.
+//!
+//! The order in which the elements of a proof are absorbed is fixed by the [`FiatShamirTranscript`]
+//! trait, while its implementations define how elements are absorbed & challenges are squeezed.
+
+pub mod poseidon;
 
 use alloc::vec::Vec;
 use ark_ff::{BigInt, BigInteger, PrimeField};
 use contracts_common::{
     backends::HashBackend,
-    constants::{HASH_SAMPLE_BYTES, NUM_BYTES_FELT, SPLIT_INDEX, TRANSCRIPT_STATE_SIZE},
+    constants::{
+        HASH_SAMPLE_BYTES, NUM_BYTES_FELT, NUM_LOOKUP_ZETA_EVALS, NUM_LOOKUP_ZETA_OMEGA_EVALS,
+        SPLIT_INDEX, TRANSCRIPT_STATE_SIZE,
+    },
     custom_serde::{bigint_from_le_bytes, BytesSerializable, SerdeError, TranscriptG1},
-    types::{Challenges, G1Affine, Proof, PublicInputs, ScalarField, VerificationKey},
+    types::{
+        Challenges, ExtendedProof, ExtendedVerificationKey, G1Affine, LookupEvaluations,
+        PublicInputs, ScalarField,
+    },
 };
 use core::marker::PhantomData;
 
-/// The Fiat-Shamir transcript used in the Plonk protocol.
+/// A Fiat-Shamir transcript from which the challenges of the Plonk & proof linking protocols are computed.
+///
+/// The provided methods absorb the elements of a proof in the same order as the Jellyfish prover.
+pub trait FiatShamirTranscript: Sized {
+    /// Creates a new, empty transcript
+    fn new() -> Self;
+
+    /// Absorbs a 32-bit integer, i.e. the bit size of the scalar field
+    fn append_u32(&mut self, value: u32);
+
+    /// Absorbs a 64-bit integer, e.g. the size of the evaluation domain
+    fn append_u64(&mut self, value: u64);
+
+    /// Absorbs a slice of scalars
+    fn append_scalars(&mut self, scalars: &[ScalarField]);
+
+    /// Absorbs a slice of G1 points
+    fn append_g1s(&mut self, points: &[G1Affine]);
+
+    /// Computes a challenge and updates the transcript state
+    fn get_and_append_challenge(&mut self) -> Result<ScalarField, SerdeError>;
+
+    /// Computes all the challenges used in the Plonk protocol,
+    /// given a verification key, a proof, and a set of public inputs.
+    ///
+    /// If the verification key expects a lookup argument, the elements of the lookup wire
+    /// are absorbed after their TurboPlonk counterparts, and the elements of the lookup
+    /// argument are absorbed in the same rounds as in the Jellyfish UltraPlonk prover.
+    fn compute_plonk_challenges(
+        &mut self,
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        public_inputs: &PublicInputs,
+    ) -> Result<Challenges, SerdeError> {
+        // Absorb verification key & public inputs
+        self.append_u32(ScalarField::MODULUS_BIT_SIZE);
+        self.append_u64(vkey.n);
+        self.append_u64(vkey.l);
+        // For equivalency with Jellyfish, which expects as many coset constants as there are wire types,
+        // we inject an identity constant, which generates the first coset
+        self.append_scalars(&vkey.k);
+        if let Some(lookup_vkey) = &vkey.lookup_vkey {
+            self.append_scalars(&[lookup_vkey.lookup_k]);
+        }
+        self.append_g1s(&vkey.q_comms);
+        if let Some(lookup_vkey) = &vkey.lookup_vkey {
+            self.append_g1s(&[lookup_vkey.q_lookup_comm]);
+        }
+        self.append_g1s(&vkey.sigma_comms);
+        if let Some(lookup_vkey) = &vkey.lookup_vkey {
+            self.append_g1s(&[lookup_vkey.lookup_sigma_comm]);
+        }
+        self.append_scalars(&public_inputs.0);
+
+        // Prover round 1: absorb wire polynomial commitments, squeeze tau challenge
+        self.append_g1s(&proof.wire_comms);
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_g1s(&[lookup_proof.lookup_wire_comm]);
+        }
+        // For TurboPlonk proofs, we squeeze `tau` only for consistency with the Jellyfish implementation
+        let tau = self.get_and_append_challenge()?;
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_g1s(&lookup_proof.h_poly_comms);
+        }
+
+        // Prover round 2: squeeze beta & gamma challenges, absorb grand product polynomial commitments
+        let beta = self.get_and_append_challenge()?;
+        let gamma = self.get_and_append_challenge()?;
+        self.append_g1s(&[proof.z_comm]);
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_g1s(&[lookup_proof.prod_lookup_poly_comm]);
+        }
+
+        // Prover round 3: squeeze alpha challenge, absorb split quotient polynomial commitments
+        let alpha = self.get_and_append_challenge()?;
+        self.append_g1s(&proof.quotient_comms);
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_g1s(&[lookup_proof.lookup_quotient_comm]);
+        }
+
+        // Prover round 4: squeeze zeta challenge, absorb wire, permutation, and grand product polynomial evaluations
+        let zeta = self.get_and_append_challenge()?;
+        self.append_scalars(&proof.wire_evals);
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_scalars(&[lookup_proof.lookup_wire_eval]);
+        }
+        self.append_scalars(&proof.sigma_evals);
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_scalars(&[lookup_proof.last_sigma_eval]);
+        }
+        self.append_scalars(&[proof.z_bar]);
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            self.append_scalars(&lookup_zeta_evals(&lookup_proof.lookup_evals));
+            self.append_scalars(&lookup_zeta_omega_evals(&lookup_proof.lookup_evals));
+        }
+
+        // Prover round 5: squeeze v challenge, absorb opening proofs
+        let v = self.get_and_append_challenge()?;
+        self.append_g1s(&[proof.w_zeta]);
+        self.append_g1s(&[proof.w_zeta_omega]);
+
+        // Squeeze u challenge
+        let u = self.get_and_append_challenge()?;
+
+        Ok(Challenges {
+            tau,
+            beta,
+            gamma,
+            alpha,
+            zeta,
+            v,
+            u,
+        })
+    }
+
+    /// Compute the eta challenge used in the proof linking protocol,
+    /// given the commitments to the linked wiring polynomials and the
+    /// linking quotient polynomial.
+    fn compute_linking_proof_challenge(
+        &mut self,
+        wire_poly_comm_1: G1Affine,
+        wire_poly_comm_2: G1Affine,
+        linking_quotient_poly_comm: G1Affine,
+    ) -> Result<ScalarField, SerdeError> {
+        self.append_g1s(&[wire_poly_comm_1]);
+        self.append_g1s(&[wire_poly_comm_2]);
+        self.append_g1s(&[linking_quotient_poly_comm]);
+
+        self.get_and_append_challenge()
+    }
+}
+
+/// The Keccak-based Fiat-Shamir transcript used in the Plonk protocol,
+/// byte-for-byte compatible with the Jellyfish `SolidityTranscript`.
 ///
 /// Defined generically over the hashing implementation.
 pub struct Transcript<H: HashBackend> {
@@ -23,9 +167,15 @@ pub struct Transcript<H: HashBackend> {
 }
 
 impl<H: HashBackend> Transcript<H> {
+    /// Appends a message to the transcript
+    pub fn append_message(&mut self, message: &[u8]) {
+        self.transcript.extend_from_slice(message);
+    }
+}
+
+impl<H: HashBackend> FiatShamirTranscript for Transcript<H> {
     /// Creates a new transcript with a zeroed-out hash state
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
+    fn new() -> Self {
         Transcript {
             transcript: Vec::new(),
             state: [0u8; TRANSCRIPT_STATE_SIZE],
@@ -33,13 +183,23 @@ impl<H: HashBackend> Transcript<H> {
         }
     }
 
-    /// Appends a message to the transcript
-    pub fn append_message(&mut self, message: &[u8]) {
-        self.transcript.extend_from_slice(message);
+    fn append_u32(&mut self, value: u32) {
+        self.append_message(&value.to_le_bytes());
     }
 
-    /// Computes a challenge and updates the transcript state
-    pub fn get_and_append_challenge(&mut self) -> Result<ScalarField, SerdeError> {
+    fn append_u64(&mut self, value: u64) {
+        self.append_message(&value.to_le_bytes());
+    }
+
+    fn append_scalars(&mut self, scalars: &[ScalarField]) {
+        self.append_message(&serialize_scalars_for_transcript(scalars));
+    }
+
+    fn append_g1s(&mut self, points: &[G1Affine]) {
+        self.append_message(&serialize_g1s_for_transcript(points));
+    }
+
+    fn get_and_append_challenge(&mut self) -> Result<ScalarField, SerdeError> {
         let input0 = [self.state.as_ref(), self.transcript.as_ref(), &[0u8]].concat();
         let input1 = [self.state.as_ref(), self.transcript.as_ref(), &[1u8]].concat();
 
@@ -76,80 +236,37 @@ impl<H: HashBackend> Transcript<H> {
         // Finally, we add the two scalars together. Again, reduction is done for us by using modular addition.
         Ok(res + rem_scalar)
     }
+}
 
-    /// Computes all the challenges used in the Plonk protocol,
-    /// given a verification key, a proof, and a set of public inputs.
-    pub fn compute_plonk_challenges(
-        &mut self,
-        vkey: &VerificationKey,
-        proof: &Proof,
-        public_inputs: &PublicInputs,
-    ) -> Result<Challenges, SerdeError> {
-        // Absorb verification key & public inputs
-        self.append_message(&ScalarField::MODULUS_BIT_SIZE.to_le_bytes());
-        self.append_message(&vkey.n.to_le_bytes());
-        self.append_message(&vkey.l.to_le_bytes());
-        // For equivalency with Jellyfish, which expects as many coset constants as there are wire types,
-        // we inject an identity constant, which generates the first coset
-        self.append_message(&serialize_scalars_for_transcript(&vkey.k));
-        self.append_message(&serialize_g1s_for_transcript(&vkey.q_comms));
-        self.append_message(&serialize_g1s_for_transcript(&vkey.sigma_comms));
-        self.append_message(&serialize_scalars_for_transcript(&public_inputs.0));
-
-        // Prover round 1: absorb wire polynomial commitments
-        self.append_message(&serialize_g1s_for_transcript(&proof.wire_comms));
-        // Here, for consistency with the Jellyfish implementation, we squeeze an unused challenge
-        // `tau`, which would be used for Plookup
-        self.get_and_append_challenge()?;
-
-        // Prover round 2: squeeze beta & gamma challenges, absorb grand product polynomial commitment
-        let beta = self.get_and_append_challenge()?;
-        let gamma = self.get_and_append_challenge()?;
-        self.append_message(&serialize_g1s_for_transcript(&[proof.z_comm]));
-
-        // Prover round 3: squeeze alpha challenge, absorb split quotient polynomial commitments
-        let alpha = self.get_and_append_challenge()?;
-        self.append_message(&serialize_g1s_for_transcript(&proof.quotient_comms));
-
-        // Prover round 4: squeeze zeta challenge, absorb wire, permutation, and grand product polynomial evaluations
-        let zeta = self.get_and_append_challenge()?;
-        self.append_message(&serialize_scalars_for_transcript(&proof.wire_evals));
-        self.append_message(&serialize_scalars_for_transcript(&proof.sigma_evals));
-        self.append_message(&serialize_scalars_for_transcript(&[proof.z_bar]));
-
-        // Prover round 5: squeeze v challenge, absorb opening proofs
-        let v = self.get_and_append_challenge()?;
-        self.append_message(&serialize_g1s_for_transcript(&[proof.w_zeta]));
-        self.append_message(&serialize_g1s_for_transcript(&[proof.w_zeta_omega]));
-
-        // Squeeze u challenge
-        let u = self.get_and_append_challenge()?;
-
-        Ok(Challenges {
-            beta,
-            gamma,
-            alpha,
-            zeta,
-            v,
-            u,
-        })
-    }
-
-    /// Compute the eta challenge used in the proof linking protocol,
-    /// given the commitments to the linked wiring polynomials and the
-    /// linking quotient polynomial.
-    pub fn compute_linking_proof_challenge(
-        &mut self,
-        wire_poly_comm_1: G1Affine,
-        wire_poly_comm_2: G1Affine,
-        linking_quotient_poly_comm: G1Affine,
-    ) -> Result<ScalarField, SerdeError> {
-        self.append_message(&serialize_g1s_for_transcript(&[wire_poly_comm_1]));
-        self.append_message(&serialize_g1s_for_transcript(&[wire_poly_comm_2]));
-        self.append_message(&serialize_g1s_for_transcript(&[linking_quotient_poly_comm]));
+/// Returns the evaluations of the lookup argument's polynomials at the challenge point `zeta`,
+/// in the order in which they are absorbed into the transcript & opened by the prover
+pub fn lookup_zeta_evals(lookup_evals: &LookupEvaluations) -> [ScalarField; NUM_LOOKUP_ZETA_EVALS] {
+    [
+        lookup_evals.range_table_eval,
+        lookup_evals.key_table_eval,
+        lookup_evals.h_1_eval,
+        lookup_evals.q_lookup_eval,
+        lookup_evals.table_dom_sep_eval,
+        lookup_evals.q_dom_sep_eval,
+    ]
+}
 
-        self.get_and_append_challenge()
-    }
+/// Returns the evaluations of the lookup argument's polynomials at the challenge point `zeta * omega`,
+/// in the order in which they are absorbed into the transcript & opened by the prover
+pub fn lookup_zeta_omega_evals(
+    lookup_evals: &LookupEvaluations,
+) -> [ScalarField; NUM_LOOKUP_ZETA_OMEGA_EVALS] {
+    [
+        lookup_evals.prod_next_eval,
+        lookup_evals.range_table_next_eval,
+        lookup_evals.key_table_next_eval,
+        lookup_evals.h_1_next_eval,
+        lookup_evals.h_2_next_eval,
+        lookup_evals.q_lookup_next_eval,
+        lookup_evals.w_3_next_eval,
+        lookup_evals.w_4_next_eval,
+        lookup_evals.table_dom_sep_next_eval,
+    ]
 }
 
 /// Serializes a slice of scalars into a little-endian byte array.
@@ -177,36 +294,215 @@ pub fn serialize_g1s_for_transcript(points: &[G1Affine]) -> Vec<u8> {
 
 #[cfg(test)]
 pub mod tests {
-    use alloc::vec::Vec;
+    extern crate std;
+
+    use alloc::{format, vec::Vec};
     use arbitrum_client::conversion::to_contract_proof;
     use ark_std::UniformRand;
     use circuit_types::PlonkProof;
     use constants::SystemCurve;
     use contracts_common::{
-        constants::{NUM_SELECTORS, NUM_WIRE_TYPES},
-        types::{G1Affine, G2Affine, Proof, PublicInputs, ScalarField, VerificationKey},
+        backends::HashBackend,
+        constants::{HASH_OUTPUT_SIZE, NUM_SELECTORS, NUM_WIRE_TYPES, TRANSCRIPT_STATE_SIZE},
+        serde_def_types::SerdeG1Affine,
+        types::{
+            Challenges as ContractChallenges, ExtendedProof, ExtendedVerificationKey, G1Affine,
+            G1BaseField, G2Affine, LinkingProof, PublicInputs, ScalarField,
+        },
     };
     use contracts_utils::{
-        conversion::to_contract_vkey,
-        crypto::NativeHasher,
-        proof_system::test_data::{random_commitments, random_scalars},
+        conversion::{to_contract_lookup_proof, to_contract_vkey},
+        proof_system::{
+            poseidon_transcript::PoseidonPlonkTranscript,
+            test_data::{random_commitments, random_scalars},
+            transcript_vectors::{
+                gen_transcript_test_vectors, LinkingTranscriptVector, PlonkTranscriptVector,
+                TranscriptRound, TranscriptTestVectors,
+            },
+        },
     };
+    use ethers::utils::hex;
     use jf_primitives::pcs::prelude::{Commitment, UnivariateVerifierParam};
     use mpc_plonk::{
         proof_system::{
-            structs::{BatchProof, Challenges, ProofEvaluations, VerifyingKey},
+            structs::{
+                BatchProof, Challenges, PlookupEvaluations, PlookupProof, PlookupVerifyingKey,
+                ProofEvaluations, VerifyingKey,
+            },
             verifier::Verifier,
         },
-        transcript::SolidityTranscript,
+        transcript::{PlonkTranscript, SolidityTranscript},
     };
-    use rand::thread_rng;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
+    use serde::de::DeserializeOwned;
+    use std::{cell::RefCell, fs, path::Path};
 
-    use super::Transcript;
+    use crate::native::NativeHasher;
+
+    use super::{
+        poseidon::PoseidonTranscript, serialize_scalars_for_transcript, FiatShamirTranscript,
+        Transcript,
+    };
 
     const N: usize = 1024;
     const L: usize = 512;
 
-    fn dummy_vkeys(n: u64, l: u64) -> (VerificationKey, VerifyingKey<SystemCurve>) {
+    /// The path of the transcript test vectors, relative to the crate root.
+    ///
+    /// These are generated by the `gen-transcript-vectors` script, which records
+    /// the transcript of the Jellyfish prover when proving random dummy circuits.
+    const TRANSCRIPT_TEST_VECTORS_PATH: &str = "test-vectors/transcript.json";
+
+    /// The number of transcript test vectors of each kind to generate in-process,
+    /// in addition to those replayed from the committed fixture
+    const NUM_GENERATED_TRANSCRIPT_TEST_VECTORS: usize = 2;
+
+    /// The seed of the RNG from which the in-process transcript test vectors are generated,
+    /// so that a failure can be reproduced
+    const GENERATED_TRANSCRIPT_TEST_VECTORS_SEED: u64 = 42;
+
+    std::thread_local! {
+        /// The inputs hashed by the [`RecordingHasher`] on this thread
+        static HASH_INPUTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// A hashing backend which records its inputs, from which the
+    /// messages absorbed into the transcript in each round are recovered
+    struct RecordingHasher;
+
+    impl HashBackend for RecordingHasher {
+        fn hash(input: &[u8]) -> [u8; HASH_OUTPUT_SIZE] {
+            HASH_INPUTS.with(|inputs| inputs.borrow_mut().push(input.to_vec()));
+            NativeHasher::hash(input)
+        }
+    }
+
+    /// Takes the inputs hashed on this thread so far, clearing them
+    fn take_hash_inputs() -> Vec<Vec<u8>> {
+        HASH_INPUTS.with(|inputs| inputs.take())
+    }
+
+    /// Recovers the bytes absorbed into the transcript in each round from the inputs hashed
+    /// when squeezing its challenges.
+    ///
+    /// Each challenge is squeezed by hashing `state || transcript || 0` and `state || transcript || 1`,
+    /// so the bytes absorbed in a round are those appended to the transcript since the previous pair.
+    fn absorbed_per_round(hash_inputs: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let mut absorbed = Vec::new();
+        let mut num_absorbed = 0;
+        for input in hash_inputs.iter().step_by(2) {
+            let transcript = &input[TRANSCRIPT_STATE_SIZE..input.len() - 1];
+            absorbed.push(transcript[num_absorbed..].to_vec());
+            num_absorbed = transcript.len();
+        }
+
+        absorbed
+    }
+
+    fn load_transcript_test_vectors() -> TranscriptTestVectors {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(TRANSCRIPT_TEST_VECTORS_PATH);
+        let vectors_json = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "could not read transcript test vectors at {}, generate them with the \
+                 `gen-transcript-vectors` script: {e}",
+                path.display()
+            )
+        });
+
+        serde_json::from_str(&vectors_json).unwrap()
+    }
+
+    fn from_postcard_hex<T: DeserializeOwned>(data: &str) -> T {
+        postcard::from_bytes(&hex::decode(data).unwrap()).unwrap()
+    }
+
+    /// Replays the rounds recorded on the Jellyfish prover side against the bytes absorbed &
+    /// challenges squeezed by the contracts' transcript, panicking at the first divergence
+    fn replay_rounds(
+        vector_name: &str,
+        recorded_rounds: &[TranscriptRound],
+        absorbed: &[Vec<u8>],
+        challenges: &[ScalarField],
+    ) {
+        assert!(
+            recorded_rounds.len() <= challenges.len(),
+            "{vector_name}: prover squeezed {} challenges, transcript only squeezed {}",
+            recorded_rounds.len(),
+            challenges.len(),
+        );
+
+        for (i, round) in recorded_rounds.iter().enumerate() {
+            let mut offset = 0;
+            for message in &round.messages {
+                let data = hex::decode(&message.data).unwrap();
+                assert_eq!(
+                    absorbed[i].get(offset..offset + data.len()),
+                    Some(data.as_slice()),
+                    "{vector_name}: round {i} diverged at message \"{}\" (byte offset {offset})",
+                    message.label,
+                );
+                offset += data.len();
+            }
+            assert_eq!(
+                absorbed[i].len(),
+                offset,
+                "{vector_name}: round {i} absorbed more bytes than the prover",
+            );
+
+            assert_eq!(
+                hex::encode(serialize_scalars_for_transcript(&[challenges[i]])),
+                round.challenge,
+                "{vector_name}: challenge squeezed in round {i} diverged",
+            );
+        }
+    }
+
+    fn replay_plonk_transcript_vector(vector_name: &str, vector: &PlonkTranscriptVector) {
+        let vkey: ExtendedVerificationKey = from_postcard_hex(&vector.vkey);
+        let proof: ExtendedProof = from_postcard_hex(&vector.proof);
+        let public_inputs: PublicInputs = from_postcard_hex(&vector.public_inputs);
+
+        take_hash_inputs();
+        let challenges = Transcript::<RecordingHasher>::new()
+            .compute_plonk_challenges(&vkey, &proof, &public_inputs)
+            .unwrap();
+        let absorbed = absorbed_per_round(&take_hash_inputs());
+
+        replay_rounds(
+            vector_name,
+            &vector.rounds,
+            &absorbed,
+            &[
+                challenges.tau,
+                challenges.beta,
+                challenges.gamma,
+                challenges.alpha,
+                challenges.zeta,
+                challenges.v,
+                challenges.u,
+            ],
+        );
+    }
+
+    fn replay_linking_transcript_vector(vector_name: &str, vector: &LinkingTranscriptVector) {
+        let SerdeG1Affine(wire_poly_comm_1) = from_postcard_hex(&vector.wire_poly_comm_1);
+        let SerdeG1Affine(wire_poly_comm_2) = from_postcard_hex(&vector.wire_poly_comm_2);
+        let linking_proof: LinkingProof = from_postcard_hex(&vector.linking_proof);
+
+        take_hash_inputs();
+        let eta = Transcript::<RecordingHasher>::new()
+            .compute_linking_proof_challenge(
+                wire_poly_comm_1,
+                wire_poly_comm_2,
+                linking_proof.linking_quotient_poly_comm,
+            )
+            .unwrap();
+        let absorbed = absorbed_per_round(&take_hash_inputs());
+
+        replay_rounds(vector_name, &vector.rounds, &absorbed, &[eta]);
+    }
+
+    fn dummy_vkeys(n: u64, l: u64) -> (ExtendedVerificationKey, VerifyingKey<SystemCurve>) {
         let mut rng = thread_rng();
 
         let jf_vkey = VerifyingKey {
@@ -229,7 +525,35 @@ pub mod tests {
         (vkey, jf_vkey)
     }
 
-    fn dummy_proofs() -> (Proof, BatchProof<SystemCurve>) {
+    fn dummy_lookup_vkeys(n: u64, l: u64) -> (ExtendedVerificationKey, VerifyingKey<SystemCurve>) {
+        let mut rng = thread_rng();
+
+        let jf_vkey = VerifyingKey {
+            domain_size: n as usize,
+            num_inputs: l as usize,
+            sigma_comms: random_commitments(NUM_WIRE_TYPES + 1, &mut rng),
+            selector_comms: random_commitments(NUM_SELECTORS + 1, &mut rng),
+            k: random_scalars(NUM_WIRE_TYPES + 1, &mut rng),
+            open_key: UnivariateVerifierParam {
+                g: G1Affine::rand(&mut rng),
+                h: G2Affine::rand(&mut rng),
+                beta_h: G2Affine::rand(&mut rng),
+            },
+            is_merged: false,
+            plookup_vk: Some(PlookupVerifyingKey {
+                range_table_comm: Commitment(G1Affine::rand(&mut rng)),
+                key_table_comm: Commitment(G1Affine::rand(&mut rng)),
+                table_dom_sep_comm: Commitment(G1Affine::rand(&mut rng)),
+                q_dom_sep_comm: Commitment(G1Affine::rand(&mut rng)),
+            }),
+        };
+
+        let vkey = to_contract_vkey(jf_vkey.clone()).unwrap();
+
+        (vkey, jf_vkey)
+    }
+
+    fn dummy_proofs() -> (ExtendedProof, BatchProof<SystemCurve>) {
         let mut rng = thread_rng();
 
         let jf_proof = PlonkProof {
@@ -246,18 +570,60 @@ pub mod tests {
             shifted_opening_proof: Commitment(G1Affine::rand(&mut rng)),
         };
 
-        let proof = to_contract_proof(&jf_proof).unwrap();
+        let proof = to_contract_proof(&jf_proof).unwrap().into();
 
         (proof, jf_proof.into())
     }
 
-    fn get_jf_challenges(
+    fn dummy_lookup_proofs() -> (ExtendedProof, BatchProof<SystemCurve>) {
+        let mut rng = thread_rng();
+
+        let jf_proof = PlonkProof {
+            wires_poly_comms: random_commitments(NUM_WIRE_TYPES + 1, &mut rng),
+            prod_perm_poly_comm: Commitment(G1Affine::rand(&mut rng)),
+            poly_evals: ProofEvaluations {
+                wires_evals: random_scalars(NUM_WIRE_TYPES + 1, &mut rng),
+                wire_sigma_evals: random_scalars(NUM_WIRE_TYPES, &mut rng),
+                perm_next_eval: ScalarField::rand(&mut rng),
+            },
+            plookup_proof: Some(PlookupProof {
+                h_poly_comms: random_commitments(2, &mut rng),
+                prod_lookup_poly_comm: Commitment(G1Affine::rand(&mut rng)),
+                poly_evals: PlookupEvaluations {
+                    range_table_eval: ScalarField::rand(&mut rng),
+                    key_table_eval: ScalarField::rand(&mut rng),
+                    table_dom_sep_eval: ScalarField::rand(&mut rng),
+                    q_dom_sep_eval: ScalarField::rand(&mut rng),
+                    h_1_eval: ScalarField::rand(&mut rng),
+                    q_lookup_eval: ScalarField::rand(&mut rng),
+                    prod_next_eval: ScalarField::rand(&mut rng),
+                    range_table_next_eval: ScalarField::rand(&mut rng),
+                    key_table_next_eval: ScalarField::rand(&mut rng),
+                    table_dom_sep_next_eval: ScalarField::rand(&mut rng),
+                    h_1_next_eval: ScalarField::rand(&mut rng),
+                    h_2_next_eval: ScalarField::rand(&mut rng),
+                    q_lookup_next_eval: ScalarField::rand(&mut rng),
+                    w_3_next_eval: ScalarField::rand(&mut rng),
+                    w_4_next_eval: ScalarField::rand(&mut rng),
+                },
+            }),
+            split_quot_poly_comms: random_commitments(NUM_WIRE_TYPES + 1, &mut rng),
+            opening_proof: Commitment(G1Affine::rand(&mut rng)),
+            shifted_opening_proof: Commitment(G1Affine::rand(&mut rng)),
+        };
+
+        let proof = to_contract_lookup_proof(&jf_proof).unwrap();
+
+        (proof, jf_proof.into())
+    }
+
+    fn get_jf_challenges<T: PlonkTranscript<G1BaseField>>(
         vkey: &VerifyingKey<SystemCurve>,
         public_inputs: &[ScalarField],
         proof: &BatchProof<SystemCurve>,
         extra_transcript_init_message: &Option<Vec<u8>>,
     ) -> Challenges<ScalarField> {
-        Verifier::compute_challenges::<SolidityTranscript>(
+        Verifier::compute_challenges::<T>(
             &[vkey],
             &[public_inputs],
             proof,
@@ -266,25 +632,120 @@ pub mod tests {
         .unwrap()
     }
 
-    #[test]
-    fn test_transcript_equivalency() {
+    /// Asserts that the challenges computed over the given vkey & proof by the contracts'
+    /// transcript `T` match those computed by the Jellyfish verifier using the transcript `J`
+    fn assert_transcript_equivalency<T: FiatShamirTranscript, J: PlonkTranscript<G1BaseField>>(
+        (vkey, jf_vkey): (ExtendedVerificationKey, VerifyingKey<SystemCurve>),
+        (proof, jf_proof): (ExtendedProof, BatchProof<SystemCurve>),
+    ) {
         let mut rng = thread_rng();
-        let (vkey, jf_vkey) = dummy_vkeys(N as u64, L as u64);
-        let (proof, jf_proof) = dummy_proofs();
         let public_inputs = PublicInputs(random_scalars(L, &mut rng));
 
-        let mut stylus_transcript = Transcript::<NativeHasher>::new();
-        let challenges = stylus_transcript
+        let challenges = T::new()
             .compute_plonk_challenges(&vkey, &proof, &public_inputs)
             .unwrap();
 
-        let jf_challenges = get_jf_challenges(&jf_vkey, &public_inputs.0, &jf_proof, &None);
+        let jf_challenges = get_jf_challenges::<J>(&jf_vkey, &public_inputs.0, &jf_proof, &None);
+
+        assert_eq!(
+            challenges,
+            ContractChallenges {
+                tau: jf_challenges.tau,
+                beta: jf_challenges.beta,
+                gamma: jf_challenges.gamma,
+                alpha: jf_challenges.alpha,
+                zeta: jf_challenges.zeta,
+                v: jf_challenges.v,
+                u: jf_challenges.u,
+            }
+        );
+    }
+
+    #[test]
+    fn test_transcript_equivalency() {
+        assert_transcript_equivalency::<Transcript<NativeHasher>, SolidityTranscript>(
+            dummy_vkeys(N as u64, L as u64),
+            dummy_proofs(),
+        );
+    }
+
+    #[test]
+    fn test_lookup_transcript_equivalency() {
+        assert_transcript_equivalency::<Transcript<NativeHasher>, SolidityTranscript>(
+            dummy_lookup_vkeys(N as u64, L as u64),
+            dummy_lookup_proofs(),
+        );
+    }
 
-        assert_eq!(challenges.beta, jf_challenges.beta);
-        assert_eq!(challenges.gamma, jf_challenges.gamma);
-        assert_eq!(challenges.alpha, jf_challenges.alpha);
-        assert_eq!(challenges.zeta, jf_challenges.zeta);
-        assert_eq!(challenges.v, jf_challenges.v);
-        assert_eq!(challenges.u, jf_challenges.u);
+    #[test]
+    fn test_poseidon_transcript_equivalency() {
+        assert_transcript_equivalency::<PoseidonTranscript, PoseidonPlonkTranscript>(
+            dummy_vkeys(N as u64, L as u64),
+            dummy_proofs(),
+        );
+    }
+
+    #[test]
+    fn test_poseidon_lookup_transcript_equivalency() {
+        assert_transcript_equivalency::<PoseidonTranscript, PoseidonPlonkTranscript>(
+            dummy_lookup_vkeys(N as u64, L as u64),
+            dummy_lookup_proofs(),
+        );
+    }
+
+    #[test]
+    fn test_plonk_transcript_vectors() {
+        let vectors = load_transcript_test_vectors();
+        assert!(
+            !vectors.plonk.is_empty(),
+            "no Plonk transcript test vectors"
+        );
+
+        for (i, vector) in vectors.plonk.iter().enumerate() {
+            replay_plonk_transcript_vector(&format!("Plonk vector {i}"), vector);
+        }
+    }
+
+    #[test]
+    fn test_lookup_transcript_vectors() {
+        let vectors = load_transcript_test_vectors();
+        assert!(
+            !vectors.lookup.is_empty(),
+            "no lookup transcript test vectors"
+        );
+
+        for (i, vector) in vectors.lookup.iter().enumerate() {
+            replay_plonk_transcript_vector(&format!("lookup vector {i}"), vector);
+        }
+    }
+
+    #[test]
+    fn test_linking_transcript_vectors() {
+        let vectors = load_transcript_test_vectors();
+        assert!(
+            !vectors.linking.is_empty(),
+            "no linking transcript test vectors"
+        );
+
+        for (i, vector) in vectors.linking.iter().enumerate() {
+            replay_linking_transcript_vector(&format!("linking vector {i}"), vector);
+        }
+    }
+
+    #[test]
+    fn test_generated_transcript_vectors() {
+        let mut rng = StdRng::seed_from_u64(GENERATED_TRANSCRIPT_TEST_VECTORS_SEED);
+        let vectors =
+            gen_transcript_test_vectors(NUM_GENERATED_TRANSCRIPT_TEST_VECTORS, &mut rng).unwrap();
+
+        for (i, vector) in vectors.plonk.iter().enumerate() {
+            replay_plonk_transcript_vector(&format!("generated Plonk vector {i}"), vector);
+        }
+        for (i, vector) in vectors.lookup.iter().enumerate() {
+            replay_plonk_transcript_vector(&format!("generated lookup vector {i}"), vector);
+        }
+        for (i, vector) in vectors.linking.iter().enumerate() {
+            replay_linking_transcript_vector(&format!("generated linking vector {i}"), vector);
+        }
     }
 }
@@ -0,0 +1,81 @@
+//! A field-native Fiat-Shamir transcript, built on the Poseidon2 sponge used in
+//! [`compute_poseidon_hash`](crate::crypto::poseidon::compute_poseidon_hash).
+//!
+//! This absorbs scalars & G1 coordinates directly, and squeezes scalars without any byte
+//! reduction, so that the transcript can be efficiently recomputed within a circuit.
+
+use ark_ff::PrimeField;
+use contracts_common::{
+    custom_serde::SerdeError,
+    types::{G1Affine, G1BaseField, ScalarField},
+};
+use renegade_crypto::hash::Poseidon2Sponge;
+
+use super::FiatShamirTranscript;
+
+/// The number of scalars into which a G1 point is split when absorbed into the transcript
+pub const NUM_SCALARS_PER_G1: usize = 4;
+
+/// The Poseidon2-based Fiat-Shamir transcript used in the Plonk protocol
+pub struct PoseidonTranscript {
+    /// The sponge absorbing the elements of the current round
+    sponge: Poseidon2Sponge,
+}
+
+impl FiatShamirTranscript for PoseidonTranscript {
+    fn new() -> Self {
+        PoseidonTranscript {
+            sponge: Poseidon2Sponge::new(),
+        }
+    }
+
+    fn append_u32(&mut self, value: u32) {
+        self.sponge.absorb(&ScalarField::from(value));
+    }
+
+    fn append_u64(&mut self, value: u64) {
+        self.sponge.absorb(&ScalarField::from(value));
+    }
+
+    fn append_scalars(&mut self, scalars: &[ScalarField]) {
+        self.sponge.absorb_batch(scalars);
+    }
+
+    fn append_g1s(&mut self, points: &[G1Affine]) {
+        for point in points {
+            self.sponge.absorb_batch(&g1_to_transcript_scalars(point));
+        }
+    }
+
+    fn get_and_append_challenge(&mut self) -> Result<ScalarField, SerdeError> {
+        let challenge = self.sponge.squeeze();
+
+        // The sponge cannot absorb once squeezed, so we chain the rounds of the
+        // transcript by absorbing the challenge into a fresh sponge
+        self.sponge = Poseidon2Sponge::new();
+        self.sponge.absorb(&challenge);
+
+        Ok(challenge)
+    }
+}
+
+/// Splits the coordinates of a G1 point into the scalars absorbed into the transcript.
+///
+/// The coordinates are elements of the base field, which is larger than the scalar field,
+/// so each is split into a low & high 128-bit limb to be absorbed injectively.
+/// The identity is represented by the coordinates `(0, 0)`.
+pub fn g1_to_transcript_scalars(point: &G1Affine) -> [ScalarField; NUM_SCALARS_PER_G1] {
+    let [x_low, x_high] = base_field_to_limbs(point.x);
+    let [y_low, y_high] = base_field_to_limbs(point.y);
+
+    [x_low, x_high, y_low, y_high]
+}
+
+/// Splits a base field element into its low & high 128-bit limbs, as scalars
+fn base_field_to_limbs(elem: G1BaseField) -> [ScalarField; 2] {
+    let limbs = elem.into_bigint().0;
+    let low = ((limbs[1] as u128) << 64) | (limbs[0] as u128);
+    let high = ((limbs[3] as u128) << 64) | (limbs[2] as u128);
+
+    [ScalarField::from(low), ScalarField::from(high)]
+}
@@ -0,0 +1,216 @@
+//! A diagnostic mode for the Plonk verifier, which records the intermediate values computed
+//! when verifying a single proof, and the step of the verification algorithm at which it failed.
+//!
+//! This is intended to be used with native backends, e.g. when debugging a mismatch between
+//! the proofs produced by the relayer and the ones accepted by the contracts.
+
+use alloc::vec;
+use contracts_common::types::{
+    Challenges, ExtendedProof, ExtendedVerificationKey, G1Affine, OpeningElems, PublicInputs,
+    ScalarField,
+};
+use core::{
+    fmt::{self, Display, Formatter},
+    slice,
+};
+
+use super::{errors::VerifierError, G1ArithmeticBackend, HashBackend, PlonkProofEvals, Verifier};
+
+/// The steps of the verification algorithm at which the verification of a proof can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationStep {
+    /// Validating the group membership of the proof's points (step 1)
+    ProofValidation,
+    /// Checking the number of public inputs against the verification key (step 3)
+    PublicInputsLength,
+    /// Checking that the proof contains a lookup argument iff the verification key expects one
+    ProofType,
+    /// Deriving the Fiat-Shamir challenges from the transcript (step 4)
+    ChallengeDerivation,
+    /// Computing the evaluation domain & the Lagrange basis denominators (steps 5 & 6)
+    DomainSetup,
+    /// Computing the G1 elements used in the opening via multi-scalar multiplication (steps 9 - 12)
+    Msm,
+    /// The final pairing check (step 12)
+    Pairing,
+}
+
+impl Display for VerificationStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationStep::ProofValidation => write!(f, "proof validation"),
+            VerificationStep::PublicInputsLength => write!(f, "public inputs length check"),
+            VerificationStep::ProofType => write!(f, "proof type check"),
+            VerificationStep::ChallengeDerivation => write!(f, "challenge derivation"),
+            VerificationStep::DomainSetup => write!(f, "domain setup"),
+            VerificationStep::Msm => write!(f, "MSM"),
+            VerificationStep::Pairing => write!(f, "pairing check"),
+        }
+    }
+}
+
+/// A trace of the verification of a single Plonk proof.
+///
+/// Each of the intermediate values is only populated if the step computing it was reached.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationTrace {
+    /// The number of public inputs given to the verifier
+    pub num_public_inputs: usize,
+    /// The number of public inputs expected by the verification key
+    pub expected_num_public_inputs: u64,
+    /// The Fiat-Shamir challenges derived from the transcript
+    pub challenges: Option<Challenges>,
+    /// The size of the evaluation domain
+    pub domain_size: Option<u64>,
+    /// The evaluation of the zero polynomial at `zeta`
+    pub zero_poly_eval: Option<ScalarField>,
+    /// The evaluation of the first Lagrange basis polynomial at `zeta`
+    pub lagrange_1_eval: Option<ScalarField>,
+    /// The evaluation of the last Lagrange basis polynomial at `zeta`,
+    /// only computed for proofs with a lookup argument
+    pub lagrange_n_eval: Option<ScalarField>,
+    /// The evaluation of the public inputs polynomial at `zeta`
+    pub pi_eval: Option<ScalarField>,
+    /// The constant term of the linearization polynomial
+    pub r_0: Option<ScalarField>,
+    /// The LHS & RHS G1 elements used in the pairing check
+    pub opening_elems: Option<(G1Affine, G1Affine)>,
+    /// The step at which verification failed, if it did
+    pub failed_step: Option<VerificationStep>,
+    /// The error encountered during verification, if any.
+    ///
+    /// This is `None` if the proof was rejected by the pairing check itself.
+    pub error: Option<VerifierError>,
+}
+
+impl VerificationTrace {
+    /// Whether or not the proof verified successfully
+    pub fn verified(&self) -> bool {
+        self.failed_step.is_none()
+    }
+}
+
+/// The failure of a step of the verification algorithm, along with the error it produced, if any
+type StepFailure = (VerificationStep, Option<VerifierError>);
+
+impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
+    /// Verify a proof, recording the intermediate values computed along the way
+    /// and the step at which verification failed, if any.
+    ///
+    /// This follows the same algorithm as [`Verifier::verify`].
+    pub fn verify_with_trace(
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        public_inputs: &PublicInputs,
+    ) -> VerificationTrace {
+        let mut trace = VerificationTrace {
+            num_public_inputs: public_inputs.0.len(),
+            expected_num_public_inputs: vkey.l,
+            ..Default::default()
+        };
+
+        if let Err((failed_step, error)) =
+            Self::trace_verification(vkey, proof, public_inputs, &mut trace)
+        {
+            trace.failed_step = Some(failed_step);
+            trace.error = error;
+        }
+
+        trace
+    }
+
+    /// Runs the steps of the verification algorithm, populating the given trace as they complete
+    fn trace_verification(
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        public_inputs: &PublicInputs,
+        trace: &mut VerificationTrace,
+    ) -> Result<(), StepFailure> {
+        Self::step_1(proof).map_err(|e| (VerificationStep::ProofValidation, Some(e)))?;
+
+        Self::step_3(public_inputs, vkey)
+            .map_err(|e| (VerificationStep::PublicInputsLength, Some(e)))?;
+
+        Self::check_proof_type(vkey, proof).map_err(|e| (VerificationStep::ProofType, Some(e)))?;
+
+        let challenges = Self::step_4(vkey, proof, public_inputs).map_err(|_| {
+            (
+                VerificationStep::ChallengeDerivation,
+                Some(VerifierError::ScalarConversion),
+            )
+        })?;
+        trace.challenges = Some(challenges);
+
+        let (domain_size, domain_elements, mut lagrange_basis_denominators) =
+            Self::prep_domain_and_basis_denominators(
+                vkey.n,
+                vkey.l as usize,
+                challenges.zeta,
+                vkey.lookup_vkey.is_some(),
+            )
+            .map_err(|e| (VerificationStep::DomainSetup, Some(e)))?;
+        trace.domain_size = Some(domain_size);
+
+        let zero_poly_eval = Self::step_5(domain_size, &challenges);
+        trace.zero_poly_eval = Some(zero_poly_eval);
+
+        let lagrange_bases = Self::batch_invert_lagrange_basis_denominators(
+            &mut lagrange_basis_denominators,
+            &[zero_poly_eval],
+            slice::from_ref(vkey),
+        )
+        .remove(0);
+
+        let lagrange_1_eval = Self::step_6(&lagrange_bases, &domain_elements);
+        trace.lagrange_1_eval = Some(lagrange_1_eval);
+
+        let lookup_domain_evals =
+            Self::lookup_domain_evals(vkey, &lagrange_bases, &domain_elements);
+        trace.lagrange_n_eval = lookup_domain_evals.map(|evals| evals.lagrange_n_eval);
+
+        let pi_eval = Self::step_7(
+            lagrange_1_eval,
+            &lagrange_bases,
+            &domain_elements,
+            public_inputs,
+        );
+        trace.pi_eval = Some(pi_eval);
+
+        let r_0 = Self::step_8(
+            pi_eval,
+            lagrange_1_eval,
+            lookup_domain_evals,
+            &challenges,
+            proof,
+        );
+        trace.r_0 = Some(r_0);
+
+        let evals = PlonkProofEvals {
+            challenges,
+            zero_poly_eval,
+            lagrange_1_eval,
+            lookup_domain_evals,
+            r_0,
+            omega: domain_elements[1],
+        };
+
+        let (lhs_g1, rhs_g1) = Self::prep_plonk_proof_opening_elems(vkey, proof, &evals)
+            .map_err(|e| (VerificationStep::Msm, Some(e)))?;
+        trace.opening_elems = Some((lhs_g1, rhs_g1));
+
+        let opening_elems = OpeningElems {
+            g1_lhs_elems: vec![lhs_g1],
+            g1_rhs_elems: vec![rhs_g1],
+            transcript_elements: vec![challenges.u],
+        };
+
+        let verified = Self::batch_opening(&opening_elems, vkey.x_h, vkey.h)
+            .map_err(|e| (VerificationStep::Pairing, Some(e)))?;
+
+        if verified {
+            Ok(())
+        } else {
+            Err((VerificationStep::Pairing, None))
+        }
+    }
+}
@@ -5,12 +5,12 @@ use contracts_common::{
     backends::G1ArithmeticError,
     constants::{
         ARITHMETIC_BACKEND_ERROR_MESSAGE, INVALID_INPUTS_ERROR_MESSAGE,
-        SCALAR_CONVERSION_ERROR_MESSAGE,
+        MALFORMED_PROOF_ERROR_MESSAGE, SCALAR_CONVERSION_ERROR_MESSAGE,
     },
 };
 
 /// Errors that can occur during Plonk verification
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VerifierError {
     /// An error that occurred when interpreting the verification inputs
     InvalidInputs,
@@ -18,6 +18,8 @@ pub enum VerifierError {
     ArithmeticBackend,
     /// An error that occurred when converting to/from scalar types
     ScalarConversion,
+    /// The proof or verification key contains a point that is not in its expected group
+    MalformedProof,
 }
 
 impl From<G1ArithmeticError> for VerifierError {
@@ -32,6 +34,7 @@ impl From<VerifierError> for Vec<u8> {
             VerifierError::InvalidInputs => INVALID_INPUTS_ERROR_MESSAGE.to_vec(),
             VerifierError::ArithmeticBackend => ARITHMETIC_BACKEND_ERROR_MESSAGE.to_vec(),
             VerifierError::ScalarConversion => SCALAR_CONVERSION_ERROR_MESSAGE.to_vec(),
+            VerifierError::MalformedProof => MALFORMED_PROOF_ERROR_MESSAGE.to_vec(),
         }
     }
 }
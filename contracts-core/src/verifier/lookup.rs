@@ -0,0 +1,312 @@
+//! Verification of the Plookup argument used in UltraPlonk-style proofs, following the Jellyfish implementation:
+//! https://github.com/renegade-fi/mpc-jellyfish/blob/main/plonk/src/proof_system/verifier.rs
+//!
+//! The lookup argument extends the TurboPlonk identities with the following, each separated by a power of `alpha`:
+//! - `alpha^3`: `L_n(X) * (h_1(X) - h_2(X * omega))`
+//! - `alpha^4`: `L_1(X) * (p(X) - 1)`
+//! - `alpha^5`: `L_n(X) * (p(X) - 1)`
+//! - `alpha^6`: `(X - omega^{-1}) * (p(X) * (1 + beta) * (gamma + f(X)) * (gamma * (1 + beta) + t(X) + beta * t(X * omega))
+//!   - p(X * omega) * (gamma * (1 + beta) + h_1(X) + beta * h_1(X * omega)) * (gamma * (1 + beta) + h_2(X) + beta * h_2(X * omega)))`
+//!
+//! Where `p` is the lookup grand product polynomial, `f` is the merged lookup witness polynomial,
+//! and `t` is the merged lookup table polynomial.
+
+use ark_ff::One;
+use contracts_common::{
+    backends::{G1ArithmeticBackend, HashBackend},
+    constants::{NUM_LOOKUP_ZETA_EVALS, NUM_LOOKUP_ZETA_OMEGA_EVALS, NUM_WIRE_TYPES},
+    types::{
+        Challenges, ExtendedVerificationKey, G1Affine, LookupEvaluations, LookupProof,
+        LookupVerificationKey, ScalarField,
+    },
+};
+
+use super::{
+    BatchPosition, MsmAccumulator, PlonkProofEvals, ProofComm, TermKey, Verifier, VkeyComm,
+};
+
+/// The evaluations of the domain-dependent values used in the lookup argument
+#[derive(Clone, Copy, Debug)]
+pub struct LookupDomainEvals {
+    /// The evaluation of the last Lagrange basis polynomial at the challenge point `zeta`
+    pub lagrange_n_eval: ScalarField,
+    /// The inverse of the generator of the evaluation domain, i.e. its last element
+    pub omega_inv: ScalarField,
+}
+
+impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
+    /// Computes the domain-dependent evaluations used in the lookup argument,
+    /// if the verification key expects one.
+    ///
+    /// For such verification keys, the last domain element & Lagrange basis are those of `omega^{-1}`,
+    /// as prepared in [`Verifier::prep_domain_and_basis_denominators`].
+    pub(super) fn lookup_domain_evals(
+        vkey: &ExtendedVerificationKey,
+        lagrange_bases: &[ScalarField],
+        domain_elements: &[ScalarField],
+    ) -> Option<LookupDomainEvals> {
+        vkey.lookup_vkey.as_ref()?;
+
+        let omega_inv = *domain_elements.last()?;
+        let lagrange_n_eval = omega_inv * lagrange_bases.last()?;
+
+        Some(LookupDomainEvals {
+            lagrange_n_eval,
+            omega_inv,
+        })
+    }
+
+    /// Computes the part of the linearization polynomial constant term, `r_0`,
+    /// contributed by the lookup argument
+    pub(super) fn lookup_r_0_term(
+        lagrange_1_eval: ScalarField,
+        lookup_domain_evals: LookupDomainEvals,
+        lookup_proof: &LookupProof,
+        challenges: &Challenges,
+    ) -> ScalarField {
+        let LookupDomainEvals {
+            lagrange_n_eval,
+            omega_inv,
+        } = lookup_domain_evals;
+        let LookupEvaluations {
+            h_1_eval,
+            h_1_next_eval,
+            h_2_next_eval,
+            prod_next_eval,
+            ..
+        } = lookup_proof.lookup_evals;
+        let Challenges {
+            alpha,
+            beta,
+            gamma,
+            zeta,
+            ..
+        } = challenges;
+
+        let alpha_2 = *alpha * alpha;
+        let alpha_3 = alpha_2 * alpha;
+        let gamma_mul_beta_plus_one = *gamma * (ScalarField::one() + beta);
+
+        let lookup_constant = lagrange_n_eval * (h_1_eval - h_2_next_eval - alpha_2)
+            - *alpha * lagrange_1_eval
+            - alpha_3
+                * (*zeta - omega_inv)
+                * prod_next_eval
+                * (gamma_mul_beta_plus_one + h_1_eval + *beta * h_1_next_eval)
+                * (gamma_mul_beta_plus_one + *beta * h_2_next_eval);
+
+        alpha_3 * lookup_constant
+    }
+
+    /// Computes the part of the batched polynomial commitment [D]1 contributed by the lookup argument,
+    /// i.e. the scalar muls of the lookup grand product polynomial commitment & the `h_2` polynomial commitment,
+    /// accumulating their terms weighted by the given scalar
+    pub(super) fn lookup_d_1_term(
+        evals: &PlonkProofEvals,
+        lookup_domain_evals: LookupDomainEvals,
+        wire_evals: &[ScalarField; NUM_WIRE_TYPES],
+        lookup_proof: &LookupProof,
+        position: BatchPosition,
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let PlonkProofEvals {
+            challenges,
+            lagrange_1_eval,
+            ..
+        } = evals;
+        let LookupDomainEvals {
+            lagrange_n_eval,
+            omega_inv,
+        } = lookup_domain_evals;
+        let LookupProof {
+            h_poly_comms,
+            prod_lookup_poly_comm,
+            lookup_wire_eval,
+            lookup_evals,
+            ..
+        } = lookup_proof;
+        let Challenges {
+            tau,
+            alpha,
+            beta,
+            gamma,
+            zeta,
+            ..
+        } = challenges;
+        let one = ScalarField::one();
+
+        let merged_lookup_eval = eval_merged_lookup_witness(
+            *tau,
+            *lookup_wire_eval,
+            wire_evals[0],
+            wire_evals[1],
+            wire_evals[2],
+            lookup_evals.q_lookup_eval,
+            lookup_evals.q_dom_sep_eval,
+        );
+        let merged_table_eval = eval_merged_table(
+            *tau,
+            lookup_evals.range_table_eval,
+            lookup_evals.key_table_eval,
+            lookup_evals.q_lookup_eval,
+            wire_evals[3],
+            wire_evals[4],
+            lookup_evals.table_dom_sep_eval,
+        );
+        let merged_table_next_eval = eval_merged_table(
+            *tau,
+            lookup_evals.range_table_next_eval,
+            lookup_evals.key_table_next_eval,
+            lookup_evals.q_lookup_next_eval,
+            lookup_evals.w_3_next_eval,
+            lookup_evals.w_4_next_eval,
+            lookup_evals.table_dom_sep_next_eval,
+        );
+
+        let alpha_2 = *alpha * alpha;
+        let alpha_4 = alpha_2 * alpha_2;
+        let alpha_5 = alpha_4 * alpha;
+        let alpha_6 = alpha_5 * alpha;
+        let gamma_mul_beta_plus_one = *gamma * (one + beta);
+        let zeta_minus_omega_inv = *zeta - omega_inv;
+
+        let prod_lookup_scalar_coeff = alpha_4 * *lagrange_1_eval
+            + alpha_5 * lagrange_n_eval
+            + alpha_6
+                * zeta_minus_omega_inv
+                * (one + beta)
+                * (*gamma + merged_lookup_eval)
+                * (gamma_mul_beta_plus_one + merged_table_eval + *beta * merged_table_next_eval);
+
+        let h_2_scalar_coeff = alpha_6
+            * zeta_minus_omega_inv
+            * lookup_evals.prod_next_eval
+            * (gamma_mul_beta_plus_one
+                + lookup_evals.h_1_eval
+                + *beta * lookup_evals.h_1_next_eval);
+
+        terms.add_terms(
+            weight,
+            &[prod_lookup_scalar_coeff, -h_2_scalar_coeff],
+            &[
+                (
+                    position.proof_comm(ProofComm::ProdLookup),
+                    *prod_lookup_poly_comm,
+                ),
+                (position.proof_comm(ProofComm::H(1)), h_poly_comms[1]),
+            ],
+        );
+    }
+}
+
+/// Returns the commitments to the lookup argument's polynomials opened at the challenge point `zeta`,
+/// in the same order as their evaluations in [`crate::transcript::lookup_zeta_evals`]
+pub(super) fn lookup_zeta_comms(
+    position: BatchPosition,
+    lookup_vkey: &LookupVerificationKey,
+    lookup_proof: &LookupProof,
+) -> [(TermKey, G1Affine); NUM_LOOKUP_ZETA_EVALS] {
+    [
+        (
+            position.vkey_comm(VkeyComm::RangeTable),
+            lookup_vkey.range_table_comm,
+        ),
+        (
+            position.vkey_comm(VkeyComm::KeyTable),
+            lookup_vkey.key_table_comm,
+        ),
+        (
+            position.proof_comm(ProofComm::H(0)),
+            lookup_proof.h_poly_comms[0],
+        ),
+        (
+            position.vkey_comm(VkeyComm::QLookup),
+            lookup_vkey.q_lookup_comm,
+        ),
+        (
+            position.vkey_comm(VkeyComm::TableDomSep),
+            lookup_vkey.table_dom_sep_comm,
+        ),
+        (
+            position.vkey_comm(VkeyComm::QDomSep),
+            lookup_vkey.q_dom_sep_comm,
+        ),
+    ]
+}
+
+/// Returns the commitments to the lookup argument's polynomials opened at the challenge point `zeta * omega`,
+/// in the same order as their evaluations in [`crate::transcript::lookup_zeta_omega_evals`]
+pub(super) fn lookup_zeta_omega_comms(
+    position: BatchPosition,
+    lookup_vkey: &LookupVerificationKey,
+    lookup_proof: &LookupProof,
+    wire_comms: &[G1Affine; NUM_WIRE_TYPES],
+) -> [(TermKey, G1Affine); NUM_LOOKUP_ZETA_OMEGA_EVALS] {
+    [
+        (
+            position.proof_comm(ProofComm::ProdLookup),
+            lookup_proof.prod_lookup_poly_comm,
+        ),
+        (
+            position.vkey_comm(VkeyComm::RangeTable),
+            lookup_vkey.range_table_comm,
+        ),
+        (
+            position.vkey_comm(VkeyComm::KeyTable),
+            lookup_vkey.key_table_comm,
+        ),
+        (
+            position.proof_comm(ProofComm::H(0)),
+            lookup_proof.h_poly_comms[0],
+        ),
+        (
+            position.proof_comm(ProofComm::H(1)),
+            lookup_proof.h_poly_comms[1],
+        ),
+        (
+            position.vkey_comm(VkeyComm::QLookup),
+            lookup_vkey.q_lookup_comm,
+        ),
+        (position.proof_comm(ProofComm::Wire(3)), wire_comms[3]),
+        (position.proof_comm(ProofComm::Wire(4)), wire_comms[4]),
+        (
+            position.vkey_comm(VkeyComm::TableDomSep),
+            lookup_vkey.table_dom_sep_comm,
+        ),
+    ]
+}
+
+/// Evaluates the merged lookup witness polynomial, `f`, given the evaluations
+/// of the polynomials from which it is constructed
+fn eval_merged_lookup_witness(
+    tau: ScalarField,
+    lookup_wire_eval: ScalarField,
+    w_0_eval: ScalarField,
+    w_1_eval: ScalarField,
+    w_2_eval: ScalarField,
+    q_lookup_eval: ScalarField,
+    q_dom_sep_eval: ScalarField,
+) -> ScalarField {
+    lookup_wire_eval
+        + q_lookup_eval
+            * tau
+            * (q_dom_sep_eval + tau * (w_0_eval + tau * (w_1_eval + tau * w_2_eval)))
+}
+
+/// Evaluates the merged lookup table polynomial, `t`, given the evaluations
+/// of the polynomials from which it is constructed
+fn eval_merged_table(
+    tau: ScalarField,
+    range_table_eval: ScalarField,
+    key_table_eval: ScalarField,
+    q_lookup_eval: ScalarField,
+    w_3_eval: ScalarField,
+    w_4_eval: ScalarField,
+    table_dom_sep_eval: ScalarField,
+) -> ScalarField {
+    range_table_eval
+        + q_lookup_eval
+            * tau
+            * (table_dom_sep_eval + tau * (key_table_eval + tau * (w_3_eval + tau * w_4_eval)))
+}
@@ -1,27 +1,45 @@
 //! The Plonk verifier, as described in section 8.3 of the paper: https://eprint.iacr.org/2019/953.pdf.
 //! Each of the steps of the verification algorithm described in the paper are represented as separate helper functions.
-//! This version of the verification algorithm currently only supports fan-in 2, fan-out 1 gates.
+//! This version of the verification algorithm supports the fan-in 2, fan-out 1 gates used by Jellyfish,
+//! optionally extended with an UltraPlonk-style lookup argument, as described in the [`lookup`] module.
 //! The verifier is an object containing a verification key, a transcript, and a backend for elliptic curve arithmetic.
 
+pub mod diagnostics;
 pub mod errors;
+pub mod lookup;
+pub mod profiling;
 
-use alloc::{vec, vec::Vec};
+use alloc::{
+    collections::{btree_map::Entry, BTreeMap},
+    vec,
+    vec::Vec,
+};
 use ark_ff::{batch_inversion, FftField, Field, One, Zero};
 use contracts_common::{
-    backends::{G1ArithmeticBackend, HashBackend},
-    constants::{NUM_MATCH_LINKING_PROOFS, NUM_WIRE_TYPES},
+    backends::{G1ArithmeticBackend, G1ArithmeticError, HashBackend},
+    constants::{
+        NUM_LOOKUP_ZETA_EVALS, NUM_LOOKUP_ZETA_OMEGA_EVALS, NUM_MATCH_LINKING_PROOFS,
+        NUM_MATCH_PROOFS, NUM_WIRE_TYPES,
+    },
     custom_serde::SerdeError,
     types::{
-        Challenges, G1Affine, G2Affine, LinkingProof, LinkingVerificationKey, MatchLinkingProofs,
-        MatchLinkingVkeys, MatchLinkingWirePolyComms, MatchProofs, MatchPublicInputs, MatchVkeys,
-        OpeningElems, Proof, PublicInputs, ScalarField, VerificationKey,
+        Challenges, ExtendedProof, ExtendedVerificationKey, G1Affine, G2Affine, LinkedBundle,
+        LinkingProof, LinkingVerificationKey, MatchLinkingProofs, MatchLinkingVkeys, MatchProofs,
+        MatchPublicInputs, MatchVkeys, OpeningElems, Proof, ProofLink, PublicInputs, ScalarField,
+        TranscriptHash, VerificationKey,
     },
 };
 use core::marker::PhantomData;
 
-use crate::transcript::{serialize_scalars_for_transcript, Transcript};
+use crate::transcript::{
+    lookup_zeta_evals, lookup_zeta_omega_evals, poseidon::PoseidonTranscript,
+    serialize_scalars_for_transcript, FiatShamirTranscript, Transcript,
+};
 
-use self::errors::VerifierError;
+use self::{
+    errors::VerifierError,
+    lookup::{lookup_zeta_comms, lookup_zeta_omega_comms, LookupDomainEvals},
+};
 
 /// The verifier struct, which is defined generically over elliptic curve arithmetic and hashing backends
 pub struct Verifier<G: G1ArithmeticBackend, H: HashBackend> {
@@ -45,15 +63,51 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
     ///
     /// Follows the algorithm laid out in section 8.3 of the paper: https://eprint.iacr.org/2019/953.pdf,
     pub fn verify(
-        vkey: VerificationKey,
-        proof: Proof,
+        vkey: ExtendedVerificationKey,
+        proof: ExtendedProof,
         public_inputs: PublicInputs,
     ) -> Result<bool, VerifierError> {
-        // Prepare Plonk proofs for batch verification
-        let opening_elems =
-            Self::prep_batch_plonk_proofs_opening(&[vkey], &[proof], &[public_inputs])?;
+        Self::batch_verify(&[vkey], &[proof], &[public_inputs])
+    }
+
+    /// Batch-verifies any number of independent Plonk proofs using a single pairing check.
+    ///
+    /// The `i`th proof is verified against the `i`th verification key & the `i`th public inputs.
+    ///
+    /// All the verification keys must have been generated using the same SRS.
+    pub fn batch_verify(
+        vkeys: &[ExtendedVerificationKey],
+        proofs: &[ExtendedProof],
+        public_inputs: &[PublicInputs],
+    ) -> Result<bool, VerifierError> {
+        if vkeys.is_empty() || proofs.len() != vkeys.len() || public_inputs.len() != vkeys.len() {
+            return Err(VerifierError::InvalidInputs);
+        }
+
+        Self::verify_proofs_and_links(vkeys, proofs, public_inputs, &[])
+    }
+
+    /// Verifies a [`LinkedBundle`], i.e. batch-verifies all of the Plonk proofs in the bundle,
+    /// and verifies the proof linking between each pair of proofs connected by a link in the bundle.
+    ///
+    /// The linking & Plonk proofs are all opened together using a single pairing check.
+    ///
+    /// All the verification keys must have been generated using the same SRS.
+    pub fn verify_linked_bundle(linked_bundle: &LinkedBundle) -> Result<bool, VerifierError> {
+        let num_proofs = linked_bundle.proofs.len();
+        if num_proofs == 0
+            || linked_bundle.vkeys.len() != num_proofs
+            || linked_bundle.public_inputs.len() != num_proofs
+        {
+            return Err(VerifierError::InvalidInputs);
+        }
 
-        Self::batch_opening(&opening_elems, vkey.x_h, vkey.h)
+        Self::verify_proofs_and_links(
+            &linked_bundle.vkeys,
+            &linked_bundle.proofs,
+            &linked_bundle.public_inputs,
+            &linked_bundle.links,
+        )
     }
 
     /// Batch-verifies:
@@ -71,86 +125,213 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
     ///
     /// Applies batch verification as implemented in Jellyfish: https://github.com/renegade-fi/mpc-jellyfish/blob/main/plonk/src/proof_system/verifier.rs#L199
     ///
-    /// This assumes that all the verification keys were generated using the same SRS.
+    /// All the verification keys must have been generated using the same SRS.
     pub fn verify_match(
         match_vkeys: MatchVkeys,
         match_linking_vkeys: MatchLinkingVkeys,
-        match_proofs: MatchProofs,
+        match_proofs: MatchProofs<ExtendedProof>,
         match_public_inputs: MatchPublicInputs,
         match_linking_proofs: MatchLinkingProofs,
     ) -> Result<bool, VerifierError> {
-        let x_h = match_vkeys.valid_commitments_vkey.x_h;
-        let h = match_vkeys.valid_commitments_vkey.h;
+        let mut linked_bundle = LinkedBundle::default();
+        Self::append_match_to_linked_bundle(
+            &mut linked_bundle,
+            &match_vkeys,
+            &match_linking_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_proofs,
+        );
 
-        // Prepare linking proofs for batch verification
-        let match_linking_wire_poly_comms = MatchLinkingWirePolyComms {
-            valid_reblind_0: match_proofs.valid_reblind_0.wire_comms[0],
-            valid_commitments_0: match_proofs.valid_commitments_0.wire_comms[0],
-            valid_reblind_1: match_proofs.valid_reblind_1.wire_comms[0],
-            valid_commitments_1: match_proofs.valid_commitments_1.wire_comms[0],
-            valid_match_settle: match_proofs.valid_match_settle.wire_comms[0],
+        Self::verify_linked_bundle(&linked_bundle)
+    }
+
+    /// Batch-verifies the proofs & linking proofs for a batch of matches,
+    /// as described in [`Verifier::verify_match`].
+    ///
+    /// The opening elements of every match bundle are folded into a single KZG batch opening,
+    /// so that the cost of the final pairing check is only incurred once for the whole batch.
+    ///
+    /// All the verification keys must have been generated using the same SRS.
+    pub fn verify_match_batch(
+        match_vkeys: MatchVkeys,
+        match_linking_vkeys: MatchLinkingVkeys,
+        match_proofs_batch: Vec<MatchProofs<ExtendedProof>>,
+        match_public_inputs_batch: Vec<MatchPublicInputs>,
+        match_linking_proofs_batch: Vec<MatchLinkingProofs>,
+    ) -> Result<bool, VerifierError> {
+        let num_matches = match_proofs_batch.len();
+        if num_matches == 0
+            || match_public_inputs_batch.len() != num_matches
+            || match_linking_proofs_batch.len() != num_matches
+        {
+            return Err(VerifierError::InvalidInputs);
+        }
+
+        let mut linked_bundle = LinkedBundle {
+            vkeys: Vec::with_capacity(num_matches * NUM_MATCH_PROOFS),
+            proofs: Vec::with_capacity(num_matches * NUM_MATCH_PROOFS),
+            public_inputs: Vec::with_capacity(num_matches * NUM_MATCH_PROOFS),
+            links: Vec::with_capacity(num_matches * NUM_MATCH_LINKING_PROOFS),
         };
 
-        let OpeningElems {
-            g1_lhs_elems: linking_g1_lhs_elems,
-            g1_rhs_elems: linking_g1_rhs_elems,
-            transcript_elements: linking_transcript_elements,
-        } = Self::prep_match_linking_proofs_opening(
-            match_linking_vkeys,
-            match_linking_proofs,
-            match_linking_wire_poly_comms,
-        )?;
+        for ((match_proofs, match_public_inputs), match_linking_proofs) in match_proofs_batch
+            .into_iter()
+            .zip(match_public_inputs_batch)
+            .zip(match_linking_proofs_batch)
+        {
+            Self::append_match_to_linked_bundle(
+                &mut linked_bundle,
+                &match_vkeys,
+                &match_linking_vkeys,
+                match_proofs,
+                match_public_inputs,
+                match_linking_proofs,
+            );
+        }
 
-        let vkey_batch = [
+        Self::verify_linked_bundle(&linked_bundle)
+    }
+
+    /// Appends the Plonk proofs & links involved in the matching and settlement
+    /// of a trade to the given [`LinkedBundle`], as described in [`Verifier::verify_match`]
+    fn append_match_to_linked_bundle(
+        linked_bundle: &mut LinkedBundle,
+        match_vkeys: &MatchVkeys,
+        match_linking_vkeys: &MatchLinkingVkeys,
+        match_proofs: MatchProofs<ExtendedProof>,
+        match_public_inputs: MatchPublicInputs,
+        match_linking_proofs: MatchLinkingProofs,
+    ) {
+        // The indices of the match's proofs within the bundle
+        let offset = linked_bundle.proofs.len();
+        let valid_commitments_0 = offset;
+        let valid_reblind_0 = offset + 1;
+        let valid_commitments_1 = offset + 2;
+        let valid_reblind_1 = offset + 3;
+        let valid_match_settle = offset + 4;
+
+        linked_bundle.vkeys.extend([
             match_vkeys.valid_commitments_vkey,
             match_vkeys.valid_reblind_vkey,
             match_vkeys.valid_commitments_vkey,
             match_vkeys.valid_reblind_vkey,
             match_vkeys.valid_match_settle_vkey,
-        ];
-        let proof_batch = [
+        ]);
+        linked_bundle.proofs.extend([
             match_proofs.valid_commitments_0,
             match_proofs.valid_reblind_0,
             match_proofs.valid_commitments_1,
             match_proofs.valid_reblind_1,
             match_proofs.valid_match_settle,
-        ];
-        let public_inputs_batch = [
+        ]);
+        linked_bundle.public_inputs.extend([
             match_public_inputs.valid_commitments_0,
             match_public_inputs.valid_reblind_0,
             match_public_inputs.valid_commitments_1,
             match_public_inputs.valid_reblind_1,
             match_public_inputs.valid_match_settle,
-        ];
+        ]);
+        linked_bundle.links.extend([
+            // PARTY 0 VALID COMMITMENTS <-> VALID MATCH SETTLE
+            ProofLink {
+                source: valid_commitments_0,
+                target: valid_match_settle,
+                linking_vkey: match_linking_vkeys.valid_commitments_match_settle_0,
+                linking_proof: match_linking_proofs.valid_commitments_match_settle_0,
+            },
+            // PARTY 0 VALID REBLIND <-> PARTY 0 VALID COMMITMENTS
+            ProofLink {
+                source: valid_reblind_0,
+                target: valid_commitments_0,
+                linking_vkey: match_linking_vkeys.valid_reblind_commitments,
+                linking_proof: match_linking_proofs.valid_reblind_commitments_0,
+            },
+            // PARTY 1 VALID COMMITMENTS <-> VALID MATCH SETTLE
+            ProofLink {
+                source: valid_commitments_1,
+                target: valid_match_settle,
+                linking_vkey: match_linking_vkeys.valid_commitments_match_settle_1,
+                linking_proof: match_linking_proofs.valid_commitments_match_settle_1,
+            },
+            // PARTY 1 VALID REBLIND <-> PARTY 1 VALID COMMITMENTS
+            ProofLink {
+                source: valid_reblind_1,
+                target: valid_commitments_1,
+                linking_vkey: match_linking_vkeys.valid_reblind_commitments,
+                linking_proof: match_linking_proofs.valid_reblind_commitments_1,
+            },
+        ]);
+    }
 
-        // Prepare Plonk proofs for batch verification
-        let OpeningElems {
-            g1_lhs_elems: plonk_g1_lhs_elems,
-            g1_rhs_elems: plonk_g1_rhs_elems,
-            transcript_elements: plonk_transcript_elements,
-        } = Self::prep_batch_plonk_proofs_opening(&vkey_batch, &proof_batch, &public_inputs_batch)?;
+    /// Batch-verifies the given Plonk proofs, and the proof linking between each pair of proofs
+    /// connected by one of the given links, using a single pairing check.
+    ///
+    /// The G1 elements used in the openings of all of the proofs are combined into a single
+    /// multi-scalar multiplication for each side of the pairing check, so that bases shared
+    /// between proofs, e.g. the commitments in a verification key used for several proofs,
+    /// are only multiplied once.
+    fn verify_proofs_and_links(
+        vkey_batch: &[ExtendedVerificationKey],
+        proof_batch: &[ExtendedProof],
+        public_inputs_batch: &[PublicInputs],
+        links: &[ProofLink],
+    ) -> Result<bool, VerifierError> {
+        // The openings are all checked in a single pairing against the SRS elements
+        // of the first verification key, so every key must share the same SRS
+        let (g, h, x_h) = (vkey_batch[0].g, vkey_batch[0].h, vkey_batch[0].x_h);
+        if vkey_batch
+            .iter()
+            .any(|vkey| vkey.g != g || vkey.h != h || vkey.x_h != x_h)
+        {
+            return Err(VerifierError::InvalidInputs);
+        }
 
-        let g1_lhs_elems = [linking_g1_lhs_elems, plonk_g1_lhs_elems].concat();
-        let g1_rhs_elems = [linking_g1_rhs_elems, plonk_g1_rhs_elems].concat();
-        let transcript_elements = [linking_transcript_elements, plonk_transcript_elements].concat();
+        // Prepare linking proofs for batch verification
+        let linking_opening_elems = Self::prep_links_opening(links, proof_batch)?;
 
-        let final_opening_elems = OpeningElems {
-            g1_lhs_elems,
-            g1_rhs_elems,
-            transcript_elements,
-        };
+        // Compute the challenges & evaluations for the Plonk proofs
+        let evals_batch =
+            Self::prep_batch_plonk_proofs_evals(vkey_batch, proof_batch, public_inputs_batch)?;
+
+        // Compute the powers of the challenge `r` weighting each of the openings
+        let transcript_elements = [
+            linking_opening_elems.transcript_elements.clone(),
+            evals_batch.iter().map(|evals| evals.challenges.u).collect(),
+        ]
+        .concat();
+        let r_powers = Self::compute_r_powers(&transcript_elements)?;
+        let (linking_r_powers, plonk_r_powers) = r_powers.split_at(links.len());
+
+        let mut lhs_terms = MsmAccumulator::default();
+        let mut rhs_terms = MsmAccumulator::default();
+
+        lhs_terms.add_link_terms(linking_r_powers, &linking_opening_elems.g1_lhs_elems);
+        rhs_terms.add_link_terms(linking_r_powers, &linking_opening_elems.g1_rhs_elems);
+
+        Self::prep_batch_plonk_proofs_opening(
+            vkey_batch,
+            proof_batch,
+            &evals_batch,
+            plonk_r_powers,
+            &mut lhs_terms,
+            &mut rhs_terms,
+        );
 
         // Batch-open all of the linking & Plonk proofs together
-        Self::batch_opening(&final_opening_elems, x_h, h)
+        let lhs_rlc = lhs_terms.msm::<G>()?;
+        let rhs_rlc = rhs_terms.msm::<G>()?;
+
+        Self::pairing_check(lhs_rlc, rhs_rlc, x_h, h)
     }
 
-    /// Computes the elements used in the final KZG batch opening pairing check
-    /// for a batch of Plonk proofs
-    fn prep_batch_plonk_proofs_opening(
-        vkey_batch: &[VerificationKey],
-        proof_batch: &[Proof],
+    /// Computes the challenges & evaluations used in the openings of a batch of Plonk proofs,
+    /// i.e. runs all of the steps of the verifier algorithm prior to the G1 arithmetic
+    fn prep_batch_plonk_proofs_evals(
+        vkey_batch: &[ExtendedVerificationKey],
+        proof_batch: &[ExtendedProof],
         public_inputs_batch: &[PublicInputs],
-    ) -> Result<OpeningElems, VerifierError> {
+    ) -> Result<Vec<PlonkProofEvals>, VerifierError> {
         assert!(
             vkey_batch.len() == proof_batch.len() && proof_batch.len() == public_inputs_batch.len()
         );
@@ -167,17 +348,26 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
             let proof = &proof_batch[i];
             let public_inputs = &public_inputs_batch[i];
 
-            // Steps 1 & 2 of the verifier algorithm are assumed to be completed by this point,
-            // by virtue of the type system. I.e., the proof should be deserialized in a manner such that
-            // elements not in the scalar field, and points not in G1, would cause a panic.
+            Self::step_1(proof)?;
+
+            // Step 2 of the verifier algorithm is assumed to be completed by this point,
+            // as the proof's evaluations are rejected at deserialization if they are not
+            // canonical scalar field elements.
 
             Self::step_3(public_inputs, vkey)?;
 
+            Self::check_proof_type(vkey, proof)?;
+
             let challenges = Self::step_4(vkey, proof, public_inputs)
                 .map_err(|_| VerifierError::ScalarConversion)?;
 
             let (domain_size, domain_elements, lagrange_basis_denominators) =
-                Self::prep_domain_and_basis_denominators(vkey.n, vkey.l as usize, challenges.zeta)?;
+                Self::prep_domain_and_basis_denominators(
+                    vkey.n,
+                    vkey.l as usize,
+                    challenges.zeta,
+                    vkey.lookup_vkey.is_some(),
+                )?;
 
             let zero_poly_eval = Self::step_5(domain_size, &challenges);
 
@@ -193,22 +383,23 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
             vkey_batch,
         );
 
-        let mut g1_lhs_elems = Vec::with_capacity(num_proofs);
-        let mut g1_rhs_elems = Vec::with_capacity(num_proofs);
-        let mut transcript_elements = Vec::with_capacity(num_proofs);
+        let mut evals_batch = Vec::with_capacity(num_proofs);
 
         for i in 0..num_proofs {
             let vkey = &vkey_batch[i];
             let proof = &proof_batch[i];
             let public_inputs = &public_inputs_batch[i];
 
-            let challenges = &challenges_batch[i];
+            let challenges = challenges_batch[i];
             let zero_poly_eval = zero_poly_evals_batch[i];
             let domain_elements = &domain_elements_batch[i];
             let lagrange_bases = &lagrange_bases_batch[i];
 
             let lagrange_1_eval = Self::step_6(lagrange_bases, domain_elements);
 
+            let lookup_domain_evals =
+                Self::lookup_domain_evals(vkey, lagrange_bases, domain_elements);
+
             let pi_eval = Self::step_7(
                 lagrange_1_eval,
                 lagrange_bases,
@@ -216,102 +407,172 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
                 public_inputs,
             );
 
-            let r_0 = Self::step_8(pi_eval, lagrange_1_eval, challenges, proof);
+            let r_0 = Self::step_8(
+                pi_eval,
+                lagrange_1_eval,
+                lookup_domain_evals,
+                &challenges,
+                proof,
+            );
+
+            evals_batch.push(PlonkProofEvals {
+                challenges,
+                zero_poly_eval,
+                lagrange_1_eval,
+                lookup_domain_evals,
+                r_0,
+                omega: domain_elements[1],
+            });
+        }
 
-            let d_1 = Self::step_9(zero_poly_eval, lagrange_1_eval, vkey, proof, challenges)?;
+        Ok(evals_batch)
+    }
 
-            // Increasing powers of v, starting w/ 1
-            let mut v_powers = [ScalarField::one(); NUM_WIRE_TYPES * 2];
-            for i in 1..NUM_WIRE_TYPES * 2 {
-                v_powers[i] = v_powers[i - 1] * challenges.v;
-            }
+    /// Accumulates the terms of the G1 elements used in the final KZG batch opening pairing check
+    /// for a batch of Plonk proofs, weighting the terms of each proof by the given power of `r`.
+    ///
+    /// The scalars of identical bases are accumulated across all of the proofs in the batch,
+    /// so that no scalar multiplication is done until all of the terms have been accumulated.
+    ///
+    /// The terms of the commitments of identical verification keys are keyed by the position of the first
+    /// such key in the batch, so that they are merged. A batch holds few distinct verification keys,
+    /// i.e. one per circuit, so each key is only compared against the distinct keys seen before it.
+    fn prep_batch_plonk_proofs_opening(
+        vkey_batch: &[ExtendedVerificationKey],
+        proof_batch: &[ExtendedProof],
+        evals_batch: &[PlonkProofEvals],
+        r_powers: &[ScalarField],
+        lhs_terms: &mut MsmAccumulator,
+        rhs_terms: &mut MsmAccumulator,
+    ) {
+        let mut distinct_vkeys: Vec<&ExtendedVerificationKey> = Vec::new();
+
+        for i in 0..vkey_batch.len() {
+            let vkey_index = distinct_vkeys
+                .iter()
+                .position(|vkey| **vkey == vkey_batch[i])
+                .unwrap_or_else(|| {
+                    distinct_vkeys.push(&vkey_batch[i]);
+                    distinct_vkeys.len() - 1
+                });
+
+            Self::accumulate_plonk_proof_opening(
+                &vkey_batch[i],
+                &proof_batch[i],
+                BatchPosition {
+                    vkey: vkey_index,
+                    proof: i,
+                },
+                &evals_batch[i],
+                r_powers[i],
+                lhs_terms,
+                rhs_terms,
+            );
+        }
+    }
+
+    /// Computes the LHS & RHS G1 elements used in the pairing check for a single Plonk proof
+    fn prep_plonk_proof_opening_elems(
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        evals: &PlonkProofEvals,
+    ) -> Result<(G1Affine, G1Affine), VerifierError> {
+        let mut lhs_terms = MsmAccumulator::default();
+        let mut rhs_terms = MsmAccumulator::default();
+
+        Self::accumulate_plonk_proof_opening(
+            vkey,
+            proof,
+            BatchPosition { vkey: 0, proof: 0 },
+            evals,
+            ScalarField::one(),
+            &mut lhs_terms,
+            &mut rhs_terms,
+        );
 
-            let f_1 = Self::step_10(d_1, &v_powers, vkey, proof)?;
+        Ok((lhs_terms.msm::<G>()?, rhs_terms.msm::<G>()?))
+    }
 
-            let neg_e_1 = Self::step_11(r_0, &v_powers, vkey, proof, challenges)?;
+    /// Accumulates the terms of the LHS & RHS G1 elements used in the pairing check
+    /// for a single Plonk proof at the given position in the batch, weighted by the given scalar.
+    ///
+    /// This is the G1 arithmetic done in steps 9 - 12 of the verifier algorithm.
+    fn accumulate_plonk_proof_opening(
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        position: BatchPosition,
+        evals: &PlonkProofEvals,
+        weight: ScalarField,
+        lhs_terms: &mut MsmAccumulator,
+        rhs_terms: &mut MsmAccumulator,
+    ) {
+        let challenges = &evals.challenges;
 
-            let (lhs_g1, rhs_g1) =
-                Self::step_12_part_1(f_1, neg_e_1, domain_elements[1], proof, challenges)?;
+        Self::step_9(evals, vkey, proof, position, weight, rhs_terms);
 
-            g1_lhs_elems.push(lhs_g1);
-            g1_rhs_elems.push(rhs_g1);
-            transcript_elements.push(challenges.u);
-        }
+        let v_powers = Self::compute_v_powers(proof, challenges);
 
-        Ok(OpeningElems {
-            g1_lhs_elems,
-            g1_rhs_elems,
-            transcript_elements,
-        })
+        Self::step_10(
+            &v_powers, vkey, proof, position, challenges, weight, rhs_terms,
+        );
+
+        Self::step_11(
+            evals.r_0, &v_powers, vkey, proof, challenges, weight, rhs_terms,
+        );
+
+        Self::step_12_part_1(
+            evals.omega,
+            proof,
+            position,
+            challenges,
+            weight,
+            lhs_terms,
+            rhs_terms,
+        );
     }
 
     /// Computes the elements used in the final KZG batch opening pairing check
-    /// for the linking proofs involved in the matching and settlement of a trade.
-    fn prep_match_linking_proofs_opening(
-        match_linking_vkeys: MatchLinkingVkeys,
-        match_linking_proofs: MatchLinkingProofs,
-        match_linking_wire_poly_comms: MatchLinkingWirePolyComms,
+    /// for the given links between the given Plonk proofs.
+    ///
+    /// Each link is opened over the commitments to the first wiring polynomials
+    /// of its source & target proofs, in that order.
+    fn prep_links_opening(
+        links: &[ProofLink],
+        proofs: &[ExtendedProof],
     ) -> Result<OpeningElems, VerifierError> {
-        let mut g1_lhs_elems = [G1Affine::default(); NUM_MATCH_LINKING_PROOFS];
-        let mut g1_rhs_elems = [G1Affine::default(); NUM_MATCH_LINKING_PROOFS];
-        let mut transcript_elements = [ScalarField::zero(); NUM_MATCH_LINKING_PROOFS];
-
-        // Prep the PARTY 0 VALID COMMITMENTS <-> VALID MATCH SETTLE linking proof opening elements
-        let (g1_lhs_0, g1_rhs_0, eta_0) = Self::prep_linking_proof_opening_elems(
-            match_linking_vkeys.valid_commitments_match_settle_0,
-            match_linking_proofs.valid_commitments_match_settle_0,
-            (
-                match_linking_wire_poly_comms.valid_commitments_0,
-                match_linking_wire_poly_comms.valid_match_settle,
-            ),
-        )?;
-        g1_lhs_elems[0] = g1_lhs_0;
-        g1_rhs_elems[0] = g1_rhs_0;
-        transcript_elements[0] = eta_0;
-
-        // Prep the PARTY 0 VALID REBLIND <-> PARTY 0 VALID COMMITMENTS linking proof opening elements
-        let (g1_lhs_1, g1_rhs_1, eta_1) = Self::prep_linking_proof_opening_elems(
-            match_linking_vkeys.valid_reblind_commitments,
-            match_linking_proofs.valid_reblind_commitments_0,
-            (
-                match_linking_wire_poly_comms.valid_reblind_0,
-                match_linking_wire_poly_comms.valid_commitments_0,
-            ),
-        )?;
-        g1_lhs_elems[1] = g1_lhs_1;
-        g1_rhs_elems[1] = g1_rhs_1;
-        transcript_elements[1] = eta_1;
-
-        // Prep the PARTY 1 VALID COMMITMENTS <-> VALID MATCH SETTLE linking proof opening elements
-        let (g1_lhs_2, g1_rhs_2, eta_2) = Self::prep_linking_proof_opening_elems(
-            match_linking_vkeys.valid_commitments_match_settle_1,
-            match_linking_proofs.valid_commitments_match_settle_1,
-            (
-                match_linking_wire_poly_comms.valid_commitments_1,
-                match_linking_wire_poly_comms.valid_match_settle,
-            ),
-        )?;
-        g1_lhs_elems[2] = g1_lhs_2;
-        g1_rhs_elems[2] = g1_rhs_2;
-        transcript_elements[2] = eta_2;
-
-        // Prep the PARTY 1 VALID REBLIND <-> PARTY 1 VALID COMMITMENTS linking proof opening elements
-        let (g1_lhs_3, g1_rhs_3, eta_3) = Self::prep_linking_proof_opening_elems(
-            match_linking_vkeys.valid_reblind_commitments,
-            match_linking_proofs.valid_reblind_commitments_1,
-            (
-                match_linking_wire_poly_comms.valid_reblind_1,
-                match_linking_wire_poly_comms.valid_commitments_1,
-            ),
-        )?;
-        g1_lhs_elems[3] = g1_lhs_3;
-        g1_rhs_elems[3] = g1_rhs_3;
-        transcript_elements[3] = eta_3;
+        let num_links = links.len();
+
+        let mut g1_lhs_elems = Vec::with_capacity(num_links);
+        let mut g1_rhs_elems = Vec::with_capacity(num_links);
+        let mut transcript_elements = Vec::with_capacity(num_links);
+
+        for link in links {
+            if link.source == link.target {
+                return Err(VerifierError::InvalidInputs);
+            }
+
+            let source_proof = proofs
+                .get(link.source)
+                .ok_or(VerifierError::InvalidInputs)?;
+            let target_proof = proofs
+                .get(link.target)
+                .ok_or(VerifierError::InvalidInputs)?;
+
+            let (g1_lhs, g1_rhs, eta) = Self::prep_linking_proof_opening_elems(
+                link.linking_vkey,
+                link.linking_proof,
+                (source_proof.wire_comms[0], target_proof.wire_comms[0]),
+            )?;
+
+            g1_lhs_elems.push(g1_lhs);
+            g1_rhs_elems.push(g1_rhs);
+            transcript_elements.push(eta);
+        }
 
         Ok(OpeningElems {
-            g1_lhs_elems: g1_lhs_elems.to_vec(),
-            g1_rhs_elems: g1_rhs_elems.to_vec(),
-            transcript_elements: transcript_elements.to_vec(),
+            g1_lhs_elems,
+            g1_rhs_elems,
+            transcript_elements,
         })
     }
 
@@ -332,6 +593,10 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         } = linking_proof;
         let one = ScalarField::one();
 
+        if !is_valid_g1(&linking_poly_opening) || !is_valid_g1(&linking_quotient_poly_comm) {
+            return Err(VerifierError::MalformedProof);
+        }
+
         // Compute eta challenge after absorbing commitments to wiring polynomials
         // and linking quotient polynomial into transcript
 
@@ -371,11 +636,15 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
     }
 
     /// Computes the evaluation domain elements and denominators of the
-    /// Lagrange basis polynomials for a proof
+    /// Lagrange basis polynomials for a proof.
+    ///
+    /// If the proof has a lookup argument, the last domain element, `omega^{-1}`,
+    /// and the denominator of its Lagrange basis polynomial are appended.
     fn prep_domain_and_basis_denominators(
         n: u64,
         l: usize,
         zeta: ScalarField,
+        has_lookup: bool,
     ) -> Result<(u64, Vec<ScalarField>, Vec<ScalarField>), VerifierError> {
         let domain_size = if n.is_power_of_two() {
             n
@@ -386,16 +655,23 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         let omega =
             ScalarField::get_root_of_unity(domain_size).ok_or(VerifierError::InvalidInputs)?;
 
-        let mut domain_elements: Vec<ScalarField> = Vec::with_capacity(l);
+        // We always compute at least the generator `omega`, which is used in the opening at `zeta * omega`
+        let mut domain_elements: Vec<ScalarField> = Vec::with_capacity(l.max(2) + 1);
         domain_elements.push(ScalarField::one());
-        for i in 0..l - 1 {
+        for i in 0..l.max(2) - 1 {
             domain_elements.push(domain_elements[i] * omega);
         }
 
-        let lagrange_basis_denominators: Vec<ScalarField> = (0..l)
+        let mut lagrange_basis_denominators: Vec<ScalarField> = (0..l)
             .map(|i| ScalarField::from(n) * (zeta - domain_elements[i]))
             .collect();
 
+        if has_lookup {
+            let omega_inv = omega.pow([domain_size - 1]);
+            domain_elements.push(omega_inv);
+            lagrange_basis_denominators.push(ScalarField::from(n) * (zeta - omega_inv));
+        }
+
         Ok((domain_size, domain_elements, lagrange_basis_denominators))
     }
 
@@ -404,7 +680,7 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
     fn batch_invert_lagrange_basis_denominators(
         lagrange_basis_denominators: &mut [ScalarField],
         zero_poly_evals_batch: &[ScalarField],
-        vkey_batch: &[VerificationKey],
+        vkey_batch: &[ExtendedVerificationKey],
     ) -> Vec<Vec<ScalarField>> {
         let batch_size = zero_poly_evals_batch.len();
         let mut lagrange_bases_batch = Vec::with_capacity(batch_size);
@@ -412,16 +688,19 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         batch_inversion(lagrange_basis_denominators);
         let mut lagrange_bases_cursor = 0;
         for i in 0..batch_size {
-            let l = vkey_batch[i].l as usize;
+            // Proofs with a lookup argument have an additional Lagrange basis, that of `omega^{-1}`
+            let num_bases =
+                vkey_batch[i].l as usize + usize::from(vkey_batch[i].lookup_vkey.is_some());
             let zero_poly_eval = zero_poly_evals_batch[i];
 
-            let mut lagrange_bases = Vec::with_capacity(l);
-            for d in &lagrange_basis_denominators[lagrange_bases_cursor..lagrange_bases_cursor + l]
+            let mut lagrange_bases = Vec::with_capacity(num_bases);
+            for d in &lagrange_basis_denominators
+                [lagrange_bases_cursor..lagrange_bases_cursor + num_bases]
             {
                 lagrange_bases.push(d * &zero_poly_eval);
             }
 
-            lagrange_bases_cursor += l;
+            lagrange_bases_cursor += num_bases;
 
             lagrange_bases_batch.push(lagrange_bases);
         }
@@ -429,25 +708,68 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         lagrange_bases_batch
     }
 
+    /// Validate that the commitments & opening proofs in the proof are points on the curve,
+    /// in the prime-order subgroup of G1
+    fn step_1(proof: &ExtendedProof) -> Result<(), VerifierError> {
+        let mut g1_elems = Vec::with_capacity(NUM_WIRE_TYPES * 2 + 3);
+        g1_elems.extend_from_slice(&proof.wire_comms);
+        g1_elems.push(proof.z_comm);
+        g1_elems.extend_from_slice(&proof.quotient_comms);
+        g1_elems.push(proof.w_zeta);
+        g1_elems.push(proof.w_zeta_omega);
+
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            g1_elems.push(lookup_proof.lookup_wire_comm);
+            g1_elems.push(lookup_proof.lookup_quotient_comm);
+            g1_elems.extend_from_slice(&lookup_proof.h_poly_comms);
+            g1_elems.push(lookup_proof.prod_lookup_poly_comm);
+        }
+
+        if !g1_elems.iter().all(is_valid_g1) {
+            return Err(VerifierError::MalformedProof);
+        }
+        Ok(())
+    }
+
     /// Validate public inputs
     ///
-    /// Similarly to the assumptions for step 2, the membership of the public inputs in the scalar field
-    /// should be enforced by the type system.
-    fn step_3(public_inputs: &PublicInputs, vkey: &VerificationKey) -> Result<(), VerifierError> {
+    /// The public inputs are assumed to be canonical scalar field elements,
+    /// which is enforced when they are deserialized.
+    fn step_3(
+        public_inputs: &PublicInputs,
+        vkey: &ExtendedVerificationKey,
+    ) -> Result<(), VerifierError> {
         if public_inputs.0.len() != vkey.l as usize {
             return Err(VerifierError::InvalidInputs);
         }
         Ok(())
     }
 
-    /// Compute the challenges
+    /// Check that the proof contains a lookup argument if and only if the verification key expects one
+    fn check_proof_type(
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+    ) -> Result<(), VerifierError> {
+        if vkey.lookup_vkey.is_some() != proof.lookup_proof.is_some() {
+            return Err(VerifierError::InvalidInputs);
+        }
+        Ok(())
+    }
+
+    /// Compute the challenges, using the transcript specified by the verification key
     fn step_4(
-        vkey: &VerificationKey,
-        proof: &Proof,
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
         public_inputs: &PublicInputs,
     ) -> Result<Challenges, SerdeError> {
-        let mut transcript = Transcript::<H>::new();
-        transcript.compute_plonk_challenges(vkey, proof, public_inputs)
+        match vkey.transcript_hash {
+            TranscriptHash::Keccak => {
+                Transcript::<H>::new().compute_plonk_challenges(vkey, proof, public_inputs)
+            }
+            TranscriptHash::Poseidon2 => {
+                PoseidonTranscript::new().compute_plonk_challenges(vkey, proof, public_inputs)
+            }
+        }
     }
 
     /// Evaluate the zero polynomial at the challenge point `zeta`
@@ -485,55 +807,100 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
     fn step_8(
         pi_eval: ScalarField,
         lagrange_1_eval: ScalarField,
+        lookup_domain_evals: Option<LookupDomainEvals>,
         challenges: &Challenges,
-        proof: &Proof,
+        proof: &ExtendedProof,
     ) -> ScalarField {
         let Challenges {
             alpha, beta, gamma, ..
         } = challenges;
-        let Proof {
-            wire_evals,
-            sigma_evals,
-            z_bar,
-            ..
-        } = proof;
+        let Proof { z_bar, .. } = &proof.proof;
+        let wire_evals = Self::all_wire_evals(proof);
+        let sigma_evals = Self::all_sigma_evals(proof);
+        let num_wires = wire_evals.len();
 
         let mut r_0 = pi_eval - lagrange_1_eval * *alpha * *alpha;
-        let mut evals_rlc = alpha * z_bar * (wire_evals[NUM_WIRE_TYPES - 1] + gamma);
-        for i in 0..NUM_WIRE_TYPES - 1 {
+        let mut evals_rlc = alpha * z_bar * (wire_evals[num_wires - 1] + gamma);
+        for i in 0..num_wires - 1 {
             evals_rlc *= wire_evals[i] + beta * &sigma_evals[i] + gamma;
         }
         r_0 -= evals_rlc;
 
+        if let (Some(lookup_proof), Some(lookup_domain_evals)) =
+            (&proof.lookup_proof, lookup_domain_evals)
+        {
+            r_0 += Self::lookup_r_0_term(
+                lagrange_1_eval,
+                lookup_domain_evals,
+                lookup_proof,
+                challenges,
+            );
+        }
+
         r_0
     }
 
-    /// Compute first part of batched polynomial commitment [D]1
+    /// Compute first part of batched polynomial commitment [D]1,
+    /// accumulating its terms weighted by the given scalar
     fn step_9(
-        zero_poly_eval: ScalarField,
-        lagrange_1_eval: ScalarField,
-        vkey: &VerificationKey,
-        proof: &Proof,
-        challenges: &Challenges,
-    ) -> Result<G1Affine, VerifierError> {
-        let points = [
-            Self::step_9_line_1(vkey, proof)?,
-            Self::step_9_line_2(lagrange_1_eval, vkey, proof, challenges)?,
-            Self::step_9_line_3(vkey, proof, challenges)?,
-            Self::step_9_line_4(zero_poly_eval, proof, challenges)?,
-        ];
-
-        G::msm(&[ScalarField::one(); 4], &points).map_err(Into::into)
+        evals: &PlonkProofEvals,
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        position: BatchPosition,
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let PlonkProofEvals {
+            challenges,
+            zero_poly_eval,
+            lagrange_1_eval,
+            lookup_domain_evals,
+            ..
+        } = evals;
+
+        Self::step_9_line_1(vkey, proof, position, weight, terms);
+        Self::step_9_line_2(
+            *lagrange_1_eval,
+            vkey,
+            proof,
+            position,
+            challenges,
+            weight,
+            terms,
+        );
+        Self::step_9_line_3(vkey, proof, position, challenges, weight, terms);
+        Self::step_9_line_4(*zero_poly_eval, proof, position, challenges, weight, terms);
+
+        if let (Some(lookup_proof), Some(lookup_domain_evals)) =
+            (&proof.lookup_proof, lookup_domain_evals)
+        {
+            Self::lookup_d_1_term(
+                evals,
+                *lookup_domain_evals,
+                &proof.wire_evals,
+                lookup_proof,
+                position,
+                weight,
+                terms,
+            );
+        }
     }
 
     /// MSM over selector polynomial commitments
-    fn step_9_line_1(vkey: &VerificationKey, proof: &Proof) -> Result<G1Affine, VerifierError> {
-        let VerificationKey { q_comms, .. } = vkey;
-        let Proof { wire_evals, .. } = proof;
+    fn step_9_line_1(
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        position: BatchPosition,
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let VerificationKey { q_comms, .. } = &vkey.vkey;
+        let Proof { wire_evals, .. } = &proof.proof;
 
         // We hardcode the gate identity used by the Jellyfish implementation here,
         // at the cost of some generality
-        G::msm(
+        terms.add_terms(
+            weight,
             &[
                 wire_evals[0],
                 wire_evals[1],
@@ -549,22 +916,21 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
                 ScalarField::one(),
                 wire_evals[0] * wire_evals[1] * wire_evals[2] * wire_evals[3] * wire_evals[4],
             ],
-            q_comms,
-        )
-        .map_err(Into::into)
+            &position.vkey_comms(VkeyComm::Selector, q_comms),
+        );
     }
 
     /// Scalar mul of grand product polynomial commitment
     fn step_9_line_2(
         lagrange_1_eval: ScalarField,
-        vkey: &VerificationKey,
-        proof: &Proof,
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        position: BatchPosition,
         challenges: &Challenges,
-    ) -> Result<G1Affine, VerifierError> {
-        let VerificationKey { k, .. } = vkey;
-        let Proof {
-            wire_evals, z_comm, ..
-        } = proof;
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let Proof { z_comm, .. } = &proof.proof;
         let Challenges {
             alpha,
             beta,
@@ -573,6 +939,8 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
             u,
             ..
         } = challenges;
+        let k = Self::all_k(vkey);
+        let wire_evals = Self::all_wire_evals(proof);
 
         let mut z_scalar_coeff = *alpha;
         for i in 0..wire_evals.len() {
@@ -580,46 +948,68 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         }
         z_scalar_coeff += lagrange_1_eval * alpha * alpha + u;
 
-        G::ec_scalar_mul(z_scalar_coeff, *z_comm).map_err(Into::into)
+        terms.add_term(
+            position.proof_comm(ProofComm::Z),
+            weight * z_scalar_coeff,
+            *z_comm,
+        );
     }
 
     /// Scalar mul of final permutation polynomial commitment
     fn step_9_line_3(
-        vkey: &VerificationKey,
-        proof: &Proof,
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        position: BatchPosition,
         challenges: &Challenges,
-    ) -> Result<G1Affine, VerifierError> {
-        let VerificationKey { sigma_comms, .. } = vkey;
-        let Proof {
-            wire_evals,
-            sigma_evals,
-            z_bar,
-            ..
-        } = proof;
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let Proof { z_bar, .. } = &proof.proof;
         let Challenges {
             alpha, beta, gamma, ..
         } = challenges;
+        let wire_evals = Self::all_wire_evals(proof);
+        let sigma_evals = Self::all_sigma_evals(proof);
+
+        let (final_sigma_key, final_sigma_comm) = match &vkey.lookup_vkey {
+            Some(lookup_vkey) => (VkeyComm::LookupSigma, lookup_vkey.lookup_sigma_comm),
+            None => (
+                VkeyComm::Sigma(NUM_WIRE_TYPES - 1),
+                vkey.sigma_comms[NUM_WIRE_TYPES - 1],
+            ),
+        };
 
         let mut final_sigma_scalar_coeff = ScalarField::one();
-        for i in 0..NUM_WIRE_TYPES - 1 {
+        for i in 0..sigma_evals.len() {
             final_sigma_scalar_coeff *= wire_evals[i] + beta * &sigma_evals[i] + gamma
         }
         final_sigma_scalar_coeff *= alpha * beta * z_bar;
 
-        G::ec_scalar_mul(-final_sigma_scalar_coeff, sigma_comms[NUM_WIRE_TYPES - 1])
-            .map_err(Into::into)
+        terms.add_term(
+            position.vkey_comm(final_sigma_key),
+            -weight * final_sigma_scalar_coeff,
+            final_sigma_comm,
+        );
     }
 
     /// MSM over split quotient polynomial commitments
     fn step_9_line_4(
         zero_poly_eval: ScalarField,
-        proof: &Proof,
+        proof: &ExtendedProof,
+        position: BatchPosition,
         challenges: &Challenges,
-    ) -> Result<G1Affine, VerifierError> {
-        let Proof { quotient_comms, .. } = proof;
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
         let Challenges { zeta, .. } = challenges;
         let one = ScalarField::one();
 
+        // Proofs with a lookup argument split the quotient polynomial into one more part
+        let mut quotient_comms = proof.quotient_comms.to_vec();
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            quotient_comms.push(lookup_proof.lookup_quotient_comm);
+        }
+
         // In the Jellyfish implementation, they multiply each split quotient commtiment by increaseing powers of
         // zeta^{n+2}, as opposed to zeta^n, as in the paper.
         // This is in order to "achieve better balance among degrees of all splitting
@@ -628,32 +1018,67 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         let zeta_to_n_plus_two = (zero_poly_eval + one) * zeta * zeta;
 
         // Increasing powers of zeta^{n+2}, starting w/ 1
-        let mut split_quotients_scalars = [one; NUM_WIRE_TYPES];
-        for i in 1..NUM_WIRE_TYPES {
+        let mut split_quotients_scalars = vec![one; quotient_comms.len()];
+        for i in 1..quotient_comms.len() {
             split_quotients_scalars[i] = split_quotients_scalars[i - 1] * zeta_to_n_plus_two;
         }
 
-        let split_quotients_sum = G::msm(&split_quotients_scalars, quotient_comms)?;
-
-        G::ec_scalar_mul(-zero_poly_eval, split_quotients_sum).map_err(Into::into)
+        terms.add_terms(
+            -weight * zero_poly_eval,
+            &split_quotients_scalars,
+            &position.proof_comms(ProofComm::Quotient, &quotient_comms),
+        );
     }
 
-    /// Compute full batched polynomial commitment [F]1
+    /// Compute the rest of the full batched polynomial commitment [F]1,
+    /// the first part of which, [D]1, is accumulated in step 9
     fn step_10(
-        d_1: G1Affine,
-        v_powers: &[ScalarField; NUM_WIRE_TYPES * 2],
-        vkey: &VerificationKey,
-        proof: &Proof,
-    ) -> Result<G1Affine, VerifierError> {
-        let VerificationKey { sigma_comms, .. } = vkey;
-        let Proof { wire_comms, .. } = proof;
+        v_powers: &[ScalarField],
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
+        position: BatchPosition,
+        challenges: &Challenges,
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let VerificationKey { sigma_comms, .. } = &vkey.vkey;
+        let Proof { wire_comms, .. } = &proof.proof;
+        let Challenges { u, .. } = challenges;
 
-        let mut points = Vec::with_capacity(NUM_WIRE_TYPES * 2);
-        points.extend_from_slice(&[d_1]);
-        points.extend_from_slice(wire_comms);
-        points.extend_from_slice(&sigma_comms[..NUM_WIRE_TYPES - 1]);
+        // The first power of `v`, i.e. 1, weights [D]1
+        let mut scalars = v_powers[1..].to_vec();
+        let mut points = Vec::with_capacity(v_powers.len() + NUM_LOOKUP_ZETA_OMEGA_EVALS);
+        points.extend(position.proof_comms(ProofComm::Wire, wire_comms));
+
+        match (&vkey.lookup_vkey, &proof.lookup_proof) {
+            (Some(lookup_vkey), Some(lookup_proof)) => {
+                points.push((
+                    position.proof_comm(ProofComm::Wire(NUM_WIRE_TYPES)),
+                    lookup_proof.lookup_wire_comm,
+                ));
+                // The lookup wire is the last wire, so all of the TurboPlonk permutation polynomials are opened
+                points.extend(position.vkey_comms(VkeyComm::Sigma, sigma_comms));
+                points.extend_from_slice(&lookup_zeta_comms(position, lookup_vkey, lookup_proof));
+
+                // The polynomials opened at `zeta * omega` are weighted by increasing powers of `v` starting w/ `v`,
+                // scaled by `u`. The grand product polynomial, weighted by `u`, is already accounted for in [D]1.
+                points.extend_from_slice(&lookup_zeta_omega_comms(
+                    position,
+                    lookup_vkey,
+                    lookup_proof,
+                    wire_comms,
+                ));
+                scalars.extend(
+                    v_powers[1..NUM_LOOKUP_ZETA_OMEGA_EVALS + 1]
+                        .iter()
+                        .map(|v_power| *v_power * u),
+                );
+            }
+            _ => points
+                .extend(position.vkey_comms(VkeyComm::Sigma, &sigma_comms[..NUM_WIRE_TYPES - 1])),
+        }
 
-        G::msm(v_powers, &points).map_err(Into::into)
+        terms.add_terms(weight, &scalars, &points);
     }
 
     /// Compute group-encoded batch evaluation [E]1
@@ -661,60 +1086,118 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
     /// We negate the scalar here to obtain -[E]1 so that we can avoid another EC scalar mul in step 12
     fn step_11(
         r_0: ScalarField,
-        v_powers: &[ScalarField; NUM_WIRE_TYPES * 2],
-        vkey: &VerificationKey,
-        proof: &Proof,
+        v_powers: &[ScalarField],
+        vkey: &ExtendedVerificationKey,
+        proof: &ExtendedProof,
         challenges: &Challenges,
-    ) -> Result<G1Affine, VerifierError> {
-        let VerificationKey { g, .. } = vkey;
-        let Proof {
-            wire_evals,
-            sigma_evals,
-            z_bar,
-            ..
-        } = proof;
+        weight: ScalarField,
+        terms: &mut MsmAccumulator,
+    ) {
+        let VerificationKey { g, .. } = &vkey.vkey;
+        let Proof { z_bar, .. } = &proof.proof;
         let Challenges { u, .. } = challenges;
 
-        let mut e = -r_0;
-        for i in 0..NUM_WIRE_TYPES {
-            e += v_powers[i + 1] * wire_evals[i];
+        // The evaluations at `zeta` are weighted by increasing powers of `v`, starting w/ `v`
+        let mut zeta_evals = Self::all_wire_evals(proof);
+        zeta_evals.extend(Self::all_sigma_evals(proof));
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            zeta_evals.extend(lookup_zeta_evals(&lookup_proof.lookup_evals));
         }
-        for i in 0..NUM_WIRE_TYPES - 1 {
-            e += v_powers[i + NUM_WIRE_TYPES + 1] * sigma_evals[i];
+
+        let mut e = -r_0;
+        for i in 0..zeta_evals.len() {
+            e += v_powers[i + 1] * zeta_evals[i];
         }
         e += u * z_bar;
 
-        G::ec_scalar_mul(-e, *g).map_err(Into::into)
+        // The evaluations at `zeta * omega` are weighted by `u`, and increasing powers of `v` starting w/ `v`
+        // after the grand product polynomial evaluation
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            let zeta_omega_evals = lookup_zeta_omega_evals(&lookup_proof.lookup_evals);
+            for i in 0..NUM_LOOKUP_ZETA_OMEGA_EVALS {
+                e += u * v_powers[i + 1] * zeta_omega_evals[i];
+            }
+        }
+
+        terms.add_term(TermKey::Generator, -weight * e, *g);
     }
 
     /// Compute G1 elements to be used in the final pairing check
     /// for the given proof.
     ///
     /// This is the final G1 arithmetic done in step 12 of the verifier algorithm
-    /// before the pairing check.
+    /// before the pairing check. [F]1 & -[E]1 have already been accumulated into the RHS terms.
     fn step_12_part_1(
-        f_1: G1Affine,
-        neg_e_1: G1Affine,
         omega: ScalarField,
-        proof: &Proof,
+        proof: &ExtendedProof,
+        position: BatchPosition,
         challenges: &Challenges,
-    ) -> Result<(G1Affine, G1Affine), VerifierError> {
+        weight: ScalarField,
+        lhs_terms: &mut MsmAccumulator,
+        rhs_terms: &mut MsmAccumulator,
+    ) {
         let Proof {
             w_zeta,
             w_zeta_omega,
             ..
-        } = proof;
+        } = &proof.proof;
         let Challenges { zeta, u, .. } = challenges;
         let one = ScalarField::one();
+        let opening_comms = [
+            (position.proof_comm(ProofComm::WZeta), *w_zeta),
+            (position.proof_comm(ProofComm::WZetaOmega), *w_zeta_omega),
+        ];
 
-        let lhs = G::msm(&[one, *u], &[*w_zeta, *w_zeta_omega])?;
+        lhs_terms.add_terms(weight, &[one, *u], &opening_comms);
 
-        let rhs = G::msm(
-            &[*zeta, *u * *zeta * omega, one, one],
-            &[*w_zeta, *w_zeta_omega, f_1, neg_e_1],
-        )?;
+        rhs_terms.add_terms(weight, &[*zeta, *u * *zeta * omega], &opening_comms);
+    }
+
+    /// Computes increasing powers of the challenge `v`, starting w/ 1,
+    /// one for the batched polynomial commitment [D]1 and one for each polynomial opened at `zeta`
+    fn compute_v_powers(proof: &ExtendedProof, challenges: &Challenges) -> Vec<ScalarField> {
+        // We open all of the wire polynomials, and all but the last permutation polynomial
+        let mut num_powers = NUM_WIRE_TYPES * 2;
+        if proof.lookup_proof.is_some() {
+            num_powers += 2 + NUM_LOOKUP_ZETA_EVALS;
+        }
+
+        let mut v_powers = vec![ScalarField::one(); num_powers];
+        for i in 1..num_powers {
+            v_powers[i] = v_powers[i - 1] * challenges.v;
+        }
+
+        v_powers
+    }
+
+    /// Returns the coset constants for all of the wires,
+    /// including the lookup wire if the verification key expects a lookup argument
+    fn all_k(vkey: &ExtendedVerificationKey) -> Vec<ScalarField> {
+        let mut k = vkey.k.to_vec();
+        if let Some(lookup_vkey) = &vkey.lookup_vkey {
+            k.push(lookup_vkey.lookup_k);
+        }
+        k
+    }
 
-        Ok((lhs, rhs))
+    /// Returns the evaluations of all of the wire polynomials at `zeta`,
+    /// including the lookup wire if the proof contains a lookup argument
+    fn all_wire_evals(proof: &ExtendedProof) -> Vec<ScalarField> {
+        let mut wire_evals = proof.wire_evals.to_vec();
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            wire_evals.push(lookup_proof.lookup_wire_eval);
+        }
+        wire_evals
+    }
+
+    /// Returns the evaluations of all but the last permutation polynomial at `zeta`,
+    /// which includes that of the last TurboPlonk wire if the proof contains a lookup argument
+    fn all_sigma_evals(proof: &ExtendedProof) -> Vec<ScalarField> {
+        let mut sigma_evals = proof.sigma_evals.to_vec();
+        if let Some(lookup_proof) = &proof.lookup_proof {
+            sigma_evals.push(lookup_proof.last_sigma_eval);
+        }
+        sigma_evals
     }
 
     /// Compute the final pairing check for a batch of proofs.
@@ -735,7 +1218,21 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
         x_h: G2Affine,
         h: G2Affine,
     ) -> Result<bool, VerifierError> {
-        let num_proofs = opening_elems.g1_lhs_elems.len();
+        let r_powers = Self::compute_r_powers(&opening_elems.transcript_elements)?;
+
+        // Compute the random linear combinations of G1 elements for the verification instances.
+        let lhs_rlc = G::msm(&r_powers, &opening_elems.g1_lhs_elems)?;
+        let rhs_rlc = G::msm(&r_powers, &opening_elems.g1_rhs_elems)?;
+
+        Self::pairing_check(lhs_rlc, rhs_rlc, x_h, h)
+    }
+
+    /// Computes successive powers of the challenge `r`, starting w/ 1, which are the coefficients
+    /// of the random linear combination of the openings in a batch, as described in [`Verifier::batch_opening`]
+    fn compute_r_powers(
+        transcript_elements: &[ScalarField],
+    ) -> Result<Vec<ScalarField>, VerifierError> {
+        let num_proofs = transcript_elements.len();
 
         let r = if num_proofs == 1 {
             // No need to incur an extra multiplication when only 1 proof is being verified
@@ -748,89 +1245,306 @@ impl<G: G1ArithmeticBackend, H: HashBackend> Verifier<G, H> {
 
             let mut transcript = Transcript::<H>::new();
 
-            transcript.append_message(&serialize_scalars_for_transcript(
-                &opening_elems.transcript_elements,
-            ));
+            transcript.append_message(&serialize_scalars_for_transcript(transcript_elements));
             transcript
                 .get_and_append_challenge()
                 .map_err(|_| VerifierError::ScalarConversion)?
         };
 
-        // Compute successive powers of `r`, these are the coefficients in the random linear combination
         let mut r_powers = vec![ScalarField::one(); num_proofs];
         for i in 1..num_proofs {
             r_powers[i] = r_powers[i - 1] * r;
         }
 
-        // Compute the random linear combinations of G1 elements for the verification instances.
-        let lhs_rlc = G::msm(&r_powers, &opening_elems.g1_lhs_elems)?;
-        let rhs_rlc = G::msm(&r_powers, &opening_elems.g1_rhs_elems)?;
+        Ok(r_powers)
+    }
 
-        G::ec_pairing_check(lhs_rlc, x_h, -rhs_rlc, h).map_err(Into::into)
+    /// Checks the pairing identity e(lhs, [x]2) == e(rhs, [1]2)
+    ///
+    /// The G2 points are taken from the verification key, and are assumed to have
+    /// been validated when the verification key was registered, see [`validate_vkey`]
+    fn pairing_check(
+        lhs_g1: G1Affine,
+        rhs_g1: G1Affine,
+        x_h: G2Affine,
+        h: G2Affine,
+    ) -> Result<bool, VerifierError> {
+        G::ec_pairing_check(lhs_g1, x_h, -rhs_g1, h).map_err(Into::into)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use alloc::vec;
-    use arbitrum_client::conversion::to_contract_link_proof;
-    use ark_bn254::Bn254;
-    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
-    use ark_ff::One;
-    use ark_std::UniformRand;
-    use circuit_types::{srs::SYSTEM_SRS, traits::SingleProverCircuit, ProofLinkingHint};
-    use circuits::zk_circuits::VALID_REBLIND_COMMITMENTS_LINK;
-    use constants::SystemCurve;
-    use contracts_common::{
-        backends::G1ArithmeticError,
-        custom_serde::statement_to_public_inputs,
-        types::{
-            G1Affine, G2Affine, LinkingProof, LinkingVerificationKey, OpeningElems, ScalarField,
-        },
-    };
-    use contracts_utils::{
-        constants::DUMMY_CIRCUIT_SRS_DEGREE,
-        conversion::to_linking_vkey,
-        crypto::NativeHasher,
-        proof_system::{
-            dummy_renegade_circuits::{
-                DummyValidCommitments, DummyValidCommitmentsWitness, DummyValidReblind,
-                DummyValidReblindWitness,
-            },
-            test_data::{
-                dummy_circuit_type, gen_verification_bundle, generate_match_bundle,
-                mutate_random_linking_proof, mutate_random_plonk_proof,
-            },
-        },
-    };
-    use jf_primitives::pcs::StructuredReferenceString;
-    use jf_utils::multi_pairing;
-    use mpc_plonk::{proof_system::PlonkKzgSnark, transcript::SolidityTranscript};
-    use rand::{thread_rng, CryptoRng, Rng, RngCore};
+/// The challenges & evaluations computed for a Plonk proof prior to the G1 arithmetic in its opening
+#[derive(Clone, Copy)]
+struct PlonkProofEvals {
+    /// The Fiat-Shamir challenges derived from the transcript
+    challenges: Challenges,
+    /// The evaluation of the zero polynomial at `zeta`
+    zero_poly_eval: ScalarField,
+    /// The evaluation of the first Lagrange basis polynomial at `zeta`
+    lagrange_1_eval: ScalarField,
+    /// The domain-dependent evaluations used in the lookup argument, if the proof has one
+    lookup_domain_evals: Option<LookupDomainEvals>,
+    /// The constant term of the linearization polynomial
+    r_0: ScalarField,
+    /// The generator of the evaluation domain
+    omega: ScalarField,
+}
+
+/// The position of a Plonk proof & its verification key within a batch
+#[derive(Clone, Copy)]
+struct BatchPosition {
+    /// The index of the verification key among the distinct verification keys in the batch
+    vkey: usize,
+    /// The index of the proof in the batch
+    proof: usize,
+}
+
+impl BatchPosition {
+    /// Returns the key of the given commitment of the verification key
+    fn vkey_comm(self, comm: VkeyComm) -> TermKey {
+        TermKey::Vkey(self.vkey, comm)
+    }
+
+    /// Returns the given commitments of the verification key along with their keys,
+    /// the commitment of each key being indexed by its position in the given slice
+    fn vkey_comms(
+        self,
+        comm: fn(usize) -> VkeyComm,
+        comms: &[G1Affine],
+    ) -> Vec<(TermKey, G1Affine)> {
+        comms
+            .iter()
+            .enumerate()
+            .map(|(i, base)| (self.vkey_comm(comm(i)), *base))
+            .collect()
+    }
+
+    /// Returns the key of the given commitment of the proof
+    fn proof_comm(self, comm: ProofComm) -> TermKey {
+        TermKey::Proof(self.proof, comm)
+    }
+
+    /// Returns the given commitments of the proof along with their keys,
+    /// the commitment of each key being indexed by its position in the given slice
+    fn proof_comms(
+        self,
+        comm: fn(usize) -> ProofComm,
+        comms: &[G1Affine],
+    ) -> Vec<(TermKey, G1Affine)> {
+        comms
+            .iter()
+            .enumerate()
+            .map(|(i, base)| (self.proof_comm(comm(i)), *base))
+            .collect()
+    }
+}
+
+/// A commitment in a verification key used as the base of an MSM term
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum VkeyComm {
+    /// The selector commitment with the given index
+    Selector(usize),
+    /// The permutation commitment with the given index
+    Sigma(usize),
+    /// The permutation commitment of the lookup wire
+    LookupSigma,
+    /// The range table commitment
+    RangeTable,
+    /// The key table commitment
+    KeyTable,
+    /// The lookup selector commitment
+    QLookup,
+    /// The table domain separation commitment
+    TableDomSep,
+    /// The domain separation selector commitment
+    QDomSep,
+}
 
-    use super::{G1ArithmeticBackend, Verifier};
+/// A commitment in a proof used as the base of an MSM term
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ProofComm {
+    /// The wire commitment with the given index, the lookup wire's being the last
+    Wire(usize),
+    /// The grand product commitment
+    Z,
+    /// The split quotient commitment with the given index, the lookup argument's being the last
+    Quotient(usize),
+    /// The lookup grand product commitment
+    ProdLookup,
+    /// The sorted lookup vector commitment with the given index
+    H(usize),
+    /// The opening proof at `zeta`
+    WZeta,
+    /// The opening proof at `zeta * omega`
+    WZetaOmega,
+}
+
+/// Identifies the base of an MSM term by its origin in the batch, so that the terms of
+/// identical bases can be merged without comparing the bases themselves
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum TermKey {
+    /// A commitment of the verification key with the given index among the distinct keys in the batch
+    Vkey(usize, VkeyComm),
+    /// A commitment of the proof with the given index in the batch
+    Proof(usize, ProofComm),
+    /// An opening element of the linking proof with the given index in the batch
+    Link(usize),
+    /// The generator of G1 in the SRS, which is shared by all of the verification keys in the batch
+    Generator,
+}
+
+/// The terms of a multi-scalar multiplication over G1, accumulated before any scalar multiplication is done.
+///
+/// The scalars of terms with the same key are summed, so that each distinct base is only multiplied once.
+#[derive(Default)]
+struct MsmAccumulator {
+    /// The scalars of the accumulated terms
+    scalars: Vec<ScalarField>,
+    /// The distinct bases of the accumulated terms
+    bases: Vec<G1Affine>,
+    /// The index of the accumulated term of each key
+    term_indices: BTreeMap<TermKey, usize>,
+}
 
-    pub struct ArkG1ArithmeticBackend;
-    impl G1ArithmeticBackend for ArkG1ArithmeticBackend {
-        fn ec_add(a: G1Affine, b: G1Affine) -> Result<G1Affine, G1ArithmeticError> {
-            Ok((a + b).into_affine())
+impl MsmAccumulator {
+    /// Adds a term to the MSM, summing its scalar into that of an existing term with the same key
+    fn add_term(&mut self, key: TermKey, scalar: ScalarField, base: G1Affine) {
+        match self.term_indices.entry(key) {
+            Entry::Occupied(entry) => self.scalars[*entry.get()] += scalar,
+            Entry::Vacant(entry) => {
+                entry.insert(self.bases.len());
+                self.scalars.push(scalar);
+                self.bases.push(base);
+            }
         }
-        fn ec_scalar_mul(a: ScalarField, b: G1Affine) -> Result<G1Affine, G1ArithmeticError> {
-            let mut b_group = b.into_group();
-            b_group *= a;
-            Ok(b_group.into_affine())
+    }
+
+    /// Adds the given keyed terms to the MSM, each of their scalars multiplied by the given weight
+    fn add_terms(
+        &mut self,
+        weight: ScalarField,
+        scalars: &[ScalarField],
+        bases: &[(TermKey, G1Affine)],
+    ) {
+        debug_assert_eq!(scalars.len(), bases.len());
+        for (scalar, (key, base)) in scalars.iter().zip(bases) {
+            self.add_term(*key, weight * scalar, *base);
         }
-        fn ec_pairing_check(
-            a_1: G1Affine,
-            b_1: G2Affine,
-            a_2: G1Affine,
-            b_2: G2Affine,
-        ) -> Result<bool, G1ArithmeticError> {
-            Ok(multi_pairing::<Bn254>(&[a_1, a_2], &[b_1, b_2]).0
-                == <Bn254 as Pairing>::TargetField::one())
+    }
+
+    /// Adds the opening elements of the linking proofs in the batch to the MSM,
+    /// each weighted by the given scalar
+    fn add_link_terms(&mut self, scalars: &[ScalarField], bases: &[G1Affine]) {
+        debug_assert_eq!(scalars.len(), bases.len());
+        for (i, (scalar, base)) in scalars.iter().zip(bases).enumerate() {
+            self.add_term(TermKey::Link(i), *scalar, *base);
         }
     }
 
+    /// Computes the MSM over the accumulated terms
+    fn msm<G: G1ArithmeticBackend>(&self) -> Result<G1Affine, G1ArithmeticError> {
+        G::msm(&self.scalars, &self.bases)
+    }
+}
+
+/// Validate that the G2 points of the verification key are on the curve,
+/// in the prime-order subgroup of G2.
+///
+/// This is checked once, when the verification key is registered, rather than on every verification
+pub fn validate_vkey(vkey: &VerificationKey) -> Result<(), VerifierError> {
+    if !is_valid_g2(&vkey.x_h) || !is_valid_g2(&vkey.h) {
+        return Err(VerifierError::MalformedProof);
+    }
+    Ok(())
+}
+
+/// Whether the given point is on the curve & in the prime-order subgroup of G1
+fn is_valid_g1(point: &G1Affine) -> bool {
+    point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+}
+
+/// Whether the given point is on the curve & in the prime-order subgroup of G2
+fn is_valid_g2(point: &G2Affine) -> bool {
+    point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::vec;
+    use arbitrum_client::conversion::to_contract_link_proof;
+    use ark_ec::AffineRepr;
+    use ark_ff::{One, PrimeField};
+    use ark_std::UniformRand;
+    use circuit_types::{srs::SYSTEM_SRS, traits::SingleProverCircuit, ProofLinkingHint};
+    use circuits::zk_circuits::VALID_REBLIND_COMMITMENTS_LINK;
+    use constants::SystemCurve;
+    use contracts_common::{
+        constants::{NUM_MATCH_LINKING_PROOFS, NUM_MATCH_PROOFS},
+        custom_serde::statement_to_public_inputs,
+        serde_def_types::SerdeScalarField,
+        types::{
+            ExtendedProof, ExtendedVerificationKey, G1Affine, G1BaseField, G2Affine, G2BaseField,
+            LinkedBundle, LinkingProof, LinkingVerificationKey, OpeningElems, PublicInputs,
+            ScalarField, TranscriptHash,
+        },
+    };
+    use contracts_utils::{
+        constants::DUMMY_CIRCUIT_SRS_DEGREE,
+        conversion::to_linking_vkey,
+        proof_system::{
+            dummy_renegade_circuits::{
+                DummyValidCommitments, DummyValidCommitmentsWitness, DummyValidReblind,
+                DummyValidReblindWitness,
+            },
+            test_data::{
+                dummy_circuit_type, gen_linked_bundle, gen_lookup_verification_bundle,
+                gen_poseidon_verification_bundle, gen_verification_bundle, generate_match_bundle,
+                mutate_random_bundle_link, mutate_random_linking_proof, mutate_random_plonk_proof,
+            },
+        },
+    };
+    use jf_primitives::pcs::StructuredReferenceString;
+    use mpc_plonk::{proof_system::PlonkKzgSnark, transcript::SolidityTranscript};
+    use rand::{seq::SliceRandom, thread_rng, CryptoRng, Rng, RngCore};
+    use std::sync::{Mutex, MutexGuard};
+
+    use super::{
+        diagnostics::VerificationStep,
+        profiling::{CountingG1ArithmeticBackend, CountingHasher, OpCounts, ProfiledStep},
+        validate_vkey, MsmAccumulator, TermKey, Verifier, VerifierError,
+    };
+    use crate::native::{NativeG1ArithmeticBackend, NativeHasher};
+
+    /// The number of unlinked proofs to include in a linked bundle when testing linked bundle verification
+    const NUM_UNLINKED_BUNDLE_PROOFS: usize = 1;
+
+    /// The number of linked proof pairs to include in a linked bundle when testing linked bundle verification
+    const NUM_LINKED_BUNDLE_PAIRS: usize = 2;
+
+    /// The number of proofs to include in a batch when testing batch verification
+    const NUM_BATCHED_PROOFS: usize = 3;
+
+    /// The number of matches to include in a batch when testing batched match verification
+    const NUM_BATCHED_MATCHES: usize = 3;
+
+    /// A verifier whose precompile calls & hashes are counted
+    type CountingVerifier = Verifier<
+        CountingG1ArithmeticBackend<NativeG1ArithmeticBackend>,
+        CountingHasher<NativeHasher>,
+    >;
+
+    /// Serializes the tests using the counting backends, as their counts are kept in global counters
+    static COUNTING_BACKENDS_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Acquires the lock on the counting backends, regardless of whether a test holding it panicked
+    fn lock_counting_backends() -> MutexGuard<'static, ()> {
+        COUNTING_BACKENDS_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Generate a single linking proof and the associated data needed
     /// to verify it.
     ///
@@ -893,7 +1607,7 @@ mod tests {
         let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
         let public_inputs = statement_to_public_inputs(&statement).unwrap();
         let result =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
                 .unwrap();
 
         assert!(result, "valid proof did not verify");
@@ -906,12 +1620,311 @@ mod tests {
         let public_inputs = statement_to_public_inputs(&statement).unwrap();
         proof.z_bar += ScalarField::one();
         let result =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
                 .unwrap();
 
         assert!(!result, "invalid proof verified");
     }
 
+    #[test]
+    fn test_valid_proof_trace() {
+        let mut rng = thread_rng();
+        let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+        let public_inputs = statement_to_public_inputs(&statement).unwrap();
+
+        let trace = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_with_trace(
+            &vkey,
+            &proof,
+            &public_inputs,
+        );
+
+        assert!(trace.verified(), "valid proof did not verify");
+        assert!(trace.challenges.is_some() && trace.opening_elems.is_some());
+    }
+
+    #[test]
+    fn test_invalid_public_inputs_length_trace() {
+        let mut rng = thread_rng();
+        let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+        let mut public_inputs = statement_to_public_inputs(&statement).unwrap();
+        public_inputs.0.pop();
+
+        let trace = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_with_trace(
+            &vkey,
+            &proof,
+            &public_inputs,
+        );
+
+        assert_eq!(
+            trace.failed_step,
+            Some(VerificationStep::PublicInputsLength)
+        );
+        assert_eq!(trace.error, Some(VerifierError::InvalidInputs));
+        assert!(trace.challenges.is_none());
+    }
+
+    #[test]
+    fn test_invalid_proof_trace() {
+        let mut rng = thread_rng();
+        let (statement, mut proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+        let public_inputs = statement_to_public_inputs(&statement).unwrap();
+        proof.z_bar += ScalarField::one();
+
+        let trace = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_with_trace(
+            &vkey,
+            &proof,
+            &public_inputs,
+        );
+
+        assert_eq!(trace.failed_step, Some(VerificationStep::Pairing));
+        assert!(trace.error.is_none());
+        assert!(trace.r_0.is_some() && trace.opening_elems.is_some());
+    }
+
+    #[test]
+    fn test_match_profile() {
+        let mut rng = thread_rng();
+        let (
+            match_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_vkeys,
+            match_linking_proofs,
+            _,
+        ) = generate_match_bundle(&mut rng).unwrap();
+
+        let _lock = lock_counting_backends();
+        let profile = CountingVerifier::profile_verify_match(
+            match_vkeys,
+            match_linking_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_proofs,
+        )
+        .unwrap();
+
+        assert!(profile.verified, "valid match did not verify");
+
+        let step_counts = |step| {
+            profile
+                .steps
+                .iter()
+                .find(|(s, _)| *s == step)
+                .map(|(_, counts)| *counts)
+                .unwrap()
+        };
+
+        // A single pairing check is done for the whole bundle
+        assert_eq!(profile.total().pairing_checks, 1);
+        assert_eq!(step_counts(ProfiledStep::Pairing).pairing_checks, 1);
+
+        // One linking challenge, computed from two hashes, is derived per link
+        assert_eq!(
+            step_counts(ProfiledStep::LinkingProofs).hash_calls,
+            2 * NUM_MATCH_LINKING_PROOFS as u64
+        );
+
+        // The Plonk challenges of every proof are derived by hashing
+        let challenge_counts = step_counts(ProfiledStep::ChallengeDerivation);
+        assert!(challenge_counts.hash_calls >= NUM_MATCH_PROOFS as u64);
+        assert!(challenge_counts.bytes_hashed > 0);
+        assert_eq!(challenge_counts.precompile_gas(), 0);
+
+        let total = profile.total();
+        assert_eq!(
+            total.estimated_gas(),
+            total.precompile_gas() + total.hash_gas
+        );
+    }
+
+    #[test]
+    fn test_off_curve_proof_commitment() {
+        let mut rng = thread_rng();
+        let (statement, mut proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+        let public_inputs = statement_to_public_inputs(&statement).unwrap();
+        proof.wire_comms[0] = G1Affine::new_unchecked(
+            proof.wire_comms[0].x,
+            proof.wire_comms[0].y + G1BaseField::one(),
+        );
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs);
+
+        assert_eq!(result, Err(VerifierError::MalformedProof));
+    }
+
+    #[test]
+    fn test_non_canonical_proof_evaluation() {
+        let mut rng = thread_rng();
+        let (_, proof, _) = gen_verification_bundle(&mut rng).unwrap();
+        let mut proof_bytes = postcard::to_allocvec(&proof).unwrap();
+
+        // The proof is not a lookup proof, so its serialization ends with `z_bar`,
+        // followed by the tag of the empty lookup proof.
+        // The modulus represents zero, but is not its canonical encoding
+        let z_bar_bytes = postcard::to_allocvec(&SerdeScalarField(proof.z_bar)).unwrap();
+        let non_canonical_bytes = postcard::to_allocvec(&SerdeScalarField(
+            ScalarField::new_unchecked(ScalarField::MODULUS),
+        ))
+        .unwrap();
+        let z_bar_offset = proof_bytes.len() - z_bar_bytes.len() - 1;
+        assert_eq!(
+            &proof_bytes[z_bar_offset..z_bar_offset + z_bar_bytes.len()],
+            &z_bar_bytes
+        );
+        proof_bytes.splice(
+            z_bar_offset..z_bar_offset + z_bar_bytes.len(),
+            non_canonical_bytes,
+        );
+
+        assert!(postcard::from_bytes::<ExtendedProof>(&proof_bytes).is_err());
+    }
+
+    #[test]
+    fn test_off_curve_vkey_g2_element() {
+        let mut rng = thread_rng();
+        let (_, _, mut vkey) = gen_verification_bundle(&mut rng).unwrap();
+        assert!(validate_vkey(&vkey).is_ok());
+
+        vkey.x_h = G2Affine::new_unchecked(vkey.x_h.x, vkey.x_h.y + G2BaseField::one());
+        assert_eq!(validate_vkey(&vkey), Err(VerifierError::MalformedProof));
+    }
+
+    #[test]
+    fn test_non_subgroup_vkey_g2_element() {
+        let mut rng = thread_rng();
+        let (_, _, mut vkey) = gen_verification_bundle(&mut rng).unwrap();
+
+        // G2 has a large cofactor, so an arbitrary point on the curve is not in the prime-order subgroup
+        let non_subgroup_point = (1_u64..)
+            .find_map(|x| G2Affine::get_point_from_x_unchecked(G2BaseField::from(x), false))
+            .unwrap();
+        assert!(!non_subgroup_point.is_in_correct_subgroup_assuming_on_curve());
+
+        vkey.h = non_subgroup_point;
+        assert_eq!(validate_vkey(&vkey), Err(VerifierError::MalformedProof));
+    }
+
+    #[test]
+    fn test_off_curve_linking_proof() {
+        let mut rng = thread_rng();
+        let (mut linking_proof, linking_vkey, _) = gen_single_link_proof_and_vkey(&mut rng);
+        linking_proof.linking_poly_opening = G1Affine::new_unchecked(
+            linking_proof.linking_poly_opening.x,
+            linking_proof.linking_poly_opening.y + G1BaseField::one(),
+        );
+
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::prep_linking_proof_opening_elems(
+                linking_vkey,
+                linking_proof,
+                (G1Affine::generator(), G1Affine::generator()),
+            );
+
+        assert_eq!(result.err(), Some(VerifierError::MalformedProof));
+    }
+
+    #[test]
+    fn test_valid_lookup_proof_verification() {
+        let mut rng = thread_rng();
+        let (public_inputs, proof, vkey) = gen_lookup_verification_bundle(&mut rng).unwrap();
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
+                .unwrap();
+
+        assert!(result, "valid lookup proof did not verify");
+    }
+
+    #[test]
+    fn test_invalid_lookup_proof_verification() {
+        let mut rng = thread_rng();
+        let (public_inputs, mut proof, vkey) = gen_lookup_verification_bundle(&mut rng).unwrap();
+        proof
+            .lookup_proof
+            .as_mut()
+            .unwrap()
+            .lookup_evals
+            .h_2_next_eval += ScalarField::one();
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
+                .unwrap();
+
+        assert!(!result, "invalid lookup proof verified");
+    }
+
+    #[test]
+    fn test_lookup_proof_type_mismatch() {
+        let mut rng = thread_rng();
+        let (public_inputs, mut proof, vkey) = gen_lookup_verification_bundle(&mut rng).unwrap();
+        proof.lookup_proof = None;
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs);
+
+        assert_eq!(result, Err(VerifierError::InvalidInputs));
+    }
+
+    #[test]
+    fn test_valid_poseidon_proof_verification() {
+        let mut rng = thread_rng();
+        let (public_inputs, proof, vkey) = gen_poseidon_verification_bundle(&mut rng).unwrap();
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
+                .unwrap();
+
+        assert!(result, "valid Poseidon2 transcript proof did not verify");
+    }
+
+    #[test]
+    fn test_poseidon_proof_keccak_vkey() {
+        let mut rng = thread_rng();
+        let (public_inputs, proof, mut vkey) = gen_poseidon_verification_bundle(&mut rng).unwrap();
+        vkey.transcript_hash = TranscriptHash::Keccak;
+        let result =
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify(vkey, proof, public_inputs)
+                .unwrap();
+
+        assert!(!result, "Poseidon2 proof verified with Keccak transcript");
+    }
+
+    #[test]
+    fn test_valid_mixed_transcript_batch_verification() {
+        let mut rng = thread_rng();
+
+        let (lookup_public_inputs, lookup_proof, lookup_vkey) =
+            gen_lookup_verification_bundle(&mut rng).unwrap();
+        let (poseidon_public_inputs, poseidon_proof, poseidon_vkey) =
+            gen_poseidon_verification_bundle(&mut rng).unwrap();
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &[lookup_vkey, poseidon_vkey],
+            &[lookup_proof, poseidon_proof],
+            &[lookup_public_inputs, poseidon_public_inputs],
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
+    #[test]
+    fn test_valid_mixed_lookup_batch_verification() {
+        let mut rng = thread_rng();
+
+        let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+        let (lookup_public_inputs, lookup_proof, lookup_vkey) =
+            gen_lookup_verification_bundle(&mut rng).unwrap();
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &[vkey, lookup_vkey],
+            &[proof, lookup_proof],
+            &[
+                statement_to_public_inputs(&statement).unwrap(),
+                lookup_public_inputs,
+            ],
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
     #[test]
     fn test_valid_match_plonk_proofs_verification() {
         let mut rng = thread_rng();
@@ -940,19 +1953,12 @@ mod tests {
             match_public_inputs.valid_match_settle,
         ];
 
-        let opening_elems =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::prep_batch_plonk_proofs_opening(
-                &vkey_batch,
-                &proof_batch,
-                &public_inputs_batch,
-            )
-            .unwrap();
-
         // Verify Plonk proofs batch opening
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::batch_opening(
-            &opening_elems,
-            SYSTEM_SRS.beta_h,
-            SYSTEM_SRS.h,
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_proofs_and_links(
+            &vkey_batch,
+            &proof_batch,
+            &public_inputs_batch,
+            &[],
         )
         .unwrap();
 
@@ -990,25 +1996,150 @@ mod tests {
             match_public_inputs.valid_match_settle,
         ];
 
-        let opening_elems =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::prep_batch_plonk_proofs_opening(
-                &vkey_batch,
-                &proof_batch,
-                &public_inputs_batch,
-            )
-            .unwrap();
-
         // Verify Plonk proofs batch opening
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::batch_opening(
-            &opening_elems,
-            SYSTEM_SRS.beta_h,
-            SYSTEM_SRS.h,
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_proofs_and_links(
+            &vkey_batch,
+            &proof_batch,
+            &public_inputs_batch,
+            &[],
+        )
+        .unwrap();
+
+        assert!(!result)
+    }
+
+    #[test]
+    fn test_valid_batch_verification() {
+        let mut rng = thread_rng();
+
+        let mut vkey_batch = vec![];
+        let mut proof_batch = vec![];
+        let mut public_inputs_batch = vec![];
+        for _ in 0..NUM_BATCHED_PROOFS {
+            let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+            vkey_batch.push(vkey);
+            proof_batch.push(proof);
+            public_inputs_batch.push(statement_to_public_inputs(&statement).unwrap());
+        }
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &vkey_batch,
+            &proof_batch,
+            &public_inputs_batch,
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
+    #[test]
+    fn test_invalid_batch_verification() {
+        let mut rng = thread_rng();
+
+        let mut vkey_batch = vec![];
+        let mut proof_batch = vec![];
+        let mut public_inputs_batch = vec![];
+        for _ in 0..NUM_BATCHED_PROOFS {
+            let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+            vkey_batch.push(vkey);
+            proof_batch.push(proof);
+            public_inputs_batch.push(statement_to_public_inputs(&statement).unwrap());
+        }
+
+        // Corrupt a single proof in the batch
+        let corrupted_proof = rng.gen_range(0..NUM_BATCHED_PROOFS);
+        proof_batch[corrupted_proof].z_bar += ScalarField::one();
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &vkey_batch,
+            &proof_batch,
+            &public_inputs_batch,
         )
         .unwrap();
 
         assert!(!result)
     }
 
+    #[test]
+    fn test_valid_match_plonk_proofs_batch_verification() {
+        let mut rng = thread_rng();
+        let (match_vkeys, match_proofs, match_public_inputs, _, _, _) =
+            generate_match_bundle(&mut rng).unwrap();
+
+        let vkey_batch = [
+            match_vkeys.valid_commitments_vkey,
+            match_vkeys.valid_reblind_vkey,
+            match_vkeys.valid_commitments_vkey,
+            match_vkeys.valid_reblind_vkey,
+            match_vkeys.valid_match_settle_vkey,
+        ];
+        let proof_batch = [
+            match_proofs.valid_commitments_0,
+            match_proofs.valid_reblind_0,
+            match_proofs.valid_commitments_1,
+            match_proofs.valid_reblind_1,
+            match_proofs.valid_match_settle,
+        ];
+        let public_inputs_batch = [
+            match_public_inputs.valid_commitments_0,
+            match_public_inputs.valid_reblind_0,
+            match_public_inputs.valid_commitments_1,
+            match_public_inputs.valid_reblind_1,
+            match_public_inputs.valid_match_settle,
+        ];
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &vkey_batch,
+            &proof_batch,
+            &public_inputs_batch,
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
+    #[test]
+    fn test_batch_verification_mismatched_lengths() {
+        let mut rng = thread_rng();
+        let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+        let public_inputs = statement_to_public_inputs(&statement).unwrap();
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &[vkey, vkey],
+            &[proof],
+            &[public_inputs],
+        );
+
+        assert!(matches!(result, Err(VerifierError::InvalidInputs)))
+    }
+
+    #[test]
+    fn test_batch_verification_mismatched_srs() {
+        let mut rng = thread_rng();
+
+        let mut vkey_batch = vec![];
+        let mut proof_batch = vec![];
+        let mut public_inputs_batch = vec![];
+        for _ in 0..NUM_BATCHED_PROOFS {
+            let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+            vkey_batch.push(vkey);
+            proof_batch.push(proof);
+            public_inputs_batch.push(statement_to_public_inputs(&statement).unwrap());
+        }
+
+        // Swap in valid G2 points from a different SRS in a single vkey
+        let mismatched_vkey = rng.gen_range(1..NUM_BATCHED_PROOFS);
+        vkey_batch[mismatched_vkey].x_h = vkey_batch[mismatched_vkey].h;
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_verify(
+            &vkey_batch,
+            &proof_batch,
+            &public_inputs_batch,
+        );
+
+        assert!(matches!(result, Err(VerifierError::InvalidInputs)))
+    }
+
     #[test]
     fn test_valid_linking_proof_verification() {
         let mut rng = thread_rng();
@@ -1017,7 +2148,7 @@ mod tests {
 
         // Prep linking proof opening elements
         let (g1_lhs, g1_rhs, eta) =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::prep_linking_proof_opening_elems(
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::prep_linking_proof_opening_elems(
                 linking_vkey,
                 link_proof,
                 (
@@ -1034,7 +2165,7 @@ mod tests {
         };
 
         // Verify linking proof opening
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::batch_opening(
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_opening(
             &opening_elems,
             SYSTEM_SRS.beta_h,
             SYSTEM_SRS.h,
@@ -1053,7 +2184,7 @@ mod tests {
 
         // Prep linking proof opening elements
         let (g1_lhs, g1_rhs, eta) =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::prep_linking_proof_opening_elems(
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::prep_linking_proof_opening_elems(
                 linking_vkey,
                 link_proof,
                 (
@@ -1070,7 +2201,7 @@ mod tests {
         };
 
         // Verify linking proof opening
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::batch_opening(
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_opening(
             &opening_elems,
             SYSTEM_SRS.beta_h,
             SYSTEM_SRS.h,
@@ -1084,20 +2215,35 @@ mod tests {
     fn test_valid_match_linking_proofs_verification() {
         let mut rng = thread_rng();
 
-        let (_, _, _, match_linking_vkeys, match_linking_proofs, match_linking_wire_poly_comms) =
-            generate_match_bundle(&mut rng).unwrap();
+        let (
+            match_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_vkeys,
+            match_linking_proofs,
+            _,
+        ) = generate_match_bundle(&mut rng).unwrap();
+
+        let mut linked_bundle = LinkedBundle::default();
+        Verifier::<NativeG1ArithmeticBackend, NativeHasher>::append_match_to_linked_bundle(
+            &mut linked_bundle,
+            &match_vkeys,
+            &match_linking_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_proofs,
+        );
 
         // Prep linking proof opening elements
         let opening_elems =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::prep_match_linking_proofs_opening(
-                match_linking_vkeys,
-                match_linking_proofs,
-                match_linking_wire_poly_comms,
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::prep_links_opening(
+                &linked_bundle.links,
+                &linked_bundle.proofs,
             )
             .unwrap();
 
         // Verify linking proofs batch opening
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::batch_opening(
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_opening(
             &opening_elems,
             SYSTEM_SRS.beta_h,
             SYSTEM_SRS.h,
@@ -1111,22 +2257,37 @@ mod tests {
     fn test_invalid_match_linking_proofs_verification() {
         let mut rng = thread_rng();
 
-        let (_, _, _, match_linking_vkeys, mut match_linking_proofs, match_linking_wire_poly_comms) =
-            generate_match_bundle(&mut rng).unwrap();
+        let (
+            match_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_vkeys,
+            mut match_linking_proofs,
+            _,
+        ) = generate_match_bundle(&mut rng).unwrap();
 
         mutate_random_linking_proof(&mut rng, &mut match_linking_proofs);
 
+        let mut linked_bundle = LinkedBundle::default();
+        Verifier::<NativeG1ArithmeticBackend, NativeHasher>::append_match_to_linked_bundle(
+            &mut linked_bundle,
+            &match_vkeys,
+            &match_linking_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_proofs,
+        );
+
         // Prep linking proof opening elements
         let opening_elems =
-            Verifier::<ArkG1ArithmeticBackend, NativeHasher>::prep_match_linking_proofs_opening(
-                match_linking_vkeys,
-                match_linking_proofs,
-                match_linking_wire_poly_comms,
+            Verifier::<NativeG1ArithmeticBackend, NativeHasher>::prep_links_opening(
+                &linked_bundle.links,
+                &linked_bundle.proofs,
             )
             .unwrap();
 
         // Verify linking proofs batch opening
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::batch_opening(
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::batch_opening(
             &opening_elems,
             SYSTEM_SRS.beta_h,
             SYSTEM_SRS.h,
@@ -1136,6 +2297,126 @@ mod tests {
         assert!(!result)
     }
 
+    #[test]
+    fn test_valid_linked_bundle() {
+        let mut rng = thread_rng();
+        let linked_bundle = gen_linked_bundle(
+            &mut rng,
+            NUM_UNLINKED_BUNDLE_PROOFS,
+            NUM_LINKED_BUNDLE_PAIRS,
+        )
+        .unwrap();
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_linked_bundle(
+            &linked_bundle,
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
+    #[test]
+    fn test_invalid_linked_bundle() {
+        let mut rng = thread_rng();
+        let mut linked_bundle = gen_linked_bundle(
+            &mut rng,
+            NUM_UNLINKED_BUNDLE_PROOFS,
+            NUM_LINKED_BUNDLE_PAIRS,
+        )
+        .unwrap();
+
+        if rng.gen_bool(0.5) {
+            let proof = linked_bundle.proofs.choose_mut(&mut rng).unwrap();
+            proof.z_bar += ScalarField::one();
+        } else {
+            mutate_random_bundle_link(&mut rng, &mut linked_bundle);
+        }
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_linked_bundle(
+            &linked_bundle,
+        )
+        .unwrap();
+
+        assert!(!result)
+    }
+
+    #[test]
+    fn test_linked_bundle_wrong_link_target() {
+        let mut rng = thread_rng();
+        let mut linked_bundle = gen_linked_bundle(
+            &mut rng,
+            NUM_UNLINKED_BUNDLE_PROOFS,
+            NUM_LINKED_BUNDLE_PAIRS,
+        )
+        .unwrap();
+
+        // Point a link at an unlinked proof
+        linked_bundle.links[0].target = 0;
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_linked_bundle(
+            &linked_bundle,
+        )
+        .unwrap();
+
+        assert!(!result)
+    }
+
+    #[test]
+    fn test_linked_bundle_invalid_link_indices() {
+        let mut rng = thread_rng();
+        let linked_bundle = gen_linked_bundle(
+            &mut rng,
+            NUM_UNLINKED_BUNDLE_PROOFS,
+            NUM_LINKED_BUNDLE_PAIRS,
+        )
+        .unwrap();
+
+        let num_proofs = linked_bundle.proofs.len();
+
+        // A link to a proof outside of the bundle
+        let mut out_of_bounds_bundle = linked_bundle.clone();
+        out_of_bounds_bundle.links[0].target = num_proofs;
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_linked_bundle(
+            &out_of_bounds_bundle,
+        );
+        assert!(matches!(result, Err(VerifierError::InvalidInputs)));
+
+        // A link from a proof to itself
+        let mut self_link_bundle = linked_bundle;
+        self_link_bundle.links[0].target = self_link_bundle.links[0].source;
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_linked_bundle(
+            &self_link_bundle,
+        );
+        assert!(matches!(result, Err(VerifierError::InvalidInputs)));
+    }
+
+    #[test]
+    fn test_linked_bundle_serde() {
+        let mut rng = thread_rng();
+        let linked_bundle = gen_linked_bundle(
+            &mut rng,
+            NUM_UNLINKED_BUNDLE_PROOFS,
+            NUM_LINKED_BUNDLE_PAIRS,
+        )
+        .unwrap();
+
+        let linked_bundle_ser = postcard::to_allocvec(&linked_bundle).unwrap();
+        let linked_bundle_deser: LinkedBundle = postcard::from_bytes(&linked_bundle_ser).unwrap();
+
+        assert_eq!(linked_bundle_deser.links.len(), linked_bundle.links.len());
+        for (link, link_deser) in linked_bundle.links.iter().zip(&linked_bundle_deser.links) {
+            assert_eq!(link.source, link_deser.source);
+            assert_eq!(link.target, link_deser.target);
+        }
+
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_linked_bundle(
+            &linked_bundle_deser,
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
     #[test]
     fn test_valid_match() {
         let mut rng = thread_rng();
@@ -1149,7 +2430,7 @@ mod tests {
             _,
         ) = generate_match_bundle(&mut rng).unwrap();
 
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::verify_match(
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_match(
             match_vkeys,
             match_linking_vkeys,
             match_proofs,
@@ -1183,7 +2464,7 @@ mod tests {
             mutate_random_linking_proof(&mut rng, &mut match_linking_proofs);
         }
 
-        let result = Verifier::<ArkG1ArithmeticBackend, NativeHasher>::verify_match(
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_match(
             match_vkeys,
             match_linking_vkeys,
             match_proofs,
@@ -1194,4 +2475,265 @@ mod tests {
 
         assert!(!result)
     }
+
+    #[test]
+    fn test_match_batch_of_one_equivalence() {
+        let mut rng = thread_rng();
+
+        let (
+            match_vkeys,
+            mut match_proofs,
+            match_public_inputs,
+            match_linking_vkeys,
+            mut match_linking_proofs,
+            _,
+        ) = generate_match_bundle(&mut rng).unwrap();
+
+        // Optionally corrupt the bundle, both paths should agree either way
+        if rng.gen_bool(0.5) {
+            if rng.gen_bool(0.5) {
+                mutate_random_plonk_proof(&mut rng, &mut match_proofs);
+            } else {
+                mutate_random_linking_proof(&mut rng, &mut match_linking_proofs);
+            }
+        }
+
+        let single_result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_match(
+            match_vkeys,
+            match_linking_vkeys,
+            match_proofs,
+            match_public_inputs.clone(),
+            match_linking_proofs,
+        )
+        .unwrap();
+
+        let batch_result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_match_batch(
+            match_vkeys,
+            match_linking_vkeys,
+            vec![match_proofs],
+            vec![match_public_inputs],
+            vec![match_linking_proofs],
+        )
+        .unwrap();
+
+        assert_eq!(single_result, batch_result)
+    }
+
+    #[test]
+    fn test_valid_match_batch() {
+        let mut rng = thread_rng();
+
+        let mut match_proofs_batch = vec![];
+        let mut match_public_inputs_batch = vec![];
+        let mut match_linking_proofs_batch = vec![];
+        let mut vkeys = None;
+
+        for _ in 0..NUM_BATCHED_MATCHES {
+            let (
+                match_vkeys,
+                match_proofs,
+                match_public_inputs,
+                match_linking_vkeys,
+                match_linking_proofs,
+                _,
+            ) = generate_match_bundle(&mut rng).unwrap();
+
+            match_proofs_batch.push(match_proofs);
+            match_public_inputs_batch.push(match_public_inputs);
+            match_linking_proofs_batch.push(match_linking_proofs);
+            vkeys = Some((match_vkeys, match_linking_vkeys));
+        }
+
+        let (match_vkeys, match_linking_vkeys) = vkeys.unwrap();
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_match_batch(
+            match_vkeys,
+            match_linking_vkeys,
+            match_proofs_batch,
+            match_public_inputs_batch,
+            match_linking_proofs_batch,
+        )
+        .unwrap();
+
+        assert!(result)
+    }
+
+    #[test]
+    fn test_invalid_match_batch() {
+        let mut rng = thread_rng();
+
+        let mut match_proofs_batch = vec![];
+        let mut match_public_inputs_batch = vec![];
+        let mut match_linking_proofs_batch = vec![];
+        let mut vkeys = None;
+
+        for _ in 0..NUM_BATCHED_MATCHES {
+            let (
+                match_vkeys,
+                match_proofs,
+                match_public_inputs,
+                match_linking_vkeys,
+                match_linking_proofs,
+                _,
+            ) = generate_match_bundle(&mut rng).unwrap();
+
+            match_proofs_batch.push(match_proofs);
+            match_public_inputs_batch.push(match_public_inputs);
+            match_linking_proofs_batch.push(match_linking_proofs);
+            vkeys = Some((match_vkeys, match_linking_vkeys));
+        }
+
+        // Corrupt a single match in the batch
+        let corrupted_match = rng.gen_range(0..NUM_BATCHED_MATCHES);
+        if rng.gen_bool(0.5) {
+            mutate_random_plonk_proof(&mut rng, &mut match_proofs_batch[corrupted_match]);
+        } else {
+            mutate_random_linking_proof(&mut rng, &mut match_linking_proofs_batch[corrupted_match]);
+        }
+
+        let (match_vkeys, match_linking_vkeys) = vkeys.unwrap();
+        let result = Verifier::<NativeG1ArithmeticBackend, NativeHasher>::verify_match_batch(
+            match_vkeys,
+            match_linking_vkeys,
+            match_proofs_batch,
+            match_public_inputs_batch,
+            match_linking_proofs_batch,
+        )
+        .unwrap();
+
+        assert!(!result)
+    }
+
+    #[test]
+    fn test_match_batch_ec_mul_reduction() {
+        let mut rng = thread_rng();
+
+        let mut linked_bundle = LinkedBundle::default();
+        for _ in 0..NUM_BATCHED_MATCHES {
+            let (
+                match_vkeys,
+                match_proofs,
+                match_public_inputs,
+                match_linking_vkeys,
+                match_linking_proofs,
+                _,
+            ) = generate_match_bundle(&mut rng).unwrap();
+
+            CountingVerifier::append_match_to_linked_bundle(
+                &mut linked_bundle,
+                &match_vkeys,
+                &match_linking_vkeys,
+                match_proofs,
+                match_public_inputs,
+                match_linking_proofs,
+            );
+        }
+
+        let LinkedBundle {
+            vkeys,
+            proofs,
+            public_inputs,
+            links,
+        } = &linked_bundle;
+
+        let _lock = lock_counting_backends();
+
+        let counts_before = OpCounts::current();
+        let accumulated_result = CountingVerifier::verify_linked_bundle(&linked_bundle).unwrap();
+        let accumulated_counts = OpCounts::current() - counts_before;
+
+        // Open the same batch without the accumulator, i.e. computing the opening elements
+        // of each proof separately before taking their random linear combination
+        let counts_before = OpCounts::current();
+        let mut opening_elems = CountingVerifier::prep_links_opening(links, proofs).unwrap();
+        let evals_batch =
+            CountingVerifier::prep_batch_plonk_proofs_evals(vkeys, proofs, public_inputs).unwrap();
+        for ((vkey, proof), evals) in vkeys.iter().zip(proofs).zip(&evals_batch) {
+            let (lhs, rhs) =
+                CountingVerifier::prep_plonk_proof_opening_elems(vkey, proof, evals).unwrap();
+
+            opening_elems.g1_lhs_elems.push(lhs);
+            opening_elems.g1_rhs_elems.push(rhs);
+            opening_elems.transcript_elements.push(evals.challenges.u);
+        }
+        let unaccumulated_result =
+            CountingVerifier::batch_opening(&opening_elems, vkeys[0].x_h, vkeys[0].h).unwrap();
+        let unaccumulated_counts = OpCounts::current() - counts_before;
+
+        assert!(accumulated_result && unaccumulated_result);
+
+        // The verification key commitments are only multiplied once for the whole batch,
+        // so the accumulator should save `ecMul` calls over opening each proof separately
+        assert!(accumulated_counts.ec_mul_calls < unaccumulated_counts.ec_mul_calls);
+        assert_eq!(accumulated_counts.pairing_checks, 1);
+        assert_eq!(unaccumulated_counts.pairing_checks, 1);
+    }
+
+    /// Accumulates the LHS & RHS terms of the opening of the given batch of Plonk proofs
+    fn accumulate_batch_opening(
+        vkeys: &[ExtendedVerificationKey],
+        proofs: &[ExtendedProof],
+        public_inputs: &[PublicInputs],
+    ) -> (MsmAccumulator, MsmAccumulator) {
+        let evals_batch =
+            CountingVerifier::prep_batch_plonk_proofs_evals(vkeys, proofs, public_inputs).unwrap();
+        let r_powers = vec![ScalarField::one(); proofs.len()];
+
+        let mut lhs_terms = MsmAccumulator::default();
+        let mut rhs_terms = MsmAccumulator::default();
+        CountingVerifier::prep_batch_plonk_proofs_opening(
+            vkeys,
+            proofs,
+            &evals_batch,
+            &r_powers,
+            &mut lhs_terms,
+            &mut rhs_terms,
+        );
+
+        (lhs_terms, rhs_terms)
+    }
+
+    /// Returns the number of accumulated terms whose bases are not verification key commitments
+    fn num_non_vkey_terms(terms: &MsmAccumulator) -> usize {
+        terms
+            .term_indices
+            .keys()
+            .filter(|key| !matches!(key, TermKey::Vkey(..) | TermKey::Generator))
+            .count()
+    }
+
+    #[test]
+    fn test_identical_vkey_batch_msm_terms() {
+        let mut rng = thread_rng();
+
+        let mut bundles = vec![];
+        let mut lookup_bundles = vec![];
+        for _ in 0..NUM_BATCHED_PROOFS {
+            let (statement, proof, vkey) = gen_verification_bundle(&mut rng).unwrap();
+            bundles.push((vkey, proof, statement_to_public_inputs(&statement).unwrap()));
+            let (public_inputs, proof, vkey) = gen_lookup_verification_bundle(&mut rng).unwrap();
+            lookup_bundles.push((vkey, proof, public_inputs));
+        }
+
+        for bundles in [bundles, lookup_bundles] {
+            // Verify all of the proofs in the batch against the same verification key
+            let vkeys = vec![bundles[0].0; NUM_BATCHED_PROOFS];
+            let proofs: Vec<_> = bundles.iter().map(|(_, proof, _)| *proof).collect();
+            let public_inputs: Vec<_> = bundles.into_iter().map(|(_, _, pi)| pi).collect();
+
+            let (single_lhs, single_rhs) =
+                accumulate_batch_opening(&vkeys[..1], &proofs[..1], &public_inputs[..1]);
+            let (batched_lhs, batched_rhs) =
+                accumulate_batch_opening(&vkeys, &proofs, &public_inputs);
+
+            // Each proof after the first may only add the terms of its own commitments,
+            // as the terms of the shared verification key's commitments are merged
+            for (single, batched) in [(single_lhs, batched_lhs), (single_rhs, batched_rhs)] {
+                assert!(
+                    batched.bases.len()
+                        <= single.bases.len()
+                            + (NUM_BATCHED_PROOFS - 1) * num_non_vkey_terms(&single)
+                );
+            }
+        }
+    }
 }
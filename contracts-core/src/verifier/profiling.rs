@@ -0,0 +1,392 @@
+//! A profiling mode for the Plonk verifier, which records the number of precompile calls
+//! made & bytes hashed at each step of the verification algorithm, and estimates their gas cost.
+//!
+//! Profiling is done by wrapping the backends used by the verifier in the counting backends
+//! defined here. As the backends are stateless, the counts are kept in global counters,
+//! so profiling should not be run concurrently with any other use of the counting backends.
+//!
+//! This is intended to be used with native backends, e.g. to catch regressions in
+//! the cost of verification as the circuits grow.
+
+use alloc::{vec, vec::Vec};
+use ark_ff::One;
+use contracts_common::{
+    backends::G1ArithmeticError,
+    constants::HASH_OUTPUT_SIZE,
+    types::{
+        ExtendedProof, ExtendedVerificationKey, G1Affine, G2Affine, LinkedBundle,
+        MatchLinkingProofs, MatchLinkingVkeys, MatchProofs, MatchPublicInputs, MatchVkeys,
+        PublicInputs, ScalarField,
+    },
+};
+use core::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Sub},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::{errors::VerifierError, G1ArithmeticBackend, HashBackend, MsmAccumulator, Verifier};
+
+/// The gas cost of a call to the `ecAdd` precompile, as set in EIP-1108
+pub const EC_ADD_GAS_COST: u64 = 150;
+
+/// The gas cost of a call to the `ecMul` precompile, as set in EIP-1108
+pub const EC_MUL_GAS_COST: u64 = 6_000;
+
+/// The base gas cost of a call to the `ecPairing` precompile, as set in EIP-1108
+pub const EC_PAIRING_BASE_GAS_COST: u64 = 45_000;
+
+/// The gas cost of each pair of points checked by the `ecPairing` precompile, as set in EIP-1108
+pub const EC_PAIRING_PER_PAIR_GAS_COST: u64 = 34_000;
+
+/// The number of pairs of points checked in each call to the `ecPairing` precompile
+/// made by [`G1ArithmeticBackend::ec_pairing_check`]
+pub const NUM_PAIRS_PER_PAIRING_CHECK: u64 = 2;
+
+/// The base gas cost of a Keccak-256 hash, as charged by the `KECCAK256` opcode
+pub const KECCAK_BASE_GAS_COST: u64 = 30;
+
+/// The gas cost of each word hashed by the `KECCAK256` opcode
+pub const KECCAK_PER_WORD_GAS_COST: u64 = 6;
+
+/// The number of calls made to the `ecAdd` precompile by the counting backend
+static EC_ADD_CALLS: AtomicU64 = AtomicU64::new(0);
+/// The number of calls made to the `ecMul` precompile by the counting backend
+static EC_MUL_CALLS: AtomicU64 = AtomicU64::new(0);
+/// The number of calls made to the `ecPairing` precompile by the counting backend
+static PAIRING_CHECKS: AtomicU64 = AtomicU64::new(0);
+/// The number of hashes computed by the counting hasher
+static HASH_CALLS: AtomicU64 = AtomicU64::new(0);
+/// The number of bytes hashed by the counting hasher
+static BYTES_HASHED: AtomicU64 = AtomicU64::new(0);
+/// The gas cost of the hashes computed by the counting hasher,
+/// which is tracked separately as it depends on the length of each input
+static HASH_GAS: AtomicU64 = AtomicU64::new(0);
+
+/// The number of precompile calls made & bytes hashed over some part of the verification algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    /// The number of calls to the `ecAdd` precompile
+    pub ec_add_calls: u64,
+    /// The number of calls to the `ecMul` precompile
+    pub ec_mul_calls: u64,
+    /// The number of calls to the `ecPairing` precompile
+    pub pairing_checks: u64,
+    /// The number of Keccak-256 hashes computed
+    pub hash_calls: u64,
+    /// The total number of bytes hashed
+    pub bytes_hashed: u64,
+    /// The gas cost of the Keccak-256 hashes computed
+    pub hash_gas: u64,
+}
+
+impl OpCounts {
+    /// Returns the counts recorded by the counting backends so far
+    pub fn current() -> Self {
+        Self {
+            ec_add_calls: EC_ADD_CALLS.load(Ordering::SeqCst),
+            ec_mul_calls: EC_MUL_CALLS.load(Ordering::SeqCst),
+            pairing_checks: PAIRING_CHECKS.load(Ordering::SeqCst),
+            hash_calls: HASH_CALLS.load(Ordering::SeqCst),
+            bytes_hashed: BYTES_HASHED.load(Ordering::SeqCst),
+            hash_gas: HASH_GAS.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Resets the counts recorded by the counting backends
+    pub fn reset() {
+        EC_ADD_CALLS.store(0, Ordering::SeqCst);
+        EC_MUL_CALLS.store(0, Ordering::SeqCst);
+        PAIRING_CHECKS.store(0, Ordering::SeqCst);
+        HASH_CALLS.store(0, Ordering::SeqCst);
+        BYTES_HASHED.store(0, Ordering::SeqCst);
+        HASH_GAS.store(0, Ordering::SeqCst);
+    }
+
+    /// The estimated gas cost of the precompile calls made
+    pub fn precompile_gas(&self) -> u64 {
+        self.ec_add_calls * EC_ADD_GAS_COST
+            + self.ec_mul_calls * EC_MUL_GAS_COST
+            + self.pairing_checks
+                * (EC_PAIRING_BASE_GAS_COST
+                    + NUM_PAIRS_PER_PAIRING_CHECK * EC_PAIRING_PER_PAIR_GAS_COST)
+    }
+
+    /// The estimated gas cost of the precompile calls made & the hashes computed.
+    ///
+    /// This does not account for the cost of the calls to the precompiles themselves,
+    /// nor for any of the field arithmetic done in the verifier.
+    pub fn estimated_gas(&self) -> u64 {
+        self.precompile_gas() + self.hash_gas
+    }
+}
+
+impl Add for OpCounts {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            ec_add_calls: self.ec_add_calls + rhs.ec_add_calls,
+            ec_mul_calls: self.ec_mul_calls + rhs.ec_mul_calls,
+            pairing_checks: self.pairing_checks + rhs.pairing_checks,
+            hash_calls: self.hash_calls + rhs.hash_calls,
+            bytes_hashed: self.bytes_hashed + rhs.bytes_hashed,
+            hash_gas: self.hash_gas + rhs.hash_gas,
+        }
+    }
+}
+
+impl AddAssign for OpCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for OpCounts {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            ec_add_calls: self.ec_add_calls - rhs.ec_add_calls,
+            ec_mul_calls: self.ec_mul_calls - rhs.ec_mul_calls,
+            pairing_checks: self.pairing_checks - rhs.pairing_checks,
+            hash_calls: self.hash_calls - rhs.hash_calls,
+            bytes_hashed: self.bytes_hashed - rhs.bytes_hashed,
+            hash_gas: self.hash_gas - rhs.hash_gas,
+        }
+    }
+}
+
+/// A G1 arithmetic backend which counts the precompile calls made through the wrapped backend.
+///
+/// Operations which the precompile backend short-circuits without calling out to a precompile,
+/// i.e. additions with the identity & multiplications by one, are not counted.
+pub struct CountingG1ArithmeticBackend<G: G1ArithmeticBackend> {
+    #[doc(hidden)]
+    _phantom: PhantomData<G>,
+}
+
+impl<G: G1ArithmeticBackend> G1ArithmeticBackend for CountingG1ArithmeticBackend<G> {
+    fn ec_add(a: G1Affine, b: G1Affine) -> Result<G1Affine, G1ArithmeticError> {
+        if a != G1Affine::identity() && b != G1Affine::identity() {
+            EC_ADD_CALLS.fetch_add(1, Ordering::SeqCst);
+        }
+        G::ec_add(a, b)
+    }
+
+    fn ec_scalar_mul(a: ScalarField, b: G1Affine) -> Result<G1Affine, G1ArithmeticError> {
+        if a != ScalarField::one() {
+            EC_MUL_CALLS.fetch_add(1, Ordering::SeqCst);
+        }
+        G::ec_scalar_mul(a, b)
+    }
+
+    fn ec_pairing_check(
+        a_1: G1Affine,
+        b_1: G2Affine,
+        a_2: G1Affine,
+        b_2: G2Affine,
+    ) -> Result<bool, G1ArithmeticError> {
+        PAIRING_CHECKS.fetch_add(1, Ordering::SeqCst);
+        G::ec_pairing_check(a_1, b_1, a_2, b_2)
+    }
+
+    // We purposefully use the default `msm` implementation, rather than that of the wrapped backend,
+    // so that its individual additions & scalar multiplications are counted as they would be on-chain
+}
+
+/// A hashing backend which counts the hashes computed & bytes hashed through the wrapped backend
+pub struct CountingHasher<H: HashBackend> {
+    #[doc(hidden)]
+    _phantom: PhantomData<H>,
+}
+
+impl<H: HashBackend> HashBackend for CountingHasher<H> {
+    fn hash(input: &[u8]) -> [u8; HASH_OUTPUT_SIZE] {
+        let num_words = input.len().div_ceil(32) as u64;
+
+        HASH_CALLS.fetch_add(1, Ordering::SeqCst);
+        BYTES_HASHED.fetch_add(input.len() as u64, Ordering::SeqCst);
+        HASH_GAS.fetch_add(
+            KECCAK_BASE_GAS_COST + num_words * KECCAK_PER_WORD_GAS_COST,
+            Ordering::SeqCst,
+        );
+        H::hash(input)
+    }
+}
+
+/// The steps of the verification algorithm over which precompile calls & hashes are profiled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfiledStep {
+    /// Computing the opening elements of the linking proofs
+    LinkingProofs,
+    /// Deriving the Fiat-Shamir challenges of the Plonk proofs from their transcripts (step 4)
+    ChallengeDerivation,
+    /// Deriving the challenge `r` used to batch the openings of all of the proofs
+    BatchingChallenge,
+    /// Computing the G1 elements used in the batch opening via multi-scalar multiplication (steps 9 - 12)
+    Msm,
+    /// The final pairing check (step 12)
+    Pairing,
+}
+
+impl Display for ProfiledStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfiledStep::LinkingProofs => write!(f, "linking proofs"),
+            ProfiledStep::ChallengeDerivation => write!(f, "challenge derivation"),
+            ProfiledStep::BatchingChallenge => write!(f, "batching challenge derivation"),
+            ProfiledStep::Msm => write!(f, "MSM"),
+            ProfiledStep::Pairing => write!(f, "pairing check"),
+        }
+    }
+}
+
+/// A profile of the verification of a bundle of proofs.
+///
+/// The counts of each step are summed over all of the proofs in the bundle.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationProfile {
+    /// The counts recorded at each step, in the order in which the steps were first run
+    pub steps: Vec<(ProfiledStep, OpCounts)>,
+    /// Whether or not the bundle verified successfully
+    pub verified: bool,
+}
+
+impl VerificationProfile {
+    /// The counts recorded over all of the steps
+    pub fn total(&self) -> OpCounts {
+        self.steps
+            .iter()
+            .fold(OpCounts::default(), |acc, (_, counts)| acc + *counts)
+    }
+
+    /// Runs the given step of the verification algorithm,
+    /// adding the counts recorded while running it to those of the step
+    fn record<T>(&mut self, step: ProfiledStep, run_step: impl FnOnce() -> T) -> T {
+        let counts_before = OpCounts::current();
+        let res = run_step();
+        let step_counts = OpCounts::current() - counts_before;
+
+        match self.steps.iter_mut().find(|(s, _)| *s == step) {
+            Some((_, counts)) => *counts += step_counts,
+            None => self.steps.push((step, step_counts)),
+        }
+
+        res
+    }
+}
+
+impl<G: G1ArithmeticBackend, H: HashBackend>
+    Verifier<CountingG1ArithmeticBackend<G>, CountingHasher<H>>
+{
+    /// Verify a proof, profiling the precompile calls & hashes at each step.
+    ///
+    /// This follows the same algorithm as [`Verifier::verify`].
+    pub fn profile_verify(
+        vkey: ExtendedVerificationKey,
+        proof: ExtendedProof,
+        public_inputs: PublicInputs,
+    ) -> Result<VerificationProfile, VerifierError> {
+        let linked_bundle = LinkedBundle {
+            vkeys: vec![vkey],
+            proofs: vec![proof],
+            public_inputs: vec![public_inputs],
+            links: vec![],
+        };
+
+        Self::profile_linked_bundle(&linked_bundle)
+    }
+
+    /// Verify the proofs & linking proofs involved in the matching and settlement of a trade,
+    /// profiling the precompile calls & hashes at each step.
+    ///
+    /// This follows the same algorithm as [`Verifier::verify_match`].
+    pub fn profile_verify_match(
+        match_vkeys: MatchVkeys,
+        match_linking_vkeys: MatchLinkingVkeys,
+        match_proofs: MatchProofs<ExtendedProof>,
+        match_public_inputs: MatchPublicInputs,
+        match_linking_proofs: MatchLinkingProofs,
+    ) -> Result<VerificationProfile, VerifierError> {
+        let mut linked_bundle = LinkedBundle::default();
+        Self::append_match_to_linked_bundle(
+            &mut linked_bundle,
+            &match_vkeys,
+            &match_linking_vkeys,
+            match_proofs,
+            match_public_inputs,
+            match_linking_proofs,
+        );
+
+        Self::profile_linked_bundle(&linked_bundle)
+    }
+
+    /// Verify a [`LinkedBundle`], profiling the precompile calls & hashes at each step.
+    ///
+    /// This follows the same algorithm as [`Verifier::verify_linked_bundle`].
+    pub fn profile_linked_bundle(
+        linked_bundle: &LinkedBundle,
+    ) -> Result<VerificationProfile, VerifierError> {
+        let LinkedBundle {
+            vkeys,
+            proofs,
+            public_inputs,
+            links,
+        } = linked_bundle;
+
+        let num_proofs = proofs.len();
+        if num_proofs == 0 || vkeys.len() != num_proofs || public_inputs.len() != num_proofs {
+            return Err(VerifierError::InvalidInputs);
+        }
+
+        let mut profile = VerificationProfile::default();
+
+        let linking_opening_elems = profile.record(ProfiledStep::LinkingProofs, || {
+            Self::prep_links_opening(links, proofs)
+        })?;
+
+        let evals_batch = profile.record(ProfiledStep::ChallengeDerivation, || {
+            Self::prep_batch_plonk_proofs_evals(vkeys, proofs, public_inputs)
+        })?;
+
+        let transcript_elements = [
+            linking_opening_elems.transcript_elements.clone(),
+            evals_batch.iter().map(|evals| evals.challenges.u).collect(),
+        ]
+        .concat();
+        let r_powers = profile.record(ProfiledStep::BatchingChallenge, || {
+            Self::compute_r_powers(&transcript_elements)
+        })?;
+        let (linking_r_powers, plonk_r_powers) = r_powers.split_at(links.len());
+
+        let (lhs_rlc, rhs_rlc) = profile.record(ProfiledStep::Msm, || {
+            let mut lhs_terms = MsmAccumulator::default();
+            let mut rhs_terms = MsmAccumulator::default();
+
+            lhs_terms.add_link_terms(linking_r_powers, &linking_opening_elems.g1_lhs_elems);
+            rhs_terms.add_link_terms(linking_r_powers, &linking_opening_elems.g1_rhs_elems);
+
+            Self::prep_batch_plonk_proofs_opening(
+                vkeys,
+                proofs,
+                &evals_batch,
+                plonk_r_powers,
+                &mut lhs_terms,
+                &mut rhs_terms,
+            );
+
+            Ok::<_, VerifierError>((
+                lhs_terms.msm::<CountingG1ArithmeticBackend<G>>()?,
+                rhs_terms.msm::<CountingG1ArithmeticBackend<G>>()?,
+            ))
+        })?;
+
+        profile.verified = profile.record(ProfiledStep::Pairing, || {
+            Self::pairing_check(lhs_rlc, rhs_rlc, vkeys[0].x_h, vkeys[0].h)
+        })?;
+
+        Ok(profile)
+    }
+}
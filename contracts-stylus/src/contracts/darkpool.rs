@@ -2,31 +2,64 @@
 //! verifying the various proofs of the Renegade protocol, and handling deposits / withdrawals.
 
 use alloc::{vec, vec::Vec};
+use alloy_sol_types::SolCall;
+use contracts_common::constants::{
+    ALL_OPERATIONS_PAUSED, DARKPOOL_CORE_ADDRESS_SELECTOR, FEE_MANAGER_ROLE, KEY_ROTATOR_ROLE,
+    MERKLE_ADDRESS_SELECTOR, NEW_WALLET_OPERATION, NUM_CIRCUITS, NUM_PAUSABLE_OPERATIONS,
+    PAUSER_ROLE, PROCESS_MATCH_SETTLE_OPERATION, REDEEM_FEE_OPERATION,
+    SETTLE_OFFLINE_FEE_OPERATION, SETTLE_ONLINE_RELAYER_FEE_OPERATION,
+    TRANSFER_EXECUTOR_ADDRESS_SELECTOR, UPGRADER_ROLE, VERIFIER_ADDRESS_SELECTOR,
+    VKEYS_ADDRESS_SELECTOR, VKEY_REGISTRY_ADDRESS_SELECTOR,
+};
 use core::borrow::{Borrow, BorrowMut};
 use stylus_sdk::{
     abi::Bytes,
-    alloy_primitives::{Address, U256, U64},
+    alloy_primitives::{Address, B256, U256, U64, U8},
+    block,
+    crypto::keccak,
     evm, msg,
     prelude::*,
-    storage::{StorageAddress, StorageArray, StorageBool, StorageMap, StorageU256, StorageU64},
+    storage::{
+        StorageAddress, StorageArray, StorageB256, StorageBool, StorageMap, StorageU256,
+        StorageU64, StorageU8,
+    },
 };
 
 use crate::{
     assert_result,
     utils::{
         constants::{
-            INVALID_VERSION_ERROR_MESSAGE, MERKLE_STORAGE_GAP_SIZE, NOT_OWNER_ERROR_MESSAGE,
-            PAUSED_ERROR_MESSAGE, TRANSFER_EXECUTOR_STORAGE_GAP_SIZE, UNPAUSED_ERROR_MESSAGE,
-            ZERO_ADDRESS_ERROR_MESSAGE, ZERO_FEE_ERROR_MESSAGE,
+            INVALID_ADDRESS_SELECTOR_ERROR_MESSAGE, INVALID_CIRCUIT_ID_ERROR_MESSAGE,
+            INVALID_MULTICALL_CALL_ERROR_MESSAGE, INVALID_OPERATION_ERROR_MESSAGE,
+            INVALID_VERSION_ERROR_MESSAGE, MERKLE_STORAGE_GAP_SIZE, MISSING_ROLE_ERROR_MESSAGE,
+            NOT_OWNER_ERROR_MESSAGE, NOT_PENDING_OWNER_ERROR_MESSAGE,
+            NO_PENDING_ADDRESS_CHANGE_ERROR_MESSAGE, NO_PENDING_OWNER_ERROR_MESSAGE,
+            OPERATION_PAUSED_ERROR_MESSAGE, PAUSED_ERROR_MESSAGE,
+            REENTRANT_MULTICALL_ERROR_MESSAGE, TIMELOCK_NOT_ELAPSED_ERROR_MESSAGE,
+            TRANSFER_EXECUTOR_STORAGE_GAP_SIZE, UNPAUSED_ERROR_MESSAGE,
+            UNREGISTERED_VKEY_HASH_ERROR_MESSAGE, ZERO_ADDRESS_ERROR_MESSAGE,
+            ZERO_FEE_ERROR_MESSAGE,
+        },
+        helpers::{
+            delegate_call_helper, get_paused_operations, is_operation_paused, validate_vkey_bytes,
         },
-        helpers::delegate_call_helper,
         solidity::{
-            init_0Call as initMerkleCall, init_1Call as initTransferExecutorCall, newWalletCall,
-            processMatchSettleCall, redeemFeeCall, rootCall, rootInHistoryCall,
-            settleOfflineFeeCall, settleOnlineRelayerFeeCall, updateWalletCall,
-            DarkpoolCoreAddressChanged, FeeChanged, MerkleAddressChanged, OwnershipTransferred,
-            Paused, PubkeyRotated, TransferExecutorAddressChanged, Unpaused,
-            VerifierAddressChanged, VkeysAddressChanged,
+            init_0Call as initMerkleCall, init_1Call as initTransferExecutorCall,
+            migrateRootHistoryCall, newWalletCall, newWalletWithVkeysCall,
+            processMatchSettleBatchCall, processMatchSettleBatchWithVkeysCall,
+            processMatchSettleCall, processMatchSettleWithVkeysCall, redeemFeeCall,
+            redeemFeeWithVkeysCall, rootCall, rootHistoryParamsCall, rootInHistoryCall,
+            rootInfoCall, setRootHistoryParamsCall, settleOfflineFeeCall,
+            settleOfflineFeeWithVkeysCall, settleOnlineRelayerFeeCall,
+            settleOnlineRelayerFeeWithVkeysCall, treeIdCall, updateWalletCall,
+            updateWalletWithVkeysCall, AddressChangeCanceled, AddressChangeDelayChangeCanceled,
+            AddressChangeDelayChangeScheduled, AddressChangeDelayChanged, AddressChangeScheduled,
+            DarkpoolCoreAddressChanged, FeeChanged, FeeOverrideChanged, FeeOverrideRemoved,
+            MerkleAddressChanged, OperationPaused, OperationUnpaused, OwnershipTransferCanceled,
+            OwnershipTransferStarted, OwnershipTransferred, Paused, PubkeyGracePeriodChanged,
+            PubkeyRotated, RoleGranted, RoleRevoked, RootHistoryMigrated, RootHistoryParamsChanged,
+            TransferExecutorAddressChanged, Unpaused, VerifierAddressChanged, VkeyHashRegistered,
+            VkeyHashRetired, VkeyRegistryAddressChanged, VkeysAddressChanged,
         },
     },
 };
@@ -47,7 +80,9 @@ pub struct DarkpoolContract {
     /// Whether or not the darkpool has been initialized
     initialized: StorageU64,
 
-    /// Whether or not the darkpool is paused
+    /// Whether or not the darkpool is paused, as set by darkpools that predate
+    /// per-operation pausing. If set, all operations are paused until the pause
+    /// bitmask is next written, at which point it is cleared
     paused: StorageBool,
 
     /// The address of the darkpool core contract
@@ -84,6 +119,78 @@ pub struct DarkpoolContract {
 
     /// The BabyJubJub EC-ElGamal public encryption key for the protocol
     protocol_public_encryption_key: StorageArray<StorageU256, 2>,
+
+    /// The set of accounts granted each role, representing a mapping from
+    /// a role identifier to a mapping from an account to a boolean indicating
+    /// whether or not the account holds the role
+    roles: StorageMap<u8, StorageMap<Address, StorageBool>>,
+
+    /// The address pending acceptance of ownership of the darkpool
+    pending_owner: StorageAddress,
+
+    /// The delay, in seconds, between scheduling an implementation
+    /// address change and being able to execute it
+    address_change_delay: StorageU256,
+
+    /// The pending implementation address changes, representing a mapping
+    /// from an address selector to the scheduled new address
+    pending_address_changes: StorageMap<u8, StorageAddress>,
+
+    /// The timestamps at which the pending implementation address changes
+    /// may be executed, keyed by address selector
+    pending_address_change_timestamps: StorageMap<u8, StorageU256>,
+
+    /// The per-asset overrides of the protocol fee, representing a mapping
+    /// from a mint to the fee charged on matches of the pair with that base mint.
+    ///
+    /// A value of zero indicates that no override is set, in which case the
+    /// global protocol fee applies
+    protocol_fee_overrides: StorageMap<Address, StorageU256>,
+
+    /// The number of blocks for which a protocol public encryption key
+    /// is still accepted after it has been rotated out
+    pubkey_grace_period: StorageU256,
+
+    /// The number of protocol public encryption keys in the key history
+    pub(crate) pubkey_history_len: StorageU64,
+
+    /// The history of protocol public encryption keys, representing a mapping
+    /// from the index of a key in the history to its affine coordinates
+    pubkey_history: StorageMap<u64, StorageArray<StorageU256, 2>>,
+
+    /// The block numbers at which the keys in the history were activated,
+    /// keyed by index in the history
+    pubkey_activation_blocks: StorageMap<u64, StorageU256>,
+
+    /// The last block numbers at which the keys in the history are accepted,
+    /// keyed by index in the history. Zero if the key has not been rotated out
+    pubkey_expiry_blocks: StorageMap<u64, StorageU256>,
+
+    /// Whether or not a multicall is in progress,
+    /// used to prevent nested multicalls
+    multicall_entered: StorageBool,
+
+    /// The registered verification key commitments, representing a mapping
+    /// from a circuit identifier to the keccak hash of the circuit's serialized
+    /// verification keys.
+    ///
+    /// The zero hash indicates that no commitment is registered for the circuit
+    vkey_hashes: StorageMap<u8, StorageB256>,
+
+    /// The address of the vkey registry contract, from which versioned
+    /// verification keys are fetched
+    pub(crate) vkey_registry_address: StorageAddress,
+
+    /// The pending change of the address change delay
+    pending_address_change_delay: StorageU256,
+
+    /// The timestamp at which the pending change of the address change delay
+    /// may be executed, or zero if there is no pending change
+    pending_address_change_delay_timestamp: StorageU256,
+
+    /// A bitmask of the paused darkpool operations, in which bit `i`
+    /// is set if the operation with identifier `i` is paused
+    paused_operations: StorageU8,
 }
 
 #[external]
@@ -104,6 +211,7 @@ impl DarkpoolContract {
         permit2_address: Address,
         protocol_fee: U256,
         protocol_public_encryption_key: [U256; 2],
+        address_change_delay: U256,
     ) -> Result<(), Vec<u8>> {
         // Initialize the Merkle tree
         delegate_call_helper::<initMerkleCall>(storage, merkle_address, ())?;
@@ -117,11 +225,24 @@ impl DarkpoolContract {
 
         // Set the stored addresses
         DarkpoolContract::_transfer_ownership(storage, msg::sender());
-        DarkpoolContract::set_darkpool_core_address(storage, darkpool_core_address)?;
-        DarkpoolContract::set_verifier_address(storage, verifier_address)?;
-        DarkpoolContract::set_vkeys_address(storage, vkeys_address)?;
-        DarkpoolContract::set_merkle_address(storage, merkle_address)?;
-        DarkpoolContract::set_transfer_executor_address(storage, transfer_executor_address)?;
+        for (address_selector, address) in [
+            (DARKPOOL_CORE_ADDRESS_SELECTOR, darkpool_core_address),
+            (VERIFIER_ADDRESS_SELECTOR, verifier_address),
+            (VKEYS_ADDRESS_SELECTOR, vkeys_address),
+            (MERKLE_ADDRESS_SELECTOR, merkle_address),
+            (
+                TRANSFER_EXECUTOR_ADDRESS_SELECTOR,
+                transfer_executor_address,
+            ),
+        ] {
+            DarkpoolContract::_set_implementation_address(storage, address_selector, address)?;
+        }
+
+        // Set the delay for implementation address changes
+        storage
+            .borrow_mut()
+            .address_change_delay
+            .set(address_change_delay);
 
         // Set the protocol fee
         DarkpoolContract::set_fee(storage, protocol_fee)?;
@@ -144,7 +265,17 @@ impl DarkpoolContract {
         Ok(storage.borrow().owner.get())
     }
 
-    /// Transfers ownership of the darkpool to the provided address
+    /// Returns the address pending acceptance of ownership of the darkpool,
+    /// or the zero address if there is no pending ownership transfer
+    pub fn pending_owner<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+    ) -> Result<Address, Vec<u8>> {
+        Ok(storage.borrow().pending_owner.get())
+    }
+
+    /// Begins transferring ownership of the darkpool to the provided address.
+    ///
+    /// Ownership is not transferred until the new owner calls `accept_ownership`
     pub fn transfer_ownership<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         new_owner: Address,
@@ -152,8 +283,105 @@ impl DarkpoolContract {
         DarkpoolContract::_check_owner(storage)?;
 
         DarkpoolContract::check_address_not_zero(new_owner)?;
-        DarkpoolContract::_transfer_ownership(storage, new_owner);
+        storage.borrow_mut().pending_owner.set(new_owner);
+        evm::log(OwnershipTransferStarted { new_owner });
+
+        Ok(())
+    }
+
+    /// Accepts a pending ownership transfer, making the sender the owner of the darkpool
+    pub fn accept_ownership<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+    ) -> Result<(), Vec<u8>> {
+        let sender = msg::sender();
+        assert_result!(
+            storage.borrow_mut().pending_owner.get() == sender,
+            NOT_PENDING_OWNER_ERROR_MESSAGE
+        )?;
+
+        DarkpoolContract::_transfer_ownership(storage, sender);
+
+        Ok(())
+    }
+
+    /// Cancels a pending ownership transfer
+    pub fn cancel_ownership_transfer<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_owner(storage)?;
+
+        let pending_owner = storage.borrow_mut().pending_owner.get();
+        assert_result!(
+            pending_owner != Address::ZERO,
+            NO_PENDING_OWNER_ERROR_MESSAGE
+        )?;
+
+        storage.borrow_mut().pending_owner.set(Address::ZERO);
+        evm::log(OwnershipTransferCanceled { pending_owner });
+
+        Ok(())
+    }
+
+    /// Renounces ownership of the darkpool, leaving it without an owner.
+    ///
+    /// This permanently disables all owner-only functionality,
+    /// including granting & revoking roles
+    pub fn renounce_ownership<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::_transfer_ownership(storage, Address::ZERO);
+
+        Ok(())
+    }
+
+    // ------------------
+    // | ACCESS CONTROL |
+    // ------------------
+
+    /// Returns whether or not the given account holds the given role
+    pub fn has_role<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        role: u8,
+        account: Address,
+    ) -> Result<bool, Vec<u8>> {
+        Ok(storage.borrow().roles.getter(role).get(account))
+    }
+
+    /// Grants the given role to the given account
+    pub fn grant_role<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        role: u8,
+        account: Address,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::check_address_not_zero(account)?;
+
+        storage
+            .borrow_mut()
+            .roles
+            .setter(role)
+            .insert(account, true);
+
+        evm::log(RoleGranted { role, account });
+        Ok(())
+    }
+
+    /// Revokes the given role from the given account
+    pub fn revoke_role<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        role: u8,
+        account: Address,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_owner(storage)?;
+
+        storage
+            .borrow_mut()
+            .roles
+            .setter(role)
+            .insert(account, false);
 
+        evm::log(RoleRevoked { role, account });
         Ok(())
     }
 
@@ -161,29 +389,74 @@ impl DarkpoolContract {
     // | PAUSABLE |
     // ------------
 
-    /// Returns whether or not the darkpool is paused
+    /// Returns whether or not the darkpool is paused, i.e. whether all of its operations are paused
     pub fn paused<S: TopLevelStorage + Borrow<Self>>(storage: &S) -> Result<bool, Vec<u8>> {
-        Ok(storage.borrow().paused.get())
+        Ok(DarkpoolContract::_get_paused_operations(storage) == ALL_OPERATIONS_PAUSED)
+    }
+
+    /// Returns the full pause state of the darkpool, as a bitmask in which bit `i`
+    /// is set if the operation with identifier `i` is paused
+    pub fn get_paused_operations<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+    ) -> Result<u8, Vec<u8>> {
+        Ok(DarkpoolContract::_get_paused_operations(storage))
+    }
+
+    /// Returns whether or not the given operation is paused
+    pub fn is_operation_paused<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        operation: u8,
+    ) -> Result<bool, Vec<u8>> {
+        DarkpoolContract::_check_operation(operation)?;
+        Ok(is_operation_paused(
+            DarkpoolContract::_get_paused_operations(storage),
+            operation,
+        ))
     }
 
-    /// Pauses the darkpool
+    /// Pauses all operations on the darkpool
     pub fn pause<S: TopLevelStorage + BorrowMut<Self>>(storage: &mut S) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::_check_role(storage, PAUSER_ROLE)?;
         DarkpoolContract::_check_not_paused(storage)?;
-        storage.borrow_mut().paused.set(true);
+        DarkpoolContract::_set_paused_operations(storage, ALL_OPERATIONS_PAUSED);
         evm::log(Paused {});
         Ok(())
     }
 
-    /// Unpauses the darkpool
+    /// Unpauses all operations on the darkpool, regardless of whether
+    /// they were paused all at once or individually
     pub fn unpause<S: TopLevelStorage + BorrowMut<Self>>(storage: &mut S) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::_check_role(storage, PAUSER_ROLE)?;
         DarkpoolContract::_check_paused(storage)?;
-        storage.borrow_mut().paused.set(false);
+        DarkpoolContract::_set_paused_operations(storage, 0);
         evm::log(Unpaused {});
         Ok(())
     }
 
+    /// Pauses or unpauses the given operation on the darkpool
+    pub fn set_operation_paused<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        operation: u8,
+        paused: bool,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_role(storage, PAUSER_ROLE)?;
+        DarkpoolContract::_check_operation(operation)?;
+
+        let paused_operations = DarkpoolContract::_get_paused_operations(storage);
+        if is_operation_paused(paused_operations, operation) == paused {
+            return Ok(());
+        }
+
+        let new_paused_operations = if paused {
+            paused_operations | (1 << operation)
+        } else {
+            paused_operations & !(1 << operation)
+        };
+        DarkpoolContract::_set_paused_operations(storage, new_paused_operations);
+
+        Ok(())
+    }
+
     // -----------
     // | GETTERS |
     // -----------
@@ -206,7 +479,7 @@ impl DarkpoolContract {
         Ok(this.public_blinder_set.get(blinder))
     }
 
-    /// Returns the current root of the Merkle tree
+    /// Returns the current root of the active Merkle tree
     pub fn get_root<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
     ) -> Result<U256, Vec<u8>> {
@@ -215,7 +488,17 @@ impl DarkpoolContract {
         Ok(res)
     }
 
-    /// Returns whether or not the given root is a valid historical Merkle root
+    /// Returns the identifier of the active Merkle tree
+    pub fn get_tree_id<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+    ) -> Result<u64, Vec<u8>> {
+        let merkle_address = storage.borrow_mut().merkle_address.get();
+        let (res,) = delegate_call_helper::<treeIdCall>(storage, merkle_address, ())?.into();
+        Ok(res)
+    }
+
+    /// Returns whether or not the given root is a valid historical Merkle root,
+    /// reverting if the root is in the history but has expired
     pub fn root_in_history<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         root: U256,
@@ -227,11 +510,55 @@ impl DarkpoolContract {
         Ok(res)
     }
 
+    /// Returns the metadata of the given historical Merkle root, as a tuple of the
+    /// identifier of the tree to which it belongs, the number of leaves in that tree
+    /// covered by the root, and the block at which it was stored.
+    ///
+    /// All values are zero for unknown roots, and for roots stored
+    /// before the root history expiry policy was introduced
+    pub fn root_info<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        root: U256,
+    ) -> Result<(u64, u128, U256), Vec<u8>> {
+        let merkle_address = storage.borrow_mut().merkle_address.get();
+        let res = delegate_call_helper::<rootInfoCall>(storage, merkle_address, (root,))?;
+
+        Ok((res._0, res._1, res._2))
+    }
+
+    /// Returns the parameters of the Merkle root history expiry policy, as a tuple of the
+    /// number of most recent roots accepted, the number of blocks for which a root is accepted,
+    /// and the last block at which roots stored before the policy was introduced are accepted.
+    ///
+    /// A zero value indicates that the corresponding bound is disabled
+    pub fn get_root_history_params<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+    ) -> Result<(u64, U256, U256), Vec<u8>> {
+        let merkle_address = storage.borrow_mut().merkle_address.get();
+        let res = delegate_call_helper::<rootHistoryParamsCall>(storage, merkle_address, ())?;
+
+        Ok((res._0, res._1, res._2))
+    }
+
     /// Returns the protocol fee
     pub fn get_fee<S: TopLevelStorage + Borrow<Self>>(storage: &S) -> Result<U256, Vec<u8>> {
         Ok(storage.borrow().protocol_fee.get())
     }
 
+    /// Returns the protocol fee charged on matches of the pair with the given base mint,
+    /// falling back to the global protocol fee if no override is set for the mint
+    pub fn get_fee_for_mint<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        mint: Address,
+    ) -> Result<U256, Vec<u8>> {
+        let fee_override = storage.borrow().protocol_fee_overrides.get(mint);
+        if fee_override != U256::ZERO {
+            return Ok(fee_override);
+        }
+
+        Ok(storage.borrow().protocol_fee.get())
+    }
+
     /// Returns the protocol public encryption key
     pub fn get_pubkey<S: TopLevelStorage + Borrow<Self>>(
         storage: &S,
@@ -239,6 +566,38 @@ impl DarkpoolContract {
         Ok(DarkpoolContract::_get_protocol_pubkey_coords(storage))
     }
 
+    /// Returns the history of protocol public encryption keys, from oldest to newest.
+    ///
+    /// Each entry contains the affine coordinates of the key, the block at which
+    /// it was activated, and the last block at which it is accepted.
+    /// The expiry block of the current key is zero.
+    pub fn get_pubkey_history<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+    ) -> Result<Vec<(U256, U256, U256, U256)>, Vec<u8>> {
+        let history_len = storage.borrow().pubkey_history_len.get().to::<u64>();
+        let history = (0..history_len)
+            .map(|i| {
+                let pubkey = storage.borrow().pubkey_history.getter(i);
+                (
+                    pubkey.get(0).unwrap(),
+                    pubkey.get(1).unwrap(),
+                    storage.borrow().pubkey_activation_blocks.get(i),
+                    storage.borrow().pubkey_expiry_blocks.get(i),
+                )
+            })
+            .collect();
+
+        Ok(history)
+    }
+
+    /// Returns the number of blocks for which a protocol public encryption key
+    /// is still accepted after it has been rotated out
+    pub fn get_pubkey_grace_period<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+    ) -> Result<U256, Vec<u8>> {
+        Ok(storage.borrow().pubkey_grace_period.get())
+    }
+
     // -----------
     // | SETTERS |
     // -----------
@@ -248,19 +607,60 @@ impl DarkpoolContract {
         storage: &mut S,
         new_fee: U256,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::_check_role(storage, FEE_MANAGER_ROLE)?;
         assert_result!(new_fee != U256::ZERO, ZERO_FEE_ERROR_MESSAGE)?;
         storage.borrow_mut().protocol_fee.set(new_fee);
         evm::log(FeeChanged { new_fee });
         Ok(())
     }
 
+    /// Set the protocol fee charged on matches of the pair with the given base mint,
+    /// overriding the global protocol fee
+    pub fn set_fee_override<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        mint: Address,
+        new_fee: U256,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_role(storage, FEE_MANAGER_ROLE)?;
+        assert_result!(new_fee != U256::ZERO, ZERO_FEE_ERROR_MESSAGE)?;
+        storage
+            .borrow_mut()
+            .protocol_fee_overrides
+            .insert(mint, new_fee);
+        evm::log(FeeOverrideChanged { mint, new_fee });
+        Ok(())
+    }
+
+    /// Remove the protocol fee override for the given base mint,
+    /// so that matches of the pair are charged the global protocol fee
+    pub fn remove_fee_override<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        mint: Address,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_role(storage, FEE_MANAGER_ROLE)?;
+        storage.borrow_mut().protocol_fee_overrides.delete(mint);
+        evm::log(FeeOverrideRemoved { mint });
+        Ok(())
+    }
+
     /// Set the protocol public encryption key
     pub fn set_public_encryption_key<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         new_public_encryption_key: [U256; 2],
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::_check_role(storage, KEY_ROTATOR_ROLE)?;
+
+        // Darkpools upgraded from a version without a key history start with an empty
+        // history, in which case we seed it with the key being replaced so that it is
+        // still given a grace window. We do not know when that key was activated, so
+        // its activation block is recorded as the current block.
+        let current_public_encryption_key = DarkpoolContract::_get_protocol_pubkey_coords(storage);
+        if storage.borrow().pubkey_history_len.get() == U64::ZERO
+            && current_public_encryption_key != [U256::ZERO; 2]
+        {
+            DarkpoolContract::_push_pubkey_history(storage, current_public_encryption_key);
+        }
+
         let mut pubkey_x = storage
             .borrow_mut()
             .protocol_public_encryption_key
@@ -275,132 +675,388 @@ impl DarkpoolContract {
             .unwrap();
         pubkey_y.set(new_public_encryption_key[1]);
 
+        let previous_pubkey_expiry_block =
+            DarkpoolContract::_push_pubkey_history(storage, new_public_encryption_key);
+
         evm::log(PubkeyRotated {
             new_pubkey_x: new_public_encryption_key[0],
             new_pubkey_y: new_public_encryption_key[1],
+            previous_pubkey_expiry_block,
         });
 
         Ok(())
     }
 
-    /// Sets the darkpool core address
-    pub fn set_darkpool_core_address<S: TopLevelStorage + BorrowMut<Self>>(
+    /// Set the number of blocks for which a protocol public encryption key
+    /// is still accepted after it has been rotated out
+    pub fn set_pubkey_grace_period<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        darkpool_core_address: Address,
+        new_grace_period: U256,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
-
-        DarkpoolContract::check_address_not_zero(darkpool_core_address)?;
+        DarkpoolContract::_check_role(storage, KEY_ROTATOR_ROLE)?;
         storage
             .borrow_mut()
-            .darkpool_core_address
-            .set(darkpool_core_address);
-
-        evm::log(DarkpoolCoreAddressChanged {
-            new_address: darkpool_core_address,
-        });
-
+            .pubkey_grace_period
+            .set(new_grace_period);
+        evm::log(PubkeyGracePeriodChanged { new_grace_period });
         Ok(())
     }
 
-    /// Sets the verifier address
-    pub fn set_verifier_address<S: TopLevelStorage + BorrowMut<Self>>(
+    /// Set the parameters of the Merkle root history expiry policy, i.e. the number of
+    /// most recent roots accepted, and the number of blocks for which a root is accepted.
+    ///
+    /// A zero value for either parameter disables the corresponding bound
+    pub fn set_root_history_params<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        verifier_address: Address,
+        max_root_history_len: u64,
+        root_expiry_period: U256,
     ) -> Result<(), Vec<u8>> {
         DarkpoolContract::_check_owner(storage)?;
-        DarkpoolContract::check_address_not_zero(verifier_address)?;
-        storage.borrow_mut().verifier_address.set(verifier_address);
-        evm::log(VerifierAddressChanged {
-            new_address: verifier_address,
+
+        let merkle_address = storage.borrow_mut().merkle_address.get();
+        delegate_call_helper::<setRootHistoryParamsCall>(
+            storage,
+            merkle_address,
+            (max_root_history_len, root_expiry_period),
+        )?;
+
+        evm::log(RootHistoryParamsChanged {
+            max_root_history_len,
+            root_expiry_period,
         });
+
         Ok(())
     }
 
-    /// Sets the vkeys address
-    pub fn set_vkeys_address<S: TopLevelStorage + BorrowMut<Self>>(
+    /// Migrates the Merkle root history from the unbounded set of roots,
+    /// setting the last block at which roots stored before the expiry policy
+    /// was introduced are accepted.
+    ///
+    /// A zero block indicates that these roots are accepted indefinitely
+    pub fn migrate_root_history<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        vkeys_address: Address,
+        legacy_root_expiry_block: U256,
     ) -> Result<(), Vec<u8>> {
         DarkpoolContract::_check_owner(storage)?;
-        DarkpoolContract::check_address_not_zero(vkeys_address)?;
-        storage.borrow_mut().vkeys_address.set(vkeys_address);
-        evm::log(VkeysAddressChanged {
-            new_address: vkeys_address,
+
+        let merkle_address = storage.borrow_mut().merkle_address.get();
+        delegate_call_helper::<migrateRootHistoryCall>(
+            storage,
+            merkle_address,
+            (legacy_root_expiry_block,),
+        )?;
+
+        evm::log(RootHistoryMigrated {
+            legacy_root_expiry_block,
         });
+
         Ok(())
     }
 
-    /// Sets the Merkle address
-    pub fn set_merkle_address<S: TopLevelStorage + BorrowMut<Self>>(
+    // ------------
+    // | TIMELOCK |
+    // ------------
+
+    /// Returns the delay, in seconds, between scheduling an implementation
+    /// address change and being able to execute it
+    pub fn get_address_change_delay<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+    ) -> Result<U256, Vec<u8>> {
+        Ok(storage.borrow().address_change_delay.get())
+    }
+
+    /// Returns the pending implementation address change for the given address selector,
+    /// as a tuple of the new address and the timestamp at which the change may be executed.
+    ///
+    /// If there is no pending change, this returns the zero address and a zero timestamp
+    pub fn get_pending_address_change<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        address_selector: u8,
+    ) -> Result<(Address, U256), Vec<u8>> {
+        let this = storage.borrow();
+        Ok((
+            this.pending_address_changes.get(address_selector),
+            this.pending_address_change_timestamps.get(address_selector),
+        ))
+    }
+
+    /// Schedules a change of the implementation address for the given address selector,
+    /// which may be executed once the address change delay has elapsed.
+    ///
+    /// Scheduling a change overwrites any pending change for the same address selector
+    pub fn schedule_address_change<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        merkle_address: Address,
+        address_selector: u8,
+        new_address: Address,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
-        DarkpoolContract::check_address_not_zero(merkle_address)?;
-        storage.borrow_mut().merkle_address.set(merkle_address);
-        evm::log(MerkleAddressChanged {
-            new_address: merkle_address,
+        DarkpoolContract::_check_role(storage, UPGRADER_ROLE)?;
+        DarkpoolContract::_check_address_selector(address_selector)?;
+        DarkpoolContract::check_address_not_zero(new_address)?;
+
+        let this = storage.borrow_mut();
+        let effective_timestamp = U256::from(block::timestamp()) + this.address_change_delay.get();
+
+        this.pending_address_changes
+            .insert(address_selector, new_address);
+        this.pending_address_change_timestamps
+            .insert(address_selector, effective_timestamp);
+
+        evm::log(AddressChangeScheduled {
+            address_selector,
+            new_address,
+            effective_timestamp,
         });
+
         Ok(())
     }
 
-    /// Sets the transfer executor address
-    pub fn set_transfer_executor_address<S: TopLevelStorage + BorrowMut<Self>>(
+    /// Executes the pending implementation address change for the given address selector.
+    ///
+    /// This may be called by anyone once the address change delay has elapsed
+    pub fn execute_address_change<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        transfer_executor_address: Address,
+        address_selector: u8,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_owner(storage)?;
-        DarkpoolContract::check_address_not_zero(transfer_executor_address)?;
+        let (new_address, effective_timestamp) =
+            DarkpoolContract::get_pending_address_change(storage, address_selector)?;
 
-        storage
-            .borrow_mut()
-            .transfer_executor_address
-            .set(transfer_executor_address);
+        assert_result!(
+            new_address != Address::ZERO,
+            NO_PENDING_ADDRESS_CHANGE_ERROR_MESSAGE
+        )?;
+        assert_result!(
+            U256::from(block::timestamp()) >= effective_timestamp,
+            TIMELOCK_NOT_ELAPSED_ERROR_MESSAGE
+        )?;
+
+        DarkpoolContract::_clear_pending_address_change(storage, address_selector);
+        DarkpoolContract::_set_implementation_address(storage, address_selector, new_address)
+    }
+
+    /// Returns the pending change of the address change delay, as a tuple of the new delay
+    /// and the timestamp at which the change may be executed.
+    ///
+    /// If there is no pending change, the timestamp is zero
+    pub fn get_pending_address_change_delay<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+    ) -> Result<(U256, U256), Vec<u8>> {
+        let this = storage.borrow();
+        Ok((
+            this.pending_address_change_delay.get(),
+            this.pending_address_change_delay_timestamp.get(),
+        ))
+    }
+
+    /// Schedules a change of the address change delay, which may be executed
+    /// once the current address change delay has elapsed.
+    ///
+    /// Darkpools upgraded from a version without the timelock have a zero delay,
+    /// so their initial delay may be scheduled & executed immediately.
+    /// Scheduling a change overwrites any pending change of the delay
+    pub fn schedule_address_change_delay<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        new_delay: U256,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_role(storage, UPGRADER_ROLE)?;
+
+        let this = storage.borrow_mut();
+        let effective_timestamp = U256::from(block::timestamp()) + this.address_change_delay.get();
+
+        this.pending_address_change_delay.set(new_delay);
+        this.pending_address_change_delay_timestamp
+            .set(effective_timestamp);
 
-        evm::log(TransferExecutorAddressChanged {
-            new_address: transfer_executor_address,
+        evm::log(AddressChangeDelayChangeScheduled {
+            new_delay,
+            effective_timestamp,
         });
+
         Ok(())
     }
 
-    // ----------------
-    // | CORE METHODS |
-    // ----------------
-
-    /// Adds a new wallet to the commitment tree
-    pub fn new_wallet<S: TopLevelStorage + BorrowMut<Self>>(
+    /// Executes the pending change of the address change delay.
+    ///
+    /// This may be called by anyone once the current address change delay has elapsed
+    pub fn execute_address_change_delay<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        proof: Bytes,
-        valid_wallet_create_statement_bytes: Bytes,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_not_paused(storage)?;
+        let (new_delay, effective_timestamp) =
+            DarkpoolContract::get_pending_address_change_delay(storage)?;
 
-        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
-        delegate_call_helper::<newWalletCall>(
-            storage,
-            darkpool_core_address,
-            (
-                proof.to_vec().into(),
-                valid_wallet_create_statement_bytes.to_vec().into(),
-            ),
-        )
-        .map(|_| ())
+        assert_result!(
+            effective_timestamp != U256::ZERO,
+            NO_PENDING_ADDRESS_CHANGE_ERROR_MESSAGE
+        )?;
+        assert_result!(
+            U256::from(block::timestamp()) >= effective_timestamp,
+            TIMELOCK_NOT_ELAPSED_ERROR_MESSAGE
+        )?;
+
+        let this = storage.borrow_mut();
+        this.pending_address_change_delay.set(U256::ZERO);
+        this.pending_address_change_delay_timestamp.set(U256::ZERO);
+        this.address_change_delay.set(new_delay);
+
+        evm::log(AddressChangeDelayChanged { new_delay });
+
+        Ok(())
     }
 
-    /// Update a wallet in the commitment tree
-    pub fn update_wallet<S: TopLevelStorage + BorrowMut<Self>>(
+    /// Cancels the pending change of the address change delay
+    pub fn cancel_address_change_delay<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
-        proof: Bytes,
-        valid_wallet_update_statement_bytes: Bytes,
-        wallet_commitment_signature: Bytes,
-        transfer_aux_data_bytes: Bytes,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_not_paused(storage)?;
+        DarkpoolContract::_check_role(storage, UPGRADER_ROLE)?;
 
-        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
-        delegate_call_helper::<updateWalletCall>(
-            storage,
+        let (new_delay, effective_timestamp) =
+            DarkpoolContract::get_pending_address_change_delay(storage)?;
+
+        assert_result!(
+            effective_timestamp != U256::ZERO,
+            NO_PENDING_ADDRESS_CHANGE_ERROR_MESSAGE
+        )?;
+
+        let this = storage.borrow_mut();
+        this.pending_address_change_delay.set(U256::ZERO);
+        this.pending_address_change_delay_timestamp.set(U256::ZERO);
+
+        evm::log(AddressChangeDelayChangeCanceled { new_delay });
+
+        Ok(())
+    }
+
+    /// Cancels the pending implementation address change for the given address selector
+    pub fn cancel_address_change<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        address_selector: u8,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_role(storage, UPGRADER_ROLE)?;
+
+        let (new_address, _) =
+            DarkpoolContract::get_pending_address_change(storage, address_selector)?;
+
+        assert_result!(
+            new_address != Address::ZERO,
+            NO_PENDING_ADDRESS_CHANGE_ERROR_MESSAGE
+        )?;
+
+        DarkpoolContract::_clear_pending_address_change(storage, address_selector);
+
+        evm::log(AddressChangeCanceled {
+            address_selector,
+            new_address,
+        });
+
+        Ok(())
+    }
+
+    // ---------------------------
+    // | VERIFICATION KEY HASHES |
+    // ---------------------------
+
+    /// Returns the verification key commitment registered for the given circuit,
+    /// or the zero hash if there is none
+    pub fn get_vkey_hash<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        circuit_id: u8,
+    ) -> Result<B256, Vec<u8>> {
+        Ok(storage.borrow().vkey_hashes.get(circuit_id))
+    }
+
+    /// Registers the keccak hash of the given serialized verification keys for the given circuit.
+    ///
+    /// Once registered, callers of the `_with_vkeys` core methods may pass the circuit's
+    /// verification keys in calldata in place of fetching them from the vkeys contract.
+    /// Registering a hash overwrites any hash previously registered for the circuit.
+    ///
+    /// The G2 points of verification keys given in calldata are not validated by the verifier,
+    /// so they are validated here, before their hash is registered
+    pub fn register_vkey_hash<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        circuit_id: u8,
+        vkeys: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_owner(storage)?;
+        DarkpoolContract::_check_circuit_id(circuit_id)?;
+        validate_vkey_bytes(circuit_id, &vkeys)?;
+
+        let vkey_hash = keccak(&vkeys.0);
+
+        storage
+            .borrow_mut()
+            .vkey_hashes
+            .insert(circuit_id, vkey_hash);
+
+        evm::log(VkeyHashRegistered {
+            circuit_id,
+            vkey_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Retires the verification key commitment registered for the given circuit,
+    /// after which verification keys for the circuit are no longer accepted in calldata
+    pub fn retire_vkey_hash<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        circuit_id: u8,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_owner(storage)?;
+
+        let vkey_hash = DarkpoolContract::get_vkey_hash(storage, circuit_id)?;
+        assert_result!(
+            vkey_hash != B256::ZERO,
+            UNREGISTERED_VKEY_HASH_ERROR_MESSAGE
+        )?;
+
+        storage.borrow_mut().vkey_hashes.delete(circuit_id);
+
+        evm::log(VkeyHashRetired {
+            circuit_id,
+            vkey_hash,
+        });
+
+        Ok(())
+    }
+
+    // ----------------
+    // | CORE METHODS |
+    // ----------------
+
+    /// Adds a new wallet to the commitment tree
+    pub fn new_wallet<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_wallet_create_statement_bytes: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, NEW_WALLET_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<newWalletCall>(
+            storage,
+            darkpool_core_address,
+            (
+                proof.to_vec().into(),
+                valid_wallet_create_statement_bytes.to_vec().into(),
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Update a wallet in the commitment tree
+    pub fn update_wallet<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_wallet_update_statement_bytes: Bytes,
+        wallet_commitment_signature: Bytes,
+        transfer_aux_data_bytes: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        // The darkpool core contract checks whether the wallet update is paused,
+        // as this depends on the direction of the external transfer in the statement
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<updateWalletCall>(
+            storage,
             darkpool_core_address,
             (
                 proof.to_vec().into(),
@@ -426,7 +1082,7 @@ impl DarkpoolContract {
         match_proofs: Bytes,
         match_linking_proofs: Bytes,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_not_paused(storage)?;
+        DarkpoolContract::_check_operation_not_paused(storage, PROCESS_MATCH_SETTLE_OPERATION)?;
 
         let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
         delegate_call_helper::<processMatchSettleCall>(
@@ -443,6 +1099,36 @@ impl DarkpoolContract {
         .map(|_| ())
     }
 
+    /// Settles a batch of matched orders, inserting the updated wallets
+    /// of both parties in each match into the commitment tree.
+    ///
+    /// Each argument is the serialization of a vector of the corresponding
+    /// argument to `process_match_settle`, one element per match
+    pub fn process_match_settle_batch<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payloads: Bytes,
+        party_1_match_payloads: Bytes,
+        valid_match_settle_statements: Bytes,
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, PROCESS_MATCH_SETTLE_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<processMatchSettleBatchCall>(
+            storage,
+            darkpool_core_address,
+            (
+                party_0_match_payloads.to_vec().into(),
+                party_1_match_payloads.to_vec().into(),
+                valid_match_settle_statements.to_vec().into(),
+                match_proofs.to_vec().into(),
+                match_linking_proofs.to_vec().into(),
+            ),
+        )
+        .map(|_| ())
+    }
+
     /// Settles the fee accumulated by a relayer for a given balance in a managed wallet
     /// into the relayer's wallet
     pub fn settle_online_relayer_fee<S: TopLevelStorage + BorrowMut<Self>>(
@@ -451,7 +1137,10 @@ impl DarkpoolContract {
         valid_relayer_fee_settlement_statement: Bytes,
         relayer_wallet_commitment_signature: Bytes,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_not_paused(storage)?;
+        DarkpoolContract::_check_operation_not_paused(
+            storage,
+            SETTLE_ONLINE_RELAYER_FEE_OPERATION,
+        )?;
 
         let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
         delegate_call_helper::<settleOnlineRelayerFeeCall>(
@@ -473,7 +1162,7 @@ impl DarkpoolContract {
         proof: Bytes,
         valid_offline_fee_settlement_statement: Bytes,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_not_paused(storage)?;
+        DarkpoolContract::_check_operation_not_paused(storage, SETTLE_OFFLINE_FEE_OPERATION)?;
 
         let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
         delegate_call_helper::<settleOfflineFeeCall>(
@@ -494,7 +1183,7 @@ impl DarkpoolContract {
         valid_fee_redemption_statement: Bytes,
         recipient_wallet_commitment_signature: Bytes,
     ) -> Result<(), Vec<u8>> {
-        DarkpoolContract::_check_not_paused(storage)?;
+        DarkpoolContract::_check_operation_not_paused(storage, REDEEM_FEE_OPERATION)?;
 
         let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
         delegate_call_helper::<redeemFeeCall>(
@@ -508,6 +1197,262 @@ impl DarkpoolContract {
         )
         .map(|_| ())
     }
+
+    /// Adds a new wallet to the commitment tree, verifying its proof
+    /// using the given verification keys
+    ///
+    /// The `vkeys` argument is either empty, in which case the verification keys are fetched
+    /// from the vkeys contract, or the serialized verification keys for the circuit, which are
+    /// checked against the registered commitment.
+    ///
+    /// A nonzero `vkey_version` instead fetches the verification keys of that version from the
+    /// vkey registry, in which case `vkeys` must be empty.
+    ///
+    /// The same holds for all of the `_with_vkeys` core methods, which otherwise behave like
+    /// their counterparts above, which always fetch the verification keys from the vkeys contract
+    pub fn new_wallet_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_wallet_create_statement_bytes: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, NEW_WALLET_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<newWalletWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                proof.to_vec().into(),
+                valid_wallet_create_statement_bytes.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Update a wallet in the commitment tree
+    ///
+    /// The proof is verified using the given verification keys, see `new_wallet_with_vkeys`
+    pub fn update_wallet_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_wallet_update_statement_bytes: Bytes,
+        wallet_commitment_signature: Bytes,
+        transfer_aux_data_bytes: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        // The darkpool core contract checks whether the wallet update is paused,
+        // as this depends on the direction of the external transfer in the statement
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<updateWalletWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                proof.to_vec().into(),
+                valid_wallet_update_statement_bytes.to_vec().into(),
+                wallet_commitment_signature.to_vec().into(),
+                transfer_aux_data_bytes.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Settles a matched order between two parties,
+    /// inserting the updated wallets into the commitment tree.
+    ///
+    /// The `match_proofs` argument is the serialization of the [`contracts_common::types::MatchProofs`]
+    /// struct, and the `match_linking_proofs` argument is the serialization of the
+    /// [`contracts_common::types::MatchLinkingProofs`] struct
+    ///
+    /// The proofs are verified using the given verification keys, see `new_wallet_with_vkeys`
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_match_settle_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payload: Bytes,
+        party_1_match_payload: Bytes,
+        valid_match_settle_statement: Bytes,
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, PROCESS_MATCH_SETTLE_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<processMatchSettleWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                party_0_match_payload.to_vec().into(),
+                party_1_match_payload.to_vec().into(),
+                valid_match_settle_statement.to_vec().into(),
+                match_proofs.to_vec().into(),
+                match_linking_proofs.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Settles a batch of matched orders, inserting the updated wallets
+    /// of both parties in each match into the commitment tree.
+    ///
+    /// Each argument is the serialization of a vector of the corresponding
+    /// argument to `process_match_settle`, one element per match
+    ///
+    /// The proofs are verified using the given verification keys, see `new_wallet_with_vkeys`
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_match_settle_batch_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payloads: Bytes,
+        party_1_match_payloads: Bytes,
+        valid_match_settle_statements: Bytes,
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, PROCESS_MATCH_SETTLE_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<processMatchSettleBatchWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                party_0_match_payloads.to_vec().into(),
+                party_1_match_payloads.to_vec().into(),
+                valid_match_settle_statements.to_vec().into(),
+                match_proofs.to_vec().into(),
+                match_linking_proofs.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Settles the fee accumulated by a relayer for a given balance in a managed wallet
+    /// into the relayer's wallet
+    ///
+    /// The proof is verified using the given verification keys, see `new_wallet_with_vkeys`
+    pub fn settle_online_relayer_fee_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_relayer_fee_settlement_statement: Bytes,
+        relayer_wallet_commitment_signature: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(
+            storage,
+            SETTLE_ONLINE_RELAYER_FEE_OPERATION,
+        )?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<settleOnlineRelayerFeeWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                proof.to_vec().into(),
+                valid_relayer_fee_settlement_statement.to_vec().into(),
+                relayer_wallet_commitment_signature.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Settles the fee accumulated either by a relayer or the protocol
+    /// into an encrypted note which is committed to the Merkle tree
+    ///
+    /// The proof is verified using the given verification keys, see `new_wallet_with_vkeys`
+    pub fn settle_offline_fee_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_offline_fee_settlement_statement: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, SETTLE_OFFLINE_FEE_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<settleOfflineFeeWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                proof.to_vec().into(),
+                valid_offline_fee_settlement_statement.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Redeems a fee note into the recipient's wallet, nullifying the note
+    ///
+    /// The proof is verified using the given verification keys, see `new_wallet_with_vkeys`
+    pub fn redeem_fee_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_fee_redemption_statement: Bytes,
+        recipient_wallet_commitment_signature: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::_check_operation_not_paused(storage, REDEEM_FEE_OPERATION)?;
+
+        let darkpool_core_address = storage.borrow_mut().darkpool_core_address.get();
+        delegate_call_helper::<redeemFeeWithVkeysCall>(
+            storage,
+            darkpool_core_address,
+            (
+                proof.to_vec().into(),
+                valid_fee_redemption_statement.to_vec().into(),
+                recipient_wallet_commitment_signature.to_vec().into(),
+                vkeys.to_vec().into(),
+                vkey_version,
+            ),
+        )
+        .map(|_| ())
+    }
+
+    /// Atomically executes a bundle of calls to the darkpool core methods.
+    ///
+    /// Each call is the ABI-encoded calldata of one of the core methods above, and is
+    /// dispatched through that method, so it is subject to the same pause checks.
+    /// If any call fails, the entire bundle reverts.
+    ///
+    /// Only nesting is guarded against: a bundle may not contain a call to `multicall`,
+    /// and `multicall` may not be re-entered while a bundle is executing. The core methods
+    /// themselves do not check whether a multicall is in progress, so a call re-entering
+    /// one of them during a bundle is subject to the same checks as a direct call.
+    pub fn multicall<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        calls: Vec<Bytes>,
+    ) -> Result<(), Vec<u8>> {
+        assert_result!(
+            !storage.borrow().multicall_entered.get(),
+            REENTRANT_MULTICALL_ERROR_MESSAGE
+        )?;
+
+        storage.borrow_mut().multicall_entered.set(true);
+        for call in calls {
+            DarkpoolContract::_dispatch_core_call(storage, &call)?;
+        }
+        storage.borrow_mut().multicall_entered.set(false);
+
+        Ok(())
+    }
 }
 
 /// Internal helper methods
@@ -535,12 +1480,15 @@ impl DarkpoolContract {
     // | OWNABLE |
     // -----------
 
-    /// Updates the stored owner address to `new_owner`
+    /// Updates the stored owner address to `new_owner`,
+    /// clearing any pending ownership transfer
     pub fn _transfer_ownership<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         new_owner: Address,
     ) {
-        storage.borrow_mut().owner.set(new_owner);
+        let this = storage.borrow_mut();
+        this.owner.set(new_owner);
+        this.pending_owner.set(Address::ZERO);
         evm::log(OwnershipTransferred { new_owner })
     }
 
@@ -552,20 +1500,353 @@ impl DarkpoolContract {
         )
     }
 
+    // ------------------
+    // | ACCESS CONTROL |
+    // ------------------
+
+    /// Checks that the sender either holds the given role or is the owner.
+    ///
+    /// The owner implicitly holds every role, so that it retains the ability
+    /// to perform any privileged action.
+    pub fn _check_role<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        role: u8,
+    ) -> Result<(), Vec<u8>> {
+        let this = storage.borrow();
+        let sender = msg::sender();
+        assert_result!(
+            this.owner.get() == sender || this.roles.getter(role).get(sender),
+            MISSING_ROLE_ERROR_MESSAGE
+        )
+    }
+
     // ------------
     // | PAUSABLE |
     // ------------
 
-    /// Checks that the darkpool is paused
+    /// Checks that at least one of the darkpool's operations is paused
     pub fn _check_paused<S: TopLevelStorage + Borrow<Self>>(storage: &S) -> Result<(), Vec<u8>> {
-        assert_result!(storage.borrow().paused.get(), PAUSED_ERROR_MESSAGE)
+        assert_result!(
+            DarkpoolContract::_get_paused_operations(storage) != 0,
+            PAUSED_ERROR_MESSAGE
+        )
     }
 
-    /// Checks that the darkpool is not paused
+    /// Checks that not all of the darkpool's operations are paused
     pub fn _check_not_paused<S: TopLevelStorage + Borrow<Self>>(
         storage: &S,
     ) -> Result<(), Vec<u8>> {
-        assert_result!(!storage.borrow().paused.get(), UNPAUSED_ERROR_MESSAGE)
+        assert_result!(
+            DarkpoolContract::_get_paused_operations(storage) != ALL_OPERATIONS_PAUSED,
+            UNPAUSED_ERROR_MESSAGE
+        )
+    }
+
+    /// Checks that the given operation is not paused
+    pub fn _check_operation_not_paused<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        operation: u8,
+    ) -> Result<(), Vec<u8>> {
+        assert_result!(
+            !is_operation_paused(DarkpoolContract::_get_paused_operations(storage), operation),
+            OPERATION_PAUSED_ERROR_MESSAGE
+        )
+    }
+
+    /// Checks that the given operation identifier refers to a pausable operation
+    pub fn _check_operation(operation: u8) -> Result<(), Vec<u8>> {
+        assert_result!(
+            operation < NUM_PAUSABLE_OPERATIONS,
+            INVALID_OPERATION_ERROR_MESSAGE
+        )
+    }
+
+    /// Gets the bitmask of paused darkpool operations
+    pub fn _get_paused_operations<S: TopLevelStorage + Borrow<Self>>(storage: &S) -> u8 {
+        let this = storage.borrow();
+        get_paused_operations(this.paused.get(), this.paused_operations.get().to::<u8>())
+    }
+
+    /// Sets the bitmask of paused darkpool operations, clearing the legacy
+    /// paused flag which the bitmask now supersedes.
+    ///
+    /// Emits an `OperationPaused` or `OperationUnpaused` event for each operation
+    /// whose pause state changes
+    pub fn _set_paused_operations<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        paused_operations: u8,
+    ) {
+        let prev_paused_operations = DarkpoolContract::_get_paused_operations(storage);
+
+        let this = storage.borrow_mut();
+        this.paused.set(false);
+        this.paused_operations.set(U8::from(paused_operations));
+
+        for operation in 0..NUM_PAUSABLE_OPERATIONS {
+            let was_paused = is_operation_paused(prev_paused_operations, operation);
+            match (
+                was_paused,
+                is_operation_paused(paused_operations, operation),
+            ) {
+                (false, true) => evm::log(OperationPaused { operation }),
+                (true, false) => evm::log(OperationUnpaused { operation }),
+                _ => {}
+            }
+        }
+    }
+
+    // ------------
+    // | TIMELOCK |
+    // ------------
+
+    /// Checks that the given address selector refers to an implementation address
+    pub fn _check_address_selector(address_selector: u8) -> Result<(), Vec<u8>> {
+        assert_result!(
+            address_selector <= VKEY_REGISTRY_ADDRESS_SELECTOR,
+            INVALID_ADDRESS_SELECTOR_ERROR_MESSAGE
+        )
+    }
+
+    /// Clears the pending implementation address change for the given address selector
+    pub fn _clear_pending_address_change<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        address_selector: u8,
+    ) {
+        let this = storage.borrow_mut();
+        this.pending_address_changes.delete(address_selector);
+        this.pending_address_change_timestamps
+            .delete(address_selector);
+    }
+
+    /// Sets the implementation address for the given address selector,
+    /// emitting the corresponding event
+    pub fn _set_implementation_address<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        address_selector: u8,
+        new_address: Address,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolContract::check_address_not_zero(new_address)?;
+        let this = storage.borrow_mut();
+
+        match address_selector {
+            DARKPOOL_CORE_ADDRESS_SELECTOR => {
+                this.darkpool_core_address.set(new_address);
+                evm::log(DarkpoolCoreAddressChanged { new_address });
+            }
+            VERIFIER_ADDRESS_SELECTOR => {
+                this.verifier_address.set(new_address);
+                evm::log(VerifierAddressChanged { new_address });
+            }
+            VKEYS_ADDRESS_SELECTOR => {
+                this.vkeys_address.set(new_address);
+                evm::log(VkeysAddressChanged { new_address });
+            }
+            MERKLE_ADDRESS_SELECTOR => {
+                this.merkle_address.set(new_address);
+                evm::log(MerkleAddressChanged { new_address });
+            }
+            TRANSFER_EXECUTOR_ADDRESS_SELECTOR => {
+                this.transfer_executor_address.set(new_address);
+                evm::log(TransferExecutorAddressChanged { new_address });
+            }
+            VKEY_REGISTRY_ADDRESS_SELECTOR => {
+                this.vkey_registry_address.set(new_address);
+                evm::log(VkeyRegistryAddressChanged { new_address });
+            }
+            _ => return Err(INVALID_ADDRESS_SELECTOR_ERROR_MESSAGE.to_vec()),
+        }
+
+        Ok(())
+    }
+
+    // ---------------------------
+    // | VERIFICATION KEY HASHES |
+    // ---------------------------
+
+    /// Checks that the given circuit identifier refers to a known circuit
+    pub fn _check_circuit_id(circuit_id: u8) -> Result<(), Vec<u8>> {
+        assert_result!(circuit_id < NUM_CIRCUITS, INVALID_CIRCUIT_ID_ERROR_MESSAGE)
+    }
+
+    // -------------
+    // | MULTICALL |
+    // -------------
+
+    /// Decodes the given calldata as a call to one of the darkpool core methods,
+    /// and executes it
+    pub fn _dispatch_core_call<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        calldata: &[u8],
+    ) -> Result<(), Vec<u8>> {
+        assert_result!(calldata.len() >= 4, INVALID_MULTICALL_CALL_ERROR_MESSAGE)?;
+        // Unwrapping here is safe because we index by the exact number of bytes in a selector
+        let selector: [u8; 4] = calldata[..4].try_into().unwrap();
+
+        match selector {
+            newWalletCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<newWalletCall>(calldata)?;
+                DarkpoolContract::new_wallet(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_wallet_create_statement_bytes.to_vec().into(),
+                )
+            }
+            updateWalletCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<updateWalletCall>(calldata)?;
+                DarkpoolContract::update_wallet(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_wallet_update_statement_bytes.to_vec().into(),
+                    call.wallet_commitment_signature.to_vec().into(),
+                    call.transfer_aux_data.to_vec().into(),
+                )
+            }
+            processMatchSettleCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<processMatchSettleCall>(calldata)?;
+                DarkpoolContract::process_match_settle(
+                    storage,
+                    call.party_0_match_payload.to_vec().into(),
+                    call.party_1_match_payload.to_vec().into(),
+                    call.valid_match_settle_statement.to_vec().into(),
+                    call.match_proofs.to_vec().into(),
+                    call.match_linking_proofs.to_vec().into(),
+                )
+            }
+            processMatchSettleBatchCall::SELECTOR => {
+                let call =
+                    DarkpoolContract::_decode_core_call::<processMatchSettleBatchCall>(calldata)?;
+                DarkpoolContract::process_match_settle_batch(
+                    storage,
+                    call.party_0_match_payloads.to_vec().into(),
+                    call.party_1_match_payloads.to_vec().into(),
+                    call.valid_match_settle_statements.to_vec().into(),
+                    call.match_proofs.to_vec().into(),
+                    call.match_linking_proofs.to_vec().into(),
+                )
+            }
+            settleOnlineRelayerFeeCall::SELECTOR => {
+                let call =
+                    DarkpoolContract::_decode_core_call::<settleOnlineRelayerFeeCall>(calldata)?;
+                DarkpoolContract::settle_online_relayer_fee(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_relayer_fee_settlement_statement.to_vec().into(),
+                    call.relayer_wallet_commitment_signature.to_vec().into(),
+                )
+            }
+            settleOfflineFeeCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<settleOfflineFeeCall>(calldata)?;
+                DarkpoolContract::settle_offline_fee(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_offline_fee_settlement_statement.to_vec().into(),
+                )
+            }
+            redeemFeeCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<redeemFeeCall>(calldata)?;
+                DarkpoolContract::redeem_fee(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_fee_redemption_statement.to_vec().into(),
+                    call.recipient_wallet_commitment_signature.to_vec().into(),
+                )
+            }
+            newWalletWithVkeysCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<newWalletWithVkeysCall>(calldata)?;
+                DarkpoolContract::new_wallet_with_vkeys(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_wallet_create_statement_bytes.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            updateWalletWithVkeysCall::SELECTOR => {
+                let call =
+                    DarkpoolContract::_decode_core_call::<updateWalletWithVkeysCall>(calldata)?;
+                DarkpoolContract::update_wallet_with_vkeys(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_wallet_update_statement_bytes.to_vec().into(),
+                    call.wallet_commitment_signature.to_vec().into(),
+                    call.transfer_aux_data.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            processMatchSettleWithVkeysCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<processMatchSettleWithVkeysCall>(
+                    calldata,
+                )?;
+                DarkpoolContract::process_match_settle_with_vkeys(
+                    storage,
+                    call.party_0_match_payload.to_vec().into(),
+                    call.party_1_match_payload.to_vec().into(),
+                    call.valid_match_settle_statement.to_vec().into(),
+                    call.match_proofs.to_vec().into(),
+                    call.match_linking_proofs.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            processMatchSettleBatchWithVkeysCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<
+                    processMatchSettleBatchWithVkeysCall,
+                >(calldata)?;
+                DarkpoolContract::process_match_settle_batch_with_vkeys(
+                    storage,
+                    call.party_0_match_payloads.to_vec().into(),
+                    call.party_1_match_payloads.to_vec().into(),
+                    call.valid_match_settle_statements.to_vec().into(),
+                    call.match_proofs.to_vec().into(),
+                    call.match_linking_proofs.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            settleOnlineRelayerFeeWithVkeysCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<
+                    settleOnlineRelayerFeeWithVkeysCall,
+                >(calldata)?;
+                DarkpoolContract::settle_online_relayer_fee_with_vkeys(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_relayer_fee_settlement_statement.to_vec().into(),
+                    call.relayer_wallet_commitment_signature.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            settleOfflineFeeWithVkeysCall::SELECTOR => {
+                let call =
+                    DarkpoolContract::_decode_core_call::<settleOfflineFeeWithVkeysCall>(calldata)?;
+                DarkpoolContract::settle_offline_fee_with_vkeys(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_offline_fee_settlement_statement.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            redeemFeeWithVkeysCall::SELECTOR => {
+                let call = DarkpoolContract::_decode_core_call::<redeemFeeWithVkeysCall>(calldata)?;
+                DarkpoolContract::redeem_fee_with_vkeys(
+                    storage,
+                    call.proof.to_vec().into(),
+                    call.valid_fee_redemption_statement.to_vec().into(),
+                    call.recipient_wallet_commitment_signature.to_vec().into(),
+                    call.vkeys.to_vec().into(),
+                    call.vkey_version,
+                )
+            }
+            _ => Err(INVALID_MULTICALL_CALL_ERROR_MESSAGE.to_vec()),
+        }
+    }
+
+    /// Decodes the given calldata as a call to the darkpool core method defined by `C`
+    pub fn _decode_core_call<C: SolCall>(calldata: &[u8]) -> Result<C, Vec<u8>> {
+        C::abi_decode(calldata, true /* validate */)
+            .map_err(|_| INVALID_MULTICALL_CALL_ERROR_MESSAGE.to_vec())
     }
 
     // ----------------
@@ -596,4 +1877,46 @@ impl DarkpoolContract {
 
         [protocol_pubkey_x, protocol_pubkey_y]
     }
+
+    /// Appends the given protocol public encryption key to the key history,
+    /// starting the grace window of the key it replaces.
+    ///
+    /// Returns the expiry block of the replaced key, or zero if the history was empty
+    pub fn _push_pubkey_history<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        new_public_encryption_key: [U256; 2],
+    ) -> U256 {
+        let this = storage.borrow_mut();
+        let history_len = this.pubkey_history_len.get().to::<u64>();
+        let current_block = U256::from(block::number());
+
+        let mut previous_pubkey_expiry_block = U256::ZERO;
+        if history_len > 0 {
+            previous_pubkey_expiry_block =
+                current_block.saturating_add(this.pubkey_grace_period.get());
+
+            // We cap the expiry of all prior keys at that of the replaced key, so that
+            // expiry blocks are non-decreasing along the history. This ensures that a
+            // shortened grace period also applies to keys that are still in their grace window.
+            for i in (0..history_len).rev() {
+                let expiry_block = this.pubkey_expiry_blocks.get(i);
+                if expiry_block != U256::ZERO && expiry_block <= previous_pubkey_expiry_block {
+                    break;
+                }
+
+                this.pubkey_expiry_blocks
+                    .insert(i, previous_pubkey_expiry_block);
+            }
+        }
+
+        let mut pubkey = this.pubkey_history.setter(history_len);
+        pubkey.setter(0).unwrap().set(new_public_encryption_key[0]);
+        pubkey.setter(1).unwrap().set(new_public_encryption_key[1]);
+
+        this.pubkey_activation_blocks
+            .insert(history_len, current_block);
+        this.pubkey_history_len.set(U64::from(history_len + 1));
+
+        previous_pubkey_expiry_block
+    }
 }
@@ -3,50 +3,74 @@
 //! certain storage elements are set by the outer contract. As such, its storage layout must
 //! exactly align with that of the outer contract.
 
-use core::borrow::{Borrow, BorrowMut};
+use core::{
+    borrow::{Borrow, BorrowMut},
+    slice,
+};
 
 use crate::{
     assert_result, if_verifying,
     utils::{
         constants::{
-            CALL_RETDATA_DECODING_ERROR_MESSAGE, INVALID_ARR_LEN_ERROR_MESSAGE,
-            INVALID_ORDER_SETTLEMENT_INDICES_ERROR_MESSAGE, INVALID_PROTOCOL_FEE_ERROR_MESSAGE,
-            INVALID_PROTOCOL_PUBKEY_ERROR_MESSAGE, MERKLE_STORAGE_GAP_SIZE,
-            NULLIFIER_SPENT_ERROR_MESSAGE, PUBLIC_BLINDER_USED_ERROR_MESSAGE,
-            ROOT_NOT_IN_HISTORY_ERROR_MESSAGE, TRANSFER_EXECUTOR_STORAGE_GAP_SIZE,
+            CALL_RETDATA_DECODING_ERROR_MESSAGE, CONFLICTING_VKEY_SOURCES_ERROR_MESSAGE,
+            INVALID_ARR_LEN_ERROR_MESSAGE, INVALID_ORDER_SETTLEMENT_INDICES_ERROR_MESSAGE,
+            INVALID_PROTOCOL_FEE_ERROR_MESSAGE, INVALID_PROTOCOL_PUBKEY_ERROR_MESSAGE,
+            INVALID_VKEY_HASH_ERROR_MESSAGE, MERKLE_STORAGE_GAP_SIZE,
+            NULLIFIER_SPENT_ERROR_MESSAGE, OPERATION_PAUSED_ERROR_MESSAGE,
+            PUBLIC_BLINDER_USED_ERROR_MESSAGE, ROOT_NOT_IN_HISTORY_ERROR_MESSAGE,
+            TRANSFER_EXECUTOR_STORAGE_GAP_SIZE, UNREGISTERED_VKEY_HASH_ERROR_MESSAGE,
             VERIFICATION_FAILED_ERROR_MESSAGE,
         },
         helpers::{
-            delegate_call_helper, deserialize_from_calldata, get_public_blinder_from_shares,
-            map_call_error, postcard_serialize, serialize_match_statements_for_verification,
-            serialize_statement_for_verification, static_call_helper, u256_to_scalar,
+            delegate_call_helper, deserialize_from_calldata, get_paused_operations,
+            get_public_blinder_from_shares, is_operation_paused, map_call_error,
+            match_statements_to_public_inputs, postcard_serialize,
+            serialize_match_statements_for_verification, serialize_statement_for_verification,
+            static_call_helper, u256_to_scalar,
         },
         solidity::{
-            executeExternalTransferCall, insertNoteCommitmentCall, insertSharesCommitmentCall,
+            executeExternalTransferCall, getVkeyCall, insertNoteCommitmentCall,
+            insertSharesCommitmentCall, insertSharesCommitmentsBatchCall,
             processMatchSettleVkeysCall, rootInHistoryCall, validFeeRedemptionVkeyCall,
             validOfflineFeeSettlementVkeyCall, validRelayerFeeSettlementVkeyCall,
-            validWalletCreateVkeyCall, validWalletUpdateVkeyCall, verifyCall, verifyMatchCall,
-            verifyStateSigAndInsertCall, NotePosted, NullifierSpent, WalletUpdated,
+            validWalletCreateVkeyCall, validWalletUpdateVkeyCall, verifyCall, verifyMatchBatchCall,
+            verifyMatchCall, verifyStateSigAndInsertCall, NotePosted, NullifierSpent,
+            WalletUpdated,
         },
     },
 };
 use alloc::{vec, vec::Vec};
 use alloy_sol_types::{sol_data::Bytes as AlloyBytes, SolCall, SolType};
 use contracts_common::{
+    constants::{
+        PROCESS_MATCH_SETTLE_CIRCUIT_ID, UPDATE_WALLET_DEPOSIT_OPERATION,
+        UPDATE_WALLET_WITHDRAWAL_OPERATION, VALID_FEE_REDEMPTION_CIRCUIT_ID,
+        VALID_OFFLINE_FEE_SETTLEMENT_CIRCUIT_ID, VALID_RELAYER_FEE_SETTLEMENT_CIRCUIT_ID,
+        VALID_WALLET_CREATE_CIRCUIT_ID, VALID_WALLET_UPDATE_CIRCUIT_ID,
+    },
     custom_serde::{pk_to_u256s, scalar_to_u256},
     types::{
         ExternalTransfer, MatchPayload, PublicEncryptionKey, PublicSigningKey, ScalarField,
-        ValidFeeRedemptionStatement, ValidMatchSettleStatement, ValidOfflineFeeSettlementStatement,
-        ValidRelayerFeeSettlementStatement, ValidWalletCreateStatement, ValidWalletUpdateStatement,
+        ValidFeeRedemptionStatement, ValidMatchSettleWithMintStatement,
+        ValidOfflineFeeSettlementStatement, ValidRelayerFeeSettlementStatement,
+        ValidWalletCreateStatement, ValidWalletUpdateStatement,
     },
 };
+use contracts_core::darkpool::{
+    check_match_settle_batch_lengths, check_match_settle_indices, check_match_settle_protocol_fee,
+};
 use stylus_sdk::{
     abi::Bytes,
-    alloy_primitives::U256,
+    alloy_primitives::{Address, B256, U256},
+    block,
     call::static_call,
+    crypto::keccak,
     evm,
     prelude::*,
-    storage::{StorageAddress, StorageArray, StorageBool, StorageMap, StorageU256, StorageU64},
+    storage::{
+        StorageAddress, StorageArray, StorageB256, StorageBool, StorageMap, StorageU256,
+        StorageU64, StorageU8,
+    },
 };
 
 /// The darkpool core contract's storage layout.
@@ -73,9 +97,9 @@ pub struct DarkpoolCoreContract {
     /// (unused in the darkpool core contract)
     _initialized: StorageU64,
 
-    /// Whether or not the darkpool is paused
-    /// (unused in the darkpool core contract)
-    _paused: StorageBool,
+    /// Whether or not the darkpool is paused, as set by darkpools that predate
+    /// per-operation pausing
+    paused: StorageBool,
 
     /// The address of the darkpool core contract
     /// (unused in the darkpool core contract)
@@ -112,6 +136,80 @@ pub struct DarkpoolCoreContract {
 
     /// The BabyJubJub EC-ElGamal public encryption key for the protocol
     protocol_public_encryption_key: StorageArray<StorageU256, 2>,
+
+    /// The set of accounts granted each role
+    /// (unused in the darkpool core contract)
+    _roles: StorageMap<u8, StorageMap<Address, StorageBool>>,
+
+    /// The address pending acceptance of ownership of the darkpool
+    /// (unused in the darkpool core contract)
+    _pending_owner: StorageAddress,
+
+    /// The delay between scheduling an implementation address change and executing it
+    /// (unused in the darkpool core contract)
+    _address_change_delay: StorageU256,
+
+    /// The pending implementation address changes
+    /// (unused in the darkpool core contract)
+    _pending_address_changes: StorageMap<u8, StorageAddress>,
+
+    /// The timestamps at which the pending implementation address changes may be executed
+    /// (unused in the darkpool core contract)
+    _pending_address_change_timestamps: StorageMap<u8, StorageU256>,
+
+    /// The per-asset overrides of the protocol fee, representing a mapping
+    /// from a mint to the fee charged on matches of the pair with that base mint.
+    ///
+    /// A value of zero indicates that no override is set, in which case the
+    /// global protocol fee applies
+    protocol_fee_overrides: StorageMap<Address, StorageU256>,
+
+    /// The number of blocks for which a protocol public encryption key
+    /// is still accepted after it has been rotated out
+    /// (unused in the darkpool core contract)
+    _pubkey_grace_period: StorageU256,
+
+    /// The number of protocol public encryption keys in the key history
+    pubkey_history_len: StorageU64,
+
+    /// The history of protocol public encryption keys, representing a mapping
+    /// from the index of a key in the history to its affine coordinates
+    pubkey_history: StorageMap<u64, StorageArray<StorageU256, 2>>,
+
+    /// The block numbers at which the keys in the history were activated,
+    /// keyed by index in the history
+    /// (unused in the darkpool core contract)
+    _pubkey_activation_blocks: StorageMap<u64, StorageU256>,
+
+    /// The last block numbers at which the keys in the history are accepted,
+    /// keyed by index in the history. Zero if the key has not been rotated out
+    pubkey_expiry_blocks: StorageMap<u64, StorageU256>,
+
+    /// Whether or not a multicall is in progress
+    /// (unused in the darkpool core contract)
+    _multicall_entered: StorageBool,
+
+    /// The registered verification key commitments, representing a mapping
+    /// from a circuit identifier to the keccak hash of the circuit's serialized
+    /// verification keys
+    vkey_hashes: StorageMap<u8, StorageB256>,
+
+    /// The address of the vkey registry contract, from which versioned
+    /// verification keys are fetched
+    vkey_registry_address: StorageAddress,
+
+    /// The pending change of the address change delay
+    /// (unused in the darkpool core contract)
+    _pending_address_change_delay: StorageU256,
+
+    /// The timestamp at which the pending change of the address change delay
+    /// may be executed
+    /// (unused in the darkpool core contract)
+    _pending_address_change_delay_timestamp: StorageU256,
+
+    /// A bitmask of the paused darkpool operations, in which bit `i`
+    /// is set if the operation with identifier `i` is paused
+    paused_operations: StorageU8,
 }
 
 #[external]
@@ -121,13 +219,165 @@ impl DarkpoolCoreContract {
         storage: &mut S,
         proof: Bytes,
         valid_wallet_create_statement_bytes: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::new_wallet_with_vkeys(
+            storage,
+            proof,
+            valid_wallet_create_statement_bytes,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Update a wallet in the commitment tree
+    pub fn update_wallet<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_wallet_update_statement_bytes: Bytes,
+        wallet_commitment_signature: Bytes,
+        transfer_aux_data_bytes: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::update_wallet_with_vkeys(
+            storage,
+            proof,
+            valid_wallet_update_statement_bytes,
+            wallet_commitment_signature,
+            transfer_aux_data_bytes,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Settles a matched order between two parties,
+    /// inserting the updated wallets into the commitment tree.
+    ///
+    /// The `match_proofs` argument is the serialization of the [`contracts_common::types::MatchProofs`]
+    /// struct, and the `match_linking_proofs` argument is the serialization of the
+    /// [`contracts_common::types::MatchLinkingProofs`] struct
+    pub fn process_match_settle<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payload: Bytes,
+        party_1_match_payload: Bytes,
+        valid_match_settle_statement: Bytes,
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::process_match_settle_with_vkeys(
+            storage,
+            party_0_match_payload,
+            party_1_match_payload,
+            valid_match_settle_statement,
+            match_proofs,
+            match_linking_proofs,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Settles a batch of matched orders, inserting the updated wallets
+    /// of both parties in each match into the commitment tree.
+    ///
+    /// The proofs for all of the matches are verified together, using a single pairing check.
+    ///
+    /// The `party_0_match_payloads`, `party_1_match_payloads`, and `valid_match_settle_statements`
+    /// arguments are the serializations of vectors of [`contracts_common::types::MatchPayload`]s
+    /// and [`contracts_common::types::ValidMatchSettleWithMintStatement`]s, respectively.
+    /// Similarly, the `match_proofs` argument is the serialization of a vector of
+    /// [`contracts_common::types::MatchProofs`] structs, and the `match_linking_proofs` argument
+    /// is the serialization of a vector of [`contracts_common::types::MatchLinkingProofs`] structs
+    pub fn process_match_settle_batch<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payloads: Bytes,
+        party_1_match_payloads: Bytes,
+        valid_match_settle_statements: Bytes,
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::process_match_settle_batch_with_vkeys(
+            storage,
+            party_0_match_payloads,
+            party_1_match_payloads,
+            valid_match_settle_statements,
+            match_proofs,
+            match_linking_proofs,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Settles the fee accumulated by a relayer for a given balance in a managed wallet
+    /// into the relayer's wallet
+    pub fn settle_online_relayer_fee<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_relayer_fee_settlement_statement: Bytes,
+        relayer_wallet_commitment_signature: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::settle_online_relayer_fee_with_vkeys(
+            storage,
+            proof,
+            valid_relayer_fee_settlement_statement,
+            relayer_wallet_commitment_signature,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Settles the fee accumulated either by a relayer or the protocol
+    /// into an encrypted note which is committed to the Merkle tree
+    pub fn settle_offline_fee<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_offline_fee_settlement_statement: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::settle_offline_fee_with_vkeys(
+            storage,
+            proof,
+            valid_offline_fee_settlement_statement,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Redeems a fee note into the recipient's wallet, nullifying the note
+    pub fn redeem_fee<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_fee_redemption_statement: Bytes,
+        recipient_wallet_commitment_signature: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        DarkpoolCoreContract::redeem_fee_with_vkeys(
+            storage,
+            proof,
+            valid_fee_redemption_statement,
+            recipient_wallet_commitment_signature,
+            Vec::new().into(),
+            0, /* vkey_version */
+        )
+    }
+
+    /// Adds a new wallet to the commitment tree
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    pub fn new_wallet_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        proof: Bytes,
+        valid_wallet_create_statement_bytes: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         let valid_wallet_create_statement: ValidWalletCreateStatement =
             deserialize_from_calldata(&valid_wallet_create_statement_bytes)?;
 
         if_verifying!({
-            let valid_wallet_create_vkey_bytes =
-                DarkpoolCoreContract::fetch_vkeys(storage, &validWalletCreateVkeyCall::SELECTOR)?;
+            let valid_wallet_create_vkey_bytes = DarkpoolCoreContract::resolve_vkeys(
+                storage,
+                VALID_WALLET_CREATE_CIRCUIT_ID,
+                &validWalletCreateVkeyCall::SELECTOR,
+                vkeys,
+                vkey_version,
+            )?;
 
             assert_result!(
                 DarkpoolCoreContract::verify(
@@ -155,19 +405,34 @@ impl DarkpoolCoreContract {
     }
 
     /// Update a wallet in the commitment tree
-    pub fn update_wallet<S: TopLevelStorage + BorrowMut<Self>>(
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    pub fn update_wallet_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         proof: Bytes,
         valid_wallet_update_statement_bytes: Bytes,
         wallet_commitment_signature: Bytes,
         transfer_aux_data_bytes: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         let valid_wallet_update_statement: ValidWalletUpdateStatement =
             deserialize_from_calldata(&valid_wallet_update_statement_bytes)?;
 
+        DarkpoolCoreContract::check_wallet_update_not_paused(
+            storage,
+            &valid_wallet_update_statement.external_transfer,
+        )?;
+
         if_verifying!({
-            let valid_wallet_update_vkey_bytes =
-                DarkpoolCoreContract::fetch_vkeys(storage, &validWalletUpdateVkeyCall::SELECTOR)?;
+            let valid_wallet_update_vkey_bytes = DarkpoolCoreContract::resolve_vkeys(
+                storage,
+                VALID_WALLET_UPDATE_CIRCUIT_ID,
+                &validWalletUpdateVkeyCall::SELECTOR,
+                vkeys,
+                vkey_version,
+            )?;
 
             assert_result!(
                 DarkpoolCoreContract::verify(
@@ -208,13 +473,19 @@ impl DarkpoolCoreContract {
     /// The `match_proofs` argument is the serialization of the [`contracts_common::types::MatchProofs`]
     /// struct, and the `match_linking_proofs` argument is the serialization of the
     /// [`contracts_common::types::MatchLinkingProofs`] struct
-    pub fn process_match_settle<S: TopLevelStorage + BorrowMut<Self>>(
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_match_settle_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         party_0_match_payload: Bytes,
         party_1_match_payload: Bytes,
         valid_match_settle_statement: Bytes,
         match_proofs: Bytes,
         match_linking_proofs: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         let party_0_match_payload: MatchPayload =
             deserialize_from_calldata(&party_0_match_payload)?;
@@ -222,27 +493,15 @@ impl DarkpoolCoreContract {
         let party_1_match_payload: MatchPayload =
             deserialize_from_calldata(&party_1_match_payload)?;
 
-        let valid_match_settle_statement: ValidMatchSettleStatement =
+        let valid_match_settle_statement: ValidMatchSettleWithMintStatement =
             deserialize_from_calldata(&valid_match_settle_statement)?;
 
         if_verifying!({
-            let party0_same_indices = party_0_match_payload.valid_commitments_statement.indices
-                == valid_match_settle_statement.party0_indices;
-            let party1_same_indices = party_1_match_payload.valid_commitments_statement.indices
-                == valid_match_settle_statement.party1_indices;
-
-            assert_result!(
-                party0_same_indices && party1_same_indices,
-                INVALID_ORDER_SETTLEMENT_INDICES_ERROR_MESSAGE
-            )?;
-
-            // We convert the protocol fee directly to a scalar as it is already kept
-            // in storage as fixed-point number, no manipulation is needed to coerce it
-            // to the form expected in the statement / circuit.
-            let protocol_fee = u256_to_scalar(storage.borrow_mut().protocol_fee.get())?;
-            assert_result!(
-                valid_match_settle_statement.protocol_fee == protocol_fee,
-                INVALID_PROTOCOL_FEE_ERROR_MESSAGE
+            DarkpoolCoreContract::check_match_settle_inputs(
+                storage,
+                &party_0_match_payload,
+                &party_1_match_payload,
+                &valid_match_settle_statement,
             )?;
 
             DarkpoolCoreContract::batch_verify_process_match_settle(
@@ -252,51 +511,115 @@ impl DarkpoolCoreContract {
                 &valid_match_settle_statement,
                 match_proofs,
                 match_linking_proofs,
+                vkeys,
+                vkey_version,
             )?;
         });
 
-        DarkpoolCoreContract::rotate_wallet(
+        DarkpoolCoreContract::rotate_match_wallets(
             storage,
-            party_0_match_payload
-                .valid_reblind_statement
-                .original_shares_nullifier,
-            party_0_match_payload.valid_reblind_statement.merkle_root,
-            party_0_match_payload
-                .valid_reblind_statement
-                .reblinded_private_shares_commitment,
-            &valid_match_settle_statement.party0_modified_shares,
-        )?;
+            slice::from_ref(&party_0_match_payload),
+            slice::from_ref(&party_1_match_payload),
+            slice::from_ref(&valid_match_settle_statement),
+        )
+    }
 
-        DarkpoolCoreContract::rotate_wallet(
-            storage,
-            party_1_match_payload
-                .valid_reblind_statement
-                .original_shares_nullifier,
-            party_1_match_payload.valid_reblind_statement.merkle_root,
-            party_1_match_payload
-                .valid_reblind_statement
-                .reblinded_private_shares_commitment,
-            &valid_match_settle_statement.party1_modified_shares,
+    /// Settles a batch of matched orders, inserting the updated wallets
+    /// of both parties in each match into the commitment tree.
+    ///
+    /// The proofs for all of the matches are verified together, using a single pairing check.
+    ///
+    /// The `party_0_match_payloads`, `party_1_match_payloads`, and `valid_match_settle_statements`
+    /// arguments are the serializations of vectors of [`contracts_common::types::MatchPayload`]s
+    /// and [`contracts_common::types::ValidMatchSettleWithMintStatement`]s, respectively.
+    /// Similarly, the `match_proofs` argument is the serialization of a vector of
+    /// [`contracts_common::types::MatchProofs`] structs, and the `match_linking_proofs` argument
+    /// is the serialization of a vector of [`contracts_common::types::MatchLinkingProofs`] structs
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_match_settle_batch_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payloads: Bytes,
+        party_1_match_payloads: Bytes,
+        valid_match_settle_statements: Bytes,
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        let party_0_match_payloads: Vec<MatchPayload> =
+            deserialize_from_calldata(&party_0_match_payloads)?;
+
+        let party_1_match_payloads: Vec<MatchPayload> =
+            deserialize_from_calldata(&party_1_match_payloads)?;
+
+        let valid_match_settle_statements: Vec<ValidMatchSettleWithMintStatement> =
+            deserialize_from_calldata(&valid_match_settle_statements)?;
+
+        assert_result!(
+            check_match_settle_batch_lengths(
+                &party_0_match_payloads,
+                &party_1_match_payloads,
+                &valid_match_settle_statements,
+            ),
+            INVALID_ARR_LEN_ERROR_MESSAGE
         )?;
 
-        Ok(())
+        if_verifying!({
+            for i in 0..valid_match_settle_statements.len() {
+                DarkpoolCoreContract::check_match_settle_inputs(
+                    storage,
+                    &party_0_match_payloads[i],
+                    &party_1_match_payloads[i],
+                    &valid_match_settle_statements[i],
+                )?;
+            }
+
+            DarkpoolCoreContract::batch_verify_process_match_settle_batch(
+                storage,
+                &party_0_match_payloads,
+                &party_1_match_payloads,
+                &valid_match_settle_statements,
+                match_proofs,
+                match_linking_proofs,
+                vkeys,
+                vkey_version,
+            )?;
+        });
+
+        DarkpoolCoreContract::rotate_match_wallets(
+            storage,
+            &party_0_match_payloads,
+            &party_1_match_payloads,
+            &valid_match_settle_statements,
+        )
     }
 
     /// Settles the fee accumulated by a relayer for a given balance in a managed wallet
     /// into the relayer's wallet
-    pub fn settle_online_relayer_fee<S: TopLevelStorage + BorrowMut<Self>>(
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    pub fn settle_online_relayer_fee_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         proof: Bytes,
         valid_relayer_fee_settlement_statement: Bytes,
         relayer_wallet_commitment_signature: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         let valid_relayer_fee_settlement_statement: ValidRelayerFeeSettlementStatement =
             deserialize_from_calldata(&valid_relayer_fee_settlement_statement)?;
 
         if_verifying!({
-            let valid_relayer_fee_settlement_vkey_bytes = DarkpoolCoreContract::fetch_vkeys(
+            let valid_relayer_fee_settlement_vkey_bytes = DarkpoolCoreContract::resolve_vkeys(
                 storage,
+                VALID_RELAYER_FEE_SETTLEMENT_CIRCUIT_ID,
                 &validRelayerFeeSettlementVkeyCall::SELECTOR,
+                vkeys,
+                vkey_version,
             )?;
 
             assert_result!(
@@ -331,25 +654,34 @@ impl DarkpoolCoreContract {
 
     /// Settles the fee accumulated either by a relayer or the protocol
     /// into an encrypted note which is committed to the Merkle tree
-    pub fn settle_offline_fee<S: TopLevelStorage + BorrowMut<Self>>(
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    pub fn settle_offline_fee_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         proof: Bytes,
         valid_offline_fee_settlement_statement: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         let valid_offline_fee_settlement_statement: ValidOfflineFeeSettlementStatement =
             deserialize_from_calldata(&valid_offline_fee_settlement_statement)?;
 
         if_verifying!({
-            let protocol_pubkey =
-                DarkpoolCoreContract::get_protocol_public_encryption_key(storage)?;
             assert_result!(
-                valid_offline_fee_settlement_statement.protocol_key == protocol_pubkey,
+                DarkpoolCoreContract::is_protocol_public_encryption_key_accepted(
+                    storage,
+                    &valid_offline_fee_settlement_statement.protocol_key,
+                )?,
                 INVALID_PROTOCOL_PUBKEY_ERROR_MESSAGE
             )?;
 
-            let valid_offline_fee_settlement_vkey_bytes = DarkpoolCoreContract::fetch_vkeys(
+            let valid_offline_fee_settlement_vkey_bytes = DarkpoolCoreContract::resolve_vkeys(
                 storage,
+                VALID_OFFLINE_FEE_SETTLEMENT_CIRCUIT_ID,
                 &validOfflineFeeSettlementVkeyCall::SELECTOR,
+                vkeys,
+                vkey_version,
             )?;
 
             assert_result!(
@@ -378,18 +710,28 @@ impl DarkpoolCoreContract {
     }
 
     /// Redeems a fee note into the recipient's wallet, nullifying the note
-    pub fn redeem_fee<S: TopLevelStorage + BorrowMut<Self>>(
+    ///
+    /// The verification keys are resolved from `vkeys` & `vkey_version`,
+    /// see [`DarkpoolCoreContract::resolve_vkeys`]
+    pub fn redeem_fee_with_vkeys<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         proof: Bytes,
         valid_fee_redemption_statement: Bytes,
         recipient_wallet_commitment_signature: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         let valid_fee_redemption_statement: ValidFeeRedemptionStatement =
             deserialize_from_calldata(&valid_fee_redemption_statement)?;
 
         if_verifying!({
-            let valid_fee_redemption_vkey_bytes =
-                DarkpoolCoreContract::fetch_vkeys(storage, &validFeeRedemptionVkeyCall::SELECTOR)?;
+            let valid_fee_redemption_vkey_bytes = DarkpoolCoreContract::resolve_vkeys(
+                storage,
+                VALID_FEE_REDEMPTION_CIRCUIT_ID,
+                &validFeeRedemptionVkeyCall::SELECTOR,
+                vkeys,
+                vkey_version,
+            )?;
 
             assert_result!(
                 DarkpoolCoreContract::verify(
@@ -447,7 +789,119 @@ impl DarkpoolCoreContract {
         })
     }
 
-    /// Checks that the given Merkle root is in the root history
+    /// Checks whether the given protocol public encryption key is accepted,
+    /// i.e. it is either the current key, or a key in the history
+    /// that is still inside its grace window
+    pub fn is_protocol_public_encryption_key_accepted<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        protocol_key: &PublicEncryptionKey,
+    ) -> Result<bool, Vec<u8>> {
+        if *protocol_key == DarkpoolCoreContract::get_protocol_public_encryption_key(storage)? {
+            return Ok(true);
+        }
+
+        // The last key in the history is the current key, so we only
+        // check the keys that have been rotated out, from newest to oldest
+        let history_len = storage.borrow().pubkey_history_len.get().to::<u64>();
+        let current_block = U256::from(block::number());
+        for i in (0..history_len.saturating_sub(1)).rev() {
+            // Expiry blocks are non-decreasing along the history,
+            // so once a key has expired, so have all of the keys before it
+            if storage.borrow().pubkey_expiry_blocks.get(i) < current_block {
+                break;
+            }
+
+            let historic_key = storage.borrow().pubkey_history.getter(i);
+            let historic_key = PublicEncryptionKey {
+                x: u256_to_scalar(historic_key.get(0).unwrap())?,
+                y: u256_to_scalar(historic_key.get(1).unwrap())?,
+            };
+
+            if *protocol_key == historic_key {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Gets the protocol fee charged on matches of the pair with the given base mint,
+    /// falling back to the global protocol fee if no override is set for the mint
+    pub fn get_protocol_fee_for_mint<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        mint: Address,
+    ) -> U256 {
+        let fee_override = storage.borrow().protocol_fee_overrides.get(mint);
+        if fee_override != U256::ZERO {
+            return fee_override;
+        }
+
+        storage.borrow().protocol_fee.get()
+    }
+
+    /// Checks that a wallet update with the given external transfer is not paused.
+    ///
+    /// Deposits & withdrawals are paused independently. A wallet update with no external
+    /// transfer is only considered paused if both deposits & withdrawals are paused
+    pub fn check_wallet_update_not_paused<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        external_transfer: &Option<ExternalTransfer>,
+    ) -> Result<(), Vec<u8>> {
+        let this = storage.borrow();
+        let paused_operations =
+            get_paused_operations(this.paused.get(), this.paused_operations.get().to::<u8>());
+        let deposits_paused =
+            is_operation_paused(paused_operations, UPDATE_WALLET_DEPOSIT_OPERATION);
+        let withdrawals_paused =
+            is_operation_paused(paused_operations, UPDATE_WALLET_WITHDRAWAL_OPERATION);
+
+        let paused = match external_transfer {
+            Some(transfer) if transfer.is_withdrawal => withdrawals_paused,
+            Some(_) => deposits_paused,
+            None => deposits_paused && withdrawals_paused,
+        };
+
+        assert_result!(!paused, OPERATION_PAUSED_ERROR_MESSAGE)
+    }
+
+    /// Checks that the order settlement indices in the given match payloads are
+    /// consistent with those in the `VALID MATCH SETTLE` statement, and that the
+    /// protocol fee used in the statement is the one configured for its base mint
+    pub fn check_match_settle_inputs<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        party_0_match_payload: &MatchPayload,
+        party_1_match_payload: &MatchPayload,
+        valid_match_settle_statement: &ValidMatchSettleWithMintStatement,
+    ) -> Result<(), Vec<u8>> {
+        assert_result!(
+            check_match_settle_indices(
+                party_0_match_payload,
+                party_1_match_payload,
+                &valid_match_settle_statement.valid_match_settle,
+            ),
+            INVALID_ORDER_SETTLEMENT_INDICES_ERROR_MESSAGE
+        )?;
+
+        // We convert the protocol fee directly to a scalar as it is already kept
+        // in storage as fixed-point number, no manipulation is needed to coerce it
+        // to the form expected in the statement / circuit.
+        let protocol_fee = u256_to_scalar(DarkpoolCoreContract::get_protocol_fee_for_mint(
+            storage,
+            valid_match_settle_statement.base_mint,
+        ))?;
+        assert_result!(
+            check_match_settle_protocol_fee(
+                &valid_match_settle_statement.valid_match_settle,
+                protocol_fee,
+            ),
+            INVALID_PROTOCOL_FEE_ERROR_MESSAGE
+        )
+    }
+
+    /// Checks that the given Merkle root is in the root history.
+    ///
+    /// The Merkle contract reverts with a distinct error if the root
+    /// is in the history but has expired
     pub fn check_root_in_history<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         root: ScalarField,
@@ -475,6 +929,71 @@ impl DarkpoolCoreContract {
         Ok(vkey_bytes.to_vec())
     }
 
+    /// Resolves the verification keys for the circuit with the given identifier.
+    ///
+    /// A nonzero version tag fetches the verification keys of that version from the vkey registry,
+    /// in which case no verification keys may be given in calldata.
+    ///
+    /// Otherwise, if no verification keys are given in calldata, they are fetched from the vkeys
+    /// contract by their associated method selector. If they are, the given verification keys are
+    /// used, once checked against the commitment registered for the circuit
+    pub fn resolve_vkeys<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        circuit_id: u8,
+        selector: &[u8],
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<Vec<u8>, Vec<u8>> {
+        if vkey_version != 0 {
+            assert_result!(vkeys.is_empty(), CONFLICTING_VKEY_SOURCES_ERROR_MESSAGE)?;
+            return DarkpoolCoreContract::fetch_registry_vkeys(storage, circuit_id, vkey_version);
+        }
+
+        if vkeys.is_empty() {
+            return DarkpoolCoreContract::fetch_vkeys(storage, selector);
+        }
+
+        DarkpoolCoreContract::check_vkey_hash(storage, circuit_id, &vkeys)?;
+        Ok(vkeys.0)
+    }
+
+    /// Fetches the verification keys of the given version for the circuit
+    /// with the given identifier from the vkey registry
+    pub fn fetch_registry_vkeys<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        circuit_id: u8,
+        vkey_version: u64,
+    ) -> Result<Vec<u8>, Vec<u8>> {
+        let vkey_registry_address = storage.borrow().vkey_registry_address.get();
+        let (vkey_bytes,) = static_call_helper::<getVkeyCall>(
+            storage,
+            vkey_registry_address,
+            (circuit_id, vkey_version),
+        )?
+        .into();
+
+        Ok(vkey_bytes.to_vec())
+    }
+
+    /// Checks that the keccak hash of the given serialized verification keys
+    /// matches the commitment registered for the circuit with the given identifier
+    pub fn check_vkey_hash<S: TopLevelStorage + Borrow<Self>>(
+        storage: &S,
+        circuit_id: u8,
+        vkey_bytes: &[u8],
+    ) -> Result<(), Vec<u8>> {
+        let vkey_hash = storage.borrow().vkey_hashes.get(circuit_id);
+        assert_result!(
+            vkey_hash != B256::ZERO,
+            UNREGISTERED_VKEY_HASH_ERROR_MESSAGE
+        )?;
+
+        assert_result!(
+            keccak(vkey_bytes) == vkey_hash,
+            INVALID_VKEY_HASH_ERROR_MESSAGE
+        )
+    }
+
     /// Calls the verifier contract with the given selector.
     ///
     /// Assumes that the argument type is a single `bytes` value and the return type is a single `bool`.
@@ -566,6 +1085,32 @@ impl DarkpoolCoreContract {
         .map(|_| ())
     }
 
+    /// Prepares the private shares commitments & public wallet shares of a batch of wallets
+    /// for insertion into the Merkle tree and delegate-calls the appropriate method on the
+    /// Merkle contract, which inserts them at consecutive indices
+    pub fn insert_wallet_commitments_batch_to_merkle_tree<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        wallets: &[(ScalarField, &[ScalarField])],
+    ) -> Result<(), Vec<u8>> {
+        let total_wallet_shares: Vec<Vec<U256>> = wallets
+            .iter()
+            .map(|(private_shares_commitment, public_wallet_shares)| {
+                Self::prepare_wallet_shares_for_insertion(
+                    *private_shares_commitment,
+                    public_wallet_shares,
+                )
+            })
+            .collect();
+
+        let merkle_address = storage.borrow_mut().merkle_address.get();
+        delegate_call_helper::<insertSharesCommitmentsBatchCall>(
+            storage,
+            merkle_address,
+            (total_wallet_shares,),
+        )
+        .map(|_| ())
+    }
+
     /// Prepares the private shares commitment & public wallet shares for insertion into the Merkle
     /// tree, as well as the signature & pubkey for verification, and delegate-calls the appropriate
     /// method on the Merkle contract
@@ -646,13 +1191,20 @@ impl DarkpoolCoreContract {
         storage: &mut S,
         party_0_match_payload: &MatchPayload,
         party_1_match_payload: &MatchPayload,
-        valid_match_settle_statement: &ValidMatchSettleStatement,
+        valid_match_settle_statement: &ValidMatchSettleWithMintStatement,
         match_proofs: Bytes,
         match_linking_proofs: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
     ) -> Result<(), Vec<u8>> {
         // Fetch the Plonk & linking verification keys used in verifying the matching of a trade
-        let process_match_settle_vkeys =
-            DarkpoolCoreContract::fetch_vkeys(storage, &processMatchSettleVkeysCall::SELECTOR)?;
+        let process_match_settle_vkeys = DarkpoolCoreContract::resolve_vkeys(
+            storage,
+            PROCESS_MATCH_SETTLE_CIRCUIT_ID,
+            &processMatchSettleVkeysCall::SELECTOR,
+            vkeys,
+            vkey_version,
+        )?;
 
         let match_public_inputs = serialize_match_statements_for_verification(
             &party_0_match_payload.valid_commitments_statement,
@@ -678,6 +1230,120 @@ impl DarkpoolCoreContract {
         assert_result!(result._0, VERIFICATION_FAILED_ERROR_MESSAGE)
     }
 
+    /// Batch-verifies all of the proofs for a batch of `process_match_settle` calls
+    #[allow(clippy::too_many_arguments)]
+    pub fn batch_verify_process_match_settle_batch<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payloads: &[MatchPayload],
+        party_1_match_payloads: &[MatchPayload],
+        valid_match_settle_statements: &[ValidMatchSettleWithMintStatement],
+        match_proofs: Bytes,
+        match_linking_proofs: Bytes,
+        vkeys: Bytes,
+        vkey_version: u64,
+    ) -> Result<(), Vec<u8>> {
+        // Fetch the Plonk & linking verification keys used in verifying the matching of a trade
+        let process_match_settle_vkeys = DarkpoolCoreContract::resolve_vkeys(
+            storage,
+            PROCESS_MATCH_SETTLE_CIRCUIT_ID,
+            &processMatchSettleVkeysCall::SELECTOR,
+            vkeys,
+            vkey_version,
+        )?;
+
+        let match_public_inputs = party_0_match_payloads
+            .iter()
+            .zip(party_1_match_payloads)
+            .zip(valid_match_settle_statements)
+            .map(
+                |((party_0_match_payload, party_1_match_payload), valid_match_settle_statement)| {
+                    match_statements_to_public_inputs(
+                        &party_0_match_payload.valid_commitments_statement,
+                        &party_1_match_payload.valid_commitments_statement,
+                        &party_0_match_payload.valid_reblind_statement,
+                        &party_1_match_payload.valid_reblind_statement,
+                        valid_match_settle_statement,
+                    )
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let batch_verification_bundle_ser = [
+            process_match_settle_vkeys,
+            match_proofs.0,
+            postcard_serialize(&match_public_inputs)?,
+            match_linking_proofs.0,
+        ]
+        .concat();
+
+        let result = DarkpoolCoreContract::call_verifier::<_, verifyMatchBatchCall>(
+            storage,
+            (batch_verification_bundle_ser.into(),),
+        )?;
+
+        assert_result!(result._0, VERIFICATION_FAILED_ERROR_MESSAGE)
+    }
+
+    /// Nullifies the old wallets of both parties in each of the given matches
+    /// and commits to their updated wallets.
+    ///
+    /// The updated wallets of all the matches are inserted into the Merkle tree in a
+    /// single batch, in match order, so that the internal nodes shared between their
+    /// paths are only computed once.
+    pub fn rotate_match_wallets<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        party_0_match_payloads: &[MatchPayload],
+        party_1_match_payloads: &[MatchPayload],
+        valid_match_settle_statements: &[ValidMatchSettleWithMintStatement],
+    ) -> Result<(), Vec<u8>> {
+        let mut wallets = Vec::with_capacity(2 * valid_match_settle_statements.len());
+        for ((party_0_match_payload, party_1_match_payload), valid_match_settle_statement) in
+            party_0_match_payloads
+                .iter()
+                .zip(party_1_match_payloads)
+                .zip(valid_match_settle_statements)
+        {
+            let valid_match_settle_statement = &valid_match_settle_statement.valid_match_settle;
+
+            DarkpoolCoreContract::check_wallet_rotation(
+                storage,
+                party_0_match_payload
+                    .valid_reblind_statement
+                    .original_shares_nullifier,
+                party_0_match_payload.valid_reblind_statement.merkle_root,
+                &valid_match_settle_statement.party0_modified_shares,
+            )?;
+
+            DarkpoolCoreContract::check_wallet_rotation(
+                storage,
+                party_1_match_payload
+                    .valid_reblind_statement
+                    .original_shares_nullifier,
+                party_1_match_payload.valid_reblind_statement.merkle_root,
+                &valid_match_settle_statement.party1_modified_shares,
+            )?;
+
+            wallets.push((
+                party_0_match_payload
+                    .valid_reblind_statement
+                    .reblinded_private_shares_commitment,
+                valid_match_settle_statement
+                    .party0_modified_shares
+                    .as_slice(),
+            ));
+            wallets.push((
+                party_1_match_payload
+                    .valid_reblind_statement
+                    .reblinded_private_shares_commitment,
+                valid_match_settle_statement
+                    .party1_modified_shares
+                    .as_slice(),
+            ));
+        }
+
+        DarkpoolCoreContract::insert_wallet_commitments_batch_to_merkle_tree(storage, &wallets)
+    }
+
     /// Nullifies the old wallet and commits to the new wallet
     pub fn rotate_wallet<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
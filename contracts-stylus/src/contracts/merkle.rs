@@ -1,6 +1,9 @@
 //! A Merkle tree smart contract, used to accumulate all of the wallet commitments
 //! in the dark pool.
 //!
+//! The insertion logic is defined by the [`IncrementalMerkleTree`] in `contracts-core`;
+//! this contract persists the tree's state, maintains its root history, and emits its events.
+//!
 //! NOTE: This contract is `delegatecall`ed by the `DarkpoolContract`. This makes our contract
 //! "topology" a lot simpler: we can apply access controls and upgradability only to the top-level
 //! `DarkpoolContract` and not worry about it here.
@@ -15,21 +18,24 @@ use contracts_common::{
     custom_serde::{scalar_to_u256, BytesSerializable},
     types::{PublicSigningKey, ScalarField},
 };
-use contracts_core::crypto::poseidon::compute_poseidon_hash;
+use contracts_core::{
+    crypto::poseidon::compute_poseidon_hash,
+    merkle::{IncrementalMerkleTree, MerkleStorage, PoseidonTwoToOneHasher},
+};
 use stylus_sdk::{
     abi::Bytes,
-    alloy_primitives::{U128, U256},
-    evm,
+    alloy_primitives::{U128, U256, U64},
+    block, evm,
     prelude::*,
-    storage::{StorageBool, StorageMap, StorageU128, StorageU256},
+    storage::{StorageBool, StorageMap, StorageU128, StorageU256, StorageU64},
 };
 
 use crate::{
     assert_result, if_verifying,
     utils::{
-        constants::{TREE_FULL_ERROR_MESSAGE, ZEROS},
+        constants::{ROOT_EXPIRED_ERROR_MESSAGE, ZEROS},
         helpers::{assert_valid_signature, u256_to_scalar},
-        solidity::{MerkleInsertion, MerkleOpeningNode},
+        solidity::{MerkleInsertion, MerkleOpeningNode, MerkleTreeRolledOver, RootUpdated},
     },
 };
 
@@ -41,18 +47,43 @@ pub trait MerkleParams {
     const ZEROS: &'static [ScalarField];
 }
 
-/// The Merkle contract's storage layout
+/// The Merkle contract's storage layout.
+///
+/// Leaves are inserted into the active tree. Once the active tree is full,
+/// the next insertion starts a new, empty active tree, while the roots of
+/// all of the previous trees remain in the root history.
 #[solidity_storage]
 pub struct MerkleContract<P: MerkleParams> {
-    /// The next index at which to insert a leaf
+    /// The next index at which to insert a leaf in the active tree
     pub next_index: StorageU128,
     /// The current path of siblings for the next leaf to be inserted.
     /// Represented as a mapping from height to sibling value.
     pub sibling_path: StorageMap<u8, StorageU256>,
-    /// The current root of the Merkle tree
+    /// The current root of the active tree
     pub root: StorageU256,
-    /// The set of historic roots of the Merkle tree
+    /// The set of historic roots of all of the trees
     pub root_history: StorageMap<U256, StorageBool>,
+    /// The number of roots stored since roots began being sequenced,
+    /// i.e. the position of the most recently stored root
+    pub root_history_len: StorageU64,
+    /// The metadata of each root in the root history, keyed by root,
+    /// packed into a single slot per root as described in [`RootInfo`].
+    ///
+    /// Empty for roots stored before roots began being sequenced, which are
+    /// governed by the legacy root expiry block instead
+    pub root_infos: StorageMap<U256, StorageU256>,
+    /// The number of most recent roots which are accepted, or zero if all roots are accepted
+    pub max_root_history_len: StorageU64,
+    /// The number of blocks for which a root is accepted after it is stored,
+    /// or zero if roots do not expire with time
+    pub root_expiry_period: StorageU256,
+    /// The last block at which roots stored before roots began being sequenced are accepted,
+    /// or zero if they are accepted indefinitely
+    pub legacy_root_expiry_block: StorageU256,
+    /// The identifier of the active tree, incremented each time a new tree is started
+    pub tree_id: StorageU64,
+    /// The final root of each retired tree, keyed by tree identifier
+    pub tree_final_roots: StorageMap<u64, StorageU256>,
 
     #[doc(hidden)]
     _phantom: PhantomData<P>,
@@ -69,13 +100,8 @@ where
 
     /// Initialize this contract with a blank Merkle tree
     pub fn init(&mut self) -> Result<(), Vec<u8>> {
-        self.next_index.set(U128::ZERO);
-        let root = compute_poseidon_hash(&[P::ZEROS[0], P::ZEROS[0]]);
-        self.store_root(root);
-        for i in 0..P::HEIGHT {
-            self.sibling_path
-                .insert(i as u8, scalar_to_u256(P::ZEROS[i]));
-        }
+        self.tree_id.set(U64::ZERO);
+        self.tree().reset();
         Ok(())
     }
 
@@ -83,40 +109,91 @@ where
     // | GETTERS |
     // -----------
 
-    /// Returns the current root of the merkle tree
+    /// Returns the current root of the active merkle tree
     pub fn root(&self) -> Result<U256, Vec<u8>> {
         Ok(self.root.get())
     }
 
-    /// Returns whether or not the given root is in the root history
+    /// Returns the identifier of the active merkle tree
+    pub fn tree_id(&self) -> Result<u64, Vec<u8>> {
+        Ok(self.tree_id.get().to())
+    }
+
+    /// Returns whether or not the given root is in the root history,
+    /// reverting if the root is in the history but has expired
     pub fn root_in_history(&self, root: U256) -> Result<bool, Vec<u8>> {
-        Ok(self.root_history.get(root))
+        if !self.root_history.get(root) {
+            return Ok(false);
+        }
+
+        assert_result!(!self.is_root_expired(root), ROOT_EXPIRED_ERROR_MESSAGE)?;
+        Ok(true)
+    }
+
+    /// Returns the metadata of the given root in the root history, as a tuple of the
+    /// identifier of the tree to which it belongs, the number of leaves in that tree
+    /// covered by the root, and the block at which it was stored.
+    ///
+    /// All values are zero for roots not in the history, and for roots stored
+    /// before roots began being sequenced
+    pub fn root_info(&self, root: U256) -> Result<(u64, u128, U256), Vec<u8>> {
+        let info = self.get_root_info(root);
+        Ok((
+            info.tree_id,
+            info.next_index.into(),
+            U256::from(info.block_number),
+        ))
+    }
+
+    /// Returns the parameters of the root history expiry policy, as a tuple of the
+    /// maximum root history length, the root expiry period, and the legacy root expiry block
+    pub fn root_history_params(&self) -> Result<(u64, U256, U256), Vec<u8>> {
+        Ok((
+            self.max_root_history_len.get().to(),
+            self.root_expiry_period.get(),
+            self.legacy_root_expiry_block.get(),
+        ))
     }
 
     // -----------
     // | SETTERS |
     // -----------
 
-    /// Computes a commitment to the given wallet shares & inserts it into the Merkle tree
-    pub fn insert_shares_commitment(&mut self, shares: Vec<U256>) -> Result<(), Vec<u8>> {
-        let insert_index: u128 = self.next_index.get().to();
-        assert_result!(
-            insert_index < 2_u128.pow(P::HEIGHT as u32),
-            TREE_FULL_ERROR_MESSAGE
-        )?;
-
-        let shares_commitment = self.compute_shares_commitment(shares)?;
+    /// Sets the parameters of the root history expiry policy.
+    ///
+    /// A zero value for either parameter disables the corresponding bound
+    pub fn set_root_history_params(
+        &mut self,
+        max_root_history_len: u64,
+        root_expiry_period: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.max_root_history_len
+            .set(U64::from(max_root_history_len));
+        self.root_expiry_period.set(root_expiry_period);
+        Ok(())
+    }
 
-        self.insert_helper(
-            shares_commitment,
-            P::HEIGHT as u8,
-            insert_index,
-            true, /* subtree_filled */
-        )?;
+    /// Migrates the root history from the unbounded set of roots, by setting the last block
+    /// at which roots without a position in the history are accepted.
+    ///
+    /// The current root is sequenced if it has not been already,
+    /// so that it is governed by the expiry policy from here on
+    pub fn migrate_root_history(&mut self, legacy_root_expiry_block: U256) -> Result<(), Vec<u8>> {
+        let root = self.root.get();
+        if self.get_root_info(root).position == 0 {
+            self.sequence_root(root);
+        }
 
+        self.legacy_root_expiry_block.set(legacy_root_expiry_block);
         Ok(())
     }
 
+    /// Computes a commitment to the given wallet shares & inserts it into the Merkle tree
+    pub fn insert_shares_commitment(&mut self, shares: Vec<U256>) -> Result<(), Vec<u8>> {
+        let shares_commitment = self.compute_shares_commitment(shares)?;
+        self.tree().insert(shares_commitment)
+    }
+
     /// Computes a commitment to the given wallet shares,
     /// verifies the ECDSA signature over this commitment,
     /// & inserts it into the Merkle tree.
@@ -131,12 +208,6 @@ where
         sig: Bytes,
         old_pk_root: [U256; NUM_SCALARS_PK],
     ) -> Result<(), Vec<u8>> {
-        let insert_index: u128 = self.next_index.get().to();
-        assert_result!(
-            insert_index < 2_u128.pow(P::HEIGHT as u32),
-            TREE_FULL_ERROR_MESSAGE
-        )?;
-
         let shares_commitment = self.compute_shares_commitment(shares)?;
 
         let old_pk_root = PublicSigningKey {
@@ -156,32 +227,43 @@ where
             &sig
         )?);
 
-        self.insert_helper(
-            shares_commitment,
-            P::HEIGHT as u8,
-            insert_index,
-            true, /* subtree_filled */
-        )?;
-
-        Ok(())
+        self.tree().insert(shares_commitment)
     }
 
     /// Inserts a note commitment into the Merkle tree
     pub fn insert_note_commitment(&mut self, note_commitment: U256) -> Result<(), Vec<u8>> {
-        let insert_index: u128 = self.next_index.get().to();
-        assert_result!(
-            insert_index < 2_u128.pow(P::HEIGHT as u32),
-            TREE_FULL_ERROR_MESSAGE
-        )?;
-
-        self.insert_helper(
-            u256_to_scalar(note_commitment)?,
-            P::HEIGHT as u8,
-            insert_index,
-            true, /* subtree_filled */
-        )?;
+        let note_commitment = u256_to_scalar(note_commitment)?;
+        self.tree().insert(note_commitment)
+    }
 
-        Ok(())
+    /// Inserts a batch of leaves into consecutive indices of the Merkle tree.
+    ///
+    /// This emits the same events as inserting the leaves one at a time, but computes
+    /// each internal node shared between the leaves' paths only once.
+    pub fn insert_batch(&mut self, leaves: Vec<U256>) -> Result<(), Vec<u8>> {
+        let leaves = leaves
+            .into_iter()
+            .map(u256_to_scalar)
+            .collect::<Result<Vec<ScalarField>, Vec<u8>>>()?;
+
+        self.tree().insert_batch(&leaves)
+    }
+
+    /// Computes commitments to each of the given sets of wallet shares
+    /// & inserts them into consecutive indices of the Merkle tree.
+    ///
+    /// As in `insert_shares_commitment`, the commitments are computed here
+    /// to avoid linking Poseidon hashing code into the calling contract.
+    pub fn insert_shares_commitments_batch(
+        &mut self,
+        shares: Vec<Vec<U256>>,
+    ) -> Result<(), Vec<u8>> {
+        let shares_commitments = shares
+            .into_iter()
+            .map(|wallet_shares| self.compute_shares_commitment(wallet_shares))
+            .collect::<Result<Vec<ScalarField>, Vec<u8>>>()?;
+
+        self.tree().insert_batch(&shares_commitments)
     }
 }
 
@@ -189,12 +271,72 @@ impl<P> MerkleContract<P>
 where
     P: MerkleParams,
 {
-    /// Stores a new root, also adding it to the root history
-    pub fn store_root(&mut self, root: ScalarField) {
-        let root_u256 = scalar_to_u256(root);
+    /// Returns the incremental Merkle tree over this contract's storage
+    fn tree(&mut self) -> IncrementalMerkleTree<'_, Self, PoseidonTwoToOneHasher> {
+        IncrementalMerkleTree::new(self, P::ZEROS)
+    }
 
-        self.root.set(root_u256);
-        self.root_history.insert(root_u256, true);
+    /// Returns the metadata of the given root in the root history,
+    /// which is empty if the root has not been sequenced
+    fn get_root_info(&self, root: U256) -> RootInfo {
+        RootInfo::unpack(self.root_infos.get(root))
+    }
+
+    /// Records the position of the given root in the root history, the active tree,
+    /// the next index at which to insert a leaf, and the block at which it was stored.
+    ///
+    /// Returns the recorded tree identifier, next index & block number
+    fn sequence_root(&mut self, root: U256) -> (u64, u128, U256) {
+        let position = self.root_history_len.get() + U64::from(1);
+        self.root_history_len.set(position);
+
+        let info = RootInfo {
+            position: position.to(),
+            tree_id: self.tree_id.get().to(),
+            next_index: self.next_index.get().to(),
+            block_number: block::number(),
+        };
+        self.root_infos.insert(root, info.pack());
+
+        (
+            info.tree_id,
+            info.next_index.into(),
+            U256::from(info.block_number),
+        )
+    }
+
+    /// Returns whether or not the given root in the root history has expired,
+    /// according to the root history expiry policy.
+    ///
+    /// The current root & the final root of each retired tree never expire,
+    /// so that there is always a root against which membership in each tree can be proven
+    fn is_root_expired(&self, root: U256) -> bool {
+        if root == self.root.get() {
+            return false;
+        }
+
+        let info = self.get_root_info(root);
+        let active_tree_id: u64 = self.tree_id.get().to();
+        if info.tree_id < active_tree_id && root == self.tree_final_roots.get(info.tree_id) {
+            return false;
+        }
+
+        let current_block = U256::from(block::number());
+        if info.position == 0 {
+            let legacy_root_expiry_block = self.legacy_root_expiry_block.get();
+            return legacy_root_expiry_block != U256::ZERO
+                && current_block > legacy_root_expiry_block;
+        }
+
+        let max_root_history_len: u64 = self.max_root_history_len.get().to();
+        let root_history_len: u64 = self.root_history_len.get().to();
+        if max_root_history_len != 0 && root_history_len - info.position >= max_root_history_len {
+            return true;
+        }
+
+        let root_expiry_period = self.root_expiry_period.get();
+        root_expiry_period != U256::ZERO
+            && current_block > U256::from(info.block_number) + root_expiry_period
     }
 
     /// Computes a commitment to the given wallet shares
@@ -206,93 +348,120 @@ where
 
         Ok(compute_poseidon_hash(&shares))
     }
+}
 
-    /// A helper to insert a value into the tree
-    fn insert_helper(
-        &mut self,
-        value: ScalarField,
-        height: u8,
-        insert_index: u128,
-        subtree_filled: bool,
-    ) -> Result<(), Vec<u8>> {
-        self.insert_recursive(value, height, insert_index, subtree_filled)?;
-        evm::log(MerkleInsertion {
-            index: insert_index,
-            value: scalar_to_u256(value),
-        });
+/// The metadata of a root in the root history.
+///
+/// This is packed into a single storage slot per root, each field occupying one of its
+/// 64-bit limbs, least significant first, so that sequencing a root costs a single `SSTORE`.
+/// The next index fits in 64 bits as the tree holds at most `2^MERKLE_HEIGHT` leaves.
+#[derive(Clone, Copy)]
+struct RootInfo {
+    /// The 1-indexed position of the root in the root history,
+    /// or zero if the root has not been sequenced
+    position: u64,
+    /// The identifier of the tree to which the root belongs
+    tree_id: u64,
+    /// The next index at which to insert a leaf when the root was stored,
+    /// i.e. the number of leaves covered by the root
+    next_index: u64,
+    /// The block number at which the root was stored
+    block_number: u64,
+}
 
-        Ok(())
+impl RootInfo {
+    /// Packs the root metadata into a single storage word
+    fn pack(self) -> U256 {
+        U256::from_limbs([
+            self.position,
+            self.tree_id,
+            self.next_index,
+            self.block_number,
+        ])
     }
 
-    /// Recursive helper for inserting a value into the Merkle tree,
-    /// updating the sibling pathway along the way, and returning
-    /// the updated internal nodes
-    fn insert_recursive(
-        &mut self,
-        value: ScalarField,
-        height: u8,
-        insert_index: u128,
-        subtree_filled: bool,
-    ) -> Result<(), Vec<u8>> {
-        // Base case (root)
-        if height == 0 {
-            self.store_root(value);
-            let current_index = self.next_index.get();
-            self.next_index.set(current_index + U128::from(1));
-            return Ok(());
+    /// Unpacks the root metadata from a single storage word
+    fn unpack(packed: U256) -> Self {
+        let [position, tree_id, next_index, block_number] = *packed.as_limbs();
+        Self {
+            position,
+            tree_id,
+            next_index,
+            block_number,
         }
+    }
+}
 
-        // Fetch the least significant bit of the insertion index, this tells us
-        // whether (at the current height), we are hashing into the left or right
-        // hand value
-        let next_index = insert_index >> 1;
-        let is_left = (insert_index & 1) == 0;
-
-        // If the subtree rooted at the current node is filled, update the sibling value
-        // for the next insertion. There are two cases here:
-        //      1. The current insertion index is a left child; in this case the updated
-        //         sibling value is the newly computed node value.
-        //      2. The current insertion index is a right child; in this case, the subtree
-        //         of the parent is filled as well, meaning we should set the updated sibling
-        //         to the zero value at this height; representing an empty child of the parent's
-        //         sibling
-        let current_sibling_value = u256_to_scalar(self.sibling_path.get(height - 1))?;
-        if subtree_filled {
-            if is_left {
-                self.sibling_path.insert(height - 1, scalar_to_u256(value));
-            } else {
-                let zero_value = scalar_to_u256(P::ZEROS[height as usize - 1]);
-                self.sibling_path.insert(height - 1, zero_value);
-            }
-        }
+/// The Merkle contract's storage, over which the incremental Merkle tree is computed.
+///
+/// The sibling at height `h` is stored under the key `h - 1`
+impl<P> MerkleStorage for MerkleContract<P>
+where
+    P: MerkleParams,
+{
+    type Error = Vec<u8>;
 
-        // Mux between hashing the current value as the left or right sibling depending on
-        // the index being inserted into
-        let mut new_subtree_filled = false;
-        let inputs = if is_left {
-            [value, current_sibling_value]
-        } else {
-            new_subtree_filled = subtree_filled;
-            [current_sibling_value, value]
-        };
-        let next_value = compute_poseidon_hash(&inputs);
+    fn next_index(&self) -> u128 {
+        self.next_index.get().to()
+    }
 
-        self.insert_recursive(next_value, height - 1, next_index, new_subtree_filled)?;
+    fn set_next_index(&mut self, next_index: u128) {
+        self.next_index.set(U128::from(next_index));
+    }
 
-        // Emit the sibling coordinates and value
-        let sibling_idx = if is_left {
-            insert_index + 1
-        } else {
-            insert_index - 1
-        };
+    fn sibling(&self, height: usize) -> Result<ScalarField, Vec<u8>> {
+        u256_to_scalar(self.sibling_path.get(height as u8 - 1))
+    }
+
+    fn set_sibling(&mut self, height: usize, value: ScalarField) {
+        self.sibling_path
+            .insert(height as u8 - 1, scalar_to_u256(value));
+    }
+
+    /// Stores a new root, also adding it to the root history
+    fn store_root(&mut self, root: ScalarField) {
+        let root_u256 = scalar_to_u256(root);
 
+        self.root.set(root_u256);
+        self.root_history.insert(root_u256, true);
+        let (tree_id, next_index, block_number) = self.sequence_root(root_u256);
+
+        evm::log(RootUpdated {
+            root: root_u256,
+            tree_id,
+            next_index,
+            block_number,
+        });
+    }
+
+    fn start_new_tree(&mut self) {
+        // The root of the retired tree is final, as no more leaves can be inserted into it
+        let retired_tree_id = self.tree_id.get();
+        self.tree_final_roots
+            .insert(retired_tree_id.to(), self.root.get());
+
+        let tree_id = retired_tree_id + U64::from(1);
+        self.tree_id.set(tree_id);
+        evm::log(MerkleTreeRolledOver {
+            tree_id: tree_id.to(),
+        });
+    }
+
+    fn on_opening_node(&mut self, height: usize, index: u128, value: ScalarField) {
         evm::log(MerkleOpeningNode {
-            height,
-            index: sibling_idx,
-            new_value: scalar_to_u256(current_sibling_value),
+            height: height as u8,
+            index,
+            new_value: scalar_to_u256(value),
+            tree_id: self.tree_id.get().to(),
         });
+    }
 
-        Ok(())
+    fn on_insertion(&mut self, index: u128, value: ScalarField) {
+        evm::log(MerkleInsertion {
+            index,
+            value: scalar_to_u256(value),
+            tree_id: self.tree_id.get().to(),
+        });
     }
 }
 
@@ -330,6 +499,36 @@ impl ProdMerkleContract {
         self.merkle.root_in_history(root)
     }
 
+    #[doc(hidden)]
+    fn tree_id(&self) -> Result<u64, Vec<u8>> {
+        self.merkle.tree_id()
+    }
+
+    #[doc(hidden)]
+    fn root_info(&self, root: U256) -> Result<(u64, u128, U256), Vec<u8>> {
+        self.merkle.root_info(root)
+    }
+
+    #[doc(hidden)]
+    fn root_history_params(&self) -> Result<(u64, U256, U256), Vec<u8>> {
+        self.merkle.root_history_params()
+    }
+
+    #[doc(hidden)]
+    fn set_root_history_params(
+        &mut self,
+        max_root_history_len: u64,
+        root_expiry_period: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.merkle
+            .set_root_history_params(max_root_history_len, root_expiry_period)
+    }
+
+    #[doc(hidden)]
+    fn migrate_root_history(&mut self, legacy_root_expiry_block: U256) -> Result<(), Vec<u8>> {
+        self.merkle.migrate_root_history(legacy_root_expiry_block)
+    }
+
     #[doc(hidden)]
     fn insert_shares_commitment(&mut self, shares: Vec<U256>) -> Result<(), Vec<u8>> {
         self.merkle.insert_shares_commitment(shares)
@@ -350,4 +549,17 @@ impl ProdMerkleContract {
     pub fn insert_note_commitment(&mut self, note_commitment: U256) -> Result<(), Vec<u8>> {
         self.merkle.insert_note_commitment(note_commitment)
     }
+
+    #[doc(hidden)]
+    pub fn insert_batch(&mut self, leaves: Vec<U256>) -> Result<(), Vec<u8>> {
+        self.merkle.insert_batch(leaves)
+    }
+
+    #[doc(hidden)]
+    pub fn insert_shares_commitments_batch(
+        &mut self,
+        shares: Vec<Vec<U256>>,
+    ) -> Result<(), Vec<u8>> {
+        self.merkle.insert_shares_commitments_batch(shares)
+    }
 }
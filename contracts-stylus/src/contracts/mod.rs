@@ -15,6 +15,9 @@ mod verifier;
 #[cfg(any(feature = "vkeys", feature = "test-vkeys"))]
 mod vkeys;
 
+#[cfg(feature = "vkey-registry")]
+mod vkey_registry;
+
 #[cfg(feature = "transfer-executor")]
 mod transfer_executor;
 
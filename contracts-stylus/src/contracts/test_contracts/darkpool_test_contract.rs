@@ -5,9 +5,12 @@ use core::borrow::{Borrow, BorrowMut};
 use alloc::vec::Vec;
 use contracts_common::constants::{
     DARKPOOL_CORE_ADDRESS_SELECTOR, MERKLE_ADDRESS_SELECTOR, TRANSFER_EXECUTOR_ADDRESS_SELECTOR,
-    VERIFIER_ADDRESS_SELECTOR, VKEYS_ADDRESS_SELECTOR,
+    VERIFIER_ADDRESS_SELECTOR, VKEYS_ADDRESS_SELECTOR, VKEY_REGISTRY_ADDRESS_SELECTOR,
+};
+use stylus_sdk::{
+    alloy_primitives::{U256, U64},
+    prelude::*,
 };
-use stylus_sdk::{alloy_primitives::U256, prelude::*};
 
 use crate::{
     contracts::{darkpool::DarkpoolContract, darkpool_core::DarkpoolCoreContract},
@@ -78,6 +81,7 @@ impl DarkpoolTestContract {
             VKEYS_ADDRESS_SELECTOR => this.vkeys_address.get(),
             MERKLE_ADDRESS_SELECTOR => this.merkle_address.get(),
             TRANSFER_EXECUTOR_ADDRESS_SELECTOR => this.transfer_executor_address.get(),
+            VKEY_REGISTRY_ADDRESS_SELECTOR => this.vkey_registry_address.get(),
             _ => panic!(),
         };
 
@@ -96,4 +100,14 @@ impl DarkpoolTestContract {
 
         delegate_call_helper::<initMerkleCall>(self, merkle_address, ()).map(|_| ())
     }
+
+    /// Clears the protocol public encryption key history, leaving the current key
+    /// in place, as is the case for darkpools upgraded from a version without a key history
+    pub fn clear_pubkey_history(&mut self) -> Result<(), Vec<u8>> {
+        BorrowMut::<DarkpoolContract>::borrow_mut(self)
+            .pubkey_history_len
+            .set(U64::ZERO);
+
+        Ok(())
+    }
 }
@@ -46,6 +46,36 @@ impl TestMerkleContract {
         self.merkle.root_in_history(root)
     }
 
+    #[doc(hidden)]
+    fn tree_id(&self) -> Result<u64, Vec<u8>> {
+        self.merkle.tree_id()
+    }
+
+    #[doc(hidden)]
+    fn root_info(&self, root: U256) -> Result<(u64, u128, U256), Vec<u8>> {
+        self.merkle.root_info(root)
+    }
+
+    #[doc(hidden)]
+    fn root_history_params(&self) -> Result<(u64, U256, U256), Vec<u8>> {
+        self.merkle.root_history_params()
+    }
+
+    #[doc(hidden)]
+    fn set_root_history_params(
+        &mut self,
+        max_root_history_len: u64,
+        root_expiry_period: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.merkle
+            .set_root_history_params(max_root_history_len, root_expiry_period)
+    }
+
+    #[doc(hidden)]
+    fn migrate_root_history(&mut self, legacy_root_expiry_block: U256) -> Result<(), Vec<u8>> {
+        self.merkle.migrate_root_history(legacy_root_expiry_block)
+    }
+
     #[doc(hidden)]
     fn insert_shares_commitment(&mut self, shares: Vec<U256>) -> Result<(), Vec<u8>> {
         self.merkle.insert_shares_commitment(shares)
@@ -66,4 +96,17 @@ impl TestMerkleContract {
     pub fn insert_note_commitment(&mut self, note_commitment: U256) -> Result<(), Vec<u8>> {
         self.merkle.insert_note_commitment(note_commitment)
     }
+
+    #[doc(hidden)]
+    pub fn insert_batch(&mut self, leaves: Vec<U256>) -> Result<(), Vec<u8>> {
+        self.merkle.insert_batch(leaves)
+    }
+
+    #[doc(hidden)]
+    pub fn insert_shares_commitments_batch(
+        &mut self,
+        shares: Vec<Vec<U256>>,
+    ) -> Result<(), Vec<u8>> {
+        self.merkle.insert_shares_commitments_batch(shares)
+    }
 }
@@ -24,6 +24,19 @@ impl VerifierContract {
             .map_err(Into::into)
     }
 
+    /// Batch-verify any number of independent proofs, using the given batch verification bundle
+    pub fn verify_batch(&self, batch_verification_bundle: Bytes) -> Result<bool, Vec<u8>> {
+        let (vkeys, proofs, public_inputs): (Vec<_>, Vec<_>, Vec<_>) =
+            deserialize_from_calldata(&batch_verification_bundle)?;
+
+        Verifier::<PrecompileG1ArithmeticBackend, StylusHasher>::batch_verify(
+            &vkeys,
+            &proofs,
+            &public_inputs,
+        )
+        .map_err(Into::into)
+    }
+
     /// Batch-verify the proofs involved in matching a trade
     pub fn verify_match(&self, match_bundle: Bytes) -> Result<bool, Vec<u8>> {
         let (
@@ -43,4 +56,25 @@ impl VerifierContract {
         )
         .map_err(Into::into)
     }
+
+    /// Batch-verify the proofs involved in matching a batch of trades,
+    /// folding all of them into a single pairing check
+    pub fn verify_match_batch(&self, match_batch_bundle: Bytes) -> Result<bool, Vec<u8>> {
+        let (
+            match_vkeys,
+            match_linking_vkeys,
+            match_proofs_batch,
+            match_public_inputs_batch,
+            match_linking_proofs_batch,
+        ) = deserialize_from_calldata(&match_batch_bundle)?;
+
+        Verifier::<PrecompileG1ArithmeticBackend, StylusHasher>::verify_match_batch(
+            match_vkeys,
+            match_linking_vkeys,
+            match_proofs_batch,
+            match_public_inputs_batch,
+            match_linking_proofs_batch,
+        )
+        .map_err(Into::into)
+    }
 }
@@ -0,0 +1,222 @@
+//! The verification key registry contract, which stores the serialized verification keys
+//! of the circuits by circuit identifier & version, so that multiple versions of a circuit
+//! may be accepted at once, e.g. during relayer rollouts.
+//!
+//! Verification keys are too large to be uploaded in a single transaction, so they are
+//! uploaded in chunks and only become available once finalized by the owner.
+//!
+//! The G2 points of the verification keys are validated once, upon finalization,
+//! so that the verifier need not validate them on every verification.
+
+use alloc::vec::Vec;
+use contracts_common::constants::NUM_CIRCUITS;
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, U256},
+    block,
+    crypto::keccak,
+    evm, msg,
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageBytes, StorageMap, StorageU256},
+};
+
+use crate::{
+    assert_result,
+    utils::{
+        constants::{
+            ALREADY_INITIALIZED_ERROR_MESSAGE, EMPTY_VKEY_ERROR_MESSAGE,
+            INVALID_CHUNK_OFFSET_ERROR_MESSAGE, INVALID_CIRCUIT_ID_ERROR_MESSAGE,
+            INVALID_VKEY_VERSION_ERROR_MESSAGE, NOT_OWNER_ERROR_MESSAGE,
+            VKEY_FINALIZED_ERROR_MESSAGE, VKEY_NOT_FINALIZED_ERROR_MESSAGE,
+            VKEY_VERSION_DEPRECATED_ERROR_MESSAGE,
+        },
+        helpers::validate_vkey_bytes,
+        solidity::{VkeyChunkUploaded, VkeyFinalized, VkeyVersionDeprecated},
+    },
+};
+
+/// The vkey registry contract's storage layout
+#[solidity_storage]
+#[entrypoint]
+pub struct VkeyRegistryContract {
+    /// The owner of the vkey registry, permitted to upload, finalize,
+    /// & deprecate verification keys
+    owner: StorageAddress,
+
+    /// The serialized verification keys, representing a mapping from a circuit
+    /// identifier to a mapping from a version to the circuit's serialized
+    /// verification keys for that version
+    vkeys: StorageMap<u8, StorageMap<u64, StorageBytes>>,
+
+    /// Whether or not the verification keys of a circuit have been finalized,
+    /// keyed by circuit identifier & version
+    finalized: StorageMap<u8, StorageMap<u64, StorageBool>>,
+
+    /// The last block numbers at which the verification keys of each version
+    /// are accepted, keyed by version. Zero if the version is not deprecated
+    version_cutoff_blocks: StorageMap<u64, StorageU256>,
+}
+
+#[external]
+impl VkeyRegistryContract {
+    /// Initializes the vkey registry, setting the sender as its owner.
+    ///
+    /// The vkey registry is deployed behind a proxy which calls this method in its constructor,
+    /// so that ownership of the registry cannot be taken by front-running its initialization
+    pub fn initialize(&mut self) -> Result<(), Vec<u8>> {
+        assert_result!(
+            self.owner.get() == Address::ZERO,
+            ALREADY_INITIALIZED_ERROR_MESSAGE
+        )?;
+        self.owner.set(msg::sender());
+        Ok(())
+    }
+
+    /// Returns the owner of the vkey registry
+    pub fn owner(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.owner.get())
+    }
+
+    /// Appends a chunk to the serialized verification keys of the given version
+    /// for the circuit with the given identifier.
+    ///
+    /// The chunk must be uploaded at an offset equal to the number of bytes uploaded so far,
+    /// so that retrying the upload of a chunk which has already landed is rejected
+    /// rather than duplicating it
+    pub fn upload_vkey_chunk(
+        &mut self,
+        circuit_id: u8,
+        version: u64,
+        offset: U256,
+        chunk: Bytes,
+    ) -> Result<(), Vec<u8>> {
+        self._check_owner()?;
+        VkeyRegistryContract::_check_circuit_id_and_version(circuit_id, version)?;
+        assert_result!(
+            !self.finalized.getter(circuit_id).get(version),
+            VKEY_FINALIZED_ERROR_MESSAGE
+        )?;
+
+        let mut circuit_vkeys = self.vkeys.setter(circuit_id);
+        let mut vkey = circuit_vkeys.setter(version);
+        assert_result!(
+            U256::from(vkey.len()) == offset,
+            INVALID_CHUNK_OFFSET_ERROR_MESSAGE
+        )?;
+        vkey.extend(chunk.0);
+
+        evm::log(VkeyChunkUploaded {
+            circuit_id,
+            version,
+            offset,
+        });
+        Ok(())
+    }
+
+    /// Returns the number of bytes uploaded so far for the verification keys
+    /// of the given version for the circuit with the given identifier
+    pub fn vkey_length(&self, circuit_id: u8, version: u64) -> Result<U256, Vec<u8>> {
+        Ok(U256::from(
+            self.vkeys.getter(circuit_id).getter(version).len(),
+        ))
+    }
+
+    /// Returns whether or not the verification keys of the given version
+    /// for the circuit with the given identifier have been finalized
+    pub fn is_vkey_finalized(&self, circuit_id: u8, version: u64) -> Result<bool, Vec<u8>> {
+        Ok(self.finalized.getter(circuit_id).get(version))
+    }
+
+    /// Finalizes the verification keys of the given version for the circuit with the
+    /// given identifier, after which they may no longer be modified & may be fetched.
+    ///
+    /// Reverts if the verification keys do not deserialize, or if their G2 points are invalid
+    pub fn finalize_vkey(&mut self, circuit_id: u8, version: u64) -> Result<(), Vec<u8>> {
+        self._check_owner()?;
+        VkeyRegistryContract::_check_circuit_id_and_version(circuit_id, version)?;
+        assert_result!(
+            !self.finalized.getter(circuit_id).get(version),
+            VKEY_FINALIZED_ERROR_MESSAGE
+        )?;
+
+        let vkey_bytes = self.vkeys.getter(circuit_id).getter(version).get_bytes();
+        assert_result!(!vkey_bytes.is_empty(), EMPTY_VKEY_ERROR_MESSAGE)?;
+        validate_vkey_bytes(circuit_id, &vkey_bytes)?;
+
+        self.finalized.setter(circuit_id).insert(version, true);
+
+        evm::log(VkeyFinalized {
+            circuit_id,
+            version,
+            vkey_hash: keccak(&vkey_bytes),
+        });
+        Ok(())
+    }
+
+    /// Returns the serialized verification keys of the given version
+    /// for the circuit with the given identifier.
+    ///
+    /// Reverts if the verification keys have not been finalized,
+    /// or if the version has been deprecated & its cutoff block has passed
+    pub fn get_vkey(&self, circuit_id: u8, version: u64) -> Result<Bytes, Vec<u8>> {
+        assert_result!(
+            self.finalized.getter(circuit_id).get(version),
+            VKEY_NOT_FINALIZED_ERROR_MESSAGE
+        )?;
+
+        let cutoff_block = self.version_cutoff_blocks.get(version);
+        assert_result!(
+            cutoff_block == U256::ZERO || U256::from(block::number()) <= cutoff_block,
+            VKEY_VERSION_DEPRECATED_ERROR_MESSAGE
+        )?;
+
+        Ok(self
+            .vkeys
+            .getter(circuit_id)
+            .getter(version)
+            .get_bytes()
+            .into())
+    }
+
+    /// Returns the last block number at which the verification keys of the
+    /// given version are accepted, or zero if the version is not deprecated
+    pub fn get_version_cutoff_block(&self, version: u64) -> Result<U256, Vec<u8>> {
+        Ok(self.version_cutoff_blocks.get(version))
+    }
+
+    /// Deprecates the given version, such that its verification keys are
+    /// no longer accepted after the given cutoff block.
+    ///
+    /// This applies to the verification keys of all circuits for the version.
+    /// Setting a cutoff block of zero lifts the deprecation
+    pub fn deprecate_version(&mut self, version: u64, cutoff_block: U256) -> Result<(), Vec<u8>> {
+        self._check_owner()?;
+        assert_result!(version != 0, INVALID_VKEY_VERSION_ERROR_MESSAGE)?;
+
+        self.version_cutoff_blocks.insert(version, cutoff_block);
+
+        evm::log(VkeyVersionDeprecated {
+            version,
+            cutoff_block,
+        });
+        Ok(())
+    }
+}
+
+/// Internal helper methods
+impl VkeyRegistryContract {
+    /// Checks that the sender is the owner
+    pub fn _check_owner(&self) -> Result<(), Vec<u8>> {
+        assert_result!(self.owner.get() == msg::sender(), NOT_OWNER_ERROR_MESSAGE)
+    }
+
+    /// Checks that the given circuit identifier refers to a known circuit, and that
+    /// the given version is not the reserved version tag.
+    ///
+    /// Version zero is reserved, as it is used by the darkpool to indicate
+    /// that verification keys should not be fetched from the registry
+    pub fn _check_circuit_id_and_version(circuit_id: u8, version: u64) -> Result<(), Vec<u8>> {
+        assert_result!(circuit_id < NUM_CIRCUITS, INVALID_CIRCUIT_ID_ERROR_MESSAGE)?;
+        assert_result!(version != 0, INVALID_VKEY_VERSION_ERROR_MESSAGE)
+    }
+}
@@ -33,9 +33,43 @@ pub const INVALID_VERSION_ERROR_MESSAGE: &[u8] = b"invalid version";
 
 /// The revert message when calling an owner-only method
 /// when the caller is not the owner
-#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+#[cfg(any(
+    feature = "darkpool",
+    feature = "darkpool-test-contract",
+    feature = "vkey-registry"
+))]
 pub const NOT_OWNER_ERROR_MESSAGE: &[u8] = b"not owner";
 
+/// The revert message when accepting ownership
+/// when the caller is not the pending owner
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const NOT_PENDING_OWNER_ERROR_MESSAGE: &[u8] = b"not pending owner";
+
+/// The revert message when cancelling an ownership transfer
+/// when there is no pending owner
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const NO_PENDING_OWNER_ERROR_MESSAGE: &[u8] = b"no pending owner";
+
+/// The revert message when calling a role-gated method
+/// when the caller neither holds the role nor is the owner
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const MISSING_ROLE_ERROR_MESSAGE: &[u8] = b"missing role";
+
+/// The revert message when referencing an implementation address
+/// with an unknown address selector
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const INVALID_ADDRESS_SELECTOR_ERROR_MESSAGE: &[u8] = b"invalid address selector";
+
+/// The revert message when executing or cancelling an implementation
+/// address change that has not been scheduled
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const NO_PENDING_ADDRESS_CHANGE_ERROR_MESSAGE: &[u8] = b"no pending address change";
+
+/// The revert message when executing an implementation address change
+/// before its delay has elapsed
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const TIMELOCK_NOT_ELAPSED_ERROR_MESSAGE: &[u8] = b"timelock not elapsed";
+
 /// The revert message when calling an unpaused-only method
 /// when the contract is paused
 #[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
@@ -46,6 +80,102 @@ pub const PAUSED_ERROR_MESSAGE: &[u8] = b"paused";
 #[cfg(any(feature = "darkpool", feature = "darkpool-test-contract",))]
 pub const UNPAUSED_ERROR_MESSAGE: &[u8] = b"unpaused";
 
+/// The revert message when calling a darkpool operation
+/// which has been individually paused
+#[cfg(any(
+    feature = "darkpool",
+    feature = "darkpool-core",
+    feature = "darkpool-test-contract"
+))]
+pub const OPERATION_PAUSED_ERROR_MESSAGE: &[u8] = b"operation paused";
+
+/// The revert message when referencing an unknown
+/// pausable darkpool operation
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const INVALID_OPERATION_ERROR_MESSAGE: &[u8] = b"invalid operation";
+
+/// The revert message when calling `multicall`
+/// while a multicall is already in progress
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const REENTRANT_MULTICALL_ERROR_MESSAGE: &[u8] = b"reentrant multicall";
+
+/// The revert message when a call in a multicall bundle
+/// is not a well-formed call to a darkpool core method
+#[cfg(any(feature = "darkpool", feature = "darkpool-test-contract"))]
+pub const INVALID_MULTICALL_CALL_ERROR_MESSAGE: &[u8] = b"invalid multicall call";
+
+/// The revert message when referencing an unknown circuit
+/// by its identifier
+#[cfg(any(
+    feature = "darkpool",
+    feature = "darkpool-test-contract",
+    feature = "vkey-registry"
+))]
+pub const INVALID_CIRCUIT_ID_ERROR_MESSAGE: &[u8] = b"invalid circuit id";
+
+/// The revert message when referencing the verification key commitment
+/// of a circuit for which none is registered
+#[cfg(any(
+    feature = "darkpool",
+    feature = "darkpool-core",
+    feature = "darkpool-test-contract"
+))]
+pub const UNREGISTERED_VKEY_HASH_ERROR_MESSAGE: &[u8] = b"vkey hash not registered";
+
+/// The revert message when the verification keys given in calldata
+/// do not match the commitment registered for the circuit
+#[cfg(any(feature = "darkpool-core", feature = "darkpool-test-contract"))]
+pub const INVALID_VKEY_HASH_ERROR_MESSAGE: &[u8] = b"invalid vkey hash";
+
+/// The revert message when verification keys are given in calldata
+/// alongside a version tag referencing the vkey registry
+#[cfg(any(feature = "darkpool-core", feature = "darkpool-test-contract"))]
+pub const CONFLICTING_VKEY_SOURCES_ERROR_MESSAGE: &[u8] = b"conflicting vkey sources";
+
+/// The revert message when attempting to initialize
+/// the vkey registry more than once
+#[cfg(feature = "vkey-registry")]
+pub const ALREADY_INITIALIZED_ERROR_MESSAGE: &[u8] = b"already initialized";
+
+/// The revert message when referencing the reserved version tag
+/// in the vkey registry
+#[cfg(feature = "vkey-registry")]
+pub const INVALID_VKEY_VERSION_ERROR_MESSAGE: &[u8] = b"invalid vkey version";
+
+/// The revert message when uploading a verification key chunk
+/// at an offset other than the current length of the stored key
+#[cfg(feature = "vkey-registry")]
+pub const INVALID_CHUNK_OFFSET_ERROR_MESSAGE: &[u8] = b"invalid chunk offset";
+
+/// The revert message when attempting to modify
+/// a finalized verification key
+#[cfg(feature = "vkey-registry")]
+pub const VKEY_FINALIZED_ERROR_MESSAGE: &[u8] = b"vkey finalized";
+
+/// The revert message when attempting to finalize
+/// a verification key with no uploaded chunks
+#[cfg(feature = "vkey-registry")]
+pub const EMPTY_VKEY_ERROR_MESSAGE: &[u8] = b"empty vkey";
+
+/// The revert message when attempting to finalize or register a verification key
+/// which does not deserialize, or whose G2 points are invalid
+#[cfg(any(
+    feature = "darkpool",
+    feature = "darkpool-test-contract",
+    feature = "vkey-registry"
+))]
+pub const INVALID_VKEY_ERROR_MESSAGE: &[u8] = b"invalid vkey";
+
+/// The revert message when fetching a verification key
+/// which has not been finalized
+#[cfg(feature = "vkey-registry")]
+pub const VKEY_NOT_FINALIZED_ERROR_MESSAGE: &[u8] = b"vkey not finalized";
+
+/// The revert message when fetching a verification key
+/// of a version past its deprecation cutoff block
+#[cfg(feature = "vkey-registry")]
+pub const VKEY_VERSION_DEPRECATED_ERROR_MESSAGE: &[u8] = b"vkey version deprecated";
+
 /// The revert message when attempting to mark
 /// a spent nullifier as spent again
 #[cfg(any(feature = "darkpool-core", feature = "darkpool-test-contract"))]
@@ -77,10 +207,10 @@ pub const INVALID_PROTOCOL_FEE_ERROR_MESSAGE: &[u8] = b"invalid protocol fee";
 #[cfg(any(feature = "darkpool-core", feature = "darkpool-test-contract"))]
 pub const INVALID_PROTOCOL_PUBKEY_ERROR_MESSAGE: &[u8] = b"invalid protocol pubkey";
 
-/// The revert message when attempting to insert
-/// into a full Merkle tree
+/// The revert message when checking a historic root
+/// which has expired under the root history expiry policy
 #[cfg(any(feature = "merkle", feature = "merkle-test-contract"))]
-pub const TREE_FULL_ERROR_MESSAGE: &[u8] = b"tree full";
+pub const ROOT_EXPIRED_ERROR_MESSAGE: &[u8] = b"root expired";
 
 /// The revert message when invoking the ecRecover precompile
 /// reverts
@@ -4,14 +4,14 @@ use alloc::vec::Vec;
 use alloy_sol_types::{SolCall, SolType};
 use ark_ff::PrimeField;
 use contracts_common::{
-    constants::{NUM_BYTES_U256, SCALAR_CONVERSION_ERROR_MESSAGE},
+    constants::{ALL_OPERATIONS_PAUSED, NUM_BYTES_U256, SCALAR_CONVERSION_ERROR_MESSAGE},
     custom_serde::{bigint_from_le_bytes, statement_to_public_inputs, ScalarSerializable},
     types::{
         MatchPublicInputs, PublicSigningKey, ScalarField, ValidCommitmentsStatement,
-        ValidMatchSettleStatement, ValidReblindStatement,
+        ValidMatchSettleWithMintStatement, ValidReblindStatement,
     },
 };
-use contracts_core::crypto::ecdsa::ecdsa_verify;
+use contracts_core::{crypto::ecdsa::ecdsa_verify, darkpool};
 use serde::{Deserialize, Serialize};
 use stylus_sdk::{
     abi::Bytes,
@@ -73,23 +73,38 @@ pub fn serialize_match_statements_for_verification(
     valid_commitments_1: &ValidCommitmentsStatement,
     valid_reblind_0: &ValidReblindStatement,
     valid_reblind_1: &ValidReblindStatement,
-    valid_match_settle: &ValidMatchSettleStatement,
+    valid_match_settle: &ValidMatchSettleWithMintStatement,
 ) -> Result<Vec<u8>, Vec<u8>> {
-    let match_public_inputs = MatchPublicInputs {
-        valid_commitments_0: statement_to_public_inputs(valid_commitments_0)
-            .map_err(map_calldata_ser_error)?,
-        valid_commitments_1: statement_to_public_inputs(valid_commitments_1)
-            .map_err(map_calldata_ser_error)?,
-        valid_reblind_0: statement_to_public_inputs(valid_reblind_0)
-            .map_err(map_calldata_ser_error)?,
-        valid_reblind_1: statement_to_public_inputs(valid_reblind_1)
-            .map_err(map_calldata_ser_error)?,
-        valid_match_settle: statement_to_public_inputs(valid_match_settle)
-            .map_err(map_calldata_ser_error)?,
-    };
+    let match_public_inputs = match_statements_to_public_inputs(
+        valid_commitments_0,
+        valid_commitments_1,
+        valid_reblind_0,
+        valid_reblind_1,
+        valid_match_settle,
+    )?;
     postcard_serialize(&match_public_inputs)
 }
 
+/// Serializes the statements used in verifying the settlement of a
+/// matched trade into scalars, and builds the [`MatchPublicInputs`] struct
+#[cfg_attr(not(feature = "darkpool-core"), allow(dead_code))]
+pub fn match_statements_to_public_inputs(
+    valid_commitments_0: &ValidCommitmentsStatement,
+    valid_commitments_1: &ValidCommitmentsStatement,
+    valid_reblind_0: &ValidReblindStatement,
+    valid_reblind_1: &ValidReblindStatement,
+    valid_match_settle: &ValidMatchSettleWithMintStatement,
+) -> Result<MatchPublicInputs, Vec<u8>> {
+    darkpool::match_statements_to_public_inputs(
+        valid_commitments_0,
+        valid_commitments_1,
+        valid_reblind_0,
+        valid_reblind_1,
+        valid_match_settle,
+    )
+    .map_err(map_calldata_ser_error)
+}
+
 /// Fetch the public blinder from a set of public shares
 ///
 /// Currently this is the last share, though we separate out this logic
@@ -98,6 +113,77 @@ pub fn get_public_blinder_from_shares(shares: &[ScalarField]) -> ScalarField {
     *shares.last().unwrap()
 }
 
+/// Checks whether the given operation is paused in the given pause bitmask
+#[cfg_attr(
+    not(any(
+        feature = "darkpool",
+        feature = "darkpool-core",
+        feature = "darkpool-test-contract"
+    )),
+    allow(dead_code)
+)]
+pub fn is_operation_paused(paused_operations: u8, operation: u8) -> bool {
+    paused_operations & (1 << operation) != 0
+}
+
+/// Gets the effective pause bitmask of the darkpool from its stored bitmask and
+/// the legacy paused flag, which pauses all operations when set
+#[cfg_attr(
+    not(any(
+        feature = "darkpool",
+        feature = "darkpool-core",
+        feature = "darkpool-test-contract"
+    )),
+    allow(dead_code)
+)]
+pub fn get_paused_operations(legacy_paused: bool, paused_operations: u8) -> u8 {
+    if legacy_paused {
+        ALL_OPERATIONS_PAUSED
+    } else {
+        paused_operations
+    }
+}
+
+/// Checks that the given serialized verification keys of the circuit with the given
+/// identifier deserialize, and that their G2 points are valid.
+///
+/// The verification keys of `PROCESS MATCH SETTLE` are followed by its linking
+/// verification keys, which contain no G2 points
+#[cfg(any(
+    feature = "darkpool",
+    feature = "darkpool-test-contract",
+    feature = "vkey-registry"
+))]
+pub fn validate_vkey_bytes(circuit_id: u8, vkey_bytes: &[u8]) -> Result<(), Vec<u8>> {
+    use alloc::vec;
+    use contracts_common::{
+        constants::PROCESS_MATCH_SETTLE_CIRCUIT_ID,
+        types::{ExtendedVerificationKey, MatchVkeys},
+    };
+    use contracts_core::verifier::validate_vkey;
+
+    use crate::utils::constants::INVALID_VKEY_ERROR_MESSAGE;
+
+    let vkeys = if circuit_id == PROCESS_MATCH_SETTLE_CIRCUIT_ID {
+        let (match_vkeys, _) = postcard::take_from_bytes::<MatchVkeys>(vkey_bytes)
+            .map_err(|_| INVALID_VKEY_ERROR_MESSAGE.to_vec())?;
+
+        vec![
+            match_vkeys.valid_commitments_vkey,
+            match_vkeys.valid_reblind_vkey,
+            match_vkeys.valid_match_settle_vkey,
+        ]
+    } else {
+        vec![postcard::from_bytes::<ExtendedVerificationKey>(vkey_bytes)
+            .map_err(|_| INVALID_VKEY_ERROR_MESSAGE.to_vec())?]
+    };
+
+    vkeys
+        .iter()
+        .try_for_each(|vkey| validate_vkey(&vkey.vkey))
+        .map_err(|_| INVALID_VKEY_ERROR_MESSAGE.to_vec())
+}
+
 /// Maps an error returned from an external contract call to a `Vec<u8>`,
 /// which is the expected return type of external contract methods.
 pub fn map_call_error(e: stylus_sdk::call::Error) -> Vec<u8> {
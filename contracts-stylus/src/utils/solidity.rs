@@ -13,17 +13,34 @@ sol! {
     function newWallet(bytes memory proof, bytes memory valid_wallet_create_statement_bytes) external;
     function updateWallet(bytes memory proof, bytes memory valid_wallet_update_statement_bytes, bytes memory wallet_commitment_signature, bytes memory transfer_aux_data) external;
     function processMatchSettle(bytes memory party_0_match_payload, bytes memory party_1_match_payload, bytes memory valid_match_settle_statement, bytes memory match_proofs, bytes memory match_linking_proofs) external;
+    function processMatchSettleBatch(bytes memory party_0_match_payloads, bytes memory party_1_match_payloads, bytes memory valid_match_settle_statements, bytes memory match_proofs, bytes memory match_linking_proofs) external;
     function settleOnlineRelayerFee(bytes memory proof, bytes memory valid_relayer_fee_settlement_statement, bytes memory relayer_wallet_commitment_signature) external;
     function settleOfflineFee(bytes memory proof, bytes memory valid_offline_fee_settlement_statement) external;
     function redeemFee(bytes memory proof, bytes memory valid_fee_redemption_statement, bytes memory recipient_wallet_commitment_signature) external;
 
+    // Core functions taking verification keys in calldata, or the version of the verification keys in the vkey registry
+    function newWalletWithVkeys(bytes memory proof, bytes memory valid_wallet_create_statement_bytes, bytes memory vkeys, uint64 vkey_version) external;
+    function updateWalletWithVkeys(bytes memory proof, bytes memory valid_wallet_update_statement_bytes, bytes memory wallet_commitment_signature, bytes memory transfer_aux_data, bytes memory vkeys, uint64 vkey_version) external;
+    function processMatchSettleWithVkeys(bytes memory party_0_match_payload, bytes memory party_1_match_payload, bytes memory valid_match_settle_statement, bytes memory match_proofs, bytes memory match_linking_proofs, bytes memory vkeys, uint64 vkey_version) external;
+    function processMatchSettleBatchWithVkeys(bytes memory party_0_match_payloads, bytes memory party_1_match_payloads, bytes memory valid_match_settle_statements, bytes memory match_proofs, bytes memory match_linking_proofs, bytes memory vkeys, uint64 vkey_version) external;
+    function settleOnlineRelayerFeeWithVkeys(bytes memory proof, bytes memory valid_relayer_fee_settlement_statement, bytes memory relayer_wallet_commitment_signature, bytes memory vkeys, uint64 vkey_version) external;
+    function settleOfflineFeeWithVkeys(bytes memory proof, bytes memory valid_offline_fee_settlement_statement, bytes memory vkeys, uint64 vkey_version) external;
+    function redeemFeeWithVkeys(bytes memory proof, bytes memory valid_fee_redemption_statement, bytes memory recipient_wallet_commitment_signature, bytes memory vkeys, uint64 vkey_version) external;
+
     // Merkle functions
     function init() external;
     function root() external view returns (uint256);
     function rootInHistory(uint256 root) external view returns (bool);
+    function treeId() external view returns (uint64);
+    function rootInfo(uint256 root) external view returns (uint64, uint128, uint256);
+    function rootHistoryParams() external view returns (uint64, uint256, uint256);
+    function setRootHistoryParams(uint64 max_root_history_len, uint256 root_expiry_period) external;
+    function migrateRootHistory(uint256 legacy_root_expiry_block) external;
     function insertSharesCommitment(uint256[] shares) external;
     function verifyStateSigAndInsert(uint256[] shares, bytes sig, uint256[4] old_pk_root) external;
     function insertNoteCommitment(uint256 note_commitment) external;
+    function insertBatch(uint256[] leaves) external;
+    function insertSharesCommitmentsBatch(uint256[][] shares) external;
 
     // Vkeys functions
     function validWalletCreateVkey() external view returns (bytes);
@@ -33,9 +50,14 @@ sol! {
     function validOfflineFeeSettlementVkey() external view returns (bytes);
     function validFeeRedemptionVkey() external view returns (bytes);
 
+    // Vkey registry functions
+    function getVkey(uint8 circuit_id, uint64 version) external view returns (bytes);
+
     // Verifier functions
     function verify(bytes memory verification_bundle) external view returns (bool);
+    function verifyBatch(bytes memory batch_verification_bundle) external view returns (bool);
     function verifyMatch(bytes memory match_bundle) external view returns (bool);
+    function verifyMatchBatch(bytes memory match_batch_bundle) external view returns (bool);
 
     // Transfer executor functions
     function init(address memory permit2_address) external;
@@ -53,8 +75,10 @@ sol! {
     // ----------
 
     // Merkle events; we emit the opening path of the inserted node
-    event MerkleOpeningNode(uint8 indexed height, uint128 indexed index, uint256 indexed new_value);
-    event MerkleInsertion(uint128 indexed index, uint256 indexed value);
+    event MerkleOpeningNode(uint8 indexed height, uint128 indexed index, uint256 indexed new_value, uint64 tree_id);
+    event MerkleInsertion(uint128 indexed index, uint256 indexed value, uint64 indexed tree_id);
+    event RootUpdated(uint256 indexed root, uint64 indexed tree_id, uint128 next_index, uint256 block_number);
+    event MerkleTreeRolledOver(uint64 indexed tree_id);
 
     // Darkpool user interaction events
     event NullifierSpent(uint256 indexed nullifier);
@@ -64,13 +88,37 @@ sol! {
 
     // Darkpool controls events
     event FeeChanged(uint256 indexed new_fee);
-    event PubkeyRotated(uint256 indexed new_pubkey_x, uint256 indexed new_pubkey_y);
+    event FeeOverrideChanged(address indexed mint, uint256 indexed new_fee);
+    event FeeOverrideRemoved(address indexed mint);
+    event PubkeyRotated(uint256 indexed new_pubkey_x, uint256 indexed new_pubkey_y, uint256 previous_pubkey_expiry_block);
+    event PubkeyGracePeriodChanged(uint256 indexed new_grace_period);
+    event RootHistoryParamsChanged(uint64 indexed max_root_history_len, uint256 indexed root_expiry_period);
+    event RootHistoryMigrated(uint256 indexed legacy_root_expiry_block);
+    event OwnershipTransferStarted(address indexed new_owner);
+    event OwnershipTransferCanceled(address indexed pending_owner);
     event OwnershipTransferred(address indexed new_owner);
+    event RoleGranted(uint8 indexed role, address indexed account);
+    event RoleRevoked(uint8 indexed role, address indexed account);
     event Paused();
     event Unpaused();
+    event OperationPaused(uint8 indexed operation);
+    event OperationUnpaused(uint8 indexed operation);
     event DarkpoolCoreAddressChanged(address indexed new_address);
     event VerifierAddressChanged(address indexed new_address);
     event VkeysAddressChanged(address indexed new_address);
     event MerkleAddressChanged(address indexed new_address);
     event TransferExecutorAddressChanged(address indexed new_address);
+    event VkeyRegistryAddressChanged(address indexed new_address);
+    event AddressChangeScheduled(uint8 indexed address_selector, address indexed new_address, uint256 effective_timestamp);
+    event AddressChangeCanceled(uint8 indexed address_selector, address indexed new_address);
+    event AddressChangeDelayChangeScheduled(uint256 new_delay, uint256 effective_timestamp);
+    event AddressChangeDelayChanged(uint256 new_delay);
+    event AddressChangeDelayChangeCanceled(uint256 new_delay);
+    event VkeyHashRegistered(uint8 indexed circuit_id, bytes32 indexed vkey_hash);
+    event VkeyHashRetired(uint8 indexed circuit_id, bytes32 indexed vkey_hash);
+
+    // Vkey registry events
+    event VkeyChunkUploaded(uint8 indexed circuit_id, uint64 indexed version, uint256 offset);
+    event VkeyFinalized(uint8 indexed circuit_id, uint64 indexed version, bytes32 vkey_hash);
+    event VkeyVersionDeprecated(uint64 indexed version, uint256 cutoff_block);
 }
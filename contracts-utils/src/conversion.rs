@@ -1,16 +1,21 @@
 //! Type conversion utilities
 
-use arbitrum_client::errors::ConversionError;
+use arbitrum_client::{conversion::to_contract_proof, errors::ConversionError};
 use circuit_types::{
     keychain::{NonNativeScalar, PublicSigningKey as CircuitPublicSigningKey},
     PolynomialCommitment,
 };
 use constants::{Scalar, SystemCurve};
-use contracts_common::types::{
-    G1Affine, LinkingVerificationKey, PublicSigningKey as ContractPublicSigningKey, VerificationKey,
+use contracts_common::{
+    constants::{NUM_SELECTORS, NUM_WIRE_TYPES},
+    types::{
+        ExtendedProof, ExtendedVerificationKey, G1Affine, LinkingVerificationKey,
+        LookupEvaluations, LookupProof, LookupVerificationKey,
+        PublicSigningKey as ContractPublicSigningKey, TranscriptHash, VerificationKey,
+    },
 };
 use eyre::Result;
-use mpc_plonk::proof_system::structs::VerifyingKey;
+use mpc_plonk::proof_system::structs::{Proof, VerifyingKey};
 use mpc_relation::proof_linking::GroupLayout;
 
 /// Converts a [`GroupLayout`] (from prover-side code) to a [`LinkingVerificationKey`]
@@ -35,22 +40,144 @@ fn try_unwrap_commitments<const N: usize>(
         .map_err(|_| ConversionError::InvalidLength)
 }
 
-/// Converts a [`VerifyingKey`] (from prover-side code) to a [`VerificationKey`]
+/// Splits off the element trailing the first `N` elements of a slice, if the slice has
+/// exactly one more element than that, or returns `None` if the slice has exactly `N` elements.
+///
+/// This is used to separate the elements of the lookup wire & selector from
+/// those of the TurboPlonk wires & selectors in an UltraPlonk verifying key / proof.
+fn split_lookup_elem<T: Clone, const N: usize>(
+    elems: &[T],
+    has_lookup: bool,
+) -> Result<([T; N], Option<T>), ConversionError> {
+    if elems.len() != N + usize::from(has_lookup) {
+        return Err(ConversionError::InvalidLength);
+    }
+
+    let base_elems = elems[..N]
+        .to_vec()
+        .try_into()
+        .map_err(|_| ConversionError::InvalidLength)?;
+
+    Ok((base_elems, elems.get(N).cloned()))
+}
+
+/// Converts a [`VerifyingKey`] (from prover-side code) to a [`ExtendedVerificationKey`].
+///
+/// If the verifying key is that of an UltraPlonk circuit, i.e. one that makes use of lookups,
+/// the preprocessed elements of its lookup argument are converted to a [`LookupVerificationKey`].
+///
+/// The verification key expects the Keccak transcript, i.e. that of proofs generated
+/// using the Jellyfish `SolidityTranscript`.
 pub fn to_contract_vkey(
     jf_vkey: VerifyingKey<SystemCurve>,
-) -> Result<VerificationKey, ConversionError> {
-    Ok(VerificationKey {
-        n: jf_vkey.domain_size as u64,
-        l: jf_vkey.num_inputs as u64,
-        k: jf_vkey
-            .k
-            .try_into()
-            .map_err(|_| ConversionError::InvalidLength)?,
-        q_comms: try_unwrap_commitments(&jf_vkey.selector_comms)?,
-        sigma_comms: try_unwrap_commitments(&jf_vkey.sigma_comms)?,
-        g: jf_vkey.open_key.g,
-        h: jf_vkey.open_key.h,
-        x_h: jf_vkey.open_key.beta_h,
+) -> Result<ExtendedVerificationKey, ConversionError> {
+    let has_lookup = jf_vkey.plookup_vk.is_some();
+
+    let (k, lookup_k) = split_lookup_elem::<_, NUM_WIRE_TYPES>(&jf_vkey.k, has_lookup)?;
+    let (q_comms, q_lookup_comm) =
+        split_lookup_elem::<_, NUM_SELECTORS>(&jf_vkey.selector_comms, has_lookup)?;
+    let (sigma_comms, lookup_sigma_comm) =
+        split_lookup_elem::<_, NUM_WIRE_TYPES>(&jf_vkey.sigma_comms, has_lookup)?;
+
+    let lookup_vkey = match (
+        jf_vkey.plookup_vk,
+        lookup_k,
+        q_lookup_comm,
+        lookup_sigma_comm,
+    ) {
+        (Some(plookup_vk), Some(lookup_k), Some(q_lookup_comm), Some(lookup_sigma_comm)) => {
+            Some(LookupVerificationKey {
+                lookup_k,
+                q_lookup_comm: q_lookup_comm.0,
+                lookup_sigma_comm: lookup_sigma_comm.0,
+                range_table_comm: plookup_vk.range_table_comm.0,
+                key_table_comm: plookup_vk.key_table_comm.0,
+                table_dom_sep_comm: plookup_vk.table_dom_sep_comm.0,
+                q_dom_sep_comm: plookup_vk.q_dom_sep_comm.0,
+            })
+        }
+        _ => None,
+    };
+
+    Ok(ExtendedVerificationKey {
+        vkey: VerificationKey {
+            n: jf_vkey.domain_size as u64,
+            l: jf_vkey.num_inputs as u64,
+            k,
+            q_comms: q_comms.map(|c| c.0),
+            sigma_comms: sigma_comms.map(|c| c.0),
+            g: jf_vkey.open_key.g,
+            h: jf_vkey.open_key.h,
+            x_h: jf_vkey.open_key.beta_h,
+        },
+        lookup_vkey,
+        transcript_hash: TranscriptHash::Keccak,
+    })
+}
+
+/// Converts a [`Proof`] (from prover-side code) of an UltraPlonk circuit,
+/// i.e. one that makes use of lookups, to a [`ExtendedProof`]
+pub fn to_contract_lookup_proof(
+    jf_proof: &Proof<SystemCurve>,
+) -> Result<ExtendedProof, ConversionError> {
+    let plookup_proof = jf_proof
+        .plookup_proof
+        .as_ref()
+        .ok_or(ConversionError::InvalidLength)?;
+
+    // Convert the TurboPlonk part of the proof by stripping out the elements of the lookup wire
+    let mut turbo_plonk_proof = jf_proof.clone();
+    turbo_plonk_proof.plookup_proof = None;
+
+    let (wire_comms, lookup_wire_comm) =
+        split_lookup_elem::<_, NUM_WIRE_TYPES>(&jf_proof.wires_poly_comms, true)?;
+    let (quotient_comms, lookup_quotient_comm) =
+        split_lookup_elem::<_, NUM_WIRE_TYPES>(&jf_proof.split_quot_poly_comms, true)?;
+    let (wires_evals, lookup_wire_eval) =
+        split_lookup_elem::<_, NUM_WIRE_TYPES>(&jf_proof.poly_evals.wires_evals, true)?;
+    let (sigma_evals, last_sigma_eval) = split_lookup_elem::<_, { NUM_WIRE_TYPES - 1 }>(
+        &jf_proof.poly_evals.wire_sigma_evals,
+        true,
+    )?;
+
+    turbo_plonk_proof.wires_poly_comms = wire_comms.to_vec();
+    turbo_plonk_proof.split_quot_poly_comms = quotient_comms.to_vec();
+    turbo_plonk_proof.poly_evals.wires_evals = wires_evals.to_vec();
+    turbo_plonk_proof.poly_evals.wire_sigma_evals = sigma_evals.to_vec();
+
+    let proof = to_contract_proof(&turbo_plonk_proof)?;
+
+    let evals = &plookup_proof.poly_evals;
+    let lookup_proof = Some(LookupProof {
+        // The `unwrap`s are safe, as `split_lookup_elem` checks that the lookup elements are present
+        lookup_wire_comm: lookup_wire_comm.unwrap().0,
+        lookup_quotient_comm: lookup_quotient_comm.unwrap().0,
+        h_poly_comms: try_unwrap_commitments(&plookup_proof.h_poly_comms)?,
+        prod_lookup_poly_comm: plookup_proof.prod_lookup_poly_comm.0,
+        lookup_wire_eval: lookup_wire_eval.unwrap(),
+        last_sigma_eval: last_sigma_eval.unwrap(),
+        lookup_evals: LookupEvaluations {
+            range_table_eval: evals.range_table_eval,
+            key_table_eval: evals.key_table_eval,
+            table_dom_sep_eval: evals.table_dom_sep_eval,
+            q_dom_sep_eval: evals.q_dom_sep_eval,
+            h_1_eval: evals.h_1_eval,
+            q_lookup_eval: evals.q_lookup_eval,
+            prod_next_eval: evals.prod_next_eval,
+            range_table_next_eval: evals.range_table_next_eval,
+            key_table_next_eval: evals.key_table_next_eval,
+            table_dom_sep_next_eval: evals.table_dom_sep_next_eval,
+            h_1_next_eval: evals.h_1_next_eval,
+            h_2_next_eval: evals.h_2_next_eval,
+            q_lookup_next_eval: evals.q_lookup_next_eval,
+            w_3_next_eval: evals.w_3_next_eval,
+            w_4_next_eval: evals.w_4_next_eval,
+        },
+    });
+
+    Ok(ExtendedProof {
+        proof,
+        lookup_proof,
     })
 }
 
@@ -2,9 +2,7 @@
 
 use arbitrum_client::conversion::to_contract_public_signing_key;
 use circuit_types::keychain::PublicSigningKey as CircuitPubkey;
-use contracts_common::{
-    backends::HashBackend, constants::HASH_OUTPUT_SIZE, types::PublicSigningKey,
-};
+use contracts_common::types::PublicSigningKey;
 use ethers::{
     core::k256::ecdsa::SigningKey,
     types::{Signature, U256},
@@ -12,16 +10,6 @@ use ethers::{
 };
 use rand::{CryptoRng, RngCore};
 
-/// A hashing backend that runs natively, i.e.
-/// without using a Stylus VM-accelerated Keccak implementation
-pub struct NativeHasher;
-
-impl HashBackend for NativeHasher {
-    fn hash(input: &[u8]) -> [u8; HASH_OUTPUT_SIZE] {
-        keccak256(input)
-    }
-}
-
 /// Generates a random secp256k1 signing keypair, returning the [`SigningKey`] and the
 /// [`PublicSigningKey`] type
 pub fn random_keypair<R: CryptoRng + RngCore>(rng: &mut R) -> (SigningKey, PublicSigningKey) {
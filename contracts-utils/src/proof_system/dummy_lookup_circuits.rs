@@ -0,0 +1,24 @@
+//! Defines mock UltraPlonk circuits, i.e. ones that make use of a lookup argument,
+//! used to test the verification of lookup proofs
+
+use contracts_common::types::ScalarField;
+use mpc_plonk::errors::PlonkError;
+use mpc_relation::{traits::Circuit, PlonkCircuit};
+
+/// The bit length of the range table used in the dummy lookup circuit
+pub const DUMMY_LOOKUP_RANGE_BIT_LEN: usize = 8;
+
+/// Builds a dummy UltraPlonk circuit which range-checks each of the given public inputs
+/// against the range `[0, 2^DUMMY_LOOKUP_RANGE_BIT_LEN)` via a lookup into the range table
+pub fn dummy_range_check_circuit(
+    public_inputs: &[ScalarField],
+) -> Result<PlonkCircuit<ScalarField>, PlonkError> {
+    let mut circuit = PlonkCircuit::new_ultra_plonk(DUMMY_LOOKUP_RANGE_BIT_LEN);
+    for public_input in public_inputs {
+        let var = circuit.create_public_variable(*public_input)?;
+        circuit.add_range_check_variable(var)?;
+    }
+    circuit.finalize_for_arithmetization()?;
+
+    Ok(circuit)
+}
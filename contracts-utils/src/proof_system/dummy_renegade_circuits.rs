@@ -178,11 +178,23 @@ pub struct DummyValidMatchSettleWitness {
     pub valid_commitments_match_settle1: Scalar,
 }
 
+/// The dummy version of the `VALID MATCH SETTLE` statement,
+/// which extends the protocol statement with the base mint of the traded pair
+/// so that the contracts can look up the protocol fee applicable to the match
+#[circuit_type(singleprover_circuit)]
+#[derive(Clone)]
+pub struct DummyValidMatchSettleStatement {
+    /// The `VALID MATCH SETTLE` statement
+    pub valid_match_settle: SizedValidMatchSettleStatement,
+    /// The base mint of the traded pair
+    pub base_mint: Scalar,
+}
+
 /// The dummy version of the `VALID MATCH SETTLE` circuit
 pub struct DummyValidMatchSettle;
 
 impl SingleProverCircuit for DummyValidMatchSettle {
-    type Statement = SizedValidMatchSettleStatement;
+    type Statement = DummyValidMatchSettleStatement;
     type Witness = DummyValidMatchSettleWitness;
 
     fn name() -> String {
@@ -191,7 +203,7 @@ impl SingleProverCircuit for DummyValidMatchSettle {
 
     fn apply_constraints(
         _witness_var: <DummyValidMatchSettleWitness as CircuitBaseType>::VarType,
-        _statement_var: <SizedValidMatchSettleStatement as CircuitBaseType>::VarType,
+        _statement_var: <DummyValidMatchSettleStatement as CircuitBaseType>::VarType,
         _cs: &mut PlonkCircuit,
     ) -> Result<(), PlonkError> {
         Ok(())
@@ -16,8 +16,11 @@ use mpc_relation::proof_linking::GroupLayout;
 
 use crate::conversion::{to_contract_vkey, to_linking_vkey};
 
+pub mod dummy_lookup_circuits;
 pub mod dummy_renegade_circuits;
+pub mod poseidon_transcript;
 pub mod test_data;
+pub mod transcript_vectors;
 
 // ------------------------
 // | HIGH-LEVEL UTILITIES |
@@ -0,0 +1,156 @@
+//! A prover-side Poseidon2 transcript, which implements the Jellyfish `PlonkTranscript` trait,
+//! so that proofs can be generated for verification keys expecting the Poseidon2 transcript.
+//!
+//! This is implemented directly over the Poseidon2 sponge, independently of the contracts'
+//! [`PoseidonTranscript`](contracts_core::transcript::poseidon::PoseidonTranscript),
+//! so that the two can be tested against each other
+
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use contracts_common::{constants::NUM_BYTES_FELT, types::ScalarField};
+use jf_primitives::pcs::prelude::Commitment;
+use mpc_plonk::{
+    errors::PlonkError, proof_system::structs::VerifyingKey, transcript::PlonkTranscript,
+};
+use renegade_crypto::hash::Poseidon2Sponge;
+
+/// The number of bytes in each of the limbs into which
+/// the coordinates of a G1 point are split when absorbed
+const NUM_BYTES_COORD_LIMB: usize = NUM_BYTES_FELT / 2;
+
+/// A Jellyfish transcript which absorbs the elements of a proof into a Poseidon2 sponge,
+/// in the same order as they are absorbed by the verifier
+pub struct PoseidonPlonkTranscript {
+    /// The sponge absorbing the elements of the current round
+    sponge: Poseidon2Sponge,
+}
+
+impl PoseidonPlonkTranscript {
+    /// Absorbs the given G1 points, each as the low & high 128-bit limbs of its coordinates.
+    /// The identity is absorbed as the coordinates `(0, 0)`.
+    fn absorb_points<P: SWCurveConfig>(
+        &mut self,
+        points: &[Affine<P>],
+    ) -> Result<(), SerializationError> {
+        for point in points {
+            let mut limbs = Vec::with_capacity(4);
+            for coord in [point.x, point.y] {
+                let coord = if point.infinity {
+                    P::BaseField::zero()
+                } else {
+                    coord
+                };
+
+                // A base field element is serialized as its little-endian canonical representation
+                let mut bytes = Vec::with_capacity(coord.compressed_size());
+                coord.serialize_compressed(&mut bytes)?;
+                let (low, high) = bytes.split_at(NUM_BYTES_COORD_LIMB);
+                limbs.push(ScalarField::from_le_bytes_mod_order(low));
+                limbs.push(ScalarField::from_le_bytes_mod_order(high));
+            }
+
+            self.sponge.absorb_batch(&limbs);
+        }
+
+        Ok(())
+    }
+}
+
+impl<F> PlonkTranscript<F> for PoseidonPlonkTranscript {
+    fn new(_label: &'static [u8]) -> Self {
+        PoseidonPlonkTranscript {
+            sponge: Poseidon2Sponge::new(),
+        }
+    }
+
+    fn append_vk_and_pub_input<E, P>(
+        &mut self,
+        vk: &VerifyingKey<E>,
+        pub_input: &[E::ScalarField],
+    ) -> Result<(), PlonkError>
+    where
+        E: Pairing<BaseField = F, G1Affine = Affine<P>>,
+        P: SWCurveConfig<BaseField = F>,
+    {
+        self.sponge
+            .absorb(&ScalarField::from(E::ScalarField::MODULUS_BIT_SIZE));
+        self.sponge
+            .absorb(&ScalarField::from(vk.domain_size as u64));
+        self.sponge.absorb(&ScalarField::from(vk.num_inputs as u64));
+        self.sponge.absorb_batch(&convert_all(&vk.k)?);
+
+        let selector_comms: Vec<_> = vk.selector_comms.iter().map(|c| c.0).collect();
+        self.absorb_points(&selector_comms)?;
+        let sigma_comms: Vec<_> = vk.sigma_comms.iter().map(|c| c.0).collect();
+        self.absorb_points(&sigma_comms)?;
+
+        self.sponge.absorb_batch(&convert_all(pub_input)?);
+
+        Ok(())
+    }
+
+    /// The only messages the prover absorbs as raw bytes are serialized scalars,
+    /// e.g. the proof evaluations absorbed by the default implementations of this trait,
+    /// so these are deserialized & absorbed as scalars
+    fn append_message(&mut self, _label: &'static [u8], msg: &[u8]) -> Result<(), PlonkError> {
+        let scalars = msg
+            .chunks(NUM_BYTES_FELT)
+            .map(ScalarField::deserialize_compressed)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.sponge.absorb_batch(&scalars);
+
+        Ok(())
+    }
+
+    fn append_commitment<E, P>(
+        &mut self,
+        _label: &'static [u8],
+        comm: &Commitment<E>,
+    ) -> Result<(), PlonkError>
+    where
+        E: Pairing<BaseField = F, G1Affine = Affine<P>>,
+        P: SWCurveConfig<BaseField = F>,
+    {
+        self.absorb_points(&[comm.0])?;
+
+        Ok(())
+    }
+
+    fn get_and_append_challenge<E>(
+        &mut self,
+        _label: &'static [u8],
+    ) -> Result<E::ScalarField, PlonkError>
+    where
+        E: Pairing,
+    {
+        let challenge = self.sponge.squeeze();
+
+        // The sponge cannot absorb once squeezed, so the rounds of the
+        // transcript are chained by absorbing the challenge into a fresh sponge
+        self.sponge = Poseidon2Sponge::new();
+        self.sponge.absorb(&challenge);
+
+        Ok(convert(&challenge)?)
+    }
+}
+
+/// Converts between two arkworks types with the same serialization, i.e. between the
+/// generic types used in the Jellyfish transcript & the concrete types used in the contracts
+fn convert<T: CanonicalSerialize, U: CanonicalDeserialize>(
+    value: &T,
+) -> Result<U, SerializationError> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes)?;
+    U::deserialize_compressed(bytes.as_slice())
+}
+
+/// Converts a slice of values using [`convert`]
+fn convert_all<T: CanonicalSerialize, U: CanonicalDeserialize>(
+    values: &[T],
+) -> Result<Vec<U>, SerializationError> {
+    values.iter().map(convert).collect()
+}
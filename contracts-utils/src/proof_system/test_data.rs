@@ -1,5 +1,6 @@
 //! Utilities for generating data for the proof system tests
 
+use alloy_primitives::Address;
 use arbitrum_client::conversion::{
     to_contract_link_proof, to_contract_proof, to_contract_valid_commitments_statement,
     to_contract_valid_fee_redemption_statement, to_contract_valid_match_settle_statement,
@@ -30,41 +31,49 @@ use circuits::zk_circuits::{
 };
 use constants::{Scalar, ScalarField, SystemCurve};
 use contracts_common::{
-    custom_serde::{statement_to_public_inputs, BytesSerializable},
+    constants::NUM_BYTES_ADDRESS,
+    custom_serde::{address_to_scalar, statement_to_public_inputs, BytesSerializable},
     types::{
-        G1Affine, MatchLinkingProofs, MatchLinkingVkeys, MatchLinkingWirePolyComms, MatchPayload,
-        MatchProofs, MatchPublicInputs, MatchVkeys, Proof as ContractProof,
+        ExtendedProof as ContractProof, ExtendedVerificationKey, G1Affine, LinkedBundle,
+        MatchLinkingProofs, MatchLinkingVkeys, MatchLinkingWirePolyComms, MatchPayload,
+        MatchProofs, MatchPublicInputs, MatchVkeys, ProofLink, PublicInputs, TranscriptHash,
         ValidFeeRedemptionStatement as ContractValidFeeRedemptionStatement,
-        ValidMatchSettleStatement as ContractValidMatchSettleStatement,
+        ValidMatchSettleWithMintStatement,
         ValidOfflineFeeSettlementStatement as ContractValidOfflineFeeSettlementStatement,
         ValidRelayerFeeSettlementStatement as ContractValidRelayerFeeSettlementStatement,
         ValidWalletCreateStatement as ContractValidWalletCreateStatement,
-        ValidWalletUpdateStatement as ContractValidWalletUpdateStatement, VerificationKey,
+        ValidWalletUpdateStatement as ContractValidWalletUpdateStatement,
     },
 };
 use contracts_core::crypto::poseidon::compute_poseidon_hash;
 use ethers::types::Bytes;
-use eyre::Result;
+use eyre::{eyre, Result};
 use jf_primitives::pcs::{prelude::Commitment, StructuredReferenceString};
 
-use mpc_plonk::{proof_system::PlonkKzgSnark, transcript::SolidityTranscript};
+use mpc_plonk::{
+    proof_system::{PlonkKzgSnark, UniversalSNARK},
+    transcript::SolidityTranscript,
+};
 use rand::{seq::SliceRandom, CryptoRng, Rng, RngCore};
 use std::iter;
 
 use crate::{
     constants::DUMMY_CIRCUIT_SRS_DEGREE,
-    conversion::{to_circuit_pubkey, to_contract_vkey},
+    conversion::{to_circuit_pubkey, to_contract_lookup_proof, to_contract_vkey, to_linking_vkey},
     crypto::{hash_and_sign_message, random_keypair},
 };
 
 use super::{
+    dummy_lookup_circuits::{dummy_range_check_circuit, DUMMY_LOOKUP_RANGE_BIT_LEN},
     dummy_renegade_circuits::{
         DummyValidCommitments, DummyValidCommitmentsWitness, DummyValidFeeRedemption,
-        DummyValidMatchSettle, DummyValidMatchSettleWitness, DummyValidOfflineFeeSettlement,
-        DummyValidReblind, DummyValidReblindWitness, DummyValidRelayerFeeSettlement,
-        DummyValidWalletCreate, DummyValidWalletUpdate,
+        DummyValidMatchSettle, DummyValidMatchSettleStatement, DummyValidMatchSettleWitness,
+        DummyValidOfflineFeeSettlement, DummyValidReblind, DummyValidReblindWitness,
+        DummyValidRelayerFeeSettlement, DummyValidWalletCreate, DummyValidWalletUpdate,
     },
-    gen_match_layouts, gen_match_linking_vkeys, gen_match_vkeys, MatchGroupLayouts,
+    gen_match_layouts, gen_match_linking_vkeys, gen_match_vkeys,
+    poseidon_transcript::PoseidonPlonkTranscript,
+    MatchGroupLayouts,
 };
 
 /// Generates a vector of random scalars
@@ -187,19 +196,74 @@ pub fn gen_verification_bundle<R: CryptoRng + RngCore>(
 ) -> Result<(
     ContractValidWalletCreateStatement,
     ContractProof,
-    VerificationKey,
+    ExtendedVerificationKey,
 )> {
     let statement = dummy_circuit_type(rng);
     let contract_statement = to_contract_valid_wallet_create_statement(&statement);
 
     let jf_proof = DummyValidWalletCreate::prove((), statement)?;
-    let proof = to_contract_proof(&jf_proof)?;
+    let proof = to_contract_proof(&jf_proof)?.into();
     let jf_vkey = (*DummyValidWalletCreate::verifying_key()).clone();
     let vkey = to_contract_vkey(jf_vkey)?;
 
     Ok((contract_statement, proof, vkey))
 }
 
+/// The number of public inputs range-checked in the dummy lookup circuit
+const NUM_DUMMY_LOOKUP_PUBLIC_INPUTS: usize = 4;
+
+/// Generates dummy public inputs for the dummy lookup circuit, i.e. ones within its range
+fn dummy_lookup_public_inputs<R: CryptoRng + RngCore>(rng: &mut R) -> Vec<ScalarField> {
+    (0..NUM_DUMMY_LOOKUP_PUBLIC_INPUTS)
+        .map(|_| ScalarField::from(rng.gen_range(0..1_u64 << DUMMY_LOOKUP_RANGE_BIT_LEN)))
+        .collect()
+}
+
+/// Creates dummy public inputs, uses them to compute a valid proof of the dummy
+/// UltraPlonk range check circuit, and generates its associated verification key.
+///
+/// This is used to test the verification of proofs with a lookup argument.
+pub fn gen_lookup_verification_bundle<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<(PublicInputs, ContractProof, ExtendedVerificationKey)> {
+    let public_inputs = dummy_lookup_public_inputs(rng);
+
+    let circuit = dummy_range_check_circuit(&public_inputs)?;
+    let (pk, jf_vkey) = PlonkKzgSnark::<SystemCurve>::preprocess(&SYSTEM_SRS, &circuit)?;
+    let jf_proof =
+        PlonkKzgSnark::<SystemCurve>::prove::<_, _, SolidityTranscript>(rng, &circuit, &pk, None)?;
+
+    let proof = to_contract_lookup_proof(&jf_proof)?;
+    let vkey = to_contract_vkey(jf_vkey)?;
+
+    Ok((PublicInputs(public_inputs), proof, vkey))
+}
+
+/// Creates dummy public inputs, uses them to compute a valid proof of the dummy
+/// UltraPlonk range check circuit using the Poseidon2 transcript, and generates its
+/// associated verification key, which expects the Poseidon2 transcript.
+///
+/// This is used to test the verification of proofs with a field-native transcript.
+pub fn gen_poseidon_verification_bundle<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<(PublicInputs, ContractProof, ExtendedVerificationKey)> {
+    let public_inputs = dummy_lookup_public_inputs(rng);
+
+    let circuit = dummy_range_check_circuit(&public_inputs)?;
+    let (pk, jf_vkey) = PlonkKzgSnark::<SystemCurve>::preprocess(&SYSTEM_SRS, &circuit)?;
+    let jf_proof = PlonkKzgSnark::<SystemCurve>::prove::<_, _, PoseidonPlonkTranscript>(
+        rng, &circuit, &pk, None,
+    )?;
+
+    let proof = to_contract_lookup_proof(&jf_proof)?;
+    let vkey = ExtendedVerificationKey {
+        transcript_hash: TranscriptHash::Poseidon2,
+        ..to_contract_vkey(jf_vkey)?
+    };
+
+    Ok((PublicInputs(public_inputs), proof, vkey))
+}
+
 /// Generates the inputs for the `new_wallet` darkpool method, namely
 /// a dummy statement and associated proof for the `VALID WALLET CREATE` circuit
 pub fn gen_new_wallet_data<R: CryptoRng + RngCore>(
@@ -208,7 +272,7 @@ pub fn gen_new_wallet_data<R: CryptoRng + RngCore>(
     // Generate dummy statement & proof
     let statement: SizedValidWalletCreateStatement = dummy_circuit_type(rng);
     let jf_proof = DummyValidWalletCreate::prove((), statement.clone())?;
-    let proof = to_contract_proof(&jf_proof)?;
+    let proof = to_contract_proof(&jf_proof)?.into();
 
     // Convert the statement & proof types to the ones expected by the contract
     let contract_statement = to_contract_valid_wallet_create_statement(&statement);
@@ -237,7 +301,7 @@ pub fn gen_update_wallet_data<R: CryptoRng + RngCore>(
         circuit_pubkey,
     );
     let jf_proof = DummyValidWalletUpdate::prove((), statement.clone())?;
-    let proof = to_contract_proof(&jf_proof)?;
+    let proof = to_contract_proof(&jf_proof)?.into();
 
     // Convert the statement & proof types to the ones expected by the contract
     let contract_statement = to_contract_valid_wallet_update_statement(&statement)?;
@@ -277,7 +341,7 @@ pub fn gen_settle_online_relayer_fee_data<R: CryptoRng + RngCore>(
     // Generate dummy statement & proof
     let statement = dummy_valid_relayer_fee_settlement_statement(rng, merkle_root, circuit_pubkey);
     let jf_proof = DummyValidRelayerFeeSettlement::prove((), statement.clone())?;
-    let proof = to_contract_proof(&jf_proof)?;
+    let proof = to_contract_proof(&jf_proof)?.into();
 
     // Convert the statement & proof types to the ones expected by the contract
     let contract_statement: ContractValidRelayerFeeSettlementStatement =
@@ -314,7 +378,7 @@ pub fn gen_settle_offline_fee_data<R: CryptoRng + RngCore>(
         is_protocol_fee,
     );
     let jf_proof = DummyValidOfflineFeeSettlement::prove((), statement.clone())?;
-    let proof = to_contract_proof(&jf_proof)?;
+    let proof = to_contract_proof(&jf_proof)?.into();
 
     // Convert the statement & proof types to the ones expected by the contract
     let contract_statement: ContractValidOfflineFeeSettlementStatement =
@@ -339,7 +403,7 @@ pub fn gen_redeem_fee_data<R: CryptoRng + RngCore>(
     // Generate dummy statement & proof
     let statement = dummy_valid_fee_redemption_statement(rng, merkle_root, circuit_pubkey);
     let jf_proof = DummyValidFeeRedemption::prove((), statement.clone())?;
-    let proof = to_contract_proof(&jf_proof)?;
+    let proof = to_contract_proof(&jf_proof)?.into();
 
     // Convert the statement & proof types to the ones expected by the contract
     let contract_statement: ContractValidFeeRedemptionStatement =
@@ -366,10 +430,10 @@ pub struct ProcessMatchSettleData {
     pub match_payload_0: MatchPayload,
     /// The second party's match payload
     pub match_payload_1: MatchPayload,
-    /// The `VALID MATCH SETTLE` statement
-    pub valid_match_settle_statement: ContractValidMatchSettleStatement,
+    /// The `VALID MATCH SETTLE` statement, including the base mint of the traded pair
+    pub valid_match_settle_statement: ValidMatchSettleWithMintStatement,
     /// The Plonk proofs submitted to `process_match_settle`
-    pub match_proofs: MatchProofs,
+    pub match_proofs: MatchProofs<ContractProof>,
     /// The linking proofs submitted to `process_match_settle`
     pub match_linking_proofs: MatchLinkingProofs,
 }
@@ -379,10 +443,11 @@ fn dummy_match_statements<R: CryptoRng + RngCore>(
     rng: &mut R,
     merkle_root: Scalar,
     protocol_fee: FixedPoint,
+    base_mint: Address,
 ) -> (
     [ValidCommitmentsStatement; 2],
     [ValidReblindStatement; 2],
-    SizedValidMatchSettleStatement,
+    DummyValidMatchSettleStatement,
 ) {
     let valid_commitments0: ValidCommitmentsStatement = dummy_circuit_type(rng);
     let valid_commitments1: ValidCommitmentsStatement = dummy_circuit_type(rng);
@@ -395,10 +460,16 @@ fn dummy_match_statements<R: CryptoRng + RngCore>(
     valid_match_settle.party1_indices = valid_commitments1.indices;
     valid_match_settle.protocol_fee = protocol_fee;
 
+    // Unwrapping here is safe because an address is always smaller than the scalar field modulus
+    let base_mint = Scalar::new(address_to_scalar(base_mint).unwrap());
+
     (
         [valid_commitments0, valid_commitments1],
         [valid_reblind0, valid_reblind1],
-        valid_match_settle,
+        DummyValidMatchSettleStatement {
+            valid_match_settle,
+            base_mint,
+        },
     )
 }
 
@@ -433,7 +504,10 @@ fn dummy_match_witnesses<R: CryptoRng + RngCore>(
 }
 
 /// A type alias for the proofs and linking hints generated for the `process_match_settle` method
-type MatchProofsAndHints = (MatchProofs, [(ProofLinkingHint, ProofLinkingHint); 4]);
+type MatchProofsAndHints = (
+    MatchProofs<ContractProof>,
+    [(ProofLinkingHint, ProofLinkingHint); 4],
+);
 
 /// Generates the proofs and linking hints to be submitted to `process_match_settle`
 fn match_proofs_and_hints(
@@ -441,7 +515,7 @@ fn match_proofs_and_hints(
     valid_commitments_witnesses: [DummyValidCommitmentsWitness; 2],
     valid_reblind_statements: [ValidReblindStatement; 2],
     valid_reblind_witnesses: [DummyValidReblindWitness; 2],
-    valid_match_settle_statement: SizedValidMatchSettleStatement,
+    valid_match_settle_statement: DummyValidMatchSettleStatement,
     valid_match_settle_witness: DummyValidMatchSettleWitness,
 ) -> Result<MatchProofsAndHints> {
     let (valid_commitments_0, valid_commitments_hint_0) =
@@ -449,33 +523,33 @@ fn match_proofs_and_hints(
             valid_commitments_witnesses[0].clone(),
             valid_commitments_statements[0],
         )?;
-    let valid_commitments_0 = to_contract_proof(&valid_commitments_0)?;
+    let valid_commitments_0 = to_contract_proof(&valid_commitments_0)?.into();
 
     let (valid_commitments_1, valid_commitments_hint_1) =
         DummyValidCommitments::prove_with_link_hint(
             valid_commitments_witnesses[1].clone(),
             valid_commitments_statements[1],
         )?;
-    let valid_commitments_1 = to_contract_proof(&valid_commitments_1)?;
+    let valid_commitments_1 = to_contract_proof(&valid_commitments_1)?.into();
 
     let (valid_reblind_0, valid_reblind_hint_0) = DummyValidReblind::prove_with_link_hint(
         valid_reblind_witnesses[0].clone(),
         valid_reblind_statements[0].clone(),
     )?;
-    let valid_reblind_0 = to_contract_proof(&valid_reblind_0)?;
+    let valid_reblind_0 = to_contract_proof(&valid_reblind_0)?.into();
 
     let (valid_reblind_1, valid_reblind_hint_1) = DummyValidReblind::prove_with_link_hint(
         valid_reblind_witnesses[1].clone(),
         valid_reblind_statements[1].clone(),
     )?;
-    let valid_reblind_1 = to_contract_proof(&valid_reblind_1)?;
+    let valid_reblind_1 = to_contract_proof(&valid_reblind_1)?.into();
 
     let (valid_match_settle, valid_match_settle_hint) =
         DummyValidMatchSettle::prove_with_link_hint(
             valid_match_settle_witness.clone(),
             valid_match_settle_statement.clone(),
         )?;
-    let valid_match_settle = to_contract_proof(&valid_match_settle)?;
+    let valid_match_settle = to_contract_proof(&valid_match_settle)?.into();
 
     Ok((
         MatchProofs {
@@ -559,13 +633,15 @@ fn match_link_proofs(
 }
 
 /// Generates the data to be submitted to `process_match_settle`
+/// for a match of the pair with the given base mint
 pub fn gen_process_match_settle_data<R: CryptoRng + RngCore>(
     rng: &mut R,
     merkle_root: Scalar,
     protocol_fee: FixedPoint,
+    base_mint: Address,
 ) -> Result<ProcessMatchSettleData> {
     let (valid_commitments_statements, valid_reblind_statements, valid_match_settle_statement) =
-        dummy_match_statements(rng, merkle_root, protocol_fee);
+        dummy_match_statements(rng, merkle_root, protocol_fee, base_mint);
     let (valid_commitments_witnesses, valid_reblind_witnesses, valid_match_settle_witness) =
         dummy_match_witnesses(rng);
     let (match_proofs, link_hints) = match_proofs_and_hints(
@@ -594,9 +670,12 @@ pub fn gen_process_match_settle_data<R: CryptoRng + RngCore>(
     Ok(ProcessMatchSettleData {
         match_payload_0,
         match_payload_1,
-        valid_match_settle_statement: to_contract_valid_match_settle_statement(
-            &valid_match_settle_statement,
-        ),
+        valid_match_settle_statement: ValidMatchSettleWithMintStatement {
+            valid_match_settle: to_contract_valid_match_settle_statement(
+                &valid_match_settle_statement.valid_match_settle,
+            ),
+            base_mint,
+        },
         match_proofs,
         match_linking_proofs,
     })
@@ -626,7 +705,7 @@ pub fn generate_match_bundle<R: CryptoRng + RngCore>(
     rng: &mut R,
 ) -> Result<(
     MatchVkeys,
-    MatchProofs,
+    MatchProofs<ContractProof>,
     MatchPublicInputs,
     MatchLinkingVkeys,
     MatchLinkingProofs,
@@ -635,7 +714,8 @@ pub fn generate_match_bundle<R: CryptoRng + RngCore>(
     // Generate random `process_match_settle` test data & destructure
     let merkle_root = Scalar::random(rng);
     let protocol_fee = FixedPoint::from(Scalar::random(rng));
-    let data = gen_process_match_settle_data(rng, merkle_root, protocol_fee)?;
+    let base_mint = Address::from(rng.gen::<[u8; NUM_BYTES_ADDRESS]>());
+    let data = gen_process_match_settle_data(rng, merkle_root, protocol_fee, base_mint)?;
 
     let match_vkeys =
         gen_match_vkeys::<DummyValidCommitments, DummyValidReblind, DummyValidMatchSettle>()?;
@@ -662,10 +742,113 @@ pub fn generate_match_bundle<R: CryptoRng + RngCore>(
     ))
 }
 
+/// Generates a [`LinkedBundle`] with a non-match linking topology.
+///
+/// The bundle begins with `num_unlinked` proofs of `VALID WALLET CREATE`, which are not linked
+/// to any other proof, followed by `num_pairs` independent `VALID REBLIND` -> `VALID COMMITMENTS`
+/// proof pairs, each of which is linked by a single edge.
+pub fn gen_linked_bundle<R: CryptoRng + RngCore>(
+    rng: &mut R,
+    num_unlinked: usize,
+    num_pairs: usize,
+) -> Result<LinkedBundle> {
+    let mut linked_bundle = LinkedBundle::default();
+
+    for _ in 0..num_unlinked {
+        let (statement, proof, vkey) = gen_verification_bundle(rng)?;
+        let public_inputs = statement_to_public_inputs(&statement).map_err(|e| eyre!("{:?}", e))?;
+
+        linked_bundle.vkeys.push(vkey);
+        linked_bundle.proofs.push(proof);
+        linked_bundle.public_inputs.push(public_inputs);
+    }
+
+    let valid_reblind_vkey = to_contract_vkey((*DummyValidReblind::verifying_key()).clone())?;
+    let valid_commitments_vkey =
+        to_contract_vkey((*DummyValidCommitments::verifying_key()).clone())?;
+
+    let valid_reblind_commitments_layout =
+        gen_match_layouts::<DummyValidCommitments>()?.valid_reblind_commitments;
+    let valid_reblind_commitments_linking_vkey = to_linking_vkey(&valid_reblind_commitments_layout);
+    let commit_key = SYSTEM_SRS.extract_prover_param(DUMMY_CIRCUIT_SRS_DEGREE);
+
+    for _ in 0..num_pairs {
+        let valid_reblind_statement: ValidReblindStatement = dummy_circuit_type(rng);
+        let valid_commitments_statement: ValidCommitmentsStatement = dummy_circuit_type(rng);
+
+        let valid_commitments_witness: DummyValidCommitmentsWitness = dummy_circuit_type(rng);
+        let valid_reblind_witness = DummyValidReblindWitness {
+            valid_reblind_commitments: valid_commitments_witness.valid_reblind_commitments,
+        };
+
+        let (valid_reblind_proof, valid_reblind_hint) = DummyValidReblind::prove_with_link_hint(
+            valid_reblind_witness,
+            valid_reblind_statement.clone(),
+        )?;
+        let (valid_commitments_proof, valid_commitments_hint) =
+            DummyValidCommitments::prove_with_link_hint(
+                valid_commitments_witness,
+                valid_commitments_statement,
+            )?;
+
+        let linking_proof = to_contract_link_proof(&PlonkKzgSnark::<SystemCurve>::link_proofs::<
+            SolidityTranscript,
+        >(
+            &valid_reblind_hint,
+            &valid_commitments_hint,
+            &valid_reblind_commitments_layout,
+            &commit_key,
+        )?)?;
+
+        let valid_reblind_index = linked_bundle.proofs.len();
+        let valid_commitments_index = valid_reblind_index + 1;
+
+        linked_bundle.vkeys.push(valid_reblind_vkey);
+        linked_bundle
+            .proofs
+            .push(to_contract_proof(&valid_reblind_proof)?.into());
+        linked_bundle.public_inputs.push(
+            statement_to_public_inputs(&to_contract_valid_reblind_statement(
+                &valid_reblind_statement,
+            ))
+            .map_err(|e| eyre!("{:?}", e))?,
+        );
+
+        linked_bundle.vkeys.push(valid_commitments_vkey);
+        linked_bundle
+            .proofs
+            .push(to_contract_proof(&valid_commitments_proof)?.into());
+        linked_bundle.public_inputs.push(
+            statement_to_public_inputs(&to_contract_valid_commitments_statement(
+                valid_commitments_statement,
+            ))
+            .map_err(|e| eyre!("{:?}", e))?,
+        );
+
+        linked_bundle.links.push(ProofLink {
+            source: valid_reblind_index,
+            target: valid_commitments_index,
+            linking_vkey: valid_reblind_commitments_linking_vkey,
+            linking_proof,
+        });
+    }
+
+    Ok(linked_bundle)
+}
+
+/// Picks a random linking proof from the given [`LinkedBundle`] and mutates it
+pub fn mutate_random_bundle_link<R: CryptoRng + RngCore>(
+    rng: &mut R,
+    linked_bundle: &mut LinkedBundle,
+) {
+    let link = linked_bundle.links.choose_mut(rng).unwrap();
+    link.linking_proof.linking_quotient_poly_comm = G1Affine::rand(rng);
+}
+
 /// Picks a random Plonk proof from the batch of proofs verified in `verify_match` and mutates it
 pub fn mutate_random_plonk_proof<R: CryptoRng + RngCore>(
     rng: &mut R,
-    match_proofs: &mut MatchProofs,
+    match_proofs: &mut MatchProofs<ContractProof>,
 ) {
     let mut proofs = [
         &mut match_proofs.valid_commitments_0,
@@ -0,0 +1,430 @@
+//! Generates test vectors for the Fiat-Shamir transcript, recording every message absorbed
+//! & challenge squeezed by the Jellyfish prover's `SolidityTranscript` when proving random
+//! dummy circuits.
+//!
+//! These are replayed against the contracts' transcript in `contracts-core`, so that a
+//! divergence from Jellyfish can be pinpointed to the exact round in which it occurs.
+//! The `gen-transcript-vectors` script exports them to the JSON fixture replayed there,
+//! and the tests additionally replay vectors generated in-process from a fixed seed.
+
+use std::{cell::RefCell, mem};
+
+use arbitrum_client::conversion::{to_contract_link_proof, to_contract_proof};
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, SerializationError};
+use ark_std::UniformRand;
+use circuit_types::{srs::SYSTEM_SRS, traits::SingleProverCircuit, PlonkProof};
+use constants::{Scalar, ScalarField, SystemCurve};
+use contracts_common::{
+    serde_def_types::SerdeG1Affine,
+    types::{ExtendedProof, PublicInputs},
+};
+use ethers::utils::hex;
+use eyre::{eyre, Result};
+use jf_primitives::pcs::StructuredReferenceString;
+use mpc_plonk::{
+    errors::PlonkError,
+    proof_system::{structs::VerifyingKey, PlonkKzgSnark, UniversalSNARK},
+    transcript::{PlonkTranscript, SolidityTranscript},
+};
+use mpc_relation::{traits::Circuit, PlonkCircuit};
+use rand::{seq::SliceRandom, CryptoRng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::DUMMY_CIRCUIT_SRS_DEGREE,
+    conversion::{to_contract_lookup_proof, to_contract_vkey},
+};
+
+use super::{
+    dummy_lookup_circuits::{dummy_range_check_circuit, DUMMY_LOOKUP_RANGE_BIT_LEN},
+    dummy_renegade_circuits::{
+        DummyValidCommitments, DummyValidCommitmentsWitness, DummyValidReblind,
+        DummyValidReblindWitness,
+    },
+    gen_match_layouts,
+    test_data::{dummy_circuit_type, dummy_valid_reblind_statement},
+};
+
+/// The maximum number of public inputs in the random dummy TurboPlonk circuits
+const MAX_DUMMY_PUBLIC_INPUTS: usize = 16;
+
+/// The maximum number of gates, beyond those constraining the public inputs,
+/// in the random dummy TurboPlonk circuits
+const MAX_DUMMY_GATES: usize = 64;
+
+/// The maximum number of public inputs range-checked in the dummy lookup circuits
+const MAX_DUMMY_LOOKUP_PUBLIC_INPUTS: usize = 8;
+
+// ---------
+// | TYPES |
+// ---------
+
+/// A message absorbed into the transcript by the prover
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbsorbedMessage {
+    /// The label with which the prover absorbed the message
+    pub label: String,
+    /// The hex-encoded message
+    pub data: String,
+}
+
+/// A round of the transcript, i.e. the messages absorbed by the prover
+/// since the previous challenge was squeezed, and the challenge squeezed after them
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptRound {
+    /// The messages absorbed in this round, in order
+    pub messages: Vec<AbsorbedMessage>,
+    /// The hex-encoded challenge squeezed at the end of this round,
+    /// serialized in little-endian order
+    pub challenge: String,
+}
+
+/// A test vector for the transcript of a Plonk proof
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlonkTranscriptVector {
+    /// The hex-encoded, `postcard`-serialized verification key of the circuit
+    pub vkey: String,
+    /// The hex-encoded, `postcard`-serialized proof
+    pub proof: String,
+    /// The hex-encoded, `postcard`-serialized public inputs to the proof
+    pub public_inputs: String,
+    /// The rounds of the transcript recorded on the prover side.
+    ///
+    /// The final `u` challenge is only squeezed by the verifier, so it is not recorded here.
+    pub rounds: Vec<TranscriptRound>,
+}
+
+/// A test vector for the transcript of a linking proof
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LinkingTranscriptVector {
+    /// The hex-encoded, `postcard`-serialized commitment to the first linked wiring polynomial
+    pub wire_poly_comm_1: String,
+    /// The hex-encoded, `postcard`-serialized commitment to the second linked wiring polynomial
+    pub wire_poly_comm_2: String,
+    /// The hex-encoded, `postcard`-serialized linking proof
+    pub linking_proof: String,
+    /// The rounds of the transcript recorded on the prover side
+    pub rounds: Vec<TranscriptRound>,
+}
+
+/// The full set of transcript test vectors, as stored in the JSON fixture
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranscriptTestVectors {
+    /// Vectors for proofs of random dummy TurboPlonk circuits
+    pub plonk: Vec<PlonkTranscriptVector>,
+    /// Vectors for proofs of dummy UltraPlonk circuits, i.e. ones with a lookup argument
+    pub lookup: Vec<PlonkTranscriptVector>,
+    /// Vectors for linking proofs between the dummy `VALID REBLIND` & `VALID COMMITMENTS` circuits
+    pub linking: Vec<LinkingTranscriptVector>,
+}
+
+// ------------------------
+// | RECORDING TRANSCRIPT |
+// ------------------------
+
+thread_local! {
+    /// The rounds recorded by the [`RecordingTranscript`]s used on this thread
+    static RECORDED_ROUNDS: RefCell<Vec<TranscriptRound>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Takes the rounds recorded on this thread so far, clearing them
+fn take_recorded_rounds() -> Vec<TranscriptRound> {
+    RECORDED_ROUNDS.with(|rounds| rounds.take())
+}
+
+/// A transcript which wraps the Jellyfish `SolidityTranscript`, recording every message
+/// absorbed & challenge squeezed by the prover.
+///
+/// The recorded messages are also absorbed into a separate "shadow" transcript, whose challenges
+/// are checked against those of the prover's transcript. This ensures that the recorded messages
+/// are exactly the ones absorbed by the prover, even where the `SolidityTranscript` does not
+/// absorb messages through `append_message`.
+pub struct RecordingTranscript {
+    /// The transcript used by the prover
+    inner: SolidityTranscript,
+    /// A transcript absorbing only the recorded messages
+    shadow: SolidityTranscript,
+    /// The messages absorbed since the previous challenge was squeezed
+    messages: Vec<AbsorbedMessage>,
+}
+
+impl RecordingTranscript {
+    /// Records a message as absorbed in the current round
+    fn record_message<F>(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), PlonkError> {
+        <SolidityTranscript as PlonkTranscript<F>>::append_message(&mut self.shadow, label, msg)?;
+        self.messages.push(AbsorbedMessage {
+            label: String::from_utf8_lossy(label).into_owned(),
+            data: hex::encode(msg),
+        });
+
+        Ok(())
+    }
+}
+
+impl<F> PlonkTranscript<F> for RecordingTranscript {
+    fn new(label: &'static [u8]) -> Self {
+        RecordingTranscript {
+            inner: <SolidityTranscript as PlonkTranscript<F>>::new(label),
+            shadow: <SolidityTranscript as PlonkTranscript<F>>::new(label),
+            messages: Vec::new(),
+        }
+    }
+
+    fn append_vk_and_pub_input<E, P>(
+        &mut self,
+        vk: &VerifyingKey<E>,
+        pub_input: &[E::ScalarField],
+    ) -> Result<(), PlonkError>
+    where
+        E: Pairing<BaseField = F, G1Affine = Affine<P>>,
+        P: SWCurveConfig<BaseField = F>,
+    {
+        <SolidityTranscript as PlonkTranscript<F>>::append_vk_and_pub_input(
+            &mut self.inner,
+            vk,
+            pub_input,
+        )?;
+
+        // The `SolidityTranscript` absorbs these directly, without the SRS element
+        // absorbed by the default implementation, so we record them one by one
+        self.record_message::<F>(
+            b"field size in bits",
+            &E::ScalarField::MODULUS_BIT_SIZE.to_le_bytes(),
+        )?;
+        self.record_message::<F>(b"domain size", &vk.domain_size.to_le_bytes())?;
+        self.record_message::<F>(b"input size", &vk.num_inputs.to_le_bytes())?;
+        for k in vk.k.iter() {
+            self.record_message::<F>(b"wire subsets separators", &to_bytes(k)?)?;
+        }
+        for selector_comm in vk.selector_comms.iter() {
+            self.record_message::<F>(b"selector commitments", &to_bytes(selector_comm)?)?;
+        }
+        for sigma_comm in vk.sigma_comms.iter() {
+            self.record_message::<F>(b"sigma commitments", &to_bytes(sigma_comm)?)?;
+        }
+        for input in pub_input.iter() {
+            self.record_message::<F>(b"public input", &to_bytes(input)?)?;
+        }
+
+        Ok(())
+    }
+
+    fn append_message(&mut self, label: &'static [u8], msg: &[u8]) -> Result<(), PlonkError> {
+        <SolidityTranscript as PlonkTranscript<F>>::append_message(&mut self.inner, label, msg)?;
+        self.record_message::<F>(label, msg)
+    }
+
+    fn get_and_append_challenge<E>(
+        &mut self,
+        label: &'static [u8],
+    ) -> Result<E::ScalarField, PlonkError>
+    where
+        E: Pairing,
+    {
+        let challenge = <SolidityTranscript as PlonkTranscript<F>>::get_and_append_challenge::<E>(
+            &mut self.inner,
+            label,
+        )?;
+        let shadow_challenge =
+            <SolidityTranscript as PlonkTranscript<F>>::get_and_append_challenge::<E>(
+                &mut self.shadow,
+                label,
+            )?;
+
+        assert_eq!(
+            challenge,
+            shadow_challenge,
+            "recorded messages differ from those absorbed by the prover in the round squeezing {}",
+            String::from_utf8_lossy(label),
+        );
+
+        let round = TranscriptRound {
+            messages: mem::take(&mut self.messages),
+            challenge: hex::encode(to_bytes(&challenge)?),
+        };
+        RECORDED_ROUNDS.with(|rounds| rounds.borrow_mut().push(round));
+
+        Ok(challenge)
+    }
+}
+
+// --------------
+// | GENERATION |
+// --------------
+
+/// Generates the given number of test vectors for each of the Plonk, lookup,
+/// and linking proof transcripts
+pub fn gen_transcript_test_vectors<R: CryptoRng + RngCore>(
+    num_vectors: usize,
+    rng: &mut R,
+) -> Result<TranscriptTestVectors> {
+    let plonk = (0..num_vectors)
+        .map(|_| gen_plonk_transcript_vector(rng))
+        .collect::<Result<_>>()?;
+    let lookup = (0..num_vectors)
+        .map(|_| gen_lookup_transcript_vector(rng))
+        .collect::<Result<_>>()?;
+    let linking = (0..num_vectors)
+        .map(|_| gen_linking_transcript_vector(rng))
+        .collect::<Result<_>>()?;
+
+    Ok(TranscriptTestVectors {
+        plonk,
+        lookup,
+        linking,
+    })
+}
+
+/// Generates a transcript test vector for a proof of a random dummy TurboPlonk circuit
+pub fn gen_plonk_transcript_vector<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<PlonkTranscriptVector> {
+    let num_public_inputs = rng.gen_range(1..=MAX_DUMMY_PUBLIC_INPUTS);
+    let num_gates = rng.gen_range(1..=MAX_DUMMY_GATES);
+    let public_inputs: Vec<ScalarField> = (0..num_public_inputs)
+        .map(|_| ScalarField::rand(rng))
+        .collect();
+
+    let circuit = random_dummy_circuit(&public_inputs, num_gates, rng)?;
+    let (jf_vkey, jf_proof, rounds) = prove_and_record(&circuit, &public_inputs, rng)?;
+
+    Ok(PlonkTranscriptVector {
+        vkey: to_postcard_hex(&to_contract_vkey(jf_vkey)?)?,
+        proof: to_postcard_hex(&ExtendedProof::from(to_contract_proof(&jf_proof)?))?,
+        public_inputs: to_postcard_hex(&PublicInputs(public_inputs))?,
+        rounds,
+    })
+}
+
+/// Generates a transcript test vector for a proof of the dummy UltraPlonk range check circuit
+pub fn gen_lookup_transcript_vector<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<PlonkTranscriptVector> {
+    let num_public_inputs = rng.gen_range(1..=MAX_DUMMY_LOOKUP_PUBLIC_INPUTS);
+    let public_inputs: Vec<ScalarField> = (0..num_public_inputs)
+        .map(|_| ScalarField::from(rng.gen_range(0..1_u64 << DUMMY_LOOKUP_RANGE_BIT_LEN)))
+        .collect();
+
+    let circuit = dummy_range_check_circuit(&public_inputs)?;
+    let (jf_vkey, jf_proof, rounds) = prove_and_record(&circuit, &public_inputs, rng)?;
+
+    Ok(PlonkTranscriptVector {
+        vkey: to_postcard_hex(&to_contract_vkey(jf_vkey)?)?,
+        proof: to_postcard_hex(&to_contract_lookup_proof(&jf_proof)?)?,
+        public_inputs: to_postcard_hex(&PublicInputs(public_inputs))?,
+        rounds,
+    })
+}
+
+/// Generates a transcript test vector for a linking proof between
+/// the dummy `VALID REBLIND` & `VALID COMMITMENTS` circuits
+pub fn gen_linking_transcript_vector<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<LinkingTranscriptVector> {
+    let valid_commitments_witness: DummyValidCommitmentsWitness = dummy_circuit_type(rng);
+    let valid_reblind_witness = DummyValidReblindWitness {
+        valid_reblind_commitments: valid_commitments_witness.valid_reblind_commitments,
+    };
+
+    let valid_reblind_statement = dummy_valid_reblind_statement(rng, Scalar::random(rng));
+    let (_, valid_reblind_hint) =
+        DummyValidReblind::prove_with_link_hint(valid_reblind_witness, valid_reblind_statement)?;
+    let (_, valid_commitments_hint) = DummyValidCommitments::prove_with_link_hint(
+        valid_commitments_witness,
+        dummy_circuit_type(rng),
+    )?;
+
+    let layout = gen_match_layouts::<DummyValidCommitments>()?.valid_reblind_commitments;
+    let commit_key = SYSTEM_SRS.extract_prover_param(DUMMY_CIRCUIT_SRS_DEGREE);
+
+    take_recorded_rounds();
+    let jf_linking_proof = PlonkKzgSnark::<SystemCurve>::link_proofs::<RecordingTranscript>(
+        &valid_reblind_hint,
+        &valid_commitments_hint,
+        &layout,
+        &commit_key,
+    )?;
+    let rounds = take_recorded_rounds();
+
+    Ok(LinkingTranscriptVector {
+        wire_poly_comm_1: to_postcard_hex(&SerdeG1Affine(valid_reblind_hint.linking_wire_comm.0))?,
+        wire_poly_comm_2: to_postcard_hex(&SerdeG1Affine(
+            valid_commitments_hint.linking_wire_comm.0,
+        ))?,
+        linking_proof: to_postcard_hex(&to_contract_link_proof(&jf_linking_proof)?)?,
+        rounds,
+    })
+}
+
+/// Builds a random TurboPlonk circuit over the given public inputs,
+/// which computes a random sequence of additions & multiplications of them
+fn random_dummy_circuit<R: Rng>(
+    public_inputs: &[ScalarField],
+    num_gates: usize,
+    rng: &mut R,
+) -> Result<PlonkCircuit<ScalarField>, PlonkError> {
+    let mut circuit = PlonkCircuit::new_turbo_plonk();
+    let mut vars = public_inputs
+        .iter()
+        .map(|public_input| circuit.create_public_variable(*public_input))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for _ in 0..num_gates {
+        // Unwrapping here is safe because there is always at least one public input
+        let a = *vars.choose(rng).unwrap();
+        let b = *vars.choose(rng).unwrap();
+        let out = if rng.gen() {
+            circuit.add(a, b)?
+        } else {
+            circuit.mul(a, b)?
+        };
+        vars.push(out);
+    }
+    circuit.finalize_for_arithmetization()?;
+
+    Ok(circuit)
+}
+
+/// Proves the given circuit, recording the transcript of the prover.
+///
+/// The proof is then verified using the `SolidityTranscript`, to check
+/// that the recording did not alter the transcript.
+fn prove_and_record<R: CryptoRng + RngCore>(
+    circuit: &PlonkCircuit<ScalarField>,
+    public_inputs: &[ScalarField],
+    rng: &mut R,
+) -> Result<(VerifyingKey<SystemCurve>, PlonkProof, Vec<TranscriptRound>)> {
+    let (pk, jf_vkey) = PlonkKzgSnark::<SystemCurve>::preprocess(&SYSTEM_SRS, circuit)?;
+
+    take_recorded_rounds();
+    let jf_proof =
+        PlonkKzgSnark::<SystemCurve>::prove::<_, _, RecordingTranscript>(rng, circuit, &pk, None)?;
+    let rounds = take_recorded_rounds();
+
+    PlonkKzgSnark::<SystemCurve>::verify::<SolidityTranscript>(
+        &jf_vkey,
+        public_inputs,
+        &jf_proof,
+        None,
+    )?;
+
+    Ok((jf_vkey, jf_proof, rounds))
+}
+
+/// Serializes a value in the compressed arkworks format, as absorbed by the Jellyfish transcript
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, SerializationError> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Serializes a value using `postcard`, and hex-encodes the result
+fn to_postcard_hex<T: Serialize>(value: &T) -> Result<String> {
+    let bytes = postcard::to_allocvec(value).map_err(|e| eyre!(e.to_string()))?;
+    Ok(hex::encode(bytes))
+}
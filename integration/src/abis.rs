@@ -5,38 +5,84 @@ use ethers::prelude::abigen;
 abigen!(
     DarkpoolTestContract,
     r#"[
-        function initialize(address memory darkpool_core_address, address memory verifier_address, address memory vkeys_address, address memory merkle_address, address memory transfer_executor_address, address memory permit2_address, uint256 memory protocol_fee, uint256[2] protocol_public_encryption_key) external
+        function initialize(address memory darkpool_core_address, address memory verifier_address, address memory vkeys_address, address memory merkle_address, address memory transfer_executor_address, address memory permit2_address, uint256 memory protocol_fee, uint256[2] protocol_public_encryption_key, uint256 memory address_change_delay) external
 
         function owner() external view returns (address)
+        function pendingOwner() external view returns (address)
         function transferOwnership(address memory new_owner) external
+        function acceptOwnership() external
+        function cancelOwnershipTransfer() external
+        function renounceOwnership() external
+
+        function hasRole(uint8 memory role, address memory account) external view returns (bool)
+        function grantRole(uint8 memory role, address memory account) external
+        function revokeRole(uint8 memory role, address memory account) external
 
         function paused() external view returns (bool)
         function pause() external
         function unpause() external
+        function getPausedOperations() external view returns (uint8)
+        function isOperationPaused(uint8 memory operation) external view returns (bool)
+        function setOperationPaused(uint8 memory operation, bool memory paused) external
 
         function setFee(uint256 memory new_fee) external
-        function setDarkpoolCoreAddress(address memory darkpool_core_address) external
-        function setVerifierAddress(address memory verifier_address) external
-        function setVkeysAddress(address memory vkeys_address) external
-        function setMerkleAddress(address memory merkle_address) external
-        function setTransferExecutorAddress(address memory transfer_executor_address) external
+        function setFeeOverride(address memory mint, uint256 memory new_fee) external
+        function removeFeeOverride(address memory mint) external
+        function setPublicEncryptionKey(uint256[2] memory new_public_encryption_key) external
+        function setPubkeyGracePeriod(uint256 memory new_grace_period) external
+        function setRootHistoryParams(uint64 memory max_root_history_len, uint256 memory root_expiry_period) external
+        function migrateRootHistory(uint256 memory legacy_root_expiry_block) external
+
+        function getAddressChangeDelay() external view returns (uint256)
+        function getPendingAddressChange(uint8 memory address_selector) external view returns (address, uint256)
+        function scheduleAddressChange(uint8 memory address_selector, address memory new_address) external
+        function executeAddressChange(uint8 memory address_selector) external
+        function cancelAddressChange(uint8 memory address_selector) external
+        function getPendingAddressChangeDelay() external view returns (uint256, uint256)
+        function scheduleAddressChangeDelay(uint256 memory new_delay) external
+        function executeAddressChangeDelay() external
+        function cancelAddressChangeDelay() external
+
+        function getVkeyHash(uint8 memory circuit_id) external view returns (bytes32)
+        function registerVkeyHash(uint8 memory circuit_id, bytes memory vkeys) external
+        function retireVkeyHash(uint8 memory circuit_id) external
 
         function isNullifierSpent(uint256 memory nullifier) external view returns (bool)
 
         function getRoot() external view returns (uint256)
+        function getTreeId() external view returns (uint64)
+        function rootInHistory(uint256 memory root) external view returns (bool)
+        function getRootHistoryParams() external view returns (uint64, uint256, uint256)
+        function rootInfo(uint256 memory root) external view returns (uint64, uint128, uint256)
         function getFee() external view returns (uint256)
+        function getFeeForMint(address memory mint) external view returns (uint256)
         function getPubkey() external view returns (uint256[2])
+        function getPubkeyHistory() external view returns ((uint256,uint256,uint256,uint256)[])
+        function getPubkeyGracePeriod() external view returns (uint256)
 
         function newWallet(bytes memory proof, bytes memory valid_wallet_create_statement_bytes) external
         function updateWallet(bytes memory proof, bytes memory valid_wallet_update_statement_bytes, bytes memory wallet_commitment_signature, bytes memory transfer_aux_data) external
         function processMatchSettle(bytes memory party_0_match_payload, bytes memory party_1_match_payload, bytes memory valid_match_settle_statement, bytes memory match_proofs, bytes memory match_linking_proofs) external
+        function processMatchSettleBatch(bytes memory party_0_match_payloads, bytes memory party_1_match_payloads, bytes memory valid_match_settle_statements, bytes memory match_proofs, bytes memory match_linking_proofs) external
         function settleOnlineRelayerFee(bytes memory proof, bytes memory valid_relayer_fee_settlement_statement, bytes memory relayer_wallet_commitment_signature) external
         function settleOfflineFee(bytes memory proof, bytes memory valid_offline_fee_settlement_statement) external
         function redeemFee(bytes memory proof, bytes memory valid_fee_redemption_statement, bytes memory recipient_wallet_commitment_signature) external
+        function newWalletWithVkeys(bytes memory proof, bytes memory valid_wallet_create_statement_bytes, bytes memory vkeys, uint64 vkey_version) external
+        function updateWalletWithVkeys(bytes memory proof, bytes memory valid_wallet_update_statement_bytes, bytes memory wallet_commitment_signature, bytes memory transfer_aux_data, bytes memory vkeys, uint64 vkey_version) external
+        function processMatchSettleWithVkeys(bytes memory party_0_match_payload, bytes memory party_1_match_payload, bytes memory valid_match_settle_statement, bytes memory match_proofs, bytes memory match_linking_proofs, bytes memory vkeys, uint64 vkey_version) external
+        function processMatchSettleBatchWithVkeys(bytes memory party_0_match_payloads, bytes memory party_1_match_payloads, bytes memory valid_match_settle_statements, bytes memory match_proofs, bytes memory match_linking_proofs, bytes memory vkeys, uint64 vkey_version) external
+        function settleOnlineRelayerFeeWithVkeys(bytes memory proof, bytes memory valid_relayer_fee_settlement_statement, bytes memory relayer_wallet_commitment_signature, bytes memory vkeys, uint64 vkey_version) external
+        function settleOfflineFeeWithVkeys(bytes memory proof, bytes memory valid_offline_fee_settlement_statement, bytes memory vkeys, uint64 vkey_version) external
+        function redeemFeeWithVkeys(bytes memory proof, bytes memory valid_fee_redemption_statement, bytes memory recipient_wallet_commitment_signature, bytes memory vkeys, uint64 vkey_version) external
+        function multicall(bytes[] memory calls) external
 
         function markNullifierSpent(uint256 memory nullifier) external
         function isImplementationUpgraded(uint8 memory address_selector) external view returns (bool)
         function clearMerkle() external
+        function clearPubkeyHistory() external
+
+        event OperationPaused(uint8 indexed operation)
+        event OperationUnpaused(uint8 indexed operation)
     ]"#
 );
 
@@ -48,13 +94,34 @@ abigen!(
     ]"#
 );
 
+abigen!(
+    VkeyRegistryContract,
+    r#"[
+        function getVkey(uint8 circuit_id, uint64 version) external view returns (bytes)
+        function getVersionCutoffBlock(uint64 version) external view returns (uint256)
+        function deprecateVersion(uint64 version, uint256 cutoff_block) external
+        function uploadVkeyChunk(uint8 circuit_id, uint64 version, uint256 offset, bytes chunk) external
+        function finalizeVkey(uint8 circuit_id, uint64 version) external
+    ]"#
+);
+
 abigen!(
     MerkleContract,
     r#"[
         function init() external
         function root() external view returns (uint256)
         function rootInHistory(uint256 root) external view returns (bool)
+        function rootHistoryParams() external view returns (uint64, uint256, uint256)
+        function setRootHistoryParams(uint64 max_root_history_len, uint256 root_expiry_period) external
         function insertSharesCommitment(uint256[] shares) external
+        function insertBatch(uint256[] leaves) external
+        function treeId() external view returns (uint64)
+        function rootInfo(uint256 root) external view returns (uint64, uint128, uint256)
+
+        event MerkleOpeningNode(uint8 indexed height, uint128 indexed index, uint256 indexed new_value, uint64 tree_id)
+        event MerkleInsertion(uint128 indexed index, uint256 indexed value, uint64 indexed tree_id)
+        event RootUpdated(uint256 indexed root, uint64 indexed tree_id, uint128 next_index, uint256 block_number)
+        event MerkleTreeRolledOver(uint64 indexed tree_id)
     ]"#
 );
 
@@ -62,7 +129,9 @@ abigen!(
     VerifierContract,
     r#"[
         function verify(bytes memory verification_bundle) external view returns (bool)
+        function verifyBatch(bytes memory batch_verification_bundle) external view returns (bool)
         function verifyMatch(bytes memory match_bundle) external view returns (bool)
+        function verifyMatchBatch(bytes memory match_batch_bundle) external view returns (bool)
     ]"#
 );
 
@@ -10,29 +10,65 @@ pub(crate) const DEFAULT_DEVNET_PKEY: &str =
 /// The name of the `transfer_ownership` method on the Darkpool contract
 pub(crate) const TRANSFER_OWNERSHIP_METHOD_NAME: &str = "transferOwnership";
 
+/// The name of the `cancel_ownership_transfer` method on the Darkpool contract
+pub(crate) const CANCEL_OWNERSHIP_TRANSFER_METHOD_NAME: &str = "cancelOwnershipTransfer";
+
+/// The name of the `grant_role` method on the Darkpool contract
+pub(crate) const GRANT_ROLE_METHOD_NAME: &str = "grantRole";
+
+/// The name of the `revoke_role` method on the Darkpool contract
+pub(crate) const REVOKE_ROLE_METHOD_NAME: &str = "revokeRole";
+
 /// The name of the `pause` method on the Darkpool contract
 pub(crate) const PAUSE_METHOD_NAME: &str = "pause";
 
 /// The name of the `unpause` method on the Darkpool contract
 pub(crate) const UNPAUSE_METHOD_NAME: &str = "unpause";
 
+/// The name of the `set_operation_paused` method on the Darkpool contract
+pub(crate) const SET_OPERATION_PAUSED_METHOD_NAME: &str = "setOperationPaused";
+
 /// The name of the `set_fee` method on the Darkpool contract
 pub(crate) const SET_FEE_METHOD_NAME: &str = "setFee";
 
-/// The name of the `set_darkpool_core_address` method on the Darkpool contract
-pub(crate) const SET_DARKPOOL_CORE_ADDRESS_METHOD_NAME: &str = "setDarkpoolCoreAddress";
+/// The name of the `set_fee_override` method on the Darkpool contract
+pub(crate) const SET_FEE_OVERRIDE_METHOD_NAME: &str = "setFeeOverride";
+
+/// The name of the `set_pubkey_grace_period` method on the Darkpool contract
+pub(crate) const SET_PUBKEY_GRACE_PERIOD_METHOD_NAME: &str = "setPubkeyGracePeriod";
+
+/// The name of the `schedule_address_change` method on the Darkpool contract
+pub(crate) const SCHEDULE_ADDRESS_CHANGE_METHOD_NAME: &str = "scheduleAddressChange";
 
-/// The name of the `set_verifier_address` method on the Darkpool contract
-pub(crate) const SET_VERIFIER_ADDRESS_METHOD_NAME: &str = "setVerifierAddress";
+/// The name of the `cancel_address_change` method on the Darkpool contract
+pub(crate) const CANCEL_ADDRESS_CHANGE_METHOD_NAME: &str = "cancelAddressChange";
 
-/// The name of the `set_vkeys_address` method on the Darkpool contract
-pub(crate) const SET_VKEYS_ADDRESS_METHOD_NAME: &str = "setVkeysAddress";
+/// The name of the `schedule_address_change_delay` method on the Darkpool contract
+pub(crate) const SCHEDULE_ADDRESS_CHANGE_DELAY_METHOD_NAME: &str = "scheduleAddressChangeDelay";
 
-/// The name of the `set_merkle_address` method on the Darkpool contract
-pub(crate) const SET_MERKLE_ADDRESS_METHOD_NAME: &str = "setMerkleAddress";
+/// The name of the `cancel_address_change_delay` method on the Darkpool contract
+pub(crate) const CANCEL_ADDRESS_CHANGE_DELAY_METHOD_NAME: &str = "cancelAddressChangeDelay";
 
-/// The name of the `set_transfer_executor_address` method on the Darkpool contract
-pub(crate) const SET_TRANSFER_EXECUTOR_ADDRESS_METHOD_NAME: &str = "setTransferExecutorAddress";
+/// The name of the `set_root_history_params` method on the Darkpool contract
+pub(crate) const SET_ROOT_HISTORY_PARAMS_METHOD_NAME: &str = "setRootHistoryParams";
+
+/// The name of the `migrate_root_history` method on the Darkpool contract
+pub(crate) const MIGRATE_ROOT_HISTORY_METHOD_NAME: &str = "migrateRootHistory";
+
+/// The name of the `register_vkey_hash` method on the Darkpool contract
+pub(crate) const REGISTER_VKEY_HASH_METHOD_NAME: &str = "registerVkeyHash";
+
+/// The name of the `retire_vkey_hash` method on the Darkpool contract
+pub(crate) const RETIRE_VKEY_HASH_METHOD_NAME: &str = "retireVkeyHash";
 
 /// The name of the domain separator for Permit2 typed data
 pub(crate) const PERMIT2_EIP712_DOMAIN_NAME: &str = "Permit2";
+
+/// The number of independent proofs verified in a single batch in the tests
+pub(crate) const NUM_BATCHED_PROOFS: usize = 3;
+
+/// The number of matches settled in a single batch in the tests
+pub(crate) const NUM_BATCHED_MATCHES: usize = 2;
+
+/// The number of blocks for which a rotated-out protocol key is accepted in the tests
+pub(crate) const TEST_PUBKEY_GRACE_PERIOD_BLOCKS: u64 = 100;
@@ -14,6 +14,7 @@ use scripts::{
         DARKPOOL_PROXY_CONTRACT_KEY, MERKLE_CONTRACT_KEY, PERMIT2_CONTRACT_KEY,
         PRECOMPILE_TEST_CONTRACT_KEY, TEST_ERC20_TICKER, TEST_UPGRADE_TARGET_CONTRACT_KEY,
         TRANSFER_EXECUTOR_CONTRACT_KEY, VERIFIER_CONTRACT_KEY, VKEYS_CONTRACT_KEY,
+        VKEY_REGISTRY_CONTRACT_KEY,
     },
     utils::{parse_addr_from_deployments_file, setup_client, LocalWalletHttpClient},
 };
@@ -44,6 +45,8 @@ pub struct TestArgs {
     pub verifier_address: Address,
     /// The address of the verification keys contract
     pub vkeys_address: Address,
+    /// The address of the vkey registry contract
+    pub vkey_registry_address: Address,
     /// The address of the permit2 contract
     pub permit2_address: Address,
     /// The address of the transfer executor contract
@@ -90,6 +93,10 @@ impl From<Cli> for TestArgs {
         let vkeys_address =
             parse_addr_from_deployments_file(&value.deployments_file, VKEYS_CONTRACT_KEY).unwrap();
 
+        let vkey_registry_address =
+            parse_addr_from_deployments_file(&value.deployments_file, VKEY_REGISTRY_CONTRACT_KEY)
+                .unwrap();
+
         let permit2_address =
             parse_addr_from_deployments_file(&value.deployments_file, PERMIT2_CONTRACT_KEY)
                 .unwrap();
@@ -122,6 +129,7 @@ impl From<Cli> for TestArgs {
             merkle_address,
             verifier_address,
             vkeys_address,
+            vkey_registry_address,
             permit2_address,
             transfer_executor_address,
             test_erc20_address,
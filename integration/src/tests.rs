@@ -4,57 +4,76 @@ use alloy_primitives::Address as AlloyAddress;
 use ark_ec::AffineRepr;
 use ark_ff::One;
 use ark_std::UniformRand;
-use circuit_types::fixed_point::FixedPoint;
+use circuit_types::{fixed_point::FixedPoint, traits::SingleProverCircuit};
 use constants::Scalar;
 use contracts_common::{
     constants::{
-        DARKPOOL_CORE_ADDRESS_SELECTOR, MERKLE_ADDRESS_SELECTOR, TEST_MERKLE_HEIGHT,
-        TRANSFER_EXECUTOR_ADDRESS_SELECTOR, VERIFIER_ADDRESS_SELECTOR, VKEYS_ADDRESS_SELECTOR,
+        DARKPOOL_CORE_ADDRESS_SELECTOR, MERKLE_ADDRESS_SELECTOR, NEW_WALLET_OPERATION, PAUSER_ROLE,
+        PROCESS_MATCH_SETTLE_OPERATION, TEST_MERKLE_HEIGHT, TRANSFER_EXECUTOR_ADDRESS_SELECTOR,
+        UPDATE_WALLET_DEPOSIT_OPERATION, UPDATE_WALLET_WITHDRAWAL_OPERATION,
+        VALID_WALLET_CREATE_CIRCUIT_ID, VERIFIER_ADDRESS_SELECTOR, VKEYS_ADDRESS_SELECTOR,
+        VKEY_REGISTRY_ADDRESS_SELECTOR,
     },
     custom_serde::statement_to_public_inputs,
     serde_def_types::{SerdeG1Affine, SerdeG2Affine, SerdeScalarField},
-    types::{G1Affine, G2Affine, ScalarField},
+    types::{G1Affine, G2Affine, G2BaseField, ScalarField},
+};
+use contracts_core::{
+    crypto::{ecdsa::pubkey_to_address, poseidon::compute_poseidon_hash},
+    native::NativeHasher,
 };
-use contracts_core::crypto::{ecdsa::pubkey_to_address, poseidon::compute_poseidon_hash};
 use contracts_utils::{
-    crypto::{hash_and_sign_message, random_keypair, NativeHasher},
+    conversion::to_contract_vkey,
+    crypto::{hash_and_sign_message, random_keypair},
     merkle::new_ark_merkle_tree,
-    proof_system::test_data::{
-        dummy_circuit_type, gen_new_wallet_data, gen_process_match_settle_data,
-        gen_redeem_fee_data, gen_settle_offline_fee_data, gen_settle_online_relayer_fee_data,
-        gen_update_wallet_data, gen_verification_bundle, generate_match_bundle,
-        mutate_random_linking_proof, mutate_random_plonk_proof, random_scalars,
+    proof_system::{
+        dummy_renegade_circuits::DummyValidWalletCreate,
+        test_data::{
+            dummy_circuit_type, gen_new_wallet_data, gen_process_match_settle_data,
+            gen_redeem_fee_data, gen_settle_offline_fee_data, gen_settle_online_relayer_fee_data,
+            gen_update_wallet_data, gen_verification_bundle, generate_match_bundle,
+            mutate_random_linking_proof, mutate_random_plonk_proof, random_scalars,
+        },
     },
 };
 use ethers::{
     abi::Address,
+    contract::parse_log,
     providers::Middleware,
     types::{Bytes, TransactionRequest, U256},
     utils::{keccak256, parse_ether},
 };
 use eyre::{eyre, Result};
 use rand::{thread_rng, Rng, RngCore};
-use scripts::constants::TEST_FUNDING_AMOUNT;
+use scripts::constants::{TEST_ADDRESS_CHANGE_DELAY, TEST_FUNDING_AMOUNT, TEST_VKEY_VERSION};
 use test_helpers::integration_test_async;
 
 use crate::{
     abis::{
         DarkpoolProxyAdminContract, DarkpoolTestContract, DummyErc20Contract,
-        DummyUpgradeTargetContract, MerkleContract, PrecompileTestContract,
-        TransferExecutorContract, VerifierContract,
+        DummyUpgradeTargetContract, MerkleContract, MerkleInsertionFilter,
+        MerkleTreeRolledOverFilter, OperationUnpausedFilter, PrecompileTestContract,
+        RootUpdatedFilter, TransferExecutorContract, VerifierContract, VkeyRegistryContract,
     },
     constants::{
-        PAUSE_METHOD_NAME, SET_DARKPOOL_CORE_ADDRESS_METHOD_NAME, SET_FEE_METHOD_NAME,
-        SET_MERKLE_ADDRESS_METHOD_NAME, SET_TRANSFER_EXECUTOR_ADDRESS_METHOD_NAME,
-        SET_VERIFIER_ADDRESS_METHOD_NAME, SET_VKEYS_ADDRESS_METHOD_NAME,
+        CANCEL_ADDRESS_CHANGE_DELAY_METHOD_NAME, CANCEL_ADDRESS_CHANGE_METHOD_NAME,
+        CANCEL_OWNERSHIP_TRANSFER_METHOD_NAME, GRANT_ROLE_METHOD_NAME,
+        MIGRATE_ROOT_HISTORY_METHOD_NAME, NUM_BATCHED_MATCHES, NUM_BATCHED_PROOFS,
+        PAUSE_METHOD_NAME, REGISTER_VKEY_HASH_METHOD_NAME, RETIRE_VKEY_HASH_METHOD_NAME,
+        REVOKE_ROLE_METHOD_NAME, SCHEDULE_ADDRESS_CHANGE_DELAY_METHOD_NAME,
+        SCHEDULE_ADDRESS_CHANGE_METHOD_NAME, SET_FEE_METHOD_NAME, SET_FEE_OVERRIDE_METHOD_NAME,
+        SET_OPERATION_PAUSED_METHOD_NAME, SET_PUBKEY_GRACE_PERIOD_METHOD_NAME,
+        SET_ROOT_HISTORY_PARAMS_METHOD_NAME, TEST_PUBKEY_GRACE_PERIOD_BLOCKS,
         TRANSFER_OWNERSHIP_METHOD_NAME, UNPAUSE_METHOD_NAME,
     },
     utils::{
         assert_all_revert, assert_all_succeed, assert_only_owner, dummy_erc20_deposit,
         dummy_erc20_withdrawal, execute_transfer_and_get_balances, gen_transfer_aux_data,
-        get_protocol_pubkey, insert_shares_and_get_root, scalar_to_u256,
-        serialize_match_verification_bundle, serialize_to_calldata, serialize_verification_bundle,
-        setup_dummy_client, u256_to_scalar,
+        get_protocol_pubkey, insert_shares_and_get_root, merkle_tree_events, scalar_to_u256,
+        schedule_and_execute_address_change, serialize_batch_verification_bundle,
+        serialize_match_batch_verification_bundle, serialize_match_verification_bundle,
+        serialize_to_calldata, serialize_verification_bundle, setup_dummy_client, u256_to_scalar,
+        wait_for_address_change_delay,
     },
     TestArgs,
 };
@@ -195,18 +214,432 @@ async fn test_merkle(test_args: TestArgs) -> Result<()> {
         assert_eq!(ark_merkle.root(), contract_root, "Merkle root incorrect");
     }
 
+    // Once the tree is full, the next insertion starts a new tree
+    let full_tree_root = contract.root().call().await?;
+    let leaf = ScalarField::rand(&mut rng);
+    contract
+        .insert_shares_commitment(vec![scalar_to_u256(leaf)])
+        .send()
+        .await?
+        .await?;
+
+    let mut new_ark_merkle = new_ark_merkle_tree(TEST_MERKLE_HEIGHT);
+    new_ark_merkle
+        .update(0, &compute_poseidon_hash(&[leaf]))
+        .map_err(|e| eyre!("{}", e))?;
+    let contract_root = u256_to_scalar(contract.root().call().await?)?;
+
+    assert_eq!(
+        new_ark_merkle.root(),
+        contract_root,
+        "Merkle root incorrect after rollover"
+    );
+    assert_eq!(contract.tree_id().call().await?, 1, "Tree not rolled over");
     assert!(
+        contract.root_in_history(full_tree_root).call().await?,
+        "Full tree root not in history"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_merkle);
+
+/// Test that batch insertion into the Merkle tree matches the reference tree,
+/// and emits the same events as inserting each leaf individually
+async fn test_merkle_insert_batch(test_args: TestArgs) -> Result<()> {
+    let contract = MerkleContract::new(test_args.merkle_address, test_args.client);
+    let mut ark_merkle = new_ark_merkle_tree(TEST_MERKLE_HEIGHT);
+    let mut rng = thread_rng();
+
+    let num_leaves = 2_usize.pow(TEST_MERKLE_HEIGHT as u32);
+    let shares = random_scalars(num_leaves, &mut rng);
+    let leaves: Vec<ScalarField> = shares
+        .iter()
+        .map(|share| compute_poseidon_hash(&[*share]))
+        .collect();
+
+    // Insert the leaves one at a time, collecting the emitted events
+    contract.init().send().await?.await?;
+    let mut sequential_logs = Vec::new();
+    for share in &shares {
+        let receipt = contract
+            .insert_shares_commitment(vec![scalar_to_u256(*share)])
+            .send()
+            .await?
+            .await?
+            .ok_or_else(|| eyre!("no receipt for sequential insertion"))?;
+        sequential_logs.extend(receipt.logs);
+    }
+
+    // Insert the same leaves in batches of random sizes, checking the root against
+    // the reference tree after each batch
+    contract.init().send().await?.await?;
+    let mut batch_logs = Vec::new();
+    let mut next_index = 0;
+    while next_index < num_leaves {
+        let batch_size = rng.gen_range(1..=num_leaves - next_index);
+        let batch = &leaves[next_index..next_index + batch_size];
+
+        let receipt = contract
+            .insert_batch(batch.iter().copied().map(scalar_to_u256).collect())
+            .send()
+            .await?
+            .await?
+            .ok_or_else(|| eyre!("no receipt for batch insertion"))?;
+        batch_logs.extend(receipt.logs);
+
+        for (i, leaf) in batch.iter().enumerate() {
+            ark_merkle
+                .update(next_index + i, leaf)
+                .map_err(|e| eyre!("{}", e))?;
+        }
+        next_index += batch_size;
+
+        let contract_root = contract.root().call().await?;
+        assert_eq!(
+            ark_merkle.root(),
+            u256_to_scalar(contract_root)?,
+            "Merkle root incorrect after batch insertion"
+        );
+        assert!(
+            contract.root_in_history(contract_root).call().await?,
+            "Root not in history after batch insertion"
+        );
+    }
+
+    // Only the final root of a batch is stored, so we compare only the insertion & opening events
+    assert_eq!(
+        merkle_tree_events(sequential_logs),
+        merkle_tree_events(batch_logs),
+        "Batch insertion events differ from sequential insertion events"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_merkle_insert_batch);
+
+/// Test that insertions into a full Merkle tree roll over into a new tree,
+/// including batches spanning multiple trees, and that the roots of
+/// previous trees remain in the history
+async fn test_merkle_rollover(test_args: TestArgs) -> Result<()> {
+    let contract = MerkleContract::new(test_args.merkle_address, test_args.client);
+    let mut rng = thread_rng();
+
+    let tree_capacity = 2_usize.pow(TEST_MERKLE_HEIGHT as u32);
+    let num_trees = 3;
+    let leaves = random_scalars(tree_capacity * (num_trees - 1) + 1, &mut rng);
+    let mut ark_merkles: Vec<_> = (0..num_trees)
+        .map(|_| new_ark_merkle_tree(TEST_MERKLE_HEIGHT))
+        .collect();
+
+    contract.init().send().await?.await?;
+
+    let mut logs = Vec::new();
+    let mut roots = Vec::new();
+    let mut next_index = 0;
+    while next_index < leaves.len() {
+        let batch_size = rng.gen_range(1..=(leaves.len() - next_index).min(tree_capacity + 1));
+        let batch = &leaves[next_index..next_index + batch_size];
+
+        let receipt = contract
+            .insert_batch(batch.iter().copied().map(scalar_to_u256).collect())
+            .send()
+            .await?
+            .await?
+            .ok_or_else(|| eyre!("no receipt for batch insertion"))?;
+        logs.extend(receipt.logs);
+
+        for (i, leaf) in batch.iter().enumerate() {
+            let index = next_index + i;
+            ark_merkles[index / tree_capacity]
+                .update(index % tree_capacity, leaf)
+                .map_err(|e| eyre!("{}", e))?;
+        }
+        next_index += batch_size;
+
+        // The active tree is the one containing the last inserted leaf
+        let tree_id = (next_index - 1) / tree_capacity;
+        let root = contract.root().call().await?;
+        assert_eq!(
+            ark_merkles[tree_id].root(),
+            u256_to_scalar(root)?,
+            "Merkle root incorrect after batch insertion"
+        );
+        assert_eq!(
+            contract.tree_id().call().await?,
+            tree_id as u64,
+            "Incorrect active tree"
+        );
+
+        let (root_tree_id, ..) = contract.root_info(root).call().await?;
+        assert_eq!(root_tree_id, tree_id as u64, "Incorrect root tree");
+        roots.push(root);
+    }
+
+    for root in roots {
+        assert!(
+            contract.root_in_history(root).call().await?,
+            "Root of previous tree not in history"
+        );
+    }
+
+    let insertion_events = logs
+        .iter()
+        .cloned()
+        .filter_map(|log| parse_log::<MerkleInsertionFilter>(log).ok())
+        .collect::<Vec<_>>();
+    let expected_insertion_events = leaves
+        .iter()
+        .enumerate()
+        .map(|(index, leaf)| MerkleInsertionFilter {
+            index: (index % tree_capacity) as u128,
+            value: scalar_to_u256(*leaf),
+            tree_id: (index / tree_capacity) as u64,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        insertion_events, expected_insertion_events,
+        "Incorrect insertion events"
+    );
+
+    let rollover_events = logs
+        .into_iter()
+        .filter_map(|log| parse_log::<MerkleTreeRolledOverFilter>(log).ok())
+        .collect::<Vec<_>>();
+    let expected_rollover_events = (1..num_trees as u64)
+        .map(|tree_id| MerkleTreeRolledOverFilter { tree_id })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rollover_events, expected_rollover_events,
+        "Incorrect rollover events"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_merkle_rollover);
+
+/// Test that the Merkle root history expiry policy rejects expired roots
+async fn test_merkle_root_history_expiry(test_args: TestArgs) -> Result<()> {
+    let contract = MerkleContract::new(test_args.merkle_address, test_args.client);
+    let mut rng = thread_rng();
+
+    contract.init().send().await?.await?;
+    let mut roots = vec![contract.root().call().await?];
+    for _ in 0..3 {
         contract
             .insert_shares_commitment(vec![scalar_to_u256(ScalarField::rand(&mut rng))])
             .send()
+            .await?
+            .await?;
+        roots.push(contract.root().call().await?);
+    }
+
+    // Accept only the two most recent roots
+    contract
+        .set_root_history_params(2, U256::zero())
+        .send()
+        .await?
+        .await?;
+
+    for root in &roots[2..] {
+        assert!(
+            contract.root_in_history(*root).call().await?,
+            "Recent root not in history"
+        );
+    }
+    for root in &roots[..2] {
+        assert!(
+            contract.root_in_history(*root).call().await.is_err(),
+            "Expired root accepted"
+        );
+    }
+
+    // Accept roots for a single block after they are stored.
+    // Each transaction is mined in its own block, so only the current root is accepted.
+    contract
+        .set_root_history_params(0, U256::one())
+        .send()
+        .await?
+        .await?;
+
+    assert!(
+        contract.root_in_history(roots[3]).call().await?,
+        "Current root not in history"
+    );
+    assert!(
+        contract.root_in_history(roots[2]).call().await.is_err(),
+        "Expired root accepted"
+    );
+
+    // Roots which were never stored are not in the history, rather than expired
+    let unknown_root = scalar_to_u256(ScalarField::rand(&mut rng));
+    assert!(
+        !contract.root_in_history(unknown_root).call().await?,
+        "Unknown root in history"
+    );
+
+    // Disable the expiry policy so as not to expire roots used in future tests
+    contract
+        .set_root_history_params(0, U256::zero())
+        .send()
+        .await?
+        .await?;
+
+    let (max_root_history_len, root_expiry_period, _) =
+        contract.root_history_params().call().await?;
+    assert_eq!(
+        max_root_history_len, 0,
+        "Root history length bound not disabled"
+    );
+    assert_eq!(
+        root_expiry_period,
+        U256::zero(),
+        "Root expiry period not disabled"
+    );
+
+    for root in &roots {
+        assert!(
+            contract.root_in_history(*root).call().await?,
+            "Root not in history after disabling expiry"
+        );
+    }
+
+    Ok(())
+}
+integration_test_async!(test_merkle_root_history_expiry);
+
+/// Test that the final root of a retired tree does not expire,
+/// while the other roots of the retired tree do
+async fn test_merkle_final_root_expiry(test_args: TestArgs) -> Result<()> {
+    let contract = MerkleContract::new(test_args.merkle_address, test_args.client);
+    let mut rng = thread_rng();
+
+    let tree_capacity = 2_usize.pow(TEST_MERKLE_HEIGHT as u32);
+    let leaves = random_scalars(tree_capacity + 1, &mut rng);
+
+    contract.init().send().await?.await?;
+
+    // Fill the tree, so that its root is final, then roll over into a new tree
+    contract
+        .insert_batch(
+            leaves[..tree_capacity - 1]
+                .iter()
+                .copied()
+                .map(scalar_to_u256)
+                .collect(),
+        )
+        .send()
+        .await?
+        .await?;
+    let intermediate_root = contract.root().call().await?;
+
+    contract
+        .insert_shares_commitment(vec![scalar_to_u256(leaves[tree_capacity - 1])])
+        .send()
+        .await?
+        .await?;
+    let final_root = contract.root().call().await?;
+
+    contract
+        .insert_shares_commitment(vec![scalar_to_u256(leaves[tree_capacity])])
+        .send()
+        .await?
+        .await?;
+    assert_eq!(contract.tree_id().call().await?, 1, "Tree not rolled over");
+
+    // Accept roots for a single block after they are stored.
+    // Each transaction is mined in its own block, so both roots of the retired tree have
+    // passed the expiry period.
+    contract
+        .set_root_history_params(0, U256::one())
+        .send()
+        .await?
+        .await?;
+
+    assert!(
+        contract.root_in_history(final_root).call().await?,
+        "Final root of retired tree not in history"
+    );
+    assert!(
+        contract
+            .root_in_history(intermediate_root)
+            .call()
             .await
             .is_err(),
-        "Inserted more leaves than allowed"
+        "Expired root accepted"
     );
 
+    // Disable the expiry policy so as not to expire roots used in future tests
+    contract
+        .set_root_history_params(0, U256::zero())
+        .send()
+        .await?
+        .await?;
+
     Ok(())
 }
-integration_test_async!(test_merkle);
+integration_test_async!(test_merkle_final_root_expiry);
+
+/// Test that the metadata of each root is recorded & emitted when it is stored
+async fn test_merkle_root_info(test_args: TestArgs) -> Result<()> {
+    let contract = MerkleContract::new(test_args.merkle_address, test_args.client);
+    let mut rng = thread_rng();
+
+    contract.init().send().await?.await?;
+
+    let batch_sizes = [1, 2];
+    let mut next_index = 0;
+    for batch_size in batch_sizes {
+        let leaves = random_scalars(batch_size, &mut rng);
+        let receipt = contract
+            .insert_batch(leaves.into_iter().map(scalar_to_u256).collect())
+            .send()
+            .await?
+            .await?
+            .ok_or_else(|| eyre!("no receipt for batch insertion"))?;
+        next_index += batch_size as u128;
+
+        let root = contract.root().call().await?;
+        let block_number = U256::from(
+            receipt
+                .block_number
+                .ok_or_else(|| eyre!("no block number in receipt"))?
+                .as_u64(),
+        );
+
+        let (root_tree_id, root_next_index, root_block_number) =
+            contract.root_info(root).call().await?;
+        assert_eq!(root_tree_id, 0, "Incorrect root tree");
+        assert_eq!(root_next_index, next_index, "Incorrect root next index");
+        assert_eq!(root_block_number, block_number, "Incorrect root block");
+
+        let root_updated_events = receipt
+            .logs
+            .into_iter()
+            .filter_map(|log| parse_log::<RootUpdatedFilter>(log).ok())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            root_updated_events,
+            vec![RootUpdatedFilter {
+                root,
+                tree_id: 0,
+                next_index,
+                block_number,
+            }],
+            "Incorrect root update events"
+        );
+    }
+
+    let unknown_root = scalar_to_u256(ScalarField::rand(&mut rng));
+    assert_eq!(
+        contract.root_info(unknown_root).call().await?,
+        (0, 0, U256::zero()),
+        "Unknown root has metadata"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_merkle_root_info);
 
 /// Test the verifier functionality
 async fn test_verifier(test_args: TestArgs) -> Result<()> {
@@ -232,6 +665,42 @@ async fn test_verifier(test_args: TestArgs) -> Result<()> {
     let unsuccessful_res = contract.verify(verification_bundle_calldata).call().await?;
     assert!(!unsuccessful_res, "Invalid proof verified");
 
+    // Test valid batch verification of independent proofs
+
+    let mut vkeys = vec![];
+    let mut proofs = vec![];
+    let mut public_inputs_batch = vec![];
+    for _ in 0..NUM_BATCHED_PROOFS {
+        let (statement, proof, vkey) = gen_verification_bundle(&mut rng)?;
+        vkeys.push(vkey);
+        proofs.push(proof);
+        public_inputs_batch
+            .push(statement_to_public_inputs(&statement).map_err(|e| eyre!("{:?}", e))?);
+    }
+
+    let batch_verification_bundle_calldata =
+        serialize_batch_verification_bundle(&vkeys, &proofs, &public_inputs_batch)?;
+
+    let successful_res = contract
+        .verify_batch(batch_verification_bundle_calldata)
+        .call()
+        .await?;
+    assert!(successful_res, "Valid proof batch did not verify");
+
+    // Test invalid batch verification of independent proofs
+
+    let corrupted_proof = rng.gen_range(0..NUM_BATCHED_PROOFS);
+    proofs[corrupted_proof].z_bar += ScalarField::one();
+
+    let batch_verification_bundle_calldata =
+        serialize_batch_verification_bundle(&vkeys, &proofs, &public_inputs_batch)?;
+
+    let unsuccessful_res = contract
+        .verify_batch(batch_verification_bundle_calldata)
+        .call()
+        .await?;
+    assert!(!unsuccessful_res, "Invalid proof batch verified");
+
     // Test valid batch verification
 
     let (
@@ -280,6 +749,58 @@ async fn test_verifier(test_args: TestArgs) -> Result<()> {
         .await?;
     assert!(!unsuccessful_res, "Invalid match bundle verified");
 
+    // Test valid batch verification of multiple matches
+
+    let mut match_proofs_batch = vec![];
+    let mut match_public_inputs_batch = vec![];
+    let mut match_linking_proofs_batch = vec![];
+    for _ in 0..NUM_BATCHED_MATCHES {
+        let (_, match_proofs, match_public_inputs, _, match_linking_proofs, _) =
+            generate_match_bundle(&mut rng)?;
+
+        match_proofs_batch.push(match_proofs);
+        match_public_inputs_batch.push(match_public_inputs);
+        match_linking_proofs_batch.push(match_linking_proofs);
+    }
+
+    let match_batch_verification_bundle_calldata = serialize_match_batch_verification_bundle(
+        &match_vkeys,
+        &match_linking_vkeys,
+        &match_proofs_batch,
+        &match_public_inputs_batch,
+        &match_linking_proofs_batch,
+    )?;
+
+    let successful_res = contract
+        .verify_match_batch(match_batch_verification_bundle_calldata)
+        .call()
+        .await?;
+    assert!(successful_res, "Valid match batch did not verify");
+
+    // Test invalid batch verification of multiple matches
+
+    let corrupted_match = rng.gen_range(0..NUM_BATCHED_MATCHES);
+    let mutate_plonk_proof = rng.gen_bool(0.5);
+    if mutate_plonk_proof {
+        mutate_random_plonk_proof(&mut rng, &mut match_proofs_batch[corrupted_match]);
+    } else {
+        mutate_random_linking_proof(&mut rng, &mut match_linking_proofs_batch[corrupted_match]);
+    }
+
+    let match_batch_verification_bundle_calldata = serialize_match_batch_verification_bundle(
+        &match_vkeys,
+        &match_linking_vkeys,
+        &match_proofs_batch,
+        &match_public_inputs_batch,
+        &match_linking_proofs_batch,
+    )?;
+
+    let unsuccessful_res = contract
+        .verify_match_batch(match_batch_verification_bundle_calldata)
+        .call()
+        .await?;
+    assert!(!unsuccessful_res, "Invalid match batch verified");
+
     Ok(())
 }
 integration_test_async!(test_verifier);
@@ -366,43 +887,30 @@ async fn test_upgradeable(test_args: TestArgs) -> Result<()> {
 integration_test_async!(test_upgradeable);
 
 /// Test the upgradeability of the contracts the darkpool calls
-/// (verifier, vkeys, & Merkle)
+/// (darkpool core, verifier, vkeys, Merkle, & transfer executor)
 async fn test_implementation_address_setters(test_args: TestArgs) -> Result<()> {
     let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
 
-    for (method, address_selector, original_address) in [
+    for (address_selector, original_address) in [
         (
-            SET_DARKPOOL_CORE_ADDRESS_METHOD_NAME,
             DARKPOOL_CORE_ADDRESS_SELECTOR,
             test_args.darkpool_core_address,
         ),
+        (VERIFIER_ADDRESS_SELECTOR, test_args.verifier_address),
+        (VKEYS_ADDRESS_SELECTOR, test_args.vkeys_address),
+        (MERKLE_ADDRESS_SELECTOR, test_args.merkle_address),
         (
-            SET_VERIFIER_ADDRESS_METHOD_NAME,
-            VERIFIER_ADDRESS_SELECTOR,
-            test_args.verifier_address,
-        ),
-        (
-            SET_VKEYS_ADDRESS_METHOD_NAME,
-            VKEYS_ADDRESS_SELECTOR,
-            test_args.vkeys_address,
-        ),
-        (
-            SET_MERKLE_ADDRESS_METHOD_NAME,
-            MERKLE_ADDRESS_SELECTOR,
-            test_args.merkle_address,
-        ),
-        (
-            SET_TRANSFER_EXECUTOR_ADDRESS_METHOD_NAME,
             TRANSFER_EXECUTOR_ADDRESS_SELECTOR,
             test_args.transfer_executor_address,
         ),
     ] {
         // Set the new implementation address as the dummy upgrade target address
-        contract
-            .method::<Address, ()>(method, test_args.test_upgrade_target_address)?
-            .send()
-            .await?
-            .await?;
+        schedule_and_execute_address_change(
+            &contract,
+            address_selector,
+            test_args.test_upgrade_target_address,
+        )
+        .await?;
 
         // Check that the implementation address was set
         assert!(
@@ -414,11 +922,7 @@ async fn test_implementation_address_setters(test_args: TestArgs) -> Result<()>
         );
 
         // Set the implementation address back to the original address
-        contract
-            .method::<Address, ()>(method, original_address)?
-            .send()
-            .await?
-            .await?;
+        schedule_and_execute_address_change(&contract, address_selector, original_address).await?;
 
         // Check that the implementation address was unset
         assert!(
@@ -435,30 +939,186 @@ async fn test_implementation_address_setters(test_args: TestArgs) -> Result<()>
 }
 integration_test_async!(test_implementation_address_setters);
 
-/// Test the initialization of the darkpool
-async fn test_initializable(test_args: TestArgs) -> Result<()> {
-    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+/// Test the timelock on implementation address changes
+async fn test_address_change_timelock(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
 
-    let dummy_darkpool_core_address = Address::random();
-    let dummy_verifier_address = Address::random();
-    let dummy_vkeys_address = Address::random();
-    let dummy_merkle_address = Address::random();
-    let dummy_transfer_executor_address = Address::random();
-    let dummy_permit2_address = Address::random();
-    let dummy_protocol_fee = U256::from(1);
-    let dummy_protocol_public_encryption_key = [U256::from(1), U256::from(2)];
+    assert_eq!(
+        contract.get_address_change_delay().call().await?,
+        U256::from(TEST_ADDRESS_CHANGE_DELAY),
+        "Incorrect address change delay"
+    );
 
-    assert!(
-        contract
-            .initialize(
-                dummy_darkpool_core_address,
-                dummy_verifier_address,
+    contract
+        .schedule_address_change(
+            VERIFIER_ADDRESS_SELECTOR,
+            test_args.test_upgrade_target_address,
+        )
+        .send()
+        .await?
+        .await?;
+
+    // Assert that the pending change is queryable, with the correct effective timestamp
+    let block_timestamp = test_args
+        .client
+        .get_block(test_args.client.get_block_number().await?)
+        .await?
+        .ok_or_else(|| eyre!("missing latest block"))?
+        .timestamp;
+
+    let (pending_address, effective_timestamp) = contract
+        .get_pending_address_change(VERIFIER_ADDRESS_SELECTOR)
+        .call()
+        .await?;
+
+    assert_eq!(
+        pending_address, test_args.test_upgrade_target_address,
+        "Incorrect pending address"
+    );
+    assert_eq!(
+        effective_timestamp,
+        block_timestamp + TEST_ADDRESS_CHANGE_DELAY,
+        "Incorrect effective timestamp"
+    );
+
+    // Assert that the change cannot be executed before the delay has elapsed
+    assert_all_revert(vec![contract
+        .execute_address_change(VERIFIER_ADDRESS_SELECTOR)
+        .send()])
+    .await?;
+
+    // Cancel the change, and assert that it cannot be executed after the delay has elapsed
+    contract
+        .cancel_address_change(VERIFIER_ADDRESS_SELECTOR)
+        .send()
+        .await?
+        .await?;
+
+    wait_for_address_change_delay().await;
+
+    assert_all_revert(vec![
+        contract
+            .execute_address_change(VERIFIER_ADDRESS_SELECTOR)
+            .send(),
+        contract
+            .cancel_address_change(VERIFIER_ADDRESS_SELECTOR)
+            .send(),
+    ])
+    .await?;
+
+    assert!(
+        contract
+            .is_implementation_upgraded(VERIFIER_ADDRESS_SELECTOR)
+            .call()
+            .await
+            .is_err(),
+        "Implementation address changed after cancellation"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_address_change_timelock);
+
+/// Test changing the address change delay through the timelock, including setting
+/// the initial delay of a darkpool upgraded from a version without the timelock
+async fn test_address_change_delay(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+
+    // Assert that a change of the delay cannot be executed before the current delay has elapsed
+    contract
+        .schedule_address_change_delay(U256::zero())
+        .send()
+        .await?
+        .await?;
+
+    assert_all_revert(vec![contract.execute_address_change_delay().send()]).await?;
+
+    wait_for_address_change_delay().await;
+    contract
+        .execute_address_change_delay()
+        .send()
+        .await?
+        .await?;
+
+    assert_eq!(
+        contract.get_address_change_delay().call().await?,
+        U256::zero(),
+        "Address change delay not changed"
+    );
+
+    // With a zero delay, as in a darkpool upgraded from a version without the timelock,
+    // the initial delay may be scheduled & executed immediately
+    contract
+        .schedule_address_change_delay(U256::from(TEST_ADDRESS_CHANGE_DELAY))
+        .send()
+        .await?
+        .await?;
+    contract
+        .execute_address_change_delay()
+        .send()
+        .await?
+        .await?;
+
+    assert_eq!(
+        contract.get_address_change_delay().call().await?,
+        U256::from(TEST_ADDRESS_CHANGE_DELAY),
+        "Initial address change delay not set"
+    );
+
+    // Assert that a canceled change of the delay cannot be executed
+    contract
+        .schedule_address_change_delay(U256::zero())
+        .send()
+        .await?
+        .await?;
+    contract.cancel_address_change_delay().send().await?.await?;
+
+    wait_for_address_change_delay().await;
+
+    assert_all_revert(vec![
+        contract.execute_address_change_delay().send(),
+        contract.cancel_address_change_delay().send(),
+    ])
+    .await?;
+
+    assert_eq!(
+        contract.get_address_change_delay().call().await?,
+        U256::from(TEST_ADDRESS_CHANGE_DELAY),
+        "Address change delay changed after cancellation"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_address_change_delay);
+
+/// Test the initialization of the darkpool
+async fn test_initializable(test_args: TestArgs) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    let dummy_darkpool_core_address = Address::random();
+    let dummy_verifier_address = Address::random();
+    let dummy_vkeys_address = Address::random();
+    let dummy_merkle_address = Address::random();
+    let dummy_transfer_executor_address = Address::random();
+    let dummy_permit2_address = Address::random();
+    let dummy_protocol_fee = U256::from(1);
+    let dummy_protocol_public_encryption_key = [U256::from(1), U256::from(2)];
+    let dummy_address_change_delay = U256::from(1);
+
+    assert!(
+        contract
+            .initialize(
+                dummy_darkpool_core_address,
+                dummy_verifier_address,
                 dummy_vkeys_address,
                 dummy_merkle_address,
                 dummy_transfer_executor_address,
                 dummy_permit2_address,
                 dummy_protocol_fee,
-                dummy_protocol_public_encryption_key
+                dummy_protocol_public_encryption_key,
+                dummy_address_change_delay,
             )
             .send()
             .await
@@ -489,6 +1149,18 @@ async fn test_ownable(test_args: TestArgs) -> Result<()> {
     let dummy_owner_address = dummy_owner.default_sender().unwrap();
     let contract_with_dummy_owner = DarkpoolTestContract::new(contract.address(), dummy_owner);
 
+    // Fund the dummy signer with some ETH for gas, so that it can accept ownership
+    let transfer_tx = TransactionRequest::new()
+        .from(initial_owner)
+        .to(dummy_owner_address)
+        .value(parse_ether(1_u64)?);
+
+    test_args
+        .client
+        .send_transaction(transfer_tx, None)
+        .await?
+        .await?;
+
     // Assert that only the owner can transfer ownership
     assert_only_owner::<_, Address>(
         &contract,
@@ -498,33 +1170,52 @@ async fn test_ownable(test_args: TestArgs) -> Result<()> {
     )
     .await?;
 
-    // Assert that ownership was properly transferred
+    // Assert that ownership is pending, but not yet transferred
     assert_eq!(
         contract.owner().call().await?,
+        initial_owner,
+        "Ownership transferred before acceptance"
+    );
+    assert_eq!(
+        contract.pending_owner().call().await?,
         dummy_owner_address,
-        "Incorrect new owner"
+        "Incorrect pending owner"
     );
 
-    // Transfer ownership back so that future tests have the correct owner
-    // To do so, we need to fund the dummy signer with some ETH for gas
-
-    let transfer_tx = TransactionRequest::new()
-        .from(initial_owner)
-        .to(dummy_owner_address)
-        .value(parse_ether(1_u64)?);
-
-    test_args
-        .client
-        .send_transaction(transfer_tx, None)
+    // Assert that only the pending owner can accept ownership
+    assert_all_revert(vec![contract.accept_ownership().send()]).await?;
+    contract_with_dummy_owner
+        .accept_ownership()
+        .send()
         .await?
         .await?;
 
+    // Assert that ownership was properly transferred
+    assert_eq!(
+        contract.owner().call().await?,
+        dummy_owner_address,
+        "Incorrect new owner"
+    );
+    assert_eq!(
+        contract.pending_owner().call().await?,
+        Address::zero(),
+        "Pending owner not cleared"
+    );
+
+    // Transfer ownership back so that future tests have the correct owner
     contract_with_dummy_owner
         .transfer_ownership(initial_owner)
         .send()
         .await?
         .await?;
 
+    contract.accept_ownership().send().await?.await?;
+
+    // Assert that only the owner can renounce ownership.
+    // We do not renounce ownership as the owner, as this would
+    // disable the owner-only methods for all future tests.
+    assert_all_revert(vec![contract_with_dummy_owner.renounce_ownership().send()]).await?;
+
     // Assert that only the owner can call the `pause`/`unpause` methods
     assert_only_owner::<_, ()>(&contract, &contract_with_dummy_owner, PAUSE_METHOD_NAME, ())
         .await?;
@@ -545,28 +1236,75 @@ async fn test_ownable(test_args: TestArgs) -> Result<()> {
     )
     .await?;
 
-    // Assert that only the owner can call the implementation address setters
-    // We set the implementation addresses to the original addresses to ensure
-    // that future tests have the correct implementation addresses
-    assert_only_owner::<_, Address>(
+    // Assert that only the owner can schedule & cancel implementation address changes
+    assert_only_owner::<_, ()>(
         &contract,
         &contract_with_dummy_owner,
-        SET_VERIFIER_ADDRESS_METHOD_NAME,
-        test_args.verifier_address,
+        SCHEDULE_ADDRESS_CHANGE_METHOD_NAME,
+        (
+            VERIFIER_ADDRESS_SELECTOR,
+            test_args.test_upgrade_target_address,
+        ),
     )
     .await?;
-    assert_only_owner::<_, Address>(
+    assert_only_owner::<_, ()>(
         &contract,
         &contract_with_dummy_owner,
-        SET_VKEYS_ADDRESS_METHOD_NAME,
-        test_args.vkeys_address,
+        CANCEL_ADDRESS_CHANGE_METHOD_NAME,
+        VERIFIER_ADDRESS_SELECTOR,
     )
     .await?;
-    assert_only_owner::<_, Address>(
+
+    // Assert that only the owner can schedule & cancel changes of the address change delay
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_owner,
+        SCHEDULE_ADDRESS_CHANGE_DELAY_METHOD_NAME,
+        U256::from(TEST_ADDRESS_CHANGE_DELAY),
+    )
+    .await?;
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_owner,
+        CANCEL_ADDRESS_CHANGE_DELAY_METHOD_NAME,
+        (),
+    )
+    .await?;
+
+    // Assert that only the owner can register & retire verification key hashes
+    let vkey = to_contract_vkey((*DummyValidWalletCreate::verifying_key()).clone())?;
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_owner,
+        REGISTER_VKEY_HASH_METHOD_NAME,
+        (
+            VALID_WALLET_CREATE_CIRCUIT_ID,
+            serialize_to_calldata(&vkey)?,
+        ),
+    )
+    .await?;
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_owner,
+        RETIRE_VKEY_HASH_METHOD_NAME,
+        VALID_WALLET_CREATE_CIRCUIT_ID,
+    )
+    .await?;
+
+    // Assert that only the owner can set the root history expiry policy & migrate the root history.
+    // We leave both bounds disabled so as not to expire roots used in future tests.
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_owner,
+        SET_ROOT_HISTORY_PARAMS_METHOD_NAME,
+        (0_u64, U256::zero()),
+    )
+    .await?;
+    assert_only_owner::<_, ()>(
         &contract,
         &contract_with_dummy_owner,
-        SET_MERKLE_ADDRESS_METHOD_NAME,
-        test_args.merkle_address,
+        MIGRATE_ROOT_HISTORY_METHOD_NAME,
+        U256::zero(),
     )
     .await?;
 
@@ -574,6 +1312,155 @@ async fn test_ownable(test_args: TestArgs) -> Result<()> {
 }
 integration_test_async!(test_ownable);
 
+/// Test cancelling a pending ownership transfer of the darkpool
+async fn test_cancel_ownership_transfer(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+    let initial_owner = test_args.client.default_sender().unwrap();
+
+    // Set up a dummy owner account and a contract instance with that account attached as the sender
+    let dummy_owner = setup_dummy_client(test_args.client.clone()).await?;
+    let dummy_owner_address = dummy_owner.default_sender().unwrap();
+    let contract_with_dummy_owner = DarkpoolTestContract::new(contract.address(), dummy_owner);
+
+    // Fund the dummy signer with some ETH for gas
+    let transfer_tx = TransactionRequest::new()
+        .from(initial_owner)
+        .to(dummy_owner_address)
+        .value(parse_ether(1_u64)?);
+
+    test_args
+        .client
+        .send_transaction(transfer_tx, None)
+        .await?
+        .await?;
+
+    contract
+        .transfer_ownership(dummy_owner_address)
+        .send()
+        .await?
+        .await?;
+
+    // Assert that only the owner can cancel the ownership transfer
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_owner,
+        CANCEL_OWNERSHIP_TRANSFER_METHOD_NAME,
+        (),
+    )
+    .await?;
+
+    assert_eq!(
+        contract.pending_owner().call().await?,
+        Address::zero(),
+        "Pending owner not cleared"
+    );
+
+    // Assert that the previously-pending owner can no longer accept ownership,
+    // and that there is no longer a transfer to cancel
+    assert_all_revert(vec![
+        contract_with_dummy_owner.accept_ownership().send(),
+        contract.cancel_ownership_transfer().send(),
+    ])
+    .await?;
+
+    assert_eq!(
+        contract.owner().call().await?,
+        initial_owner,
+        "Incorrect owner"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_cancel_ownership_transfer);
+
+/// Test the role-based access controls of the darkpool
+async fn test_access_control(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+    let owner = test_args.client.default_sender().unwrap();
+
+    // Set up a dummy account and a contract instance with that account attached as the sender
+    let dummy_account = setup_dummy_client(test_args.client.clone()).await?;
+    let dummy_account_address = dummy_account.default_sender().unwrap();
+    let contract_with_dummy_account = DarkpoolTestContract::new(contract.address(), dummy_account);
+
+    // Fund the dummy account with some ETH for gas
+    let transfer_tx = TransactionRequest::new()
+        .from(owner)
+        .to(dummy_account_address)
+        .value(parse_ether(1_u64)?);
+
+    test_args
+        .client
+        .send_transaction(transfer_tx, None)
+        .await?
+        .await?;
+
+    // Assert that the dummy account cannot pause the darkpool without the pauser role
+    assert!(
+        !contract
+            .has_role(PAUSER_ROLE, dummy_account_address)
+            .call()
+            .await?,
+        "Dummy account holds pauser role"
+    );
+    assert_all_revert(vec![contract_with_dummy_account.pause().send()]).await?;
+
+    // Assert that only the owner can grant roles
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_account,
+        GRANT_ROLE_METHOD_NAME,
+        (PAUSER_ROLE, dummy_account_address),
+    )
+    .await?;
+
+    assert!(
+        contract
+            .has_role(PAUSER_ROLE, dummy_account_address)
+            .call()
+            .await?,
+        "Pauser role not granted"
+    );
+
+    // Assert that the pauser can pause & unpause the darkpool
+    contract_with_dummy_account.pause().send().await?.await?;
+    assert!(contract.paused().call().await?, "Contract not paused");
+    contract_with_dummy_account.unpause().send().await?.await?;
+    assert!(!contract.paused().call().await?, "Contract not unpaused");
+
+    // Assert that the pauser cannot call methods gated by other roles
+    assert_all_revert(vec![
+        contract_with_dummy_account.set_fee(U256::from(1)).send(),
+        contract_with_dummy_account
+            .schedule_address_change(VERIFIER_ADDRESS_SELECTOR, test_args.verifier_address)
+            .send(),
+    ])
+    .await?;
+
+    // Assert that only the owner can revoke roles
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_account,
+        REVOKE_ROLE_METHOD_NAME,
+        (PAUSER_ROLE, dummy_account_address),
+    )
+    .await?;
+
+    assert!(
+        !contract
+            .has_role(PAUSER_ROLE, dummy_account_address)
+            .call()
+            .await?,
+        "Pauser role not revoked"
+    );
+    assert_all_revert(vec![contract_with_dummy_account.pause().send()]).await?;
+
+    Ok(())
+}
+integration_test_async!(test_access_control);
+
 /// Test the pausability of the darkpool
 // TODO: Ensure that only the owner can pause the contract
 async fn test_pausable(test_args: TestArgs) -> Result<()> {
@@ -601,7 +1488,8 @@ async fn test_pausable(test_args: TestArgs) -> Result<()> {
     let (update_wallet_proof, update_wallet_statement, update_wallet_commitment_signature) =
         gen_update_wallet_data(&mut rng, contract_root)?;
 
-    let data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee)?;
+    let base_mint = AlloyAddress::from_slice(test_args.test_erc20_address.as_bytes());
+    let data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee, base_mint)?;
 
     let (
         valid_relayer_fee_settlement_proof,
@@ -684,7 +1572,164 @@ async fn test_pausable(test_args: TestArgs) -> Result<()> {
 
     Ok(())
 }
-integration_test_async!(test_pausable);
+integration_test_async!(test_pausable);
+
+/// Test pausing individual operations on the darkpool
+async fn test_pause_operations(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    let mut rng = thread_rng();
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let protocol_fee = FixedPoint::from(Scalar::new(u256_to_scalar(
+        contract.get_fee().call().await?,
+    )?));
+
+    // Set up a dummy account and a contract instance with that account attached as the sender
+    let dummy_account = setup_dummy_client(test_args.client.clone()).await?;
+    let contract_with_dummy_account = DarkpoolTestContract::new(contract.address(), dummy_account);
+
+    // Assert that only the owner can pause individual operations
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_account,
+        SET_OPERATION_PAUSED_METHOD_NAME,
+        (PROCESS_MATCH_SETTLE_OPERATION, true),
+    )
+    .await?;
+    contract
+        .set_operation_paused(UPDATE_WALLET_DEPOSIT_OPERATION, true)
+        .send()
+        .await?
+        .await?;
+
+    // Assert that the pause state reflects the paused operations
+    assert_eq!(
+        contract.get_paused_operations().call().await?,
+        (1 << PROCESS_MATCH_SETTLE_OPERATION) | (1 << UPDATE_WALLET_DEPOSIT_OPERATION),
+        "Incorrect pause state"
+    );
+    assert!(
+        contract
+            .is_operation_paused(PROCESS_MATCH_SETTLE_OPERATION)
+            .call()
+            .await?,
+        "Operation not paused"
+    );
+    assert!(
+        !contract.paused().call().await?,
+        "Contract paused after pausing individual operations"
+    );
+
+    // Assert that the paused operations revert, while the others succeed.
+    // A wallet update with no external transfer is only paused if both
+    // deposits & withdrawals are paused.
+
+    let (new_wallet_proof, new_wallet_statement) = gen_new_wallet_data(&mut rng)?;
+    let (update_wallet_proof, update_wallet_statement, update_wallet_commitment_signature) =
+        gen_update_wallet_data(&mut rng, contract_root)?;
+    let base_mint = AlloyAddress::from_slice(test_args.test_erc20_address.as_bytes());
+    let data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee, base_mint)?;
+
+    assert_all_revert(vec![contract
+        .process_match_settle(
+            serialize_to_calldata(&data.match_payload_0)?,
+            serialize_to_calldata(&data.match_payload_1)?,
+            serialize_to_calldata(&data.valid_match_settle_statement)?,
+            serialize_to_calldata(&data.match_proofs)?,
+            serialize_to_calldata(&data.match_linking_proofs)?,
+        )
+        .send()])
+    .await?;
+
+    assert_all_succeed(vec![
+        contract
+            .new_wallet(
+                serialize_to_calldata(&new_wallet_proof)?,
+                serialize_to_calldata(&new_wallet_statement)?,
+            )
+            .send(),
+        contract
+            .update_wallet(
+                serialize_to_calldata(&update_wallet_proof)?,
+                serialize_to_calldata(&update_wallet_statement)?,
+                update_wallet_commitment_signature,
+                Bytes::new(), /* transfer_aux_data */
+            )
+            .send(),
+    ])
+    .await?;
+
+    // Assert that a wallet update with no external transfer reverts
+    // once withdrawals are paused as well
+    contract
+        .set_operation_paused(UPDATE_WALLET_WITHDRAWAL_OPERATION, true)
+        .send()
+        .await?
+        .await?;
+
+    let (update_wallet_proof, update_wallet_statement, update_wallet_commitment_signature) =
+        gen_update_wallet_data(&mut rng, contract_root)?;
+
+    assert_all_revert(vec![contract
+        .update_wallet(
+            serialize_to_calldata(&update_wallet_proof)?,
+            serialize_to_calldata(&update_wallet_statement)?,
+            update_wallet_commitment_signature,
+            Bytes::new(), /* transfer_aux_data */
+        )
+        .send()])
+    .await?;
+
+    // Assert that pausing an operation which is already paused is a no-op
+    let receipt = contract
+        .set_operation_paused(UPDATE_WALLET_WITHDRAWAL_OPERATION, true)
+        .send()
+        .await?
+        .await?
+        .ok_or_else(|| eyre!("no receipt for pausing operation"))?;
+    assert!(
+        receipt.logs.is_empty(),
+        "Event emitted for unchanged pause state"
+    );
+
+    // Unpause all operations so that future tests are unaffected
+    let receipt = contract
+        .unpause()
+        .send()
+        .await?
+        .await?
+        .ok_or_else(|| eyre!("no receipt for unpausing"))?;
+
+    assert_eq!(
+        contract.get_paused_operations().call().await?,
+        0,
+        "Operations not unpaused"
+    );
+
+    // Assert that an event was emitted for each of the unpaused operations
+    let unpaused_operations = receipt
+        .logs
+        .into_iter()
+        .filter_map(|log| parse_log::<OperationUnpausedFilter>(log).ok())
+        .map(|event| event.operation)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        unpaused_operations,
+        vec![
+            UPDATE_WALLET_DEPOSIT_OPERATION,
+            UPDATE_WALLET_WITHDRAWAL_OPERATION,
+            PROCESS_MATCH_SETTLE_OPERATION,
+        ],
+        "Incorrect operation unpause events"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_pause_operations);
 
 /// Test the nullifier set functionality
 async fn test_nullifier_set(test_args: TestArgs) -> Result<()> {
@@ -1046,6 +2091,268 @@ async fn test_new_wallet(test_args: TestArgs) -> Result<()> {
 }
 integration_test_async!(test_new_wallet);
 
+/// Test passing verification keys in calldata to the core methods,
+/// checked against the commitment registered for the circuit
+async fn test_vkey_hashes(test_args: TestArgs) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    let mut rng = thread_rng();
+    let (proof_0, statement_0) = gen_new_wallet_data(&mut rng)?;
+    let (proof_1, statement_1) = gen_new_wallet_data(&mut rng)?;
+
+    let vkey = to_contract_vkey((*DummyValidWalletCreate::verifying_key()).clone())?;
+    let vkey_bytes = serialize_to_calldata(&vkey)?;
+    let vkey_hash = keccak256(&vkey_bytes);
+
+    let mut invalid_vkey_bytes = vkey_bytes.to_vec();
+    invalid_vkey_bytes[0] ^= 1;
+
+    let mut invalid_g2_vkey = vkey;
+    invalid_g2_vkey.x_h = G2Affine::new_unchecked(vkey.x_h.x, vkey.x_h.y + G2BaseField::one());
+
+    // Assert that verification keys in calldata are rejected
+    // before a commitment is registered for the circuit
+    assert_all_revert(vec![contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_0)?,
+            serialize_to_calldata(&statement_0)?,
+            vkey_bytes.clone(),
+            0, /* vkey_version */
+        )
+        .send()])
+    .await?;
+
+    // Assert that verification keys with G2 points off the curve cannot be registered
+    assert_all_revert(vec![contract
+        .register_vkey_hash(
+            VALID_WALLET_CREATE_CIRCUIT_ID,
+            serialize_to_calldata(&invalid_g2_vkey)?,
+        )
+        .send()])
+    .await?;
+
+    contract
+        .register_vkey_hash(VALID_WALLET_CREATE_CIRCUIT_ID, vkey_bytes.clone())
+        .send()
+        .await?
+        .await?;
+
+    assert_eq!(
+        contract
+            .get_vkey_hash(VALID_WALLET_CREATE_CIRCUIT_ID)
+            .call()
+            .await?,
+        vkey_hash,
+        "Incorrect vkey hash"
+    );
+
+    // Assert that verification keys not matching the commitment are rejected,
+    // while those matching it are accepted
+    assert_all_revert(vec![contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_0)?,
+            serialize_to_calldata(&statement_0)?,
+            invalid_vkey_bytes.into(),
+            0, /* vkey_version */
+        )
+        .send()])
+    .await?;
+
+    contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_0)?,
+            serialize_to_calldata(&statement_0)?,
+            vkey_bytes.clone(),
+            0, /* vkey_version */
+        )
+        .send()
+        .await?
+        .await?;
+
+    // Assert that verification keys in calldata are rejected once the commitment is retired,
+    // while those in the vkeys contract are still accepted
+    contract
+        .retire_vkey_hash(VALID_WALLET_CREATE_CIRCUIT_ID)
+        .send()
+        .await?
+        .await?;
+
+    assert_all_revert(vec![contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_1)?,
+            serialize_to_calldata(&statement_1)?,
+            vkey_bytes,
+            0, /* vkey_version */
+        )
+        .send()])
+    .await?;
+
+    contract
+        .new_wallet(
+            serialize_to_calldata(&proof_1)?,
+            serialize_to_calldata(&statement_1)?,
+        )
+        .send()
+        .await?
+        .await?;
+
+    Ok(())
+}
+integration_test_async!(test_vkey_hashes);
+
+/// Test fetching versioned verification keys from the vkey registry
+async fn test_vkey_registry(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+    let vkey_registry =
+        VkeyRegistryContract::new(test_args.vkey_registry_address, test_args.client.clone());
+
+    let dummy_owner = setup_dummy_client(test_args.client.clone()).await?;
+    let vkey_registry_with_dummy_owner =
+        VkeyRegistryContract::new(test_args.vkey_registry_address, dummy_owner);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    schedule_and_execute_address_change(
+        &contract,
+        VKEY_REGISTRY_ADDRESS_SELECTOR,
+        test_args.vkey_registry_address,
+    )
+    .await?;
+
+    let mut rng = thread_rng();
+    let (proof_0, statement_0) = gen_new_wallet_data(&mut rng)?;
+    let (proof_1, statement_1) = gen_new_wallet_data(&mut rng)?;
+
+    // Assert that the registered verification keys match those in the vkeys contract
+    let vkey = to_contract_vkey((*DummyValidWalletCreate::verifying_key()).clone())?;
+    assert_eq!(
+        vkey_registry
+            .get_vkey(VALID_WALLET_CREATE_CIRCUIT_ID, TEST_VKEY_VERSION)
+            .call()
+            .await?,
+        serialize_to_calldata(&vkey)?,
+        "Incorrect registered vkey"
+    );
+
+    // Assert that unregistered versions, & verification keys given in calldata
+    // alongside a version, are rejected
+    assert_all_revert(vec![
+        contract
+            .new_wallet_with_vkeys(
+                serialize_to_calldata(&proof_0)?,
+                serialize_to_calldata(&statement_0)?,
+                Bytes::new(), /* vkeys */
+                TEST_VKEY_VERSION + 1,
+            )
+            .send(),
+        contract
+            .new_wallet_with_vkeys(
+                serialize_to_calldata(&proof_0)?,
+                serialize_to_calldata(&statement_0)?,
+                serialize_to_calldata(&vkey)?,
+                TEST_VKEY_VERSION,
+            )
+            .send(),
+    ])
+    .await?;
+
+    contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_0)?,
+            serialize_to_calldata(&statement_0)?,
+            Bytes::new(), /* vkeys */
+            TEST_VKEY_VERSION,
+        )
+        .send()
+        .await?
+        .await?;
+
+    // Assert that only the owner can deprecate a version or modify a finalized vkey
+    assert_all_revert(vec![
+        vkey_registry_with_dummy_owner
+            .deprecate_version(TEST_VKEY_VERSION, U256::one())
+            .send(),
+        vkey_registry
+            .finalize_vkey(VALID_WALLET_CREATE_CIRCUIT_ID, TEST_VKEY_VERSION)
+            .send(),
+    ])
+    .await?;
+
+    // Assert that verification keys with G2 points off the curve cannot be finalized
+    let invalid_vkey_version = rng.gen_range(TEST_VKEY_VERSION + 1..u64::MAX);
+    let mut invalid_vkey = vkey;
+    invalid_vkey.x_h = G2Affine::new_unchecked(vkey.x_h.x, vkey.x_h.y + G2BaseField::one());
+    vkey_registry
+        .upload_vkey_chunk(
+            VALID_WALLET_CREATE_CIRCUIT_ID,
+            invalid_vkey_version,
+            U256::zero(),
+            serialize_to_calldata(&invalid_vkey)?,
+        )
+        .send()
+        .await?
+        .await?;
+
+    assert_all_revert(vec![vkey_registry
+        .finalize_vkey(VALID_WALLET_CREATE_CIRCUIT_ID, invalid_vkey_version)
+        .send()])
+    .await?;
+
+    // Deprecate the version as of the current block, so that
+    // its verification keys are rejected in subsequent blocks
+    let cutoff_block = U256::from(test_args.client.get_block_number().await?.as_u64());
+    vkey_registry
+        .deprecate_version(TEST_VKEY_VERSION, cutoff_block)
+        .send()
+        .await?
+        .await?;
+
+    assert_eq!(
+        vkey_registry
+            .get_version_cutoff_block(TEST_VKEY_VERSION)
+            .call()
+            .await?,
+        cutoff_block,
+        "Incorrect cutoff block"
+    );
+
+    assert_all_revert(vec![contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_1)?,
+            serialize_to_calldata(&statement_1)?,
+            Bytes::new(), /* vkeys */
+            TEST_VKEY_VERSION,
+        )
+        .send()])
+    .await?;
+
+    // Lift the deprecation, & assert that the version is accepted again
+    vkey_registry
+        .deprecate_version(TEST_VKEY_VERSION, U256::zero())
+        .send()
+        .await?
+        .await?;
+
+    contract
+        .new_wallet_with_vkeys(
+            serialize_to_calldata(&proof_1)?,
+            serialize_to_calldata(&statement_1)?,
+            Bytes::new(), /* vkeys */
+            TEST_VKEY_VERSION,
+        )
+        .send()
+        .await?
+        .await?;
+
+    Ok(())
+}
+integration_test_async!(test_vkey_registry);
+
 /// Test the `update_wallet` method on the darkpool
 async fn test_update_wallet(test_args: TestArgs) -> Result<()> {
     let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
@@ -1112,7 +2419,8 @@ async fn test_process_match_settle(test_args: TestArgs) -> Result<()> {
         contract.get_fee().call().await?,
     )?));
     let mut rng = thread_rng();
-    let data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee)?;
+    let base_mint = AlloyAddress::from_slice(test_args.test_erc20_address.as_bytes());
+    let data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee, base_mint)?;
 
     // Call `process_match_settle` with valid data
     contract
@@ -1194,70 +2502,276 @@ async fn test_process_match_settle__inconsistent_indices(test_args: TestArgs) ->
     )?));
     let mut rng = thread_rng();
 
-    let mut data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee)?;
-    // Mutate the order settlement indices to be inconsistent
-    data.valid_match_settle_statement
-        .party0_indices
-        .balance_receive += 1;
+    let base_mint = AlloyAddress::from_slice(test_args.test_erc20_address.as_bytes());
+    let mut data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee, base_mint)?;
+    // Mutate the order settlement indices to be inconsistent
+    data.valid_match_settle_statement
+        .party0_indices
+        .balance_receive += 1;
+
+    // Call `process_match_settle` with invalid data
+    assert!(
+        contract
+            .process_match_settle(
+                serialize_to_calldata(&data.match_payload_0)?,
+                serialize_to_calldata(&data.match_payload_1)?,
+                serialize_to_calldata(&data.valid_match_settle_statement)?,
+                serialize_to_calldata(&data.match_proofs)?,
+                serialize_to_calldata(&data.match_linking_proofs)?,
+            )
+            .send()
+            .await
+            .is_err(),
+        "Inconsistent order settlement indices did not fail"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_process_match_settle__inconsistent_indices);
+
+/// Test that the `process_match_settle` method on the darkpool
+/// fails when protocol fee is inconsistent
+#[allow(non_snake_case)]
+async fn test_process_match_settle__inconsistent_fee(test_args: TestArgs) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let protocol_fee = FixedPoint::from(Scalar::new(u256_to_scalar(
+        contract.get_fee().call().await?,
+    )?));
+    let mut rng = thread_rng();
+
+    let base_mint = AlloyAddress::from_slice(test_args.test_erc20_address.as_bytes());
+    let mut data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee, base_mint)?;
+    // Mutate the protocol fee to be inconsistent
+    data.valid_match_settle_statement.protocol_fee += ScalarField::one();
+
+    // Call `process_match_settle` with invalid data
+    assert!(
+        contract
+            .process_match_settle(
+                serialize_to_calldata(&data.match_payload_0)?,
+                serialize_to_calldata(&data.match_payload_1)?,
+                serialize_to_calldata(&data.valid_match_settle_statement)?,
+                serialize_to_calldata(&data.match_proofs)?,
+                serialize_to_calldata(&data.match_linking_proofs)?,
+            )
+            .send()
+            .await
+            .is_err(),
+        "Inconsistent protocol fee did not fail"
+    );
+
+    Ok(())
+}
+integration_test_async!(test_process_match_settle__inconsistent_fee);
+
+/// Test that the `process_match_settle` method on the darkpool
+/// checks the protocol fee against the override for the traded pair
+#[allow(non_snake_case)]
+async fn test_process_match_settle__fee_override(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    // Set up a dummy account and a contract instance with that account attached as the sender
+    let dummy_account = setup_dummy_client(test_args.client.clone()).await?;
+    let contract_with_dummy_account = DarkpoolTestContract::new(contract.address(), dummy_account);
+
+    let mint = test_args.test_erc20_address;
+    let base_mint = AlloyAddress::from_slice(mint.as_bytes());
+    let global_fee = contract.get_fee().call().await?;
+    let fee_override = global_fee + U256::from(1);
+
+    // Assert that only the owner can set a fee override
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_account,
+        SET_FEE_OVERRIDE_METHOD_NAME,
+        (mint, fee_override),
+    )
+    .await?;
+
+    // Assert that the override applies only to the given mint
+    assert_eq!(
+        contract.get_fee_for_mint(mint).call().await?,
+        fee_override,
+        "Incorrect fee for overridden mint"
+    );
+    assert_eq!(
+        contract
+            .get_fee_for_mint(test_args.test_upgrade_target_address)
+            .call()
+            .await?,
+        global_fee,
+        "Incorrect fee for mint without override"
+    );
+
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let mut rng = thread_rng();
+
+    // Assert that a match charged the global fee fails
+    let global_fee_data = gen_process_match_settle_data(
+        &mut rng,
+        contract_root,
+        FixedPoint::from(Scalar::new(u256_to_scalar(global_fee)?)),
+        base_mint,
+    )?;
+    let override_fee_data = gen_process_match_settle_data(
+        &mut rng,
+        contract_root,
+        FixedPoint::from(Scalar::new(u256_to_scalar(fee_override)?)),
+        base_mint,
+    )?;
+
+    assert_all_revert(vec![contract
+        .process_match_settle(
+            serialize_to_calldata(&global_fee_data.match_payload_0)?,
+            serialize_to_calldata(&global_fee_data.match_payload_1)?,
+            serialize_to_calldata(&global_fee_data.valid_match_settle_statement)?,
+            serialize_to_calldata(&global_fee_data.match_proofs)?,
+            serialize_to_calldata(&global_fee_data.match_linking_proofs)?,
+        )
+        .send()])
+    .await?;
+
+    // Assert that a match proven for the overridden mint, but which claims a mint without
+    // an override so as to be charged the global fee, fails. The base mint is a public input
+    // of the `VALID MATCH SETTLE` proof, so the proof does not verify for the claimed mint.
+    let mut mismatched_mint_data = gen_process_match_settle_data(
+        &mut rng,
+        contract_root,
+        FixedPoint::from(Scalar::new(u256_to_scalar(global_fee)?)),
+        base_mint,
+    )?;
+    mismatched_mint_data.valid_match_settle_statement.base_mint =
+        AlloyAddress::from_slice(test_args.test_upgrade_target_address.as_bytes());
+
+    assert_all_revert(vec![contract
+        .process_match_settle(
+            serialize_to_calldata(&mismatched_mint_data.match_payload_0)?,
+            serialize_to_calldata(&mismatched_mint_data.match_payload_1)?,
+            serialize_to_calldata(&mismatched_mint_data.valid_match_settle_statement)?,
+            serialize_to_calldata(&mismatched_mint_data.match_proofs)?,
+            serialize_to_calldata(&mismatched_mint_data.match_linking_proofs)?,
+        )
+        .send()])
+    .await?;
+
+    // Assert that a match charged the overridden fee succeeds
+    assert_all_succeed(vec![contract
+        .process_match_settle(
+            serialize_to_calldata(&override_fee_data.match_payload_0)?,
+            serialize_to_calldata(&override_fee_data.match_payload_1)?,
+            serialize_to_calldata(&override_fee_data.valid_match_settle_statement)?,
+            serialize_to_calldata(&override_fee_data.match_proofs)?,
+            serialize_to_calldata(&override_fee_data.match_linking_proofs)?,
+        )
+        .send()])
+    .await?;
 
-    // Call `process_match_settle` with invalid data
-    assert!(
-        contract
-            .process_match_settle(
-                serialize_to_calldata(&data.match_payload_0)?,
-                serialize_to_calldata(&data.match_payload_1)?,
-                serialize_to_calldata(&data.valid_match_settle_statement)?,
-                serialize_to_calldata(&data.match_proofs)?,
-                serialize_to_calldata(&data.match_linking_proofs)?,
-            )
-            .send()
-            .await
-            .is_err(),
-        "Inconsistent order settlement indices did not fail"
+    // Assert that removing the override falls back to the global fee
+    contract.remove_fee_override(mint).send().await?.await?;
+    assert_eq!(
+        contract.get_fee_for_mint(mint).call().await?,
+        global_fee,
+        "Fee override not removed"
     );
 
     Ok(())
 }
-integration_test_async!(test_process_match_settle__inconsistent_indices);
+integration_test_async!(test_process_match_settle__fee_override);
 
-/// Test that the `process_match_settle` method on the darkpool
-/// fails when protocol fee is inconsistent
-#[allow(non_snake_case)]
-async fn test_process_match_settle__inconsistent_fee(test_args: TestArgs) -> Result<()> {
+/// Test the `process_match_settle_batch` method on the darkpool
+async fn test_process_match_settle_batch(test_args: TestArgs) -> Result<()> {
     let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
 
     // Ensure the merkle state is cleared for the test
     contract.clear_merkle().send().await?.await?;
 
+    // Generate test data
+    let mut ark_merkle = new_ark_merkle_tree(TEST_MERKLE_HEIGHT);
+
     let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
     let protocol_fee = FixedPoint::from(Scalar::new(u256_to_scalar(
         contract.get_fee().call().await?,
     )?));
     let mut rng = thread_rng();
+    let base_mint = AlloyAddress::from_slice(test_args.test_erc20_address.as_bytes());
+    let batch_data = (0..NUM_BATCHED_MATCHES)
+        .map(|_| gen_process_match_settle_data(&mut rng, contract_root, protocol_fee, base_mint))
+        .collect::<Result<Vec<_>>>()?;
+
+    let match_payloads_0: Vec<_> = batch_data.iter().map(|d| &d.match_payload_0).collect();
+    let match_payloads_1: Vec<_> = batch_data.iter().map(|d| &d.match_payload_1).collect();
+    let valid_match_settle_statements: Vec<_> = batch_data
+        .iter()
+        .map(|d| &d.valid_match_settle_statement)
+        .collect();
+    let match_proofs: Vec<_> = batch_data.iter().map(|d| d.match_proofs).collect();
+    let match_linking_proofs: Vec<_> = batch_data.iter().map(|d| d.match_linking_proofs).collect();
+
+    // Call `process_match_settle_batch` with valid data
+    contract
+        .process_match_settle_batch(
+            serialize_to_calldata(&match_payloads_0)?,
+            serialize_to_calldata(&match_payloads_1)?,
+            serialize_to_calldata(&valid_match_settle_statements)?,
+            serialize_to_calldata(&match_proofs)?,
+            serialize_to_calldata(&match_linking_proofs)?,
+        )
+        .send()
+        .await?
+        .await?;
 
-    let mut data = gen_process_match_settle_data(&mut rng, contract_root, protocol_fee)?;
-    // Mutate the protocol fee to be inconsistent
-    data.valid_match_settle_statement.protocol_fee += ScalarField::one();
+    let mut ark_root = ark_merkle.root();
+    for (i, data) in batch_data.iter().enumerate() {
+        // Assert that correct nullifiers are spent
+        for match_payload in [&data.match_payload_0, &data.match_payload_1] {
+            let nullifier = scalar_to_u256(
+                match_payload
+                    .valid_reblind_statement
+                    .original_shares_nullifier,
+            );
+            let nullifier_spent = contract.is_nullifier_spent(nullifier).call().await?;
+            assert!(nullifier_spent, "Nullifier not spent in match {i}");
+        }
+
+        // Insert the updated wallets into the reference Merkle tree, in the order
+        // in which they should have been inserted by the contract
+        insert_shares_and_get_root(
+            &mut ark_merkle,
+            data.match_payload_0
+                .valid_reblind_statement
+                .reblinded_private_shares_commitment,
+            &data.valid_match_settle_statement.party0_modified_shares,
+            2 * i, /* index */
+        )
+        .map_err(|e| eyre!("{}", e))?;
+        ark_root = insert_shares_and_get_root(
+            &mut ark_merkle,
+            data.match_payload_1
+                .valid_reblind_statement
+                .reblinded_private_shares_commitment,
+            &data.valid_match_settle_statement.party1_modified_shares,
+            2 * i + 1, /* index */
+        )
+        .map_err(|e| eyre!("{}", e))?;
+    }
 
-    // Call `process_match_settle` with invalid data
-    assert!(
-        contract
-            .process_match_settle(
-                serialize_to_calldata(&data.match_payload_0)?,
-                serialize_to_calldata(&data.match_payload_1)?,
-                serialize_to_calldata(&data.valid_match_settle_statement)?,
-                serialize_to_calldata(&data.match_proofs)?,
-                serialize_to_calldata(&data.match_linking_proofs)?,
-            )
-            .send()
-            .await
-            .is_err(),
-        "Inconsistent protocol fee did not fail"
-    );
+    // Assert that Merkle root is correct
+    let contract_root = u256_to_scalar(contract.get_root().call().await?)?;
+
+    assert_eq!(ark_root, contract_root, "Merkle root incorrect");
 
     Ok(())
 }
-integration_test_async!(test_process_match_settle__inconsistent_fee);
+integration_test_async!(test_process_match_settle_batch);
 
 /// Test the `settle_online_relayer_fee` method on the darkpool
 async fn test_settle_online_relayer_fee(test_args: TestArgs) -> Result<()> {
@@ -1425,6 +2939,178 @@ async fn test_settle_offline_fee__incorrect_protocol_key(test_args: TestArgs) ->
 }
 integration_test_async!(test_settle_offline_fee__incorrect_protocol_key);
 
+/// Test that the `settle_offline_fee` method on the darkpool accepts
+/// a rotated-out protocol key only inside its grace window
+#[allow(non_snake_case)]
+async fn test_settle_offline_fee__rotated_protocol_key(test_args: TestArgs) -> Result<()> {
+    let contract =
+        DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client.clone());
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    // Set up a dummy account and a contract instance with that account attached as the sender
+    let dummy_account = setup_dummy_client(test_args.client.clone()).await?;
+    let contract_with_dummy_account = DarkpoolTestContract::new(contract.address(), dummy_account);
+
+    // Assert that only the owner can set the grace period
+    assert_only_owner::<_, ()>(
+        &contract,
+        &contract_with_dummy_account,
+        SET_PUBKEY_GRACE_PERIOD_METHOD_NAME,
+        U256::from(TEST_PUBKEY_GRACE_PERIOD_BLOCKS),
+    )
+    .await?;
+
+    let mut rng = thread_rng();
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let original_pubkey = contract.get_pubkey().call().await?;
+    let (original_key_proof, original_key_statement) = gen_settle_offline_fee_data(
+        &mut rng,
+        contract_root,
+        get_protocol_pubkey(&contract).await?,
+        true, /* is_protocol_fee */
+    )?;
+
+    // Rotate the protocol key
+    let rotated_pubkey = [
+        scalar_to_u256(ScalarField::rand(&mut rng)),
+        scalar_to_u256(ScalarField::rand(&mut rng)),
+    ];
+    contract
+        .set_public_encryption_key(rotated_pubkey)
+        .send()
+        .await?
+        .await?;
+
+    // Assert that the key history reflects the rotation
+    let history = contract.get_pubkey_history().call().await?;
+    let (x, y, _, expiry_block) = history[history.len() - 1];
+    assert_eq!([x, y], rotated_pubkey, "Incorrect current key in history");
+    assert_eq!(expiry_block, U256::zero(), "Current key has an expiry");
+    let (x, y, _, expiry_block) = history[history.len() - 2];
+    assert_eq!([x, y], original_pubkey, "Incorrect previous key in history");
+    assert_ne!(expiry_block, U256::zero(), "Previous key has no expiry");
+
+    let (rotated_key_proof, rotated_key_statement) = gen_settle_offline_fee_data(
+        &mut rng,
+        contract_root,
+        get_protocol_pubkey(&contract).await?,
+        true, /* is_protocol_fee */
+    )?;
+
+    // Assert that the rotated-out key is accepted inside its grace window
+    assert_all_succeed(vec![contract
+        .settle_offline_fee(
+            serialize_to_calldata(&original_key_proof)?,
+            serialize_to_calldata(&original_key_statement)?,
+        )
+        .send()])
+    .await?;
+
+    // Rotate back to the original key with no grace window,
+    // and assert that the rotated-out key is no longer accepted
+    contract
+        .set_pubkey_grace_period(U256::zero())
+        .send()
+        .await?
+        .await?;
+    contract
+        .set_public_encryption_key(original_pubkey)
+        .send()
+        .await?
+        .await?;
+
+    assert_all_revert(vec![contract
+        .settle_offline_fee(
+            serialize_to_calldata(&rotated_key_proof)?,
+            serialize_to_calldata(&rotated_key_statement)?,
+        )
+        .send()])
+    .await?;
+
+    Ok(())
+}
+integration_test_async!(test_settle_offline_fee__rotated_protocol_key);
+
+/// Test that the first key rotation on a darkpool upgraded from a version without
+/// a key history gives the key in use before the upgrade a grace window
+#[allow(non_snake_case)]
+async fn test_settle_offline_fee__rotated_pre_upgrade_protocol_key(
+    test_args: TestArgs,
+) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    // Simulate an upgraded darkpool, which has a current key but no key history
+    contract
+        .set_pubkey_grace_period(U256::from(TEST_PUBKEY_GRACE_PERIOD_BLOCKS))
+        .send()
+        .await?
+        .await?;
+    contract.clear_pubkey_history().send().await?.await?;
+    assert!(
+        contract.get_pubkey_history().call().await?.is_empty(),
+        "Key history not cleared"
+    );
+
+    let mut rng = thread_rng();
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let original_pubkey = contract.get_pubkey().call().await?;
+    let (original_key_proof, original_key_statement) = gen_settle_offline_fee_data(
+        &mut rng,
+        contract_root,
+        get_protocol_pubkey(&contract).await?,
+        true, /* is_protocol_fee */
+    )?;
+
+    // Rotate the protocol key
+    let rotated_pubkey = [
+        scalar_to_u256(ScalarField::rand(&mut rng)),
+        scalar_to_u256(ScalarField::rand(&mut rng)),
+    ];
+    contract
+        .set_public_encryption_key(rotated_pubkey)
+        .send()
+        .await?
+        .await?;
+
+    // Assert that the history was seeded with the pre-upgrade key
+    let history = contract.get_pubkey_history().call().await?;
+    assert_eq!(history.len(), 2, "Key history not seeded");
+    let (x, y, _, expiry_block) = history[0];
+    assert_eq!(
+        [x, y],
+        original_pubkey,
+        "Incorrect pre-upgrade key in history"
+    );
+    assert_ne!(expiry_block, U256::zero(), "Pre-upgrade key has no expiry");
+    let (x, y, _, expiry_block) = history[1];
+    assert_eq!([x, y], rotated_pubkey, "Incorrect current key in history");
+    assert_eq!(expiry_block, U256::zero(), "Current key has an expiry");
+
+    // Assert that the pre-upgrade key is accepted inside its grace window
+    assert_all_succeed(vec![contract
+        .settle_offline_fee(
+            serialize_to_calldata(&original_key_proof)?,
+            serialize_to_calldata(&original_key_statement)?,
+        )
+        .send()])
+    .await?;
+
+    // Restore the original key for subsequent tests
+    contract
+        .set_public_encryption_key(original_pubkey)
+        .send()
+        .await?
+        .await?;
+
+    Ok(())
+}
+integration_test_async!(test_settle_offline_fee__rotated_pre_upgrade_protocol_key);
+
 /// Test the `redeem_fee` method on the darkpool
 async fn test_redeem_fee(test_args: TestArgs) -> Result<()> {
     let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
@@ -1483,5 +3169,212 @@ async fn test_redeem_fee(test_args: TestArgs) -> Result<()> {
 }
 integration_test_async!(test_redeem_fee);
 
+/// Test that the `multicall` method on the darkpool executes
+/// a bundle of mixed core operations
+async fn test_multicall(test_args: TestArgs) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    // Generate test data
+    let mut rng = thread_rng();
+
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let (update_wallet_proof_0, update_wallet_statement_0, update_wallet_commitment_signature_0) =
+        gen_update_wallet_data(&mut rng, contract_root)?;
+    let (update_wallet_proof_1, update_wallet_statement_1, update_wallet_commitment_signature_1) =
+        gen_update_wallet_data(&mut rng, contract_root)?;
+    let (
+        valid_relayer_fee_settlement_proof,
+        valid_relayer_fee_settlement_statement,
+        relayer_wallet_commitment_signature,
+    ) = gen_settle_online_relayer_fee_data(&mut rng, contract_root)?;
+    let (valid_offline_fee_settlement_proof, valid_offline_fee_settlement_statement) =
+        gen_settle_offline_fee_data(
+            &mut rng,
+            contract_root,
+            get_protocol_pubkey(&contract).await?,
+            true, /* is_protocol_fee */
+        )?;
+
+    // Call `multicall` with a bundle of wallet updates & fee settlements
+    let calls = vec![
+        contract
+            .update_wallet(
+                serialize_to_calldata(&update_wallet_proof_0)?,
+                serialize_to_calldata(&update_wallet_statement_0)?,
+                update_wallet_commitment_signature_0,
+                Bytes::new(), /* transfer_aux_data */
+            )
+            .calldata()
+            .unwrap(),
+        contract
+            .update_wallet(
+                serialize_to_calldata(&update_wallet_proof_1)?,
+                serialize_to_calldata(&update_wallet_statement_1)?,
+                update_wallet_commitment_signature_1,
+                Bytes::new(), /* transfer_aux_data */
+            )
+            .calldata()
+            .unwrap(),
+        contract
+            .settle_online_relayer_fee(
+                serialize_to_calldata(&valid_relayer_fee_settlement_proof)?,
+                serialize_to_calldata(&valid_relayer_fee_settlement_statement)?,
+                relayer_wallet_commitment_signature,
+            )
+            .calldata()
+            .unwrap(),
+        contract
+            .settle_offline_fee(
+                serialize_to_calldata(&valid_offline_fee_settlement_proof)?,
+                serialize_to_calldata(&valid_offline_fee_settlement_statement)?,
+            )
+            .calldata()
+            .unwrap(),
+    ];
+
+    contract.multicall(calls).send().await?.await?;
+
+    // Assert that the nullifiers of all the calls are spent
+    let nullifiers = [
+        update_wallet_statement_0.old_shares_nullifier,
+        update_wallet_statement_1.old_shares_nullifier,
+        valid_relayer_fee_settlement_statement.sender_nullifier,
+        valid_relayer_fee_settlement_statement.recipient_nullifier,
+        valid_offline_fee_settlement_statement.nullifier,
+    ];
+
+    for nullifier in nullifiers {
+        let nullifier_spent = contract
+            .is_nullifier_spent(scalar_to_u256(nullifier))
+            .call()
+            .await?;
+        assert!(nullifier_spent, "Nullifier not spent");
+    }
+
+    Ok(())
+}
+integration_test_async!(test_multicall);
+
+/// Test that the `multicall` method on the darkpool reverts
+/// the entire bundle if any of its calls fail
+#[allow(non_snake_case)]
+async fn test_multicall__atomicity(test_args: TestArgs) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    // Generate test data
+    let mut rng = thread_rng();
+
+    let contract_root = Scalar::new(u256_to_scalar(contract.get_root().call().await?)?);
+    let (new_wallet_proof, new_wallet_statement) = gen_new_wallet_data(&mut rng)?;
+    let (update_wallet_proof, update_wallet_statement, update_wallet_commitment_signature) =
+        gen_update_wallet_data(&mut rng, contract_root)?;
+
+    let new_wallet_call = contract
+        .new_wallet(
+            serialize_to_calldata(&new_wallet_proof)?,
+            serialize_to_calldata(&new_wallet_statement)?,
+        )
+        .calldata()
+        .unwrap();
+    let update_wallet_call = contract
+        .update_wallet(
+            serialize_to_calldata(&update_wallet_proof)?,
+            serialize_to_calldata(&update_wallet_statement)?,
+            update_wallet_commitment_signature,
+            Bytes::new(), /* transfer_aux_data */
+        )
+        .calldata()
+        .unwrap();
+
+    // Assert that a bundle reverts if a call fails, here by spending the same nullifier twice,
+    // if it contains a call to a non-core method, or if it contains a paused operation
+    contract
+        .set_operation_paused(NEW_WALLET_OPERATION, true)
+        .send()
+        .await?
+        .await?;
+
+    assert_all_revert(vec![
+        contract
+            .multicall(vec![update_wallet_call.clone(), update_wallet_call.clone()])
+            .send(),
+        contract
+            .multicall(vec![
+                update_wallet_call.clone(),
+                contract.pause().calldata().unwrap(),
+            ])
+            .send(),
+        contract
+            .multicall(vec![update_wallet_call.clone(), new_wallet_call])
+            .send(),
+    ])
+    .await?;
+
+    contract
+        .set_operation_paused(NEW_WALLET_OPERATION, false)
+        .send()
+        .await?
+        .await?;
+
+    // Assert that none of the reverted bundles' calls took effect
+    let nullifier = scalar_to_u256(update_wallet_statement.old_shares_nullifier);
+    let nullifier_spent = contract.is_nullifier_spent(nullifier).call().await?;
+    assert!(!nullifier_spent, "Nullifier spent by reverted bundle");
+    assert!(
+        !contract.paused().call().await?,
+        "Contract paused by reverted bundle"
+    );
+
+    // Assert that the call succeeds on its own
+    assert_all_succeed(vec![contract.multicall(vec![update_wallet_call]).send()]).await?;
+
+    Ok(())
+}
+integration_test_async!(test_multicall__atomicity);
+
+/// Test that the `multicall` method on the darkpool rejects nested multicalls,
+/// and that a rejected bundle does not leave the multicall guard set
+#[allow(non_snake_case)]
+async fn test_multicall__nested(test_args: TestArgs) -> Result<()> {
+    let contract = DarkpoolTestContract::new(test_args.darkpool_proxy_address, test_args.client);
+
+    // Ensure the merkle state is cleared for the test
+    contract.clear_merkle().send().await?.await?;
+
+    // Generate test data
+    let mut rng = thread_rng();
+
+    let (new_wallet_proof, new_wallet_statement) = gen_new_wallet_data(&mut rng)?;
+    let new_wallet_call = contract
+        .new_wallet(
+            serialize_to_calldata(&new_wallet_proof)?,
+            serialize_to_calldata(&new_wallet_statement)?,
+        )
+        .calldata()
+        .unwrap();
+    let nested_multicall_call = contract
+        .multicall(vec![new_wallet_call.clone()])
+        .calldata()
+        .unwrap();
+
+    // Assert that a bundle containing a multicall reverts
+    assert_all_revert(vec![contract
+        .multicall(vec![new_wallet_call.clone(), nested_multicall_call])
+        .send()])
+    .await?;
+
+    // Assert that the guard was not left set by the reverted bundle
+    assert_all_succeed(vec![contract.multicall(vec![new_wallet_call]).send()]).await?;
+
+    Ok(())
+}
+integration_test_async!(test_multicall__nested);
+
 // TODO: Add test cases covering invalid historical Merkle roots,
 // invalid signatures over wallet commitments, and duplicate nullifiers
@@ -1,6 +1,6 @@
 //! Utilities for running integration tests
 
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use alloy_primitives::{keccak256, Address as AlloyAddress, B256, U256 as AlloyU256};
 use alloy_sol_types::{
@@ -16,28 +16,35 @@ use contracts_common::{
     custom_serde::{pk_to_u256s, BytesDeserializable, BytesSerializable},
     solidity::{DepositWitness, PermitWitnessTransferFrom, TokenPermissions},
     types::{
-        ExternalTransfer, MatchLinkingProofs, MatchLinkingVkeys, MatchProofs, MatchPublicInputs,
-        MatchVkeys, Proof, PublicInputs, PublicSigningKey, ScalarField, TransferAuxData,
-        VerificationKey,
+        ExtendedProof, ExtendedVerificationKey, ExternalTransfer, MatchLinkingProofs,
+        MatchLinkingVkeys, MatchProofs, MatchPublicInputs, MatchVkeys, PublicInputs,
+        PublicSigningKey, ScalarField, TransferAuxData,
     },
 };
 use contracts_core::crypto::poseidon::compute_poseidon_hash;
 use contracts_utils::{crypto::hash_and_sign_message, merkle::MerkleConfig};
 use ethers::{
     abi::{Address, Detokenize, Tokenize},
-    contract::ContractError,
+    contract::{ContractError, EthEvent},
     core::k256::ecdsa::SigningKey,
     providers::{JsonRpcClient, Middleware, PendingTransaction},
     signers::{LocalWallet, Signer},
-    types::{Bytes, H256, U256},
+    types::{Bytes, Log, H256, U256},
 };
 use eyre::{eyre, Result};
 use rand::{thread_rng, RngCore};
-use scripts::{constants::TEST_FUNDING_AMOUNT, utils::LocalWalletHttpClient};
+use scripts::{
+    constants::{TEST_ADDRESS_CHANGE_DELAY, TEST_FUNDING_AMOUNT},
+    utils::LocalWalletHttpClient,
+};
 use serde::Serialize;
+use tokio::time::sleep;
 
 use crate::{
-    abis::{DarkpoolTestContract, DummyErc20Contract, TransferExecutorContract},
+    abis::{
+        DarkpoolTestContract, DummyErc20Contract, MerkleInsertionFilter, MerkleOpeningNodeFilter,
+        TransferExecutorContract,
+    },
     constants::PERMIT2_EIP712_DOMAIN_NAME,
 };
 
@@ -78,6 +85,37 @@ pub async fn assert_only_owner<T: Tokenize + Clone, D: Detokenize>(
     Ok(())
 }
 
+/// Schedules a change of the implementation address for the given address selector,
+/// waits for the address change delay to elapse, and executes the change
+pub async fn schedule_and_execute_address_change(
+    contract: &DarkpoolTestContract<LocalWalletHttpClient>,
+    address_selector: u8,
+    new_address: Address,
+) -> Result<()> {
+    contract
+        .schedule_address_change(address_selector, new_address)
+        .send()
+        .await?
+        .await?;
+
+    wait_for_address_change_delay().await;
+
+    contract
+        .execute_address_change(address_selector)
+        .send()
+        .await?
+        .await?;
+
+    Ok(())
+}
+
+/// Waits until the address change delay of the testing darkpool has elapsed
+pub async fn wait_for_address_change_delay() {
+    // Wait an extra second to ensure that the next block's timestamp
+    // is past the effective timestamp of the address change
+    sleep(Duration::from_secs(TEST_ADDRESS_CHANGE_DELAY + 1)).await;
+}
+
 /// Asserts that all the given transactions revert
 pub async fn assert_all_revert<'a>(
     txs: Vec<
@@ -142,8 +180,8 @@ pub fn serialize_to_calldata<T: Serialize>(t: &T) -> Result<Bytes> {
 /// Serializes the given bundle of verification key, proof, and public inputs
 /// into a [`Bytes`] object that can be passed in as calldata
 pub fn serialize_verification_bundle(
-    vkey: &VerificationKey,
-    proof: &Proof,
+    vkey: &ExtendedVerificationKey,
+    proof: &ExtendedProof,
     public_inputs: &PublicInputs,
 ) -> Result<Bytes> {
     let vkey_ser: Vec<u8> = postcard::to_allocvec(vkey)?;
@@ -155,12 +193,28 @@ pub fn serialize_verification_bundle(
     Ok(bundle_bytes.into())
 }
 
+/// Serializes the given batch of verification keys, proofs, and public inputs
+/// into a [`Bytes`] object that can be passed in as calldata
+pub fn serialize_batch_verification_bundle(
+    vkeys: &[ExtendedVerificationKey],
+    proofs: &[ExtendedProof],
+    public_inputs: &[PublicInputs],
+) -> Result<Bytes> {
+    let vkeys_ser: Vec<u8> = postcard::to_allocvec(vkeys)?;
+    let proofs_ser: Vec<u8> = postcard::to_allocvec(proofs)?;
+    let public_inputs_ser: Vec<u8> = postcard::to_allocvec(public_inputs)?;
+
+    let bundle_bytes = [vkeys_ser, proofs_ser, public_inputs_ser].concat();
+
+    Ok(bundle_bytes.into())
+}
+
 /// Serializes the given bundle of verification key, proof, and public inputs
 /// used in a match into a [`Bytes`] object that can be passed in as calldata
 pub fn serialize_match_verification_bundle(
     match_vkeys: &MatchVkeys,
     match_linking_vkeys: &MatchLinkingVkeys,
-    match_proofs: &MatchProofs,
+    match_proofs: &MatchProofs<ExtendedProof>,
     match_public_inputs: &MatchPublicInputs,
     match_linking_proofs: &MatchLinkingProofs,
 ) -> Result<Bytes> {
@@ -182,6 +236,33 @@ pub fn serialize_match_verification_bundle(
     Ok(bundle_bytes.into())
 }
 
+/// Serializes the given bundles of match proofs, public inputs, & linking proofs
+/// into a single byte array for verification as a batch
+pub fn serialize_match_batch_verification_bundle(
+    match_vkeys: &MatchVkeys,
+    match_linking_vkeys: &MatchLinkingVkeys,
+    match_proofs_batch: &[MatchProofs<ExtendedProof>],
+    match_public_inputs_batch: &[MatchPublicInputs],
+    match_linking_proofs_batch: &[MatchLinkingProofs],
+) -> Result<Bytes> {
+    let match_vkeys_ser: Vec<u8> = postcard::to_allocvec(match_vkeys)?;
+    let match_linking_vkeys_ser: Vec<u8> = postcard::to_allocvec(match_linking_vkeys)?;
+    let match_proofs_ser: Vec<u8> = postcard::to_allocvec(match_proofs_batch)?;
+    let match_public_inputs_ser: Vec<u8> = postcard::to_allocvec(match_public_inputs_batch)?;
+    let match_linking_proofs_ser: Vec<u8> = postcard::to_allocvec(match_linking_proofs_batch)?;
+
+    let bundle_bytes = [
+        match_vkeys_ser,
+        match_linking_vkeys_ser,
+        match_proofs_ser,
+        match_public_inputs_ser,
+        match_linking_proofs_ser,
+    ]
+    .concat();
+
+    Ok(bundle_bytes.into())
+}
+
 /// Creates an [`ExternalTransfer`] object for the given account address,
 /// mint address, and transfer direction
 fn dummy_erc20_external_transfer(
@@ -249,6 +330,24 @@ pub(crate) async fn execute_transfer_and_get_balances(
     Ok((darkpool_balance, user_balance))
 }
 
+/// Filters the given logs down to the Merkle tree insertion & opening events,
+/// returning the topics & data of each
+pub(crate) fn merkle_tree_events(logs: Vec<Log>) -> Vec<(Vec<H256>, Bytes)> {
+    let event_signatures = [
+        MerkleOpeningNodeFilter::signature(),
+        MerkleInsertionFilter::signature(),
+    ];
+
+    logs.into_iter()
+        .filter(|log| {
+            log.topics
+                .first()
+                .is_some_and(|topic| event_signatures.contains(topic))
+        })
+        .map(|log| (log.topics, log.data))
+        .collect()
+}
+
 /// Computes a commitment to the given wallet shares, inserts them
 /// into the given Arkworks Merkle tree, and returns the new root
 pub(crate) fn insert_shares_and_get_root(
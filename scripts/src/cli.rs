@@ -7,8 +7,10 @@ use clap::{Args, Parser, Subcommand};
 use crate::{
     commands::{
         build_and_deploy_stylus_contract, deploy_erc20s, deploy_permit2, deploy_proxy,
-        deploy_test_contracts, gen_vkeys, upgrade,
+        deploy_test_contracts, diagnose_proof, gen_transcript_vectors, gen_vkeys, profile_verifier,
+        register_vkeys, upgrade, verify,
     },
+    constants::{NUM_TRANSCRIPT_TEST_VECTORS, VKEY_UPLOAD_CHUNK_SIZE},
     errors::ScriptError,
     types::StylusContract,
     utils::LocalWalletHttpClient,
@@ -52,6 +54,16 @@ pub enum Command {
     Upgrade(UpgradeArgs),
     /// Generate verification keys for the protocol circuits
     GenVkeys(GenVkeysArgs),
+    /// Upload the verification keys for the protocol circuits to the vkey registry
+    RegisterVkeys(RegisterVkeysArgs),
+    /// Verify a proof natively, printing a trace of the verification algorithm
+    DiagnoseProof(DiagnoseProofArgs),
+    /// Profile the precompile calls & hashes made when verifying proofs of the protocol circuits
+    ProfileVerifier(ProfileVerifierArgs),
+    /// Verify the proofs given for a call to one of the darkpool's methods natively
+    Verify(VerifyArgs),
+    /// Generate test vectors for the Fiat-Shamir transcript by recording the Jellyfish prover
+    GenTranscriptVectors(GenTranscriptVectorsArgs),
 }
 
 impl Command {
@@ -78,6 +90,11 @@ impl Command {
             }
             Command::Upgrade(args) => upgrade(args, client, deployments_path).await,
             Command::GenVkeys(args) => gen_vkeys(args),
+            Command::RegisterVkeys(args) => register_vkeys(args, client, deployments_path).await,
+            Command::DiagnoseProof(args) => diagnose_proof(args),
+            Command::ProfileVerifier(args) => profile_verifier(args),
+            Command::Verify(args) => verify(args),
+            Command::GenTranscriptVectors(args) => gen_transcript_vectors(args),
         }
     }
 }
@@ -128,6 +145,13 @@ pub struct DeployProxyArgs {
     /// If not provided, a random key will be generated.
     #[arg(short, long)]
     pub protocol_public_encryption_key: Option<String>,
+
+    /// The delay, in seconds, between scheduling a change to one of the
+    /// darkpool's implementation addresses and being able to execute it.
+    ///
+    /// The default value here is 1 day
+    #[arg(short, long, default_value = "86400")]
+    pub address_change_delay: u64,
 }
 
 /// Deploy a Stylus contract
@@ -181,3 +205,154 @@ pub struct GenVkeysArgs {
     #[arg(short, long)]
     pub test: bool,
 }
+
+/// Upload the verification keys for the protocol circuits to the vkey registry.
+///
+/// The verification keys are uploaded in chunks, and the upload of each key resumes
+/// from the number of bytes already stored in the registry, so that an interrupted
+/// registration can be completed by re-running the command.
+#[derive(Args)]
+pub struct RegisterVkeysArgs {
+    /// The directory from which to read the verification keys,
+    /// as written by the `gen-vkeys` command
+    #[arg(short, long)]
+    pub vkeys_dir: String,
+
+    /// The version under which to register the verification keys.
+    /// Version 0 is reserved, and cannot be registered
+    #[arg(long)]
+    pub version: u64,
+
+    /// The size, in bytes, of the chunks in which to upload the verification keys
+    #[arg(long, default_value_t = VKEY_UPLOAD_CHUNK_SIZE)]
+    pub chunk_size: usize,
+}
+
+/// Verify a proof natively, printing a trace of the verification algorithm
+#[derive(Args)]
+pub struct DiagnoseProofArgs {
+    /// Path to the file containing the serialized verification key
+    #[arg(long)]
+    pub vkey_path: String,
+
+    /// Path to the file containing the serialized proof
+    #[arg(long)]
+    pub proof_path: String,
+
+    /// Path to the file containing the serialized public inputs of the statement
+    #[arg(long)]
+    pub statement_path: String,
+}
+
+/// Profile the precompile calls & hashes made when verifying proofs of the protocol circuits,
+/// writing a JSON report of the counts & estimated gas cost of each step of the verifier.
+///
+/// The proofs profiled are dummy proofs of the shape expected by the verification keys,
+/// so the profile only depends on the verification keys.
+#[derive(Args)]
+pub struct ProfileVerifierArgs {
+    /// The directory from which to read the verification keys,
+    /// e.g. `contracts-stylus/vkeys/prod`
+    #[arg(short, long)]
+    pub vkeys_dir: String,
+
+    /// The path to which to write the JSON report
+    #[arg(short, long)]
+    pub output_path: String,
+}
+
+/// Verify the proofs given for a call to one of the darkpool's methods natively,
+/// running the same checks on its inputs as the darkpool core contract.
+///
+/// Checks against the darkpool's state, i.e. the Merkle root history, the nullifier set,
+/// the set of used public blinders, and the protocol public encryption key, are not run.
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// The directory from which to read the verification keys,
+    /// e.g. `contracts-stylus/vkeys/prod`
+    #[arg(short, long)]
+    pub vkeys_dir: String,
+
+    /// The darkpool method whose inputs to verify
+    #[command(subcommand)]
+    pub method: VerifyMethod,
+}
+
+/// The darkpool methods whose inputs can be verified natively
+#[derive(Subcommand)]
+pub enum VerifyMethod {
+    /// Verify the inputs to `newWallet`
+    NewWallet(VerifyProofArgs),
+    /// Verify the inputs to `updateWallet`
+    UpdateWallet(VerifyProofArgs),
+    /// Verify the inputs to `processMatchSettle`
+    ProcessMatchSettle(VerifyMatchSettleArgs),
+    /// Verify the inputs to `processMatchSettleBatch`.
+    ///
+    /// Each of the files given contains the serialization of a vector,
+    /// with one element per match in the batch
+    ProcessMatchSettleBatch(VerifyMatchSettleArgs),
+    /// Verify the inputs to `settleOnlineRelayerFee`
+    SettleOnlineRelayerFee(VerifyProofArgs),
+    /// Verify the inputs to `settleOfflineFee`
+    SettleOfflineFee(VerifyProofArgs),
+    /// Verify the inputs to `redeemFee`
+    RedeemFee(VerifyProofArgs),
+}
+
+/// The inputs to a darkpool method verifying a single proof
+#[derive(Args)]
+pub struct VerifyProofArgs {
+    /// Path to the file containing the serialized proof
+    #[arg(long)]
+    pub proof_path: String,
+
+    /// Path to the file containing the serialized statement
+    #[arg(long)]
+    pub statement_path: String,
+}
+
+/// The inputs to a darkpool method settling matched trades
+#[derive(Args)]
+pub struct VerifyMatchSettleArgs {
+    /// Path to the file containing the serialized match payload of the first party
+    #[arg(long)]
+    pub party_0_match_payload_path: String,
+
+    /// Path to the file containing the serialized match payload of the second party
+    #[arg(long)]
+    pub party_1_match_payload_path: String,
+
+    /// Path to the file containing the serialized `VALID MATCH SETTLE` statement,
+    /// including the base mint of the traded pair
+    #[arg(long)]
+    pub statement_path: String,
+
+    /// Path to the file containing the serialized Plonk proofs of the match
+    #[arg(long)]
+    pub match_proofs_path: String,
+
+    /// Path to the file containing the serialized linking proofs of the match
+    #[arg(long)]
+    pub match_linking_proofs_path: String,
+
+    /// A space-separated list of the protocol fees configured in the darkpool for
+    /// the base mint of each match, as fixed-point numbers, in the order of the matches
+    #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+    pub protocol_fees: Vec<u64>,
+}
+
+/// Generate test vectors for the Fiat-Shamir transcript by recording the messages absorbed
+/// & challenges squeezed by the Jellyfish prover when proving random dummy circuits
+#[derive(Args)]
+pub struct GenTranscriptVectorsArgs {
+    /// The path to which to write the JSON test vectors,
+    /// e.g. `contracts-core/test-vectors/transcript.json`
+    #[arg(short, long)]
+    pub output_path: String,
+
+    /// The number of test vectors to generate for each of the Plonk, lookup,
+    /// and linking proof transcripts
+    #[arg(short, long, default_value_t = NUM_TRANSCRIPT_TEST_VECTORS)]
+    pub num_vectors: usize,
+}
@@ -9,6 +9,28 @@ use circuits::zk_circuits::{
     valid_relayer_fee_settlement::SizedValidRelayerFeeSettlement,
     valid_wallet_create::SizedValidWalletCreate, valid_wallet_update::SizedValidWalletUpdate,
 };
+use contracts_common::{
+    constants::{
+        PROCESS_MATCH_SETTLE_CIRCUIT_ID, VALID_FEE_REDEMPTION_CIRCUIT_ID,
+        VALID_OFFLINE_FEE_SETTLEMENT_CIRCUIT_ID, VALID_RELAYER_FEE_SETTLEMENT_CIRCUIT_ID,
+        VALID_WALLET_CREATE_CIRCUIT_ID, VALID_WALLET_UPDATE_CIRCUIT_ID,
+    },
+    custom_serde::{statement_to_public_inputs, ScalarSerializable},
+    types::{
+        ExtendedProof, ExtendedVerificationKey, MatchLinkingProofs, MatchPayload, MatchProofs,
+        PublicInputs, ValidFeeRedemptionStatement, ValidMatchSettleWithMintStatement,
+        ValidOfflineFeeSettlementStatement, ValidRelayerFeeSettlementStatement,
+        ValidWalletCreateStatement, ValidWalletUpdateStatement,
+    },
+};
+use contracts_core::{
+    darkpool::check_match_settle_batch_lengths,
+    native::{NativeG1ArithmeticBackend, NativeHasher},
+    verifier::{
+        profiling::{CountingG1ArithmeticBackend, CountingHasher},
+        Verifier,
+    },
+};
 use contracts_utils::{
     conversion::to_contract_vkey,
     proof_system::{
@@ -18,6 +40,7 @@ use contracts_utils::{
             DummyValidWalletCreate, DummyValidWalletUpdate,
         },
         gen_match_linking_vkeys, gen_match_vkeys,
+        transcript_vectors::gen_transcript_test_vectors,
     },
 };
 use ethers::{
@@ -27,34 +50,50 @@ use ethers::{
     types::{Bytes, H256, U256 as EthersU256},
     utils::hex::FromHex,
 };
+use json::JsonValue;
 use rand::{thread_rng, Rng};
-use std::{env, str::FromStr, sync::Arc};
+use serde::de::DeserializeOwned;
+use std::{env, fs, str::FromStr, sync::Arc};
 use tracing::log::info;
 
 use crate::{
     cli::{
-        DeployErc20sArgs, DeployProxyArgs, DeployStylusArgs, DeployTestContractsArgs, GenVkeysArgs,
-        UpgradeArgs,
+        DeployErc20sArgs, DeployProxyArgs, DeployStylusArgs, DeployTestContractsArgs,
+        DiagnoseProofArgs, GenTranscriptVectorsArgs, GenVkeysArgs, ProfileVerifierArgs,
+        RegisterVkeysArgs, UpgradeArgs, VerifyArgs, VerifyMatchSettleArgs, VerifyMethod,
+        VerifyProofArgs,
     },
     constants::{
         DARKPOOL_PROXY_ADMIN_CONTRACT_KEY, DARKPOOL_PROXY_CONTRACT_KEY, DUMMY_ERC20_SYMBOL_ENV_VAR,
         NUM_BYTES_ADDRESS, NUM_BYTES_STORAGE_SLOT, NUM_DEPLOY_CONFIRMATIONS, PERMIT2_ABI,
         PERMIT2_BYTECODE, PERMIT2_CONTRACT_KEY, PROCESS_MATCH_SETTLE_VKEYS_FILE, PROXY_ABI,
-        PROXY_ADMIN_STORAGE_SLOT, PROXY_BYTECODE, TEST_ERC20_TICKER, TEST_FUNDING_AMOUNT,
-        VALID_FEE_REDEMPTION_VKEY_FILE, VALID_OFFLINE_FEE_SETTLEMENT_VKEY_FILE,
-        VALID_RELAYER_FEE_SETTLEMENT_VKEY_FILE, VALID_WALLET_CREATE_VKEY_FILE,
-        VALID_WALLET_UPDATE_VKEY_FILE,
+        PROXY_ADMIN_STORAGE_SLOT, PROXY_BYTECODE, TEST_ADDRESS_CHANGE_DELAY, TEST_ERC20_TICKER,
+        TEST_FUNDING_AMOUNT, TEST_VKEY_VERSION, VALID_FEE_REDEMPTION_VKEY_FILE,
+        VALID_OFFLINE_FEE_SETTLEMENT_VKEY_FILE, VALID_RELAYER_FEE_SETTLEMENT_VKEY_FILE,
+        VALID_WALLET_CREATE_VKEY_FILE, VALID_WALLET_UPDATE_VKEY_FILE, VKEY_REGISTRY_CONTRACT_KEY,
+        VKEY_REGISTRY_PROXY_ADMIN_CONTRACT_KEY, VKEY_UPLOAD_CHUNK_SIZE,
     },
     errors::ScriptError,
-    solidity::{DummyErc20Contract, ProxyAdminContract},
+    solidity::{DummyErc20Contract, ProxyAdminContract, VkeyRegistryContract},
     types::{RenegadeVerificationKeys, StylusContract},
     utils::{
-        build_stylus_contract, darkpool_initialize_calldata, deploy_stylus_contract,
-        get_contract_key, get_public_encryption_key, parse_addr_from_deployments_file,
-        setup_client, write_deployed_address, write_vkey_file, LocalWalletHttpClient,
+        build_stylus_contract, check_match_settle_inputs, darkpool_initialize_calldata,
+        deploy_stylus_contract, get_contract_key, get_public_encryption_key,
+        match_payloads_to_public_inputs, parse_addr_from_deployments_file, read_match_vkeys,
+        read_serialized_file, read_vkey, read_vkey_file, setup_client,
+        verification_profile_to_json, write_deployed_address, write_vkey_file,
+        write_vkey_hashes_file, DummyProofGenerator, LocalWalletHttpClient,
     },
 };
 
+/// The verifier used to profile the precompile calls & hashes made during verification,
+/// wrapping the native backends in counting backends
+type ProfilingVerifier =
+    Verifier<CountingG1ArithmeticBackend<NativeG1ArithmeticBackend>, CountingHasher<NativeHasher>>;
+
+/// The verifier used to verify proofs natively
+type NativeVerifier = Verifier<NativeG1ArithmeticBackend, NativeHasher>;
+
 /// Builds & deploys all of the contracts necessary for running the integration testing suite.
 ///
 /// This includes generating fresh verification keys for testing.
@@ -124,6 +163,25 @@ pub async fn deploy_test_contracts(
     )
     .await?;
 
+    info!("Deploying vkey registry contract");
+    deploy_stylus_args.contract = StylusContract::VkeyRegistry;
+    build_and_deploy_stylus_contract(
+        deploy_stylus_args,
+        rpc_url,
+        priv_key,
+        client.clone(),
+        deployments_path,
+    )
+    .await?;
+
+    info!("Registering testing verification keys");
+    let register_vkeys_args = RegisterVkeysArgs {
+        vkeys_dir: args.vkeys_dir.clone(),
+        version: TEST_VKEY_VERSION,
+        chunk_size: VKEY_UPLOAD_CHUNK_SIZE,
+    };
+    register_vkeys(register_vkeys_args, client.clone(), deployments_path).await?;
+
     info!("Deploying transfer executor contract");
     deploy_stylus_args.contract = StylusContract::TransferExecutor;
     build_and_deploy_stylus_contract(
@@ -191,6 +249,7 @@ pub async fn deploy_test_contracts(
         owner: args.owner,
         fee: thread_rng().gen(),
         protocol_public_encryption_key: None,
+        address_change_delay: TEST_ADDRESS_CHANGE_DELAY,
     };
     deploy_proxy(deploy_proxy_args, client, deployments_path).await?;
 
@@ -251,6 +310,8 @@ pub async fn deploy_proxy(
     let protocol_public_encryption_key =
         get_public_encryption_key(args.protocol_public_encryption_key)?;
 
+    let address_change_delay = U256::from(args.address_change_delay);
+
     let darkpool_calldata = Bytes::from(darkpool_initialize_calldata(
         darkpool_core_address,
         verifier_address,
@@ -260,6 +321,7 @@ pub async fn deploy_proxy(
         permit2_address,
         protocol_fee,
         protocol_public_encryption_key,
+        address_change_delay,
     )?);
 
     // Deploy proxy contract
@@ -278,21 +340,7 @@ pub async fn deploy_proxy(
         proxy_address
     );
 
-    // Get proxy admin contract address
-    // This is the recommended way to get the proxy admin address:
-    // https://github.com/OpenZeppelin/openzeppelin-contracts/blob/v5.0.0/contracts/proxy/ERC1967/ERC1967Utils.sol#L104-L106
-    let proxy_admin_address = Address::from_slice(
-        &client
-            .get_storage_at(
-                proxy_address,
-                // Can `unwrap` here since we know the storage slot constitutes a valid H256
-                H256::from_str(PROXY_ADMIN_STORAGE_SLOT).unwrap(),
-                None, /* block */
-            )
-            .await
-            .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?
-            [NUM_BYTES_STORAGE_SLOT - NUM_BYTES_ADDRESS..NUM_BYTES_STORAGE_SLOT],
-    );
+    let proxy_admin_address = get_proxy_admin_address(&client, proxy_address).await?;
 
     info!(
         "Proxy admin contract deployed at address:\n\t{:#x}",
@@ -308,6 +356,91 @@ pub async fn deploy_proxy(
     )
 }
 
+/// Deploys a proxy for the vkey registry implementation at the given address.
+///
+/// The proxy initializes the vkey registry in its constructor, so that ownership of the
+/// registry cannot be taken by front-running its initialization. The proxy is recorded
+/// as the vkey registry in the deployments file, as it is the address holding the registry's state
+async fn deploy_vkey_registry_proxy(
+    implementation_address: Address,
+    client: Arc<LocalWalletHttpClient>,
+    deployments_path: &str,
+) -> Result<(), ScriptError> {
+    // Get proxy contract ABI and bytecode
+    let abi: Contract =
+        serde_json::from_str(PROXY_ABI).map_err(|e| ScriptError::ArtifactParsing(e.to_string()))?;
+
+    let bytecode =
+        Bytes::from_hex(PROXY_BYTECODE).map_err(|e| ScriptError::ArtifactParsing(e.to_string()))?;
+
+    let proxy_factory = ContractFactory::new(abi, bytecode, client.clone());
+
+    // The deployer becomes both the owner of the vkey registry & of its proxy admin
+    let owner_address = client.default_sender().unwrap();
+    let vkey_registry_calldata = VkeyRegistryContract::new(implementation_address, client.clone())
+        .initialize()
+        .calldata()
+        .ok_or(ScriptError::CalldataConstruction(
+            "failed to encode vkey registry initialization".to_string(),
+        ))?;
+
+    // Deploy proxy contract
+    let proxy_contract = proxy_factory
+        .deploy((
+            implementation_address,
+            owner_address,
+            vkey_registry_calldata,
+        ))
+        .map_err(|e| ScriptError::ContractDeployment(e.to_string()))?
+        .confirmations(NUM_DEPLOY_CONFIRMATIONS)
+        .send()
+        .await
+        .map_err(|e| ScriptError::ContractDeployment(e.to_string()))?;
+
+    let proxy_address = proxy_contract.address();
+
+    info!(
+        "Vkey registry proxy contract deployed at address:\n\t{:#x}",
+        proxy_address
+    );
+
+    let proxy_admin_address = get_proxy_admin_address(&client, proxy_address).await?;
+
+    info!(
+        "Vkey registry proxy admin contract deployed at address:\n\t{:#x}",
+        proxy_admin_address
+    );
+
+    // Write deployed addresses to deployments file
+    write_deployed_address(deployments_path, VKEY_REGISTRY_CONTRACT_KEY, proxy_address)?;
+    write_deployed_address(
+        deployments_path,
+        VKEY_REGISTRY_PROXY_ADMIN_CONTRACT_KEY,
+        proxy_admin_address,
+    )
+}
+
+/// Fetches the address of the admin contract of the proxy at the given address
+async fn get_proxy_admin_address(
+    client: &LocalWalletHttpClient,
+    proxy_address: Address,
+) -> Result<Address, ScriptError> {
+    // This is the recommended way to get the proxy admin address:
+    // https://github.com/OpenZeppelin/openzeppelin-contracts/blob/v5.0.0/contracts/proxy/ERC1967/ERC1967Utils.sol#L104-L106
+    Ok(Address::from_slice(
+        &client
+            .get_storage_at(
+                proxy_address,
+                // Can `unwrap` here since we know the storage slot constitutes a valid H256
+                H256::from_str(PROXY_ADMIN_STORAGE_SLOT).unwrap(),
+                None, /* block */
+            )
+            .await
+            .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?
+            [NUM_BYTES_STORAGE_SLOT - NUM_BYTES_ADDRESS..NUM_BYTES_STORAGE_SLOT],
+    ))
+}
+
 /// Deploys the `Permit2` contract
 pub async fn deploy_permit2(
     client: Arc<LocalWalletHttpClient>,
@@ -430,17 +563,24 @@ pub async fn build_and_deploy_stylus_contract(
     deployments_path: &str,
 ) -> Result<(), ScriptError> {
     let wasm_file_path = build_stylus_contract(args.contract, args.no_verify)?;
-    deploy_stylus_contract(
+    let deployed_address = deploy_stylus_contract(
         wasm_file_path,
         rpc_url,
         priv_key,
-        client,
+        client.clone(),
         args.contract,
         deployments_path,
         None,
     )
-    .await
-    .map(|_| ())
+    .await?;
+
+    // The vkey registry is deployed behind a proxy which initializes it atomically
+    if let StylusContract::VkeyRegistry = args.contract {
+        info!("Deploying vkey registry proxy contract");
+        deploy_vkey_registry_proxy(deployed_address, client, deployments_path).await?;
+    }
+
+    Ok(())
 }
 
 /// Upgrades the darkpool implementation
@@ -520,7 +660,8 @@ fn compute_vkeys<
     })
 }
 
-/// Write the protocol verification keys to the specified directory
+/// Write the protocol verification keys, and the hashes committing to them,
+/// to the specified directory
 fn write_vkeys(vkeys_dir: &str, vkeys: &RenegadeVerificationKeys) -> Result<(), ScriptError> {
     let valid_wallet_create = postcard::to_allocvec(&vkeys.valid_wallet_create)
         .map_err(|e| ScriptError::Serde(e.to_string()))?;
@@ -545,24 +686,44 @@ fn write_vkeys(vkeys_dir: &str, vkeys: &RenegadeVerificationKeys) -> Result<(),
     // The match vkeys & linking vkeys are serialized together
     let process_match_settle = [match_vkeys, match_linking_vkeys].concat();
 
-    for (file, data) in [
-        (VALID_WALLET_CREATE_VKEY_FILE, valid_wallet_create),
-        (VALID_WALLET_UPDATE_VKEY_FILE, valid_wallet_update),
+    let vkey_files = [
+        (
+            VALID_WALLET_CREATE_VKEY_FILE,
+            VALID_WALLET_CREATE_CIRCUIT_ID,
+            valid_wallet_create,
+        ),
+        (
+            VALID_WALLET_UPDATE_VKEY_FILE,
+            VALID_WALLET_UPDATE_CIRCUIT_ID,
+            valid_wallet_update,
+        ),
         (
             VALID_RELAYER_FEE_SETTLEMENT_VKEY_FILE,
+            VALID_RELAYER_FEE_SETTLEMENT_CIRCUIT_ID,
             valid_relayer_fee_settlement,
         ),
         (
             VALID_OFFLINE_FEE_SETTLEMENT_VKEY_FILE,
+            VALID_OFFLINE_FEE_SETTLEMENT_CIRCUIT_ID,
             valid_offline_fee_settlement,
         ),
-        (VALID_FEE_REDEMPTION_VKEY_FILE, valid_fee_redemption),
-        (PROCESS_MATCH_SETTLE_VKEYS_FILE, process_match_settle),
-    ] {
-        write_vkey_file(vkeys_dir, file, &data)?;
+        (
+            VALID_FEE_REDEMPTION_VKEY_FILE,
+            VALID_FEE_REDEMPTION_CIRCUIT_ID,
+            valid_fee_redemption,
+        ),
+        (
+            PROCESS_MATCH_SETTLE_VKEYS_FILE,
+            PROCESS_MATCH_SETTLE_CIRCUIT_ID,
+            process_match_settle,
+        ),
+    ];
+
+    for (file, _, data) in &vkey_files {
+        write_vkey_file(vkeys_dir, file, data)?;
     }
 
-    Ok(())
+    write_vkey_hashes_file(vkeys_dir, &vkey_files)
 }
 
 /// Generates and writes either the testing or production protocol verification keys
@@ -594,3 +755,430 @@ pub fn gen_vkeys(args: GenVkeysArgs) -> Result<(), ScriptError> {
 
     write_vkeys(&args.vkeys_dir, &vkeys)
 }
+
+/// Uploads the protocol verification keys in the given directory to the vkey registry
+/// under the given version, finalizing each of them once fully uploaded
+pub async fn register_vkeys(
+    args: RegisterVkeysArgs,
+    client: Arc<LocalWalletHttpClient>,
+    deployments_path: &str,
+) -> Result<(), ScriptError> {
+    let vkey_registry_address = parse_addr_from_deployments_file(
+        deployments_path,
+        get_contract_key(StylusContract::VkeyRegistry),
+    )?;
+    let vkey_registry = VkeyRegistryContract::new(vkey_registry_address, client);
+
+    let vkey_files = [
+        (
+            VALID_WALLET_CREATE_VKEY_FILE,
+            VALID_WALLET_CREATE_CIRCUIT_ID,
+        ),
+        (
+            VALID_WALLET_UPDATE_VKEY_FILE,
+            VALID_WALLET_UPDATE_CIRCUIT_ID,
+        ),
+        (
+            VALID_RELAYER_FEE_SETTLEMENT_VKEY_FILE,
+            VALID_RELAYER_FEE_SETTLEMENT_CIRCUIT_ID,
+        ),
+        (
+            VALID_OFFLINE_FEE_SETTLEMENT_VKEY_FILE,
+            VALID_OFFLINE_FEE_SETTLEMENT_CIRCUIT_ID,
+        ),
+        (
+            VALID_FEE_REDEMPTION_VKEY_FILE,
+            VALID_FEE_REDEMPTION_CIRCUIT_ID,
+        ),
+        (
+            PROCESS_MATCH_SETTLE_VKEYS_FILE,
+            PROCESS_MATCH_SETTLE_CIRCUIT_ID,
+        ),
+    ];
+
+    for (file, circuit_id) in vkey_files {
+        let vkey_bytes = read_vkey_file(&args.vkeys_dir, file)?;
+        upload_vkey(
+            &vkey_registry,
+            file,
+            circuit_id,
+            args.version,
+            &vkey_bytes,
+            args.chunk_size,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Uploads the given serialized verification keys to the vkey registry in chunks,
+/// resuming from the number of bytes already stored, and finalizes them
+async fn upload_vkey(
+    vkey_registry: &VkeyRegistryContract<impl Middleware + 'static>,
+    vkey_file: &str,
+    circuit_id: u8,
+    version: u64,
+    vkey_bytes: &[u8],
+    chunk_size: usize,
+) -> Result<(), ScriptError> {
+    if vkey_registry
+        .is_vkey_finalized(circuit_id, version)
+        .call()
+        .await
+        .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?
+    {
+        info!("{vkey_file} (circuit ID {circuit_id}) already finalized for version {version}");
+        return Ok(());
+    }
+
+    let uploaded_len = vkey_registry
+        .vkey_length(circuit_id, version)
+        .call()
+        .await
+        .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?
+        .as_usize();
+    if uploaded_len > vkey_bytes.len() {
+        return Err(ScriptError::ContractInteraction(format!(
+            "{uploaded_len} bytes already uploaded for {vkey_file}, which is {} bytes long",
+            vkey_bytes.len()
+        )));
+    }
+
+    for (i, chunk) in vkey_bytes[uploaded_len..].chunks(chunk_size).enumerate() {
+        let offset = uploaded_len + i * chunk_size;
+        info!(
+            "Uploading {vkey_file} bytes {offset}..{} of {}",
+            offset + chunk.len(),
+            vkey_bytes.len()
+        );
+
+        vkey_registry
+            .upload_vkey_chunk(
+                circuit_id,
+                version,
+                EthersU256::from(offset),
+                chunk.to_vec().into(),
+            )
+            .send()
+            .await
+            .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?
+            .await
+            .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?;
+    }
+
+    info!("Finalizing {vkey_file} (circuit ID {circuit_id}) for version {version}");
+    vkey_registry
+        .finalize_vkey(circuit_id, version)
+        .send()
+        .await
+        .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?
+        .await
+        .map_err(|e| ScriptError::ContractInteraction(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Verifies a proof natively, printing a trace of the intermediate values computed
+/// and the step of the verification algorithm at which it failed, if any
+pub fn diagnose_proof(args: DiagnoseProofArgs) -> Result<(), ScriptError> {
+    let vkey_bytes = fs::read(&args.vkey_path).map_err(|e| ScriptError::ReadFile(e.to_string()))?;
+    let proof_bytes =
+        fs::read(&args.proof_path).map_err(|e| ScriptError::ReadFile(e.to_string()))?;
+    let statement_bytes =
+        fs::read(&args.statement_path).map_err(|e| ScriptError::ReadFile(e.to_string()))?;
+
+    let vkey: ExtendedVerificationKey =
+        postcard::from_bytes(&vkey_bytes).map_err(|e| ScriptError::Serde(e.to_string()))?;
+    let proof: ExtendedProof =
+        postcard::from_bytes(&proof_bytes).map_err(|e| ScriptError::Serde(e.to_string()))?;
+    let public_inputs: PublicInputs =
+        postcard::from_bytes(&statement_bytes).map_err(|e| ScriptError::Serde(e.to_string()))?;
+
+    let trace = NativeVerifier::verify_with_trace(&vkey, &proof, &public_inputs);
+
+    info!(
+        "Public inputs: {} given, {} expected",
+        trace.num_public_inputs, trace.expected_num_public_inputs
+    );
+    if let Some(challenges) = trace.challenges {
+        info!("Challenges: {:#?}", challenges);
+    }
+    if let Some(domain_size) = trace.domain_size {
+        info!("Domain size: {}", domain_size);
+    }
+    if let Some(zero_poly_eval) = trace.zero_poly_eval {
+        info!("Zero polynomial evaluation: {}", zero_poly_eval);
+    }
+    if let Some(lagrange_1_eval) = trace.lagrange_1_eval {
+        info!(
+            "First Lagrange basis polynomial evaluation: {}",
+            lagrange_1_eval
+        );
+    }
+    if let Some(pi_eval) = trace.pi_eval {
+        info!("Public inputs polynomial evaluation: {}", pi_eval);
+    }
+    if let Some(r_0) = trace.r_0 {
+        info!("Linearization polynomial constant term: {}", r_0);
+    }
+    if let Some((lhs_g1, rhs_g1)) = trace.opening_elems {
+        info!("Opening elements: LHS {}, RHS {}", lhs_g1, rhs_g1);
+    }
+
+    match trace.failed_step {
+        None => info!("Proof verified successfully"),
+        Some(step) => info!(
+            "Verification failed at {} step, error: {:?}",
+            step, trace.error
+        ),
+    }
+
+    Ok(())
+}
+
+/// Profiles the precompile calls & hashes made when verifying proofs of each of the
+/// protocol circuits, writing a JSON report keyed by verification key file name
+pub fn profile_verifier(args: ProfileVerifierArgs) -> Result<(), ScriptError> {
+    let mut report = JsonValue::new_object();
+
+    for vkey_file in [
+        VALID_WALLET_CREATE_VKEY_FILE,
+        VALID_WALLET_UPDATE_VKEY_FILE,
+        VALID_RELAYER_FEE_SETTLEMENT_VKEY_FILE,
+        VALID_OFFLINE_FEE_SETTLEMENT_VKEY_FILE,
+        VALID_FEE_REDEMPTION_VKEY_FILE,
+    ] {
+        let vkey = read_vkey(&args.vkeys_dir, vkey_file)?;
+
+        let mut dummy_proof_generator = DummyProofGenerator::new(vkey.g);
+        let proof = dummy_proof_generator.proof(&vkey)?;
+        let public_inputs = dummy_proof_generator.public_inputs(&vkey);
+
+        let profile = ProfilingVerifier::profile_verify(vkey, proof, public_inputs)
+            .map_err(|e| ScriptError::Profiling(format!("{vkey_file}: {e:?}")))?;
+
+        info!(
+            "{vkey_file} estimated verification gas: {}",
+            profile.total().estimated_gas()
+        );
+        report[vkey_file] = verification_profile_to_json(&profile);
+    }
+
+    let (match_vkeys, match_linking_vkeys) = read_match_vkeys(&args.vkeys_dir)?;
+
+    let mut dummy_proof_generator = DummyProofGenerator::new(match_vkeys.valid_match_settle_vkey.g);
+    let (match_proofs, match_public_inputs, match_linking_proofs) =
+        dummy_proof_generator.match_bundle(&match_vkeys)?;
+
+    let profile = ProfilingVerifier::profile_verify_match(
+        match_vkeys,
+        match_linking_vkeys,
+        match_proofs,
+        match_public_inputs,
+        match_linking_proofs,
+    )
+    .map_err(|e| ScriptError::Profiling(format!("{PROCESS_MATCH_SETTLE_VKEYS_FILE}: {e:?}")))?;
+
+    info!(
+        "{PROCESS_MATCH_SETTLE_VKEYS_FILE} estimated verification gas: {}",
+        profile.total().estimated_gas()
+    );
+    report[PROCESS_MATCH_SETTLE_VKEYS_FILE] = verification_profile_to_json(&profile);
+
+    fs::write(&args.output_path, json::stringify_pretty(report, 4))
+        .map_err(|e| ScriptError::WriteFile(e.to_string()))
+}
+
+/// Verifies the proofs given for a call to one of the darkpool's methods natively,
+/// running the same checks on its inputs as the darkpool core contract,
+/// other than those against the darkpool's state
+pub fn verify(args: VerifyArgs) -> Result<(), ScriptError> {
+    let VerifyArgs { vkeys_dir, method } = args;
+
+    let verified = match method {
+        VerifyMethod::NewWallet(args) => verify_proof::<ValidWalletCreateStatement>(
+            &vkeys_dir,
+            VALID_WALLET_CREATE_VKEY_FILE,
+            args,
+        ),
+        VerifyMethod::UpdateWallet(args) => verify_proof::<ValidWalletUpdateStatement>(
+            &vkeys_dir,
+            VALID_WALLET_UPDATE_VKEY_FILE,
+            args,
+        ),
+        VerifyMethod::ProcessMatchSettle(args) => verify_match_settle(&vkeys_dir, args),
+        VerifyMethod::ProcessMatchSettleBatch(args) => verify_match_settle_batch(&vkeys_dir, args),
+        VerifyMethod::SettleOnlineRelayerFee(args) => {
+            verify_proof::<ValidRelayerFeeSettlementStatement>(
+                &vkeys_dir,
+                VALID_RELAYER_FEE_SETTLEMENT_VKEY_FILE,
+                args,
+            )
+        }
+        VerifyMethod::SettleOfflineFee(args) => verify_proof::<ValidOfflineFeeSettlementStatement>(
+            &vkeys_dir,
+            VALID_OFFLINE_FEE_SETTLEMENT_VKEY_FILE,
+            args,
+        ),
+        VerifyMethod::RedeemFee(args) => verify_proof::<ValidFeeRedemptionStatement>(
+            &vkeys_dir,
+            VALID_FEE_REDEMPTION_VKEY_FILE,
+            args,
+        ),
+    }?;
+
+    if !verified {
+        return Err(ScriptError::Verification(
+            "proof verification failed".to_string(),
+        ));
+    }
+
+    info!("Inputs verified successfully");
+    Ok(())
+}
+
+/// Verifies a single proof of a statement of type `S`,
+/// using the verification key in the given file
+fn verify_proof<S: ScalarSerializable + DeserializeOwned>(
+    vkeys_dir: &str,
+    vkey_file: &str,
+    args: VerifyProofArgs,
+) -> Result<bool, ScriptError> {
+    let vkey = read_vkey(vkeys_dir, vkey_file)?;
+    let proof: ExtendedProof = read_serialized_file(&args.proof_path)?;
+    let statement: S = read_serialized_file(&args.statement_path)?;
+
+    let public_inputs =
+        statement_to_public_inputs(&statement).map_err(|e| ScriptError::Serde(format!("{e:?}")))?;
+
+    NativeVerifier::verify(vkey, proof, public_inputs)
+        .map_err(|e| ScriptError::Verification(format!("{e:?}")))
+}
+
+/// Verifies the proofs involved in the settlement of a single match,
+/// as done in the darkpool's `processMatchSettle` method
+fn verify_match_settle(vkeys_dir: &str, args: VerifyMatchSettleArgs) -> Result<bool, ScriptError> {
+    let party_0_match_payload: MatchPayload =
+        read_serialized_file(&args.party_0_match_payload_path)?;
+    let party_1_match_payload: MatchPayload =
+        read_serialized_file(&args.party_1_match_payload_path)?;
+    let valid_match_settle_statement: ValidMatchSettleWithMintStatement =
+        read_serialized_file(&args.statement_path)?;
+
+    check_num_protocol_fees(&args.protocol_fees, 1 /* num_matches */)?;
+    check_match_settle_inputs(
+        &party_0_match_payload,
+        &party_1_match_payload,
+        &valid_match_settle_statement,
+        args.protocol_fees[0],
+    )?;
+
+    let (match_vkeys, match_linking_vkeys) = read_match_vkeys(vkeys_dir)?;
+    let match_proofs: MatchProofs<ExtendedProof> = read_serialized_file(&args.match_proofs_path)?;
+    let match_linking_proofs: MatchLinkingProofs =
+        read_serialized_file(&args.match_linking_proofs_path)?;
+    let match_public_inputs = match_payloads_to_public_inputs(
+        &party_0_match_payload,
+        &party_1_match_payload,
+        &valid_match_settle_statement,
+    )?;
+
+    NativeVerifier::verify_match(
+        match_vkeys,
+        match_linking_vkeys,
+        match_proofs,
+        match_public_inputs,
+        match_linking_proofs,
+    )
+    .map_err(|e| ScriptError::Verification(format!("{e:?}")))
+}
+
+/// Verifies the proofs involved in the settlement of a batch of matches,
+/// as done in the darkpool's `processMatchSettleBatch` method
+fn verify_match_settle_batch(
+    vkeys_dir: &str,
+    args: VerifyMatchSettleArgs,
+) -> Result<bool, ScriptError> {
+    let party_0_match_payloads: Vec<MatchPayload> =
+        read_serialized_file(&args.party_0_match_payload_path)?;
+    let party_1_match_payloads: Vec<MatchPayload> =
+        read_serialized_file(&args.party_1_match_payload_path)?;
+    let valid_match_settle_statements: Vec<ValidMatchSettleWithMintStatement> =
+        read_serialized_file(&args.statement_path)?;
+
+    if !check_match_settle_batch_lengths(
+        &party_0_match_payloads,
+        &party_1_match_payloads,
+        &valid_match_settle_statements,
+    ) {
+        return Err(ScriptError::Verification(
+            "invalid array length".to_string(),
+        ));
+    }
+
+    let num_matches = valid_match_settle_statements.len();
+    check_num_protocol_fees(&args.protocol_fees, num_matches)?;
+    for i in 0..num_matches {
+        check_match_settle_inputs(
+            &party_0_match_payloads[i],
+            &party_1_match_payloads[i],
+            &valid_match_settle_statements[i],
+            args.protocol_fees[i],
+        )?;
+    }
+
+    let (match_vkeys, match_linking_vkeys) = read_match_vkeys(vkeys_dir)?;
+    let match_proofs_batch: Vec<MatchProofs<ExtendedProof>> =
+        read_serialized_file(&args.match_proofs_path)?;
+    let match_linking_proofs_batch: Vec<MatchLinkingProofs> =
+        read_serialized_file(&args.match_linking_proofs_path)?;
+    let match_public_inputs_batch = party_0_match_payloads
+        .iter()
+        .zip(&party_1_match_payloads)
+        .zip(&valid_match_settle_statements)
+        .map(
+            |((party_0_match_payload, party_1_match_payload), valid_match_settle_statement)| {
+                match_payloads_to_public_inputs(
+                    party_0_match_payload,
+                    party_1_match_payload,
+                    valid_match_settle_statement,
+                )
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+
+    NativeVerifier::verify_match_batch(
+        match_vkeys,
+        match_linking_vkeys,
+        match_proofs_batch,
+        match_public_inputs_batch,
+        match_linking_proofs_batch,
+    )
+    .map_err(|e| ScriptError::Verification(format!("{e:?}")))
+}
+
+/// Checks that a protocol fee was given for each of the matches being verified
+fn check_num_protocol_fees(protocol_fees: &[u64], num_matches: usize) -> Result<(), ScriptError> {
+    if protocol_fees.len() != num_matches {
+        return Err(ScriptError::Verification(format!(
+            "expected {num_matches} protocol fees, got {}",
+            protocol_fees.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Generates test vectors for the Fiat-Shamir transcript by recording the Jellyfish prover,
+/// and writes them to the given path as JSON
+pub fn gen_transcript_vectors(args: GenTranscriptVectorsArgs) -> Result<(), ScriptError> {
+    let mut rng = thread_rng();
+    let vectors = gen_transcript_test_vectors(args.num_vectors, &mut rng)
+        .map_err(|e| ScriptError::TestVectorGeneration(e.to_string()))?;
+
+    let vectors_json =
+        serde_json::to_string_pretty(&vectors).map_err(|e| ScriptError::Serde(e.to_string()))?;
+
+    fs::write(&args.output_path, vectors_json).map_err(|e| ScriptError::WriteFile(e.to_string()))
+}
@@ -135,6 +135,12 @@ pub const VERIFIER_CONTRACT_KEY: &str = "verifier_contract";
 /// The vkeys contract key in the `deployments.json` file
 pub const VKEYS_CONTRACT_KEY: &str = "vkeys_contract";
 
+/// The vkey registry contract key in the `deployments.json` file
+pub const VKEY_REGISTRY_CONTRACT_KEY: &str = "vkey_registry_contract";
+
+/// The vkey registry proxy admin contract key in the `deployments.json` file
+pub const VKEY_REGISTRY_PROXY_ADMIN_CONTRACT_KEY: &str = "vkey_registry_proxy_admin_contract";
+
 /// The transfer executor contract key in the `deployments.json` file
 pub const TRANSFER_EXECUTOR_CONTRACT_KEY: &str = "transfer_executor_contract";
 
@@ -149,6 +155,14 @@ pub const DUMMY_ERC20_SYMBOL_ENV_VAR: &str = "DUMMY_ERC20_SYMBOL";
 /// when deploying the testing contracts
 pub const TEST_FUNDING_AMOUNT: u128 = 1000;
 
+/// The delay, in seconds, between scheduling and executing an implementation
+/// address change on the darkpool deployed using `deploy_test_contracts`
+pub const TEST_ADDRESS_CHANGE_DELAY: u64 = 2;
+
+/// The version under which the testing verification keys are registered
+/// in the vkey registry deployed using `deploy_test_contracts`
+pub const TEST_VKEY_VERSION: u64 = 1;
+
 /// The test upgrade target contract key in the `deployments.json` file
 pub const TEST_UPGRADE_TARGET_CONTRACT_KEY: &str = "test_upgrade_target_contract";
 
@@ -174,3 +188,47 @@ pub const VALID_FEE_REDEMPTION_VKEY_FILE: &str = "valid_fee_redemption";
 /// VALID COMMITMENTS, VALID REBLIND, & VALID MATCH SETTLE
 /// verification keys
 pub const PROCESS_MATCH_SETTLE_VKEYS_FILE: &str = "process_match_settle";
+
+/// The file name for the keccak hashes of the serialized verification keys,
+/// keyed by the verification key file name
+pub const VKEY_HASHES_FILE: &str = "vkey_hashes.json";
+
+/// The key of a circuit's identifier in the verification key hashes file
+pub const CIRCUIT_ID_KEY: &str = "circuit_id";
+
+/// The key of a circuit's verification key hash in the verification key hashes file
+pub const VKEY_HASH_KEY: &str = "vkey_hash";
+
+/// The default size, in bytes, of the verification key chunks uploaded to the
+/// vkey registry in a single transaction.
+///
+/// This keeps each upload well within the calldata limit, and the cost of
+/// the storage writes for a chunk well within the block gas limit
+pub const VKEY_UPLOAD_CHUNK_SIZE: usize = 16_384;
+
+/// The default number of transcript test vectors to generate for each kind of proof
+pub const NUM_TRANSCRIPT_TEST_VECTORS: usize = 4;
+
+/// The key of the per-step counts of a circuit's verification in the verifier profile report
+pub const PROFILE_STEPS_KEY: &str = "steps";
+
+/// The key of the total counts of a circuit's verification in the verifier profile report
+pub const PROFILE_TOTAL_KEY: &str = "total";
+
+/// The key of the number of `ecAdd` precompile calls in the verifier profile report
+pub const EC_ADD_CALLS_KEY: &str = "ec_add_calls";
+
+/// The key of the number of `ecMul` precompile calls in the verifier profile report
+pub const EC_MUL_CALLS_KEY: &str = "ec_mul_calls";
+
+/// The key of the number of `ecPairing` precompile calls in the verifier profile report
+pub const PAIRING_CHECKS_KEY: &str = "pairing_checks";
+
+/// The key of the number of hashes computed in the verifier profile report
+pub const HASH_CALLS_KEY: &str = "hash_calls";
+
+/// The key of the number of bytes hashed in the verifier profile report
+pub const BYTES_HASHED_KEY: &str = "bytes_hashed";
+
+/// The key of the estimated gas cost in the verifier profile report
+pub const ESTIMATED_GAS_KEY: &str = "estimated_gas";
@@ -34,6 +34,12 @@ pub enum ScriptError {
     CircuitCreation,
     /// Error parsing the protocol public encryption key
     PubkeyParsing(String),
+    /// Error profiling the verifier
+    Profiling(String),
+    /// Error verifying the inputs to a darkpool method
+    Verification(String),
+    /// Error generating test vectors
+    TestVectorGeneration(String),
 }
 
 impl Display for ScriptError {
@@ -54,6 +60,11 @@ impl Display for ScriptError {
             ScriptError::ConversionError => write!(f, "error converting between types"),
             ScriptError::CircuitCreation => write!(f, "error creating circuit"),
             ScriptError::PubkeyParsing(s) => write!(f, "error parsing protocol pubkey: {}", s),
+            ScriptError::Profiling(s) => write!(f, "error profiling verifier: {}", s),
+            ScriptError::Verification(s) => write!(f, "error verifying inputs: {}", s),
+            ScriptError::TestVectorGeneration(s) => {
+                write!(f, "error generating test vectors: {}", s)
+            }
         }
     }
 }
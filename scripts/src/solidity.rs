@@ -4,7 +4,7 @@ use alloy_sol_types::sol;
 use ethers::contract::abigen;
 
 sol! {
-    function initialize(address memory darkpool_core_address, address memory verifier_address, address memory vkeys_address, address memory merkle_address, address memory transfer_executor_address, address memory permit2_address, uint256 memory protocol_fee, uint256[2] memory protocol_public_encryption_key) external;
+    function initialize(address memory darkpool_core_address, address memory verifier_address, address memory vkeys_address, address memory merkle_address, address memory transfer_executor_address, address memory permit2_address, uint256 memory protocol_fee, uint256[2] memory protocol_public_encryption_key, uint256 memory address_change_delay) external;
 }
 
 abigen!(
@@ -14,6 +14,17 @@ abigen!(
     ]"#,
 );
 
+abigen!(
+    VkeyRegistryContract,
+    r#"[
+        function initialize() external
+        function vkeyLength(uint8 circuit_id, uint64 version) external view returns (uint256)
+        function isVkeyFinalized(uint8 circuit_id, uint64 version) external view returns (bool)
+        function uploadVkeyChunk(uint8 circuit_id, uint64 version, uint256 offset, bytes memory chunk) external
+        function finalizeVkey(uint8 circuit_id, uint64 version) external
+    ]"#
+);
+
 abigen!(
     DummyErc20Contract,
     r#"[
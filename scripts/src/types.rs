@@ -3,7 +3,7 @@
 use std::fmt::{self, Display};
 
 use clap::ValueEnum;
-use contracts_common::types::{MatchLinkingVkeys, MatchVkeys, VerificationKey};
+use contracts_common::types::{ExtendedVerificationKey, MatchLinkingVkeys, MatchVkeys};
 
 /// The possible Stylus contracts to deploy
 #[derive(ValueEnum, Copy, Clone)]
@@ -24,6 +24,8 @@ pub enum StylusContract {
     Vkeys,
     /// The test verification keys contract
     TestVkeys,
+    /// The versioned verification key registry contract
+    VkeyRegistry,
     /// The transfer executor contract
     TransferExecutor,
     /// The dummy ERC20 contract
@@ -45,6 +47,7 @@ impl Display for StylusContract {
             StylusContract::Verifier => write!(f, "verifier"),
             StylusContract::Vkeys => write!(f, "vkeys"),
             StylusContract::TestVkeys => write!(f, "test-vkeys"),
+            StylusContract::VkeyRegistry => write!(f, "vkey-registry"),
             StylusContract::TransferExecutor => write!(f, "transfer-executor"),
             StylusContract::DummyErc20 => write!(f, "dummy-erc20"),
             StylusContract::DummyUpgradeTarget => write!(f, "dummy-upgrade-target"),
@@ -56,15 +59,15 @@ impl Display for StylusContract {
 /// A convenience struct containing all of the verification keys in the protocol
 pub struct RenegadeVerificationKeys {
     /// The `VALID WALLET CREATE` verification key
-    pub valid_wallet_create: VerificationKey,
+    pub valid_wallet_create: ExtendedVerificationKey,
     /// The `VALID WALLET UPDATE` verification key
-    pub valid_wallet_update: VerificationKey,
+    pub valid_wallet_update: ExtendedVerificationKey,
     /// The `VALID RELAYER FEE SETTLEMENT` verification key
-    pub valid_relayer_fee_settlement: VerificationKey,
+    pub valid_relayer_fee_settlement: ExtendedVerificationKey,
     /// The `VALID OFFLINE FEE SETTLEMENT` verification key
-    pub valid_offline_fee_settlement: VerificationKey,
+    pub valid_offline_fee_settlement: ExtendedVerificationKey,
     /// The `VALID FEE REDEMPTION` verification key
-    pub valid_fee_redemption: VerificationKey,
+    pub valid_fee_redemption: ExtendedVerificationKey,
     /// The verification keys used in matching & settling a trade
     pub match_vkeys: MatchVkeys,
     /// The proof linking verification keys used in
@@ -11,10 +11,27 @@ use std::{
     sync::Arc,
 };
 
-use alloy_primitives::{Address as AlloyAddress, U256};
+use alloy_primitives::{keccak256, Address as AlloyAddress, U256};
 use alloy_sol_types::SolCall;
 use ark_ed_on_bn254::EdwardsProjective as BabyJubJubProjective;
-use contracts_common::{custom_serde::scalar_to_u256, types::PublicEncryptionKey};
+use contracts_common::{
+    backends::G1ArithmeticBackend,
+    custom_serde::scalar_to_u256,
+    types::{
+        ExtendedProof, ExtendedVerificationKey, G1Affine, LinkingProof, LookupEvaluations,
+        LookupProof, MatchLinkingProofs, MatchLinkingVkeys, MatchPayload, MatchProofs,
+        MatchPublicInputs, MatchVkeys, Proof, PublicEncryptionKey, PublicInputs, ScalarField,
+        ValidMatchSettleWithMintStatement,
+    },
+};
+use contracts_core::{
+    darkpool::{
+        check_match_settle_indices, check_match_settle_protocol_fee,
+        match_statements_to_public_inputs,
+    },
+    native::NativeG1ArithmeticBackend,
+    verifier::profiling::{OpCounts, VerificationProfile},
+};
 use ethers::{
     abi::Address,
     middleware::SignerMiddleware,
@@ -25,19 +42,24 @@ use ethers::{
 use itertools::Itertools;
 use json::JsonValue;
 use rand::{distributions::Standard, thread_rng, Rng};
-use tracing::log::warn;
+use serde::de::DeserializeOwned;
+use tracing::log::{info, warn};
 use util::hex::jubjub_from_hex_string;
 
 use crate::{
     constants::{
         AGGRESSIVE_OPTIMIZATION_FLAG, AGGRESSIVE_SIZE_OPTIMIZATION_FLAG, BUILD_COMMAND,
-        CARGO_COMMAND, DARKPOOL_CONTRACT_KEY, DARKPOOL_CORE_CONTRACT_KEY, DEFAULT_RUSTFLAGS,
-        DEPLOYMENTS_KEY, DEPLOY_COMMAND, INLINE_THRESHOLD_FLAG, MANIFEST_DIR_ENV_VAR,
-        MERKLE_CONTRACT_KEY, NO_VERIFY_FEATURE, OPT_LEVEL_3, OPT_LEVEL_FLAG, OPT_LEVEL_Z,
-        PRECOMPILE_TEST_CONTRACT_KEY, RELEASE_PATH_SEGMENT, RUSTFLAGS_ENV_VAR, STYLUS_COMMAND,
-        STYLUS_CONTRACTS_CRATE_NAME, TARGET_PATH_SEGMENT, TEST_UPGRADE_TARGET_CONTRACT_KEY,
-        TRANSFER_EXECUTOR_CONTRACT_KEY, VERIFIER_CONTRACT_KEY, VKEYS_CONTRACT_KEY, WASM_EXTENSION,
-        WASM_OPT_COMMAND, WASM_OPT_EXTENSION, WASM_TARGET_TRIPLE, Z_FLAGS,
+        BYTES_HASHED_KEY, CARGO_COMMAND, CIRCUIT_ID_KEY, DARKPOOL_CONTRACT_KEY,
+        DARKPOOL_CORE_CONTRACT_KEY, DEFAULT_RUSTFLAGS, DEPLOYMENTS_KEY, DEPLOY_COMMAND,
+        EC_ADD_CALLS_KEY, EC_MUL_CALLS_KEY, ESTIMATED_GAS_KEY, HASH_CALLS_KEY,
+        INLINE_THRESHOLD_FLAG, MANIFEST_DIR_ENV_VAR, MERKLE_CONTRACT_KEY, NO_VERIFY_FEATURE,
+        OPT_LEVEL_3, OPT_LEVEL_FLAG, OPT_LEVEL_Z, PAIRING_CHECKS_KEY, PRECOMPILE_TEST_CONTRACT_KEY,
+        PROCESS_MATCH_SETTLE_VKEYS_FILE, PROFILE_STEPS_KEY, PROFILE_TOTAL_KEY,
+        RELEASE_PATH_SEGMENT, RUSTFLAGS_ENV_VAR, STYLUS_COMMAND, STYLUS_CONTRACTS_CRATE_NAME,
+        TARGET_PATH_SEGMENT, TEST_UPGRADE_TARGET_CONTRACT_KEY, TRANSFER_EXECUTOR_CONTRACT_KEY,
+        VERIFIER_CONTRACT_KEY, VKEYS_CONTRACT_KEY, VKEY_HASHES_FILE, VKEY_HASH_KEY,
+        VKEY_REGISTRY_CONTRACT_KEY, WASM_EXTENSION, WASM_OPT_COMMAND, WASM_OPT_EXTENSION,
+        WASM_TARGET_TRIPLE, Z_FLAGS,
     },
     errors::ScriptError,
     solidity::initializeCall,
@@ -136,6 +158,62 @@ pub fn write_vkey_file(
     fs::write(vkey_file_path, vkey_bytes).map_err(|e| ScriptError::WriteFile(e.to_string()))
 }
 
+/// Reads the verification key from the file at the given directory & path
+pub fn read_vkey_file(vkeys_dir: &str, vkey_file_name: &str) -> Result<Vec<u8>, ScriptError> {
+    let vkeys_dir = PathBuf::from(vkeys_dir);
+    let vkey_file_path = vkeys_dir.join(vkey_file_name);
+
+    fs::read(vkey_file_path).map_err(|e| ScriptError::ReadFile(e.to_string()))
+}
+
+/// Reads & deserializes the `postcard`-serialized value in the file at the given path
+pub fn read_serialized_file<T: DeserializeOwned>(file_path: &str) -> Result<T, ScriptError> {
+    let bytes = fs::read(file_path).map_err(|e| ScriptError::ReadFile(e.to_string()))?;
+    postcard::from_bytes(&bytes).map_err(|e| ScriptError::Serde(e.to_string()))
+}
+
+/// Reads the verification key of the circuit in the given file from the given directory
+pub fn read_vkey(
+    vkeys_dir: &str,
+    vkey_file_name: &str,
+) -> Result<ExtendedVerificationKey, ScriptError> {
+    postcard::from_bytes(&read_vkey_file(vkeys_dir, vkey_file_name)?)
+        .map_err(|e| ScriptError::Serde(e.to_string()))
+}
+
+/// Reads the verification keys & linking verification keys used in matching & settling
+/// a trade from the given directory, in which they are serialized together
+pub fn read_match_vkeys(vkeys_dir: &str) -> Result<(MatchVkeys, MatchLinkingVkeys), ScriptError> {
+    postcard::from_bytes(&read_vkey_file(vkeys_dir, PROCESS_MATCH_SETTLE_VKEYS_FILE)?)
+        .map_err(|e| ScriptError::Serde(e.to_string()))
+}
+
+/// Writes the keccak hashes of the given serialized verification keys, along with the
+/// identifiers of their circuits, to the verification key hashes file in the given directory.
+///
+/// These are the commitments that the darkpool registers for the circuits whose
+/// verification keys are passed in calldata, against which callers may check them
+pub fn write_vkey_hashes_file(
+    vkeys_dir: &str,
+    vkeys: &[(&str, u8, Vec<u8>)],
+) -> Result<(), ScriptError> {
+    let mut vkey_hashes = JsonValue::new_object();
+    for (vkey_file_name, circuit_id, vkey_bytes) in vkeys {
+        let vkey_hash = format!("{:#x}", keccak256(vkey_bytes));
+        info!("{vkey_file_name} (circuit ID {circuit_id}) vkey hash: {vkey_hash}");
+
+        vkey_hashes[*vkey_file_name][CIRCUIT_ID_KEY] = (*circuit_id).into();
+        vkey_hashes[*vkey_file_name][VKEY_HASH_KEY] = vkey_hash.into();
+    }
+
+    let vkey_hashes_file_path = PathBuf::from(vkeys_dir).join(VKEY_HASHES_FILE);
+    fs::write(
+        vkey_hashes_file_path,
+        json::stringify_pretty(vkey_hashes, 4),
+    )
+    .map_err(|e| ScriptError::WriteFile(e.to_string()))
+}
+
 /// Returns the JSON key used in the deployments file for the given contract
 pub fn get_contract_key(contract: StylusContract) -> &'static str {
     match contract {
@@ -144,6 +222,7 @@ pub fn get_contract_key(contract: StylusContract) -> &'static str {
         StylusContract::Merkle | StylusContract::MerkleTestContract => MERKLE_CONTRACT_KEY,
         StylusContract::Verifier => VERIFIER_CONTRACT_KEY,
         StylusContract::Vkeys | StylusContract::TestVkeys => VKEYS_CONTRACT_KEY,
+        StylusContract::VkeyRegistry => VKEY_REGISTRY_CONTRACT_KEY,
         StylusContract::TransferExecutor => TRANSFER_EXECUTOR_CONTRACT_KEY,
         StylusContract::DummyUpgradeTarget => TEST_UPGRADE_TARGET_CONTRACT_KEY,
         StylusContract::PrecompileTestContract => PRECOMPILE_TEST_CONTRACT_KEY,
@@ -182,6 +261,7 @@ pub fn darkpool_initialize_calldata(
     permit2_address: Address,
     protocol_fee: U256,
     protocol_public_encryption_key: PublicEncryptionKey,
+    address_change_delay: U256,
 ) -> Result<Vec<u8>, ScriptError> {
     let darkpool_core_address = AlloyAddress::from_slice(darkpool_core_address.as_bytes());
     let verifier_address = AlloyAddress::from_slice(verifier_address.as_bytes());
@@ -203,6 +283,7 @@ pub fn darkpool_initialize_calldata(
         permit2_address,
         protocol_fee,
         protocol_public_encryption_key,
+        address_change_delay,
     ))
     .abi_encode())
 }
@@ -396,3 +477,239 @@ pub async fn deploy_stylus_contract(
 
     Ok(deployed_address)
 }
+
+/// A generator of dummy proofs of the shape expected by a verification key,
+/// used to profile the verifier without having to generate real proofs.
+///
+/// The proofs will not verify, but they are composed of distinct, valid curve points
+/// & scalars other than one, so that the verifier makes the same precompile calls
+/// & hashes as it would for a valid proof.
+pub struct DummyProofGenerator {
+    /// The generator of G1, from which the dummy curve points are derived
+    generator: G1Affine,
+    /// The last scalar generated
+    counter: u64,
+}
+
+impl DummyProofGenerator {
+    /// Creates a new dummy proof generator, deriving curve points from the given generator of G1
+    pub fn new(generator: G1Affine) -> Self {
+        // We start from 2 so that no scalar or point is trivial
+        Self {
+            generator,
+            counter: 1,
+        }
+    }
+
+    /// Generates a dummy scalar
+    pub fn scalar(&mut self) -> ScalarField {
+        self.counter += 1;
+        ScalarField::from(self.counter)
+    }
+
+    /// Generates a dummy curve point
+    pub fn point(&mut self) -> Result<G1Affine, ScriptError> {
+        let scalar = self.scalar();
+        NativeG1ArithmeticBackend::ec_scalar_mul(scalar, self.generator)
+            .map_err(|_| ScriptError::ConversionError)
+    }
+
+    /// Generates an array of dummy scalars
+    fn scalars<const N: usize>(&mut self) -> [ScalarField; N] {
+        let mut scalars = [ScalarField::from(0u64); N];
+        for scalar in scalars.iter_mut() {
+            *scalar = self.scalar();
+        }
+        scalars
+    }
+
+    /// Generates an array of dummy curve points
+    fn points<const N: usize>(&mut self) -> Result<[G1Affine; N], ScriptError> {
+        let mut points = [G1Affine::identity(); N];
+        for point in points.iter_mut() {
+            *point = self.point()?;
+        }
+        Ok(points)
+    }
+
+    /// Generates dummy public inputs of the length expected by the given verification key
+    pub fn public_inputs(&mut self, vkey: &ExtendedVerificationKey) -> PublicInputs {
+        PublicInputs((0..vkey.l).map(|_| self.scalar()).collect())
+    }
+
+    /// Generates a dummy proof of the shape expected by the given verification key,
+    /// including a lookup argument if the verification key expects one
+    pub fn proof(&mut self, vkey: &ExtendedVerificationKey) -> Result<ExtendedProof, ScriptError> {
+        let lookup_proof = match vkey.lookup_vkey {
+            Some(_) => Some(self.lookup_proof()?),
+            None => None,
+        };
+
+        Ok(ExtendedProof {
+            proof: Proof {
+                wire_comms: self.points()?,
+                z_comm: self.point()?,
+                quotient_comms: self.points()?,
+                w_zeta: self.point()?,
+                w_zeta_omega: self.point()?,
+                wire_evals: self.scalars(),
+                sigma_evals: self.scalars(),
+                z_bar: self.scalar(),
+            },
+            lookup_proof,
+        })
+    }
+
+    /// Generates a dummy proof of a lookup argument
+    fn lookup_proof(&mut self) -> Result<LookupProof, ScriptError> {
+        Ok(LookupProof {
+            lookup_wire_comm: self.point()?,
+            lookup_quotient_comm: self.point()?,
+            h_poly_comms: self.points()?,
+            prod_lookup_poly_comm: self.point()?,
+            lookup_wire_eval: self.scalar(),
+            last_sigma_eval: self.scalar(),
+            lookup_evals: LookupEvaluations {
+                range_table_eval: self.scalar(),
+                key_table_eval: self.scalar(),
+                table_dom_sep_eval: self.scalar(),
+                q_dom_sep_eval: self.scalar(),
+                h_1_eval: self.scalar(),
+                q_lookup_eval: self.scalar(),
+                prod_next_eval: self.scalar(),
+                range_table_next_eval: self.scalar(),
+                key_table_next_eval: self.scalar(),
+                table_dom_sep_next_eval: self.scalar(),
+                h_1_next_eval: self.scalar(),
+                h_2_next_eval: self.scalar(),
+                q_lookup_next_eval: self.scalar(),
+                w_3_next_eval: self.scalar(),
+                w_4_next_eval: self.scalar(),
+            },
+        })
+    }
+
+    /// Generates a dummy linking proof
+    pub fn linking_proof(&mut self) -> Result<LinkingProof, ScriptError> {
+        Ok(LinkingProof {
+            linking_quotient_poly_comm: self.point()?,
+            linking_poly_opening: self.point()?,
+        })
+    }
+
+    /// Generates dummy proofs, public inputs, & linking proofs for the matching and
+    /// settlement of a trade, of the shape expected by the given verification keys
+    pub fn match_bundle(
+        &mut self,
+        match_vkeys: &MatchVkeys,
+    ) -> Result<
+        (
+            MatchProofs<ExtendedProof>,
+            MatchPublicInputs,
+            MatchLinkingProofs,
+        ),
+        ScriptError,
+    > {
+        let MatchVkeys {
+            valid_commitments_vkey,
+            valid_reblind_vkey,
+            valid_match_settle_vkey,
+        } = match_vkeys;
+
+        let match_proofs = MatchProofs {
+            valid_commitments_0: self.proof(valid_commitments_vkey)?,
+            valid_reblind_0: self.proof(valid_reblind_vkey)?,
+            valid_commitments_1: self.proof(valid_commitments_vkey)?,
+            valid_reblind_1: self.proof(valid_reblind_vkey)?,
+            valid_match_settle: self.proof(valid_match_settle_vkey)?,
+        };
+
+        let match_public_inputs = MatchPublicInputs {
+            valid_commitments_0: self.public_inputs(valid_commitments_vkey),
+            valid_reblind_0: self.public_inputs(valid_reblind_vkey),
+            valid_commitments_1: self.public_inputs(valid_commitments_vkey),
+            valid_reblind_1: self.public_inputs(valid_reblind_vkey),
+            valid_match_settle: self.public_inputs(valid_match_settle_vkey),
+        };
+
+        let match_linking_proofs = MatchLinkingProofs {
+            valid_reblind_commitments_0: self.linking_proof()?,
+            valid_commitments_match_settle_0: self.linking_proof()?,
+            valid_reblind_commitments_1: self.linking_proof()?,
+            valid_commitments_match_settle_1: self.linking_proof()?,
+        };
+
+        Ok((match_proofs, match_public_inputs, match_linking_proofs))
+    }
+}
+
+/// Converts the given operation counts into JSON, for the verifier profile report
+fn op_counts_to_json(op_counts: &OpCounts) -> JsonValue {
+    let mut op_counts_json = JsonValue::new_object();
+    op_counts_json[EC_ADD_CALLS_KEY] = op_counts.ec_add_calls.into();
+    op_counts_json[EC_MUL_CALLS_KEY] = op_counts.ec_mul_calls.into();
+    op_counts_json[PAIRING_CHECKS_KEY] = op_counts.pairing_checks.into();
+    op_counts_json[HASH_CALLS_KEY] = op_counts.hash_calls.into();
+    op_counts_json[BYTES_HASHED_KEY] = op_counts.bytes_hashed.into();
+    op_counts_json[ESTIMATED_GAS_KEY] = op_counts.estimated_gas().into();
+    op_counts_json
+}
+
+/// Converts the given verification profile into JSON, for the verifier profile report
+pub fn verification_profile_to_json(profile: &VerificationProfile) -> JsonValue {
+    let mut profile_json = JsonValue::new_object();
+    for (step, op_counts) in &profile.steps {
+        profile_json[PROFILE_STEPS_KEY][step.to_string()] = op_counts_to_json(op_counts);
+    }
+    profile_json[PROFILE_TOTAL_KEY] = op_counts_to_json(&profile.total());
+    profile_json
+}
+
+/// Runs the checks the darkpool core contract performs on the inputs to the settlement
+/// of a match before verifying its proofs, given the protocol fee configured in the
+/// darkpool for the base mint of the match
+pub fn check_match_settle_inputs(
+    party_0_match_payload: &MatchPayload,
+    party_1_match_payload: &MatchPayload,
+    valid_match_settle_statement: &ValidMatchSettleWithMintStatement,
+    protocol_fee: u64,
+) -> Result<(), ScriptError> {
+    let valid_match_settle_statement = &valid_match_settle_statement.valid_match_settle;
+    if !check_match_settle_indices(
+        party_0_match_payload,
+        party_1_match_payload,
+        valid_match_settle_statement,
+    ) {
+        return Err(ScriptError::Verification(
+            "invalid order settlement indices".to_string(),
+        ));
+    }
+
+    if !check_match_settle_protocol_fee(
+        valid_match_settle_statement,
+        ScalarField::from(protocol_fee),
+    ) {
+        return Err(ScriptError::Verification(
+            "invalid protocol fee".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Builds the public inputs of the proofs involved in the settlement of a match
+/// from the statements in the match payloads & the `VALID MATCH SETTLE` statement
+pub fn match_payloads_to_public_inputs(
+    party_0_match_payload: &MatchPayload,
+    party_1_match_payload: &MatchPayload,
+    valid_match_settle_statement: &ValidMatchSettleWithMintStatement,
+) -> Result<MatchPublicInputs, ScriptError> {
+    match_statements_to_public_inputs(
+        &party_0_match_payload.valid_commitments_statement,
+        &party_1_match_payload.valid_commitments_statement,
+        &party_0_match_payload.valid_reblind_statement,
+        &party_1_match_payload.valid_reblind_statement,
+        valid_match_settle_statement,
+    )
+    .map_err(|e| ScriptError::Serde(format!("{e:?}")))
+}